@@ -5,25 +5,37 @@ use std::{
     mem,
     path::{Path, PathBuf},
     str::FromStr,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use bytes::Bytes;
 use clap::{ArgAction, Parser as ClapParser, Subcommand as ClapSubcommand};
-use ethrex_blockchain::{BlockchainOptions, BlockchainType, L2Config, error::ChainError};
-use ethrex_common::types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis};
+use ethrex_blockchain::{
+    BlockchainOptions, BlockchainType, L2Config, error::ChainError, history_expiry::HistoryExpiry,
+};
+use ethrex_common::{
+    Address, U256,
+    types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis, GenesisAccount},
+    utils::keccak,
+};
+use ethrex_metrics::otel::{OtlpProtocol, Propagators};
 use ethrex_p2p::{
     discv4::peer_table::TARGET_PEERS, sync::SyncMode, tx_broadcaster::BROADCAST_INTERVAL_MS,
     types::Node,
 };
 use ethrex_rlp::encode::RLPEncode;
-use ethrex_storage::error::StoreError;
+use ethrex_storage::{CONSISTENCY_CHECK_DEPTH, error::StoreError};
+use rand::rngs::OsRng;
+use secp256k1::SecretKey;
 use tokio_util::sync::CancellationToken;
 use tracing::{Level, info, warn};
 
 use crate::{
     initializers::{
-        get_network, init_blockchain, init_store, init_tracing, load_store, regenerate_head_state,
+        get_network, init_blockchain, init_store, init_tracing, load_store, open_store,
+        regenerate_head_state,
     },
+    replay::{ReplayBackend, replay},
     utils::{self, default_datadir, get_client_version, get_minimal_client_version, init_datadir},
 };
 
@@ -31,7 +43,10 @@ pub const DB_ETHREX_DEV_L1: &str = "dev_ethrex_l1";
 
 #[cfg(feature = "l2")]
 pub const DB_ETHREX_DEV_L2: &str = "dev_ethrex_l2";
-use ethrex_config::networks::Network;
+use ethrex_config::{
+    chain_spec::ChainSpec,
+    networks::{LOCAL_DEVNET_GENESIS_CONTENTS, Network},
+};
 
 #[allow(clippy::upper_case_acronyms)]
 #[derive(ClapParser)]
@@ -49,7 +64,7 @@ pub struct Options {
         long = "network",
         value_name = "GENESIS_FILE_PATH",
         help = "Receives a `Genesis` struct in json format. You can look at some example genesis files at `fixtures/genesis/*`.",
-        long_help = "Alternatively, the name of a known network can be provided instead to use its preset genesis file and include its preset bootnodes. The networks currently supported include holesky, sepolia, hoodi and mainnet. If not specified, defaults to mainnet.",
+        long_help = "Alternatively, the name of a known network can be provided instead to use its preset genesis file and include its preset bootnodes. The networks currently supported include holesky, sepolia, hoodi and mainnet. A chain-spec directory, such as the ones generated by `ethrex network init`, can also be provided to load the genesis file, bootnodes and settings of a custom network. If not specified, defaults to mainnet.",
         help_heading = "Node options",
         env = "ETHREX_NETWORK",
         value_parser = clap::value_parser!(Network),
@@ -100,6 +115,41 @@ pub struct Options {
         help_heading = "Node options"
     )]
     pub metrics_enabled: bool,
+    #[arg(
+        long = "otel.endpoint",
+        value_name = "URL",
+        help = "Export tracing spans to an OpenTelemetry collector.",
+        long_help = "Base URL of the collector's OTLP/HTTP receiver, e.g. http://localhost:4318. The Binary has to be built with the `otel` feature enabled.",
+        help_heading = "Node options",
+        env = "ETHREX_OTEL_ENDPOINT"
+    )]
+    pub otel_endpoint: Option<String>,
+    #[arg(
+        long = "otel.protocol",
+        default_value_t = OtlpProtocol::HttpBinary,
+        value_name = "PROTOCOL",
+        help = "Encoding used to export spans to the collector.",
+        long_help = "Possible values: http/protobuf, http/json",
+        help_heading = "Node options"
+    )]
+    pub otel_protocol: OtlpProtocol,
+    #[arg(
+        long = "otel.service-name",
+        default_value = "ethrex",
+        value_name = "NAME",
+        help = "Service name reported to the collector.",
+        help_heading = "Node options"
+    )]
+    pub otel_service_name: String,
+    #[arg(
+        long = "otel.propagators",
+        default_value_t = Propagators::default(),
+        value_name = "PROPAGATORS",
+        help = "Comma separated formats used to read the trace context of incoming RPC requests.",
+        long_help = "Possible values: tracecontext, baggage or none",
+        help_heading = "Node options"
+    )]
+    pub otel_propagators: Propagators,
     #[arg(
         long = "dev",
         action = ArgAction::SetTrue,
@@ -108,6 +158,15 @@ pub struct Options {
         help_heading = "Node options"
     )]
     pub dev: bool,
+    #[arg(
+        long = "readonly",
+        action = ArgAction::SetTrue,
+        conflicts_with = "dev",
+        help = "Serve the RPC API from the database of a node running on the same datadir, without writing to it",
+        long_help = "If set it will be considered as `true`. Opens the database as a read-only secondary instance that follows the node's writes, and starts only the HTTP and WS servers: no P2P, sync, Engine API nor transaction submission. State can only be read at the block the node last persisted to disk, about 128 blocks behind its head, which is reported as the latest block.",
+        help_heading = "Node options"
+    )]
+    pub readonly: bool,
     #[arg(
         long = "log.level",
         default_value_t = Level::INFO,
@@ -132,6 +191,16 @@ pub struct Options {
         help_heading = "Node options"
     )]
     pub mempool_max_size: usize,
+    #[arg(
+        long = "history.expiry",
+        default_value = "all",
+        value_name = "HISTORY_EXPIRY",
+        value_parser = clap::value_parser!(HistoryExpiry),
+        help = "How much block history (bodies and receipts) to keep, as described by EIP-4444.",
+        long_help = "Possible values: all, postmerge or an amount of recent blocks. Headers are always kept.",
+        help_heading = "Node options"
+    )]
+    pub history_expiry: HistoryExpiry,
     #[arg(
         long = "http.addr",
         default_value = "0.0.0.0",
@@ -158,6 +227,14 @@ pub struct Options {
         env = "ETHREX_ENABLE_WS"
     )]
     pub ws_enabled: bool,
+    #[arg(
+        long = "trace.index",
+        default_value = "false",
+        help = "Keep an index of the addresses appearing in the call traces of finalized blocks, used to serve trace_filter over wide block ranges.",
+        help_heading = "RPC options",
+        env = "ETHREX_TRACE_INDEX"
+    )]
+    pub trace_index: bool,
     #[arg(
         long = "ws.addr",
         default_value = "0.0.0.0",
@@ -307,6 +384,7 @@ impl Default for Options {
             http_addr: Default::default(),
             http_port: Default::default(),
             ws_enabled: false,
+            trace_index: false,
             ws_addr: Default::default(),
             ws_port: Default::default(),
             log_level: Level::INFO,
@@ -325,9 +403,15 @@ impl Default for Options {
             metrics_addr: "0.0.0.0".to_owned(),
             metrics_port: Default::default(),
             metrics_enabled: Default::default(),
+            otel_endpoint: None,
+            otel_protocol: Default::default(),
+            otel_service_name: "ethrex".to_string(),
+            otel_propagators: Default::default(),
             dev: Default::default(),
+            readonly: false,
             force: false,
             mempool_max_size: Default::default(),
+            history_expiry: Default::default(),
             tx_broadcasting_time_interval: Default::default(),
             target_peers: Default::default(),
             extra_data: get_minimal_client_version(),
@@ -412,11 +496,107 @@ pub enum Subcommand {
         )]
         genesis_path: PathBuf,
     },
+    #[command(
+        name = "replay",
+        about = "Re-execute blocks offline from an execution witness"
+    )]
+    Replay {
+        #[arg(
+            required = true,
+            long = "block",
+            value_name = "BLOCK_FILE_PATH",
+            help = "Path to the blocks to replay, either RLP encoded or in JSON (.json) as returned by eth_getBlockByNumber"
+        )]
+        block: PathBuf,
+        #[arg(
+            required = true,
+            long = "witness",
+            value_name = "WITNESS_FILE_PATH",
+            help = "Path to the execution witness of the blocks, as returned by debug_executionWitness"
+        )]
+        witness: PathBuf,
+        #[arg(
+            long = "backend",
+            value_enum,
+            default_value_t = ReplayBackend::Exec,
+            help = "Backend used to re-execute the blocks. zkVM backends run in execute-only mode and report cycle counts"
+        )]
+        backend: ReplayBackend,
+    },
+    #[command(name = "network", subcommand, about = "Manage custom networks")]
+    Network(NetworkSubcommand),
+    #[command(name = "db", subcommand, about = "Inspect and repair the database")]
+    Db(DbSubcommand),
     #[cfg(feature = "l2")]
     #[command(name = "l2")]
     L2(crate::l2::L2Command),
 }
 
+#[derive(ClapSubcommand, Debug)]
+pub enum NetworkSubcommand {
+    #[command(
+        name = "init",
+        about = "Generate a chain-spec directory for a custom network, with prefunded accounts"
+    )]
+    Init {
+        #[arg(
+            required = true,
+            value_name = "OUTPUT_DIRECTORY",
+            help = "Directory where the chain spec will be written, to be passed to `--network`"
+        )]
+        output: PathBuf,
+        #[arg(
+            long = "chain-id",
+            value_name = "CHAIN_ID",
+            help = "Chain ID of the network"
+        )]
+        chain_id: u64,
+        #[arg(
+            long = "accounts",
+            value_name = "NUMBER",
+            default_value_t = 10,
+            help = "Number of prefunded accounts to generate"
+        )]
+        accounts: usize,
+        #[arg(
+            long = "balance",
+            value_name = "ETHER",
+            default_value_t = 1_000_000,
+            help = "Initial balance of each prefunded account, in ether"
+        )]
+        balance: u64,
+        #[arg(long = "bootnodes", value_parser = clap::value_parser!(Node), value_name = "BOOTNODE_LIST", value_delimiter = ',', num_args = 1.., help = "Comma separated enode URLs of the network's bootnodes")]
+        bootnodes: Vec<Node>,
+        #[arg(
+            long = "private-keys-output",
+            value_name = "FILE",
+            help = "File where the private keys of the prefunded accounts are written, required when generating accounts. Keep it outside the chain-spec directory, as the spec is meant to be shared"
+        )]
+        private_keys_output: Option<PathBuf>,
+        #[arg(long = "force", help = "Overwrite the output directory and private keys file if they already exist", action = ArgAction::SetTrue)]
+        force: bool,
+    },
+}
+
+#[derive(ClapSubcommand, Debug)]
+pub enum DbSubcommand {
+    #[command(
+        name = "check",
+        about = "Check the database for inconsistencies left by an interrupted block import"
+    )]
+    Check {
+        #[arg(
+            long = "blocks",
+            value_name = "NUMBER",
+            default_value_t = CONSISTENCY_CHECK_DEPTH,
+            help = "Amount of blocks below the head to check"
+        )]
+        blocks: u64,
+        #[arg(long = "repair", help = "Roll the head back to the newest consistent block if needed", action = ArgAction::SetTrue)]
+        repair: bool,
+    },
+}
+
 impl Subcommand {
     pub async fn run(self, opts: &Options) -> eyre::Result<()> {
         // L2 has its own init_tracing because of the ethrex monitor
@@ -488,6 +668,34 @@ impl Subcommand {
                 let state_root = genesis.compute_state_root();
                 println!("{state_root:#x}");
             }
+            Subcommand::Replay {
+                block,
+                witness,
+                backend,
+            } => {
+                let chain_config = get_network(opts).get_genesis()?.config;
+                replay(&block, &witness, chain_config, backend)?;
+            }
+            Subcommand::Network(NetworkSubcommand::Init {
+                output,
+                chain_id,
+                accounts,
+                balance,
+                bootnodes,
+                private_keys_output,
+                force,
+            }) => init_network(
+                &output,
+                chain_id,
+                accounts,
+                balance,
+                bootnodes,
+                private_keys_output.as_deref(),
+                force,
+            )?,
+            Subcommand::Db(DbSubcommand::Check { blocks, repair }) => {
+                check_db(&opts.datadir, blocks, repair).await?
+            }
             #[cfg(feature = "l2")]
             Subcommand::L2(command) => command.run().await?,
         }
@@ -530,6 +738,19 @@ impl FromStr for LogColor {
     }
 }
 
+/// Writes the private keys to a new file only readable by its owner, replacing the previous one
+fn write_private_keys(path: &Path, private_keys: &str) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    std::io::Write::write_all(&mut options.open(path)?, private_keys.as_bytes())
+}
+
 pub fn remove_db(datadir: &Path, force: bool) {
     init_datadir(datadir);
 
@@ -556,6 +777,77 @@ pub fn remove_db(datadir: &Path, force: bool) {
     }
 }
 
+/// Writes a chain spec for a new network to `output`, and the private keys of its
+/// prefunded accounts to `private_keys_output`
+pub fn init_network(
+    output: &Path,
+    chain_id: u64,
+    accounts: usize,
+    balance: u64,
+    bootnodes: Vec<Node>,
+    private_keys_output: Option<&Path>,
+    force: bool,
+) -> eyre::Result<()> {
+    if output.exists() && !force {
+        eyre::bail!("{output:?} already exists, use --force to overwrite it");
+    }
+    if accounts > 0 && private_keys_output.is_none() {
+        eyre::bail!("Pass --private-keys-output to save the keys of the prefunded accounts");
+    }
+    if let Some(path) = private_keys_output
+        && path.exists()
+        && !force
+    {
+        eyre::bail!("{path:?} already exists, use --force to overwrite it");
+    }
+
+    // Start from the local devnet genesis to keep its predeployed contracts, such as the
+    // deposit and system contracts, but not its rich accounts
+    let mut genesis: Genesis = serde_json::from_str(LOCAL_DEVNET_GENESIS_CONTENTS)?;
+    genesis.config.chain_id = chain_id;
+    genesis.timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    genesis.alloc.retain(|_, account| !account.code.is_empty());
+
+    let balance = U256::from(balance) * U256::exp10(18);
+    let mut private_keys = String::new();
+    for _ in 0..accounts {
+        let secret_key = SecretKey::new(&mut OsRng);
+        let public_key = secret_key
+            .public_key(secp256k1::SECP256K1)
+            .serialize_uncompressed();
+        let address = Address::from_slice(&keccak(&public_key[1..]).as_bytes()[12..]);
+        genesis.alloc.insert(
+            address,
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance,
+                nonce: 0,
+            },
+        );
+        private_keys.push_str(&format!("0x{}\n", hex::encode(secret_key.secret_bytes())));
+    }
+
+    ChainSpec {
+        genesis,
+        bootnodes,
+        enrs: vec![],
+        deposit_contract: None,
+    }
+    .write(output)?;
+    if let Some(path) = private_keys_output {
+        write_private_keys(path, &private_keys)?;
+    }
+
+    info!(
+        path = %output.display(),
+        accounts,
+        "Chain spec written, start the network's nodes with `--network {}`",
+        output.display()
+    );
+    Ok(())
+}
+
 pub async fn import_blocks(
     path: &str,
     datadir: &Path,
@@ -844,3 +1136,102 @@ pub async fn export_blocks(
     }
     info!(blocks = end.saturating_sub(start) + 1, path = %path, "Exported blocks to file");
 }
+
+pub async fn check_db(datadir: &Path, blocks: u64, repair: bool) -> eyre::Result<()> {
+    init_datadir(datadir);
+    let store = open_store(datadir);
+    let Some(report) = store.check_consistency(blocks).await? else {
+        warn!("The database is empty, nothing to check");
+        return Ok(());
+    };
+    info!(
+        head = report.head,
+        newest_consistent_block = ?report.newest_consistent_block,
+        newest_state_block = ?report.newest_state_block,
+        "Checked the database"
+    );
+    if report.is_consistent() {
+        info!("No inconsistencies found");
+        return Ok(());
+    }
+    for inconsistency in &report.inconsistencies {
+        warn!("{inconsistency}");
+    }
+    if !report.is_repairable() {
+        return Err(eyre::eyre!(
+            "The database inconsistencies can't be repaired. Run `ethrex removedb` and sync again"
+        ));
+    }
+    if !repair {
+        return Err(eyre::eyre!(
+            "Found database inconsistencies, run `ethrex db check --repair` to repair them"
+        ));
+    }
+    store.repair_consistency(&report).await?;
+    info!("Repaired the database");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_config::{chain_spec::GENESIS_FILE, networks::Network};
+
+    use super::*;
+
+    #[test]
+    fn network_init_writes_a_loadable_chain_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("devnet");
+        let keys_path = dir.path().join("keys.txt");
+
+        init_network(&output, 1337, 3, 5, vec![], Some(&keys_path), false).unwrap();
+
+        // Only the genesis, bootnodes and ENRs are written, so nothing overrides the genesis
+        let mut files: Vec<_> = read_dir(&output)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["bootnodes.json", "enrs.json", GENESIS_FILE]);
+
+        let genesis = Network::ChainSpec(output.clone()).get_genesis().unwrap();
+        let written: Genesis =
+            serde_json::from_slice(&std::fs::read(output.join(GENESIS_FILE)).unwrap()).unwrap();
+        assert_eq!(genesis.config, written.config);
+        assert_eq!(genesis.config.chain_id, 1337);
+
+        let keys = std::fs::read_to_string(&keys_path).unwrap();
+        assert_eq!(keys.lines().count(), 3);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&keys_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        for key in keys.lines() {
+            let secret_key =
+                SecretKey::from_slice(&hex::decode(key.trim_start_matches("0x")).unwrap()).unwrap();
+            let public_key = secret_key
+                .public_key(secp256k1::SECP256K1)
+                .serialize_uncompressed();
+            let address = Address::from_slice(&keccak(&public_key[1..]).as_bytes()[12..]);
+            assert_eq!(
+                genesis.alloc[&address].balance,
+                U256::from(5) * U256::exp10(18)
+            );
+        }
+    }
+
+    #[test]
+    fn network_init_requires_a_private_keys_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("devnet");
+
+        assert!(init_network(&output, 1337, 1, 5, vec![], None, false).is_err());
+        assert!(!output.exists());
+
+        init_network(&output, 1337, 0, 5, vec![], None, false).unwrap();
+        assert!(init_network(&output, 1337, 0, 5, vec![], None, false).is_err());
+        init_network(&output, 1337, 0, 5, vec![], None, true).unwrap();
+    }
+}
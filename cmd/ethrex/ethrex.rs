@@ -1,7 +1,7 @@
 use clap::Parser;
 use ethrex::{
     cli::CLI,
-    initializers::{init_l1, init_tracing},
+    initializers::{init_l1, init_readonly, init_tracing},
     utils::{NodeConfigFile, get_client_version, store_node_config_file},
 };
 use ethrex_p2p::{discv4::peer_table::PeerTable, types::NodeRecord};
@@ -42,6 +42,8 @@ async fn server_shutdown(
     let node_config = NodeConfigFile::new(peer_table, local_node_record).await;
     store_node_config_file(node_config, node_config_path).await;
     tokio::time::sleep(Duration::from_secs(1)).await;
+    #[cfg(feature = "otel")]
+    ethrex_metrics::otel::shutdown_tracer_provider();
     info!("Server shutting down!");
 }
 
@@ -62,6 +64,10 @@ async fn main() -> eyre::Result<()> {
 
     info!("ethrex version: {}", get_client_version());
 
+    if opts.readonly {
+        return init_readonly(opts, Some(log_filter_handler)).await;
+    }
+
     let (datadir, cancel_token, peer_table, local_node_record) =
         init_l1(opts, Some(log_filter_handler)).await?;
 
@@ -5,7 +5,10 @@ use crate::{
         read_jwtsecret_file, read_node_config_file,
     },
 };
-use ethrex_blockchain::{Blockchain, BlockchainOptions, BlockchainType};
+use ethrex_blockchain::{
+    Blockchain, BlockchainOptions, BlockchainType,
+    history_expiry::periodically_prune_expired_history, trace_index::periodically_index_traces,
+};
 use ethrex_common::fd_limit::raise_fd_limit;
 use ethrex_common::types::Genesis;
 use ethrex_config::networks::Network;
@@ -22,7 +25,7 @@ use ethrex_p2p::{
     types::{Node, NodeRecord},
     utils::public_key_from_signing_key,
 };
-use ethrex_storage::{EngineType, Store};
+use ethrex_storage::{CONSISTENCY_CHECK_DEPTH, EngineType, Store};
 use local_ip_address::{local_ip, local_ipv6};
 use rand::rngs::OsRng;
 use secp256k1::SecretKey;
@@ -34,7 +37,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     path::{Path, PathBuf},
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing::{Level, debug, error, info, warn};
@@ -72,12 +75,40 @@ pub fn init_tracing(opts: &Options) -> reload::Handle<EnvFilter, Registry> {
     let profiling_layer = opts.metrics_enabled.then_some(FunctionProfilingLayer);
 
     let subscriber = Registry::default().with(fmt_layer).with(profiling_layer);
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(init_otel_layer(opts));
 
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
+    if let Some(endpoint) = &opts.otel_endpoint {
+        if !cfg!(feature = "otel") {
+            error!("Binary wasn't built with The feature flag `otel` enabled.");
+            panic!(
+                "Build the binary with the `otel` feature in order to use the `--otel.endpoint` cli's argument."
+            );
+        }
+        info!("Exporting tracing spans to {endpoint}");
+    }
+
     filter_handle
 }
 
+/// Builds the layer exporting spans to the OTLP collector, if an endpoint was given
+#[cfg(feature = "otel")]
+pub fn init_otel_layer<S>(opts: &Options) -> Option<impl Layer<S>>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    let endpoint = opts.otel_endpoint.clone()?;
+    let config = ethrex_metrics::otel::OtlpConfig {
+        endpoint,
+        protocol: opts.otel_protocol,
+        service_name: opts.otel_service_name.clone(),
+        propagators: opts.otel_propagators.clone(),
+    };
+    Some(ethrex_metrics::otel::otlp_layer(&config).expect("Failed to start the OTLP exporter"))
+}
+
 pub fn init_metrics(opts: &Options, tracker: TaskTracker) {
     tracing::info!(
         "Starting metrics server on {}:{}",
@@ -98,6 +129,7 @@ pub fn init_metrics(opts: &Options, tracker: TaskTracker) {
 /// Opens a new or pre-existing Store and loads the initial state provided by the network
 pub async fn init_store(datadir: impl AsRef<Path>, genesis: Genesis) -> Store {
     let mut store = open_store(datadir.as_ref());
+    check_store_consistency(&store).await;
     store
         .add_initial_state(genesis)
         .await
@@ -105,6 +137,34 @@ pub async fn init_store(datadir: impl AsRef<Path>, genesis: Genesis) -> Store {
     store
 }
 
+/// Checks the database left by a previous run, rolling the head back to the newest consistent
+/// block if the node was stopped mid-import. Inconsistencies that can't be repaired are reported.
+pub async fn check_store_consistency(store: &Store) {
+    let report = match store.check_consistency(CONSISTENCY_CHECK_DEPTH).await {
+        Ok(Some(report)) => report,
+        Ok(None) => return,
+        Err(err) => {
+            error!("Failed to check the database consistency: {err}");
+            return;
+        }
+    };
+    if report.is_consistent() {
+        return;
+    }
+    for inconsistency in &report.inconsistencies {
+        warn!("Database inconsistency found: {inconsistency}");
+    }
+    if !report.is_repairable() {
+        error!(
+            "The database inconsistencies can't be repaired automatically. Run `ethrex db check` for details, or `ethrex removedb` and sync again"
+        );
+        return;
+    }
+    if let Err(err) = store.repair_consistency(&report).await {
+        error!("Failed to repair the database: {err}");
+    }
+}
+
 /// Initializes a pre-existing Store
 pub async fn load_store(datadir: &Path) -> Store {
     let store = open_store(datadir);
@@ -185,6 +245,7 @@ pub async fn init_rpc_api(
         log_filter_handler,
         opts.gas_limit,
         opts.extra_data.clone(),
+        cfg!(feature = "dev") && opts.dev,
     );
 
     tracker.spawn(rpc_api);
@@ -220,34 +281,12 @@ pub async fn init_network(
 }
 
 #[cfg(feature = "dev")]
-pub async fn init_dev_network(opts: &Options, store: &Store, tracker: TaskTracker) {
+pub fn init_dev_network() {
     info!("Running in DEV_MODE");
-
-    let head_block_hash = {
-        let current_block_number = store.get_latest_block_number().await.unwrap();
-        store
-            .get_canonical_block_hash(current_block_number)
-            .await
-            .unwrap()
-            .unwrap()
-    };
-
-    let max_tries = 3;
-
-    let url = format!(
-        "http://{authrpc_socket_addr}",
-        authrpc_socket_addr = get_authrpc_socket_addr(opts)
+    info!(
+        "Mining a block every {}ms, use `evm_setIntervalMining` and `evm_setAutomine` to change it",
+        ethrex_rpc::dev::DEFAULT_MINING_INTERVAL.as_millis()
     );
-
-    let block_producer_engine = ethrex_dev::block_producer::start_block_producer(
-        url,
-        read_jwtsecret_file(&opts.authrpc_jwtsecret),
-        head_block_hash,
-        max_tries,
-        1000,
-        ethrex_common::Address::default(),
-    );
-    tracker.spawn(block_producer_engine);
 }
 
 pub fn get_network(opts: &Options) -> Network {
@@ -262,7 +301,10 @@ pub fn get_network(opts: &Options) -> Network {
 pub fn get_bootnodes(opts: &Options, network: &Network, datadir: &Path) -> Vec<Node> {
     let mut bootnodes: Vec<Node> = opts.bootnodes.clone();
 
-    bootnodes.extend(network.get_bootnodes());
+    match network.get_bootnodes() {
+        Ok(network_bootnodes) => bootnodes.extend(network_bootnodes),
+        Err(e) => warn!("Could not read the network's bootnodes: {e}"),
+    };
 
     debug!("Loading known peers from config");
 
@@ -419,6 +461,8 @@ pub async fn init_l1(
 
     regenerate_head_state(&store, &blockchain).await?;
 
+    let merge_block = network.get_merge_block()?;
+
     let signer = get_signer(datadir);
 
     let local_p2p_node = get_local_p2p_node(&opts, &signer);
@@ -467,9 +511,19 @@ pub async fn init_l1(
         init_metrics(&opts, tracker.clone());
     }
 
+    tracker.spawn(periodically_prune_expired_history(
+        store.clone(),
+        opts.history_expiry,
+        merge_block,
+    ));
+
+    if opts.trace_index {
+        tracker.spawn(periodically_index_traces(blockchain.clone()));
+    }
+
     if opts.dev {
         #[cfg(feature = "dev")]
-        init_dev_network(&opts, &store, tracker.clone()).await;
+        init_dev_network();
     } else if !opts.p2p_disabled {
         init_network(
             &opts,
@@ -493,6 +547,69 @@ pub async fn init_l1(
     ))
 }
 
+/// How often a read-only node catches up with the writes of the node owning the database
+const READONLY_CATCH_UP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starts an RPC-only node that serves the database of another node running on the same
+/// datadir, without writing to it. Returns once the RPC servers shut down.
+pub async fn init_readonly(
+    opts: Options,
+    log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
+) -> eyre::Result<()> {
+    let datadir = &opts.datadir;
+    // RocksDB secondary instances keep their own logs and metadata apart from the database
+    let secondary_path =
+        std::env::temp_dir().join(format!("ethrex-readonly-{}", std::process::id()));
+
+    raise_fd_limit()?;
+
+    info!(?datadir, "Starting read-only node");
+    let store = Store::new_secondary(datadir, &secondary_path).await?;
+
+    let blockchain = init_blockchain(
+        store.clone(),
+        BlockchainOptions {
+            max_mempool_size: opts.mempool_max_size,
+            perf_logs_enabled: false,
+            r#type: BlockchainType::L1,
+        },
+    );
+
+    let signer = get_signer(datadir);
+    let local_p2p_node = get_local_p2p_node(&opts, &signer);
+    let local_node_record = get_local_node_record(datadir, &local_p2p_node, &signer);
+
+    let catch_up_store = store.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(READONLY_CATCH_UP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = catch_up_store.catch_up_with_primary().await {
+                warn!("Failed to catch up with the node owning the database: {err}");
+            }
+        }
+    });
+
+    let ws_socket_opts = opts.ws_enabled.then(|| get_ws_socket_addr(&opts));
+    let result = ethrex_rpc::start_readonly_api(
+        get_http_socket_addr(&opts),
+        ws_socket_opts,
+        store,
+        blockchain,
+        local_p2p_node,
+        local_node_record,
+        get_client_version(),
+        log_filter_handler,
+        opts.gas_limit,
+    )
+    .await;
+
+    if let Err(err) = fs::remove_dir_all(&secondary_path) {
+        warn!("Could not remove {secondary_path:?}: {err}");
+    }
+    result.map_err(|err| eyre::eyre!("Read-only RPC API failed: {err}"))
+}
+
 /// Regenerates the state up to the head block by re-applying blocks from the
 /// last known state root.
 ///
@@ -1,6 +1,6 @@
 use crate::{
     cli::{DB_ETHREX_DEV_L1, DB_ETHREX_DEV_L2, remove_db},
-    initializers::{init_l1, init_store, init_tracing},
+    initializers::{init_l1, init_store, init_tracing, load_store},
     l2::{
         self,
         deployer::{DeployerOptions, deploy_l1_contracts},
@@ -19,7 +19,10 @@ use ethrex_common::{
 };
 use ethrex_common::{types::BlobsBundle, utils::keccak};
 use ethrex_config::networks::Network;
-use ethrex_l2::utils::state_reconstruct::get_batch;
+use ethrex_l2::utils::{
+    snapshot::{expected_state_root, export_snapshot, import_snapshot, read_snapshot_metadata},
+    state_reconstruct::get_batch,
+};
 use ethrex_l2_common::calldata::Value;
 use ethrex_l2_sdk::call_contract;
 use ethrex_rlp::decode::RLPDecode as _;
@@ -270,6 +273,75 @@ pub enum Command {
         #[command(flatten)]
         options: DeployerOptions,
     },
+    #[command(about = "Export or import a state snapshot taken at a sealed batch.")]
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotCommand {
+    #[command(about = "Export the state (accounts, storage and code) at a sealed batch.")]
+    Export {
+        #[arg(
+            long = "datadir",
+            value_name = "DATABASE_DIRECTORY",
+            default_value = default_datadir().into_os_string(),
+            help = "Receives the name of the directory where the Database is located.",
+            env = "ETHREX_DATADIR"
+        )]
+        datadir: PathBuf,
+        #[arg(
+            long,
+            help = "Number of the sealed batch to export. Defaults to the latest sealed batch."
+        )]
+        batch: Option<u64>,
+        #[arg(short = 'o', long, help = "The directory to write the snapshot to.")]
+        output: PathBuf,
+    },
+    #[command(about = "Bootstrap a node's database from a state snapshot.")]
+    Import {
+        #[arg(
+            long = "datadir",
+            value_name = "DATABASE_DIRECTORY",
+            default_value = default_datadir().into_os_string(),
+            help = "Receives the name of the directory where the Database is located.",
+            env = "ETHREX_DATADIR"
+        )]
+        datadir: PathBuf,
+        #[arg(
+            long = "network",
+            value_name = "GENESIS_FILE_PATH",
+            help = "Receives a `Genesis` struct in json format.",
+            env = "ETHREX_NETWORK",
+            value_parser = clap::value_parser!(Network),
+        )]
+        network: Network,
+        #[arg(short = 's', long, help = "The directory to read the snapshot from.")]
+        snapshot_dir: PathBuf,
+        #[arg(
+            long,
+            env = "ETHREX_ETH_RPC_URL",
+            help = "URL of the L1 RPC. If set, the snapshot state root is checked against the one committed on L1.",
+            requires = "on_chain_proposer_address"
+        )]
+        l1_rpc_url: Option<Url>,
+        #[arg(
+            long,
+            env = "ETHREX_COMMITTER_ON_CHAIN_PROPOSER_ADDRESS",
+            help = "The address of the OnChainProposer contract.",
+            requires = "l1_rpc_url"
+        )]
+        on_chain_proposer_address: Option<Address>,
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "l1_rpc_url",
+            help = "Accept the state root of the snapshot's own batch when it can't be checked against L1 or the local rollup store."
+        )]
+        trust_snapshot: bool,
+    },
 }
 
 impl Command {
@@ -619,6 +691,58 @@ impl Command {
             Command::Deploy { options } => {
                 deploy_l1_contracts(options).await?;
             }
+            Command::Snapshot { command } => command.run().await?,
+        }
+        Ok(())
+    }
+}
+
+impl SnapshotCommand {
+    async fn run(self) -> eyre::Result<()> {
+        match self {
+            SnapshotCommand::Export {
+                datadir,
+                batch,
+                output,
+            } => {
+                let store = load_store(&datadir).await;
+                let rollup_store =
+                    l2::initializers::init_rollup_store(&datadir.join("rollup_store")).await;
+                let metadata = export_snapshot(&store, &rollup_store, batch, &output).await?;
+                info!(
+                    "Snapshot of batch {} written to {}",
+                    metadata.batch.number,
+                    output.display()
+                );
+            }
+            SnapshotCommand::Import {
+                datadir,
+                network,
+                snapshot_dir,
+                l1_rpc_url,
+                on_chain_proposer_address,
+                trust_snapshot,
+            } => {
+                init_datadir(&datadir);
+                let genesis = network.get_genesis()?;
+                let store = init_store(&datadir, genesis).await;
+                let rollup_store =
+                    l2::initializers::init_rollup_store(&datadir.join("rollup_store")).await;
+
+                let metadata = read_snapshot_metadata(&snapshot_dir)?;
+                let client = l1_rpc_url.map(EthClient::new).transpose()?;
+                let l1 = client.as_ref().zip(on_chain_proposer_address);
+                let expected_state_root =
+                    expected_state_root(&metadata, &rollup_store, l1, trust_snapshot).await?;
+
+                let metadata =
+                    import_snapshot(&store, &rollup_store, &snapshot_dir, expected_state_root)
+                        .await?;
+                info!(
+                    "Node bootstrapped at batch {} (block {})",
+                    metadata.batch.number, metadata.batch.last_block
+                );
+            }
         }
         Ok(())
     }
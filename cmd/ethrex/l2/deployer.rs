@@ -310,6 +310,30 @@ pub struct DeployerOptions {
         help = "Path to the Risc0 image id / verification key. This is used for proof verification."
     )]
     pub risc0_vk_path: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "ETHREX_SP1_AGGREGATION_VERIFICATION_KEY_PATH",
+        help_heading = "Deployer options",
+        help = "Path to the SP1 aggregation program verification key. This is used for aggregated proof verification."
+    )]
+    pub sp1_aggregation_vk_path: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "ETHREX_SP1_PROGRAM_VK_DIGEST_PATH",
+        help_heading = "Deployer options",
+        help = "Path to the 32 bytes hash of the SP1 verification key, as committed by the SP1 aggregation program. This is used for aggregated proof verification."
+    )]
+    pub sp1_program_vk_digest_path: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "ETHREX_RISC0_AGGREGATION_VERIFICATION_KEY_PATH",
+        help_heading = "Deployer options",
+        help = "Path to the Risc0 aggregation program image id. This is used for aggregated proof verification."
+    )]
+    pub risc0_aggregation_vk_path: Option<String>,
     #[arg(
         long,
         default_value = "false",
@@ -428,6 +452,9 @@ impl Default for DeployerOptions {
             on_chain_proposer_owner_pk: None,
             sp1_vk_path: None,
             risc0_vk_path: None,
+            sp1_aggregation_vk_path: None,
+            sp1_program_vk_digest_path: None,
+            risc0_aggregation_vk_path: None,
             deploy_based_contracts: false,
             sequencer_registry_owner: None,
             inclusion_max_wait: 3000,
@@ -517,6 +544,8 @@ const INITIALIZE_ON_CHAIN_PROPOSER_SIGNATURE_BASED: &str = "initialize(bool,addr
 const INITIALIZE_ON_CHAIN_PROPOSER_SIGNATURE: &str = "initialize(bool,address,bool,bool,bool,bool,address,address,address,address,bytes32,bytes32,bytes32,address[],uint256)";
 
 const INITIALIZE_BRIDGE_ADDRESS_SIGNATURE: &str = "initializeBridgeAddress(address)";
+const UPGRADE_AGGREGATION_VERIFICATION_KEYS_SIGNATURE: &str =
+    "upgradeAggregationVerificationKeys(bytes32,bytes32,bytes32)";
 const TRANSFER_OWNERSHIP_SIGNATURE: &str = "transferOwnership(address)";
 const ACCEPT_OWNERSHIP_SIGNATURE: &str = "acceptOwnership()";
 const BRIDGE_INITIALIZER_SIGNATURE: &str = "initialize(address,address,uint256)";
@@ -896,6 +925,53 @@ fn get_vk(prover_type: ProverType, opts: &DeployerOptions) -> Result<Bytes, Depl
     }
 }
 
+/// Reads the keys used to verify aggregated proofs: the SP1 aggregation program key, the hash
+/// of the SP1 program key committed by it, and the Risc0 aggregation program image id.
+/// Keys of disabled provers are left empty.
+fn get_aggregation_vks(opts: &DeployerOptions) -> Result<[Bytes; 3], DeployerError> {
+    Ok([
+        read_guest_program_vk(
+            opts.sp1,
+            &opts.sp1_aggregation_vk_path,
+            "sp1_aggregation/out/riscv32im-succinct-zkvm-vk-bn254",
+        )?,
+        read_guest_program_vk(
+            opts.sp1,
+            &opts.sp1_program_vk_digest_path,
+            "sp1/out/riscv32im-succinct-zkvm-vk-u32",
+        )?,
+        read_guest_program_vk(
+            opts.risc0,
+            &opts.risc0_aggregation_vk_path,
+            "risc0_aggregation/out/riscv32im-risc0-vk",
+        )?,
+    ])
+}
+
+/// Reads the key in `path`, or the one built in the local repo at `local_path`, relative to the
+/// guest program sources
+fn read_guest_program_vk(
+    required: bool,
+    path: &Option<String>,
+    local_path: &str,
+) -> Result<Bytes, DeployerError> {
+    if !required {
+        return Ok(Bytes::new());
+    }
+    if let Some(path) = path {
+        return read_vk(path);
+    }
+    info!(local_path, "Using vk from local repo");
+    let path = std::fs::canonicalize(format!(
+        "{}/../../crates/l2/prover/src/guest_program/src/{local_path}",
+        env!("CARGO_MANIFEST_DIR")
+    ))?;
+    read_vk(
+        path.to_str()
+            .ok_or(DeployerError::FailedToGetStringFromPath)?,
+    )
+}
+
 fn read_vk(path: &str) -> Result<Bytes, DeployerError> {
     let string = std::fs::read_to_string(path)?;
     let trimmed = string.trim_start_matches("0x").trim();
@@ -1112,6 +1188,49 @@ async fn initialize_contracts(
 
     tx_hashes.push(initialize_bridge_address_tx_hash);
 
+    // Aggregated proofs are only verified by the OnChainProposer without Based config, and not
+    // when verifying through Aligned
+    if !opts.deploy_based_contracts && !opts.aligned && (opts.sp1 || opts.risc0) {
+        let [
+            sp1_aggregation_vk,
+            sp1_program_vk_digest,
+            risc0_aggregation_vk,
+        ] = get_aggregation_vks(opts)?;
+        let initializer_nonce = eth_client
+            .get_nonce(
+                initializer.address(),
+                BlockIdentifier::Tag(BlockTag::Pending),
+            )
+            .await?;
+        let upgrade_aggregation_vks_calldata = encode_calldata(
+            UPGRADE_AGGREGATION_VERIFICATION_KEYS_SIGNATURE,
+            &[
+                Value::FixedBytes(sp1_aggregation_vk),
+                Value::FixedBytes(sp1_program_vk_digest),
+                Value::FixedBytes(risc0_aggregation_vk),
+            ],
+        )?;
+        let upgrade_aggregation_vks_tx_hash = initialize_contract_no_wait(
+            contract_addresses.on_chain_proposer_address,
+            upgrade_aggregation_vks_calldata,
+            initializer,
+            eth_client,
+            Overrides {
+                nonce: Some(initializer_nonce),
+                gas_limit: Some(TRANSACTION_GAS_LIMIT),
+                max_fee_per_gas: Some(gas_price),
+                max_priority_fee_per_gas: Some(gas_price),
+                ..Default::default()
+            },
+        )
+        .await?;
+        info!(
+            tx_hash = %format!("{upgrade_aggregation_vks_tx_hash:#x}"),
+            "OnChainProposer aggregation verification keys set"
+        );
+        tx_hashes.push(upgrade_aggregation_vks_tx_hash);
+    }
+
     if opts.on_chain_proposer_owner != initializer.address() {
         let initializer_nonce = eth_client
             .get_nonce(
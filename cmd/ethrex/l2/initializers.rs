@@ -1,7 +1,7 @@
 use crate::cli::Options as L1Options;
 use crate::initializers::{
     self, get_authrpc_socket_addr, get_http_socket_addr, get_local_node_record, get_local_p2p_node,
-    get_network, get_signer, init_blockchain, init_network, init_store,
+    get_network, get_signer, get_ws_socket_addr, init_blockchain, init_network, init_store,
 };
 use crate::l2::{L2Options, SequencerOptions};
 use crate::utils::{
@@ -14,6 +14,7 @@ use ethrex_common::{Address, types::DEFAULT_BUILDER_GAS_CEIL};
 use ethrex_l2::sequencer::block_producer;
 use ethrex_l2::sequencer::l1_committer;
 use ethrex_l2::sequencer::l1_committer::regenerate_head_state;
+use ethrex_l2_rpc::preconfirmations::Preconfirmations;
 use ethrex_p2p::{
     discv4::peer_table::PeerTable,
     network::P2PContext,
@@ -49,11 +50,19 @@ async fn init_rpc_api(
     rollup_store: StoreRollup,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     gas_ceil: Option<u64>,
+    preconfirmations: Preconfirmations,
 ) {
     init_datadir(&opts.datadir);
 
+    let ws_socket_opts = if opts.ws_enabled {
+        Some(get_ws_socket_addr(opts))
+    } else {
+        None
+    };
+
     let rpc_api = ethrex_l2_rpc::start_api(
         get_http_socket_addr(opts),
+        ws_socket_opts,
         get_authrpc_socket_addr(opts),
         store,
         blockchain,
@@ -68,6 +77,7 @@ async fn init_rpc_api(
         rollup_store,
         log_filter_handler,
         gas_ceil.unwrap_or(DEFAULT_BUILDER_GAS_CEIL),
+        preconfirmations,
     );
 
     tracker.spawn(rpc_api);
@@ -96,8 +106,12 @@ pub async fn init_rollup_store(datadir: &Path) -> StoreRollup {
     let engine_type = EngineTypeRollup::SQL;
     #[cfg(not(feature = "l2-sql"))]
     let engine_type = EngineTypeRollup::InMemory;
+    open_rollup_store(datadir, engine_type).await
+}
+
+async fn open_rollup_store(location: &Path, engine_type: EngineTypeRollup) -> StoreRollup {
     let rollup_store =
-        StoreRollup::new(datadir, engine_type).expect("Failed to create StoreRollup");
+        StoreRollup::new(location, engine_type).expect("Failed to create StoreRollup");
     rollup_store
         .init()
         .await
@@ -125,6 +139,8 @@ pub fn init_tracing(opts: &L2Options) -> Option<reload::Handle<EnvFilter, Regist
         let subscriber = tracing_subscriber::registry()
             .with(TuiTracingSubscriberLayer)
             .with(level_filter);
+        #[cfg(feature = "otel")]
+        let subscriber = subscriber.with(initializers::init_otel_layer(&opts.node_opts));
         tracing::subscriber::set_global_default(subscriber)
             .expect("setting default subscriber failed");
         tui_logger::init_logger(LevelFilter::max()).expect("Failed to initialize tui_logger");
@@ -162,6 +178,7 @@ pub async fn init_l2(
     opts: L2Options,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
 ) -> eyre::Result<()> {
+    opts.validate_high_availability()?;
     raise_fd_limit()?;
     let datadir = opts.node_opts.datadir.clone();
     init_datadir(&opts.node_opts.datadir);
@@ -175,6 +192,14 @@ pub async fn init_l2(
 
     let genesis = network.get_genesis()?;
     let store = init_store(&datadir, genesis.clone()).await;
+    #[cfg(feature = "l2-postgres")]
+    let rollup_store = match &opts.rollup_store_url {
+        Some(url) => {
+            open_rollup_store(&rollup_store_dir, EngineTypeRollup::Postgres(url.clone())).await
+        }
+        None => init_rollup_store(&rollup_store_dir).await,
+    };
+    #[cfg(not(feature = "l2-postgres"))]
     let rollup_store = init_rollup_store(&rollup_store_dir).await;
 
     let operator_fee_config = get_operator_fee_config(&opts.sequencer_opts).await?;
@@ -217,9 +242,10 @@ pub async fn init_l2(
 
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
-    let based = opts.sequencer_opts.based;
+    // Based sequencers and active/standby sequencers share blocks over P2P
+    let p2p_enabled = opts.sequencer_opts.based || opts.sequencer_opts.ha_opts.ha;
 
-    let (peer_handler, syncer) = if based {
+    let (peer_handler, syncer) = if p2p_enabled {
         let peer_table = PeerTable::spawn(opts.node_opts.target_peers);
         let p2p_context = P2PContext::new(
             local_p2p_node.clone(),
@@ -283,6 +309,9 @@ pub async fn init_l2(
         (None, None)
     };
 
+    // Shared between the block producer, which issues the preconfirmations, and the RPC
+    let preconfirmations = Preconfirmations::new();
+
     init_rpc_api(
         &opts.node_opts,
         &opts,
@@ -296,6 +325,7 @@ pub async fn init_l2(
         rollup_store.clone(),
         log_filter_handler,
         Some(opts.sequencer_opts.block_producer_opts.block_gas_limit),
+        preconfirmations.clone(),
     )
     .await;
 
@@ -319,6 +349,7 @@ pub async fn init_l2(
         l2_url,
         genesis,
         checkpoints_dir,
+        preconfirmations,
     )
     .await?;
     join_set.spawn(l2_sequencer);
@@ -339,12 +370,14 @@ pub async fn init_l2(
     let node_config_path = datadir.join("node_config.json");
     info!(path = %node_config_path.display(), "Storing node config");
     cancel_token.cancel();
-    if based {
+    if p2p_enabled {
         let peer_handler = peer_handler.ok_or_eyre("Peer handler not initialized")?;
         let node_config = NodeConfigFile::new(peer_handler.peer_table, local_node_record).await;
         store_node_config_file(node_config, node_config_path).await;
     }
     tokio::time::sleep(Duration::from_secs(1)).await;
+    #[cfg(feature = "otel")]
+    ethrex_metrics::otel::shutdown_tracer_provider();
     info!("Server shutting down!");
     Ok(())
 }
@@ -2,6 +2,7 @@ pub mod cli;
 pub mod initializers;
 #[cfg(feature = "l2")]
 pub mod l2;
+pub mod replay;
 pub mod utils;
 
 mod decode;
@@ -0,0 +1,372 @@
+use std::{fs::File, path::Path, time::Instant};
+
+use clap::ValueEnum;
+use ethrex_blockchain::{validate_block, validate_requests_hash};
+use ethrex_common::types::{
+    Block, ChainConfig, ELASTICITY_MULTIPLIER, block_execution_witness::ExecutionWitness,
+    block_execution_witness::GuestProgramState, compute_receipts_root,
+};
+use ethrex_rpc::{
+    debug::execution_witness::{RpcExecutionWitness, execution_witness_from_rpc_chain_config},
+    types::block::RpcBlock,
+};
+use ethrex_vm::{Evm, GuestProgramStateWrapper};
+use eyre::{OptionExt, eyre};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::decode;
+
+#[derive(Default, Debug, Clone, Copy, ValueEnum)]
+pub enum ReplayBackend {
+    /// Re-executes the blocks natively, reporting every mismatch found.
+    #[default]
+    Exec,
+    /// Runs the L1 guest program in the SP1 zkVM in execute-only mode.
+    #[cfg(all(feature = "sp1", not(feature = "l2")))]
+    #[value(name = "sp1")]
+    SP1,
+    /// Runs the L1 guest program in the RISC0 zkVM in execute-only mode.
+    #[cfg(all(feature = "risc0", not(feature = "l2")))]
+    #[value(name = "risc0")]
+    RISC0,
+}
+
+/// Re-executes the blocks in `block_path` on top of the state provided by the execution
+/// witness in `witness_path`, without needing access to a database or to the network.
+pub fn replay(
+    block_path: &Path,
+    witness_path: &Path,
+    chain_config: ChainConfig,
+    backend: ReplayBackend,
+) -> eyre::Result<()> {
+    let blocks = read_blocks(block_path)?;
+    let first_block_number = blocks
+        .first()
+        .ok_or_eyre("Block file doesn't contain any block")?
+        .header
+        .number;
+    let rpc_witness: RpcExecutionWitness = read_json(witness_path)?;
+    let witness =
+        execution_witness_from_rpc_chain_config(rpc_witness, chain_config, first_block_number)
+            .map_err(|err| eyre!("Failed to build execution witness: {err}"))?;
+
+    info!(
+        first_block = first_block_number,
+        blocks = blocks.len(),
+        ?backend,
+        "Replaying blocks"
+    );
+
+    match backend {
+        ReplayBackend::Exec => replay_exec(blocks, witness),
+        #[cfg(all(feature = "sp1", not(feature = "l2")))]
+        ReplayBackend::SP1 => {
+            replay_zkvm(ethrex_prover_lib::backend::Backend::SP1, blocks, witness)
+        }
+        #[cfg(all(feature = "risc0", not(feature = "l2")))]
+        ReplayBackend::RISC0 => {
+            replay_zkvm(ethrex_prover_lib::backend::Backend::RISC0, blocks, witness)
+        }
+    }
+}
+
+fn replay_exec(blocks: Vec<Block>, witness: ExecutionWitness) -> eyre::Result<()> {
+    let chain_config = witness.chain_config;
+    let witness_first_block = witness.first_block_number;
+    let guest_program_state = GuestProgramState::try_from(witness)
+        .map_err(|err| eyre!("Invalid execution witness: {err}"))?;
+    let db = GuestProgramStateWrapper::new(guest_program_state);
+
+    db.initialize_block_header_hashes(&blocks)?;
+    if let Some(invalid_block) = db.get_first_invalid_block_hash()? {
+        return Err(eyre!(
+            "Witness header of block {invalid_block} is not the parent of its successor"
+        ));
+    }
+    let mut parent_header = db.get_block_parent_header(witness_first_block)?;
+    let initial_state_root = db.state_trie_root()?;
+    if initial_state_root != parent_header.state_root {
+        return Err(eyre!(
+            "Witness state root {initial_state_root:#x} doesn't match the state root of the parent block {:#x}",
+            parent_header.state_root
+        ));
+    }
+
+    let mut mismatches = 0;
+    for block in &blocks {
+        let number = block.header.number;
+        let start = Instant::now();
+
+        if let Err(err) =
+            validate_block(block, &parent_header, &chain_config, ELASTICITY_MULTIPLIER)
+        {
+            warn!(block = number, "Block validation failed: {err}");
+            mismatches += 1;
+        }
+
+        let mut vm = Evm::new_for_l1(db.clone());
+        let result = vm.execute_block(block)?;
+        let account_updates = vm.get_state_transitions()?;
+        db.apply_account_updates(&account_updates)?;
+        let elapsed = start.elapsed();
+
+        let gas_used = result
+            .receipts
+            .last()
+            .map(|receipt| receipt.cumulative_gas_used)
+            .unwrap_or_default();
+        let receipts_root = compute_receipts_root(&result.receipts);
+        let state_root = db.state_trie_root()?;
+
+        mismatches += report_mismatch(number, "gas used", gas_used, block.header.gas_used);
+        mismatches += report_mismatch(
+            number,
+            "receipts root",
+            receipts_root,
+            block.header.receipts_root,
+        );
+        mismatches += report_mismatch(number, "state root", state_root, block.header.state_root);
+        if let Err(err) = validate_requests_hash(&block.header, &chain_config, &result.requests) {
+            warn!(block = number, "Requests hash mismatch: {err}");
+            mismatches += 1;
+        }
+
+        info!(
+            block = number,
+            hash = %format!("{:#x}", block.hash()),
+            txs = block.body.transactions.len(),
+            gas_used,
+            state_root = %format!("{state_root:#x}"),
+            "Replayed block in {elapsed:.2?}"
+        );
+
+        parent_header = block.header.clone();
+    }
+
+    if mismatches > 0 {
+        return Err(eyre!(
+            "Replay finished with {mismatches} mismatches against the block headers"
+        ));
+    }
+    info!(blocks = blocks.len(), "Replay finished, all blocks match");
+    Ok(())
+}
+
+/// Runs the blocks through the guest program, which is built for L1 (and so runs
+/// `stateless_validation_l1`) when ethrex is built without the `l2` feature.
+#[cfg(all(any(feature = "sp1", feature = "risc0"), not(feature = "l2")))]
+fn replay_zkvm(
+    backend: ethrex_prover_lib::backend::Backend,
+    blocks: Vec<Block>,
+    witness: ExecutionWitness,
+) -> eyre::Result<()> {
+    let input = guest_program::input::ProgramInput {
+        blocks,
+        execution_witness: witness,
+        elasticity_multiplier: ELASTICITY_MULTIPLIER,
+        fee_configs: None,
+    };
+    ethrex_prover_lib::execute(backend, input).map_err(|err| eyre!("{err}"))
+}
+
+fn report_mismatch<T: PartialEq + std::fmt::LowerHex>(
+    block: u64,
+    field: &str,
+    computed: T,
+    expected: T,
+) -> usize {
+    if computed == expected {
+        return 0;
+    }
+    warn!(
+        block,
+        computed = %format!("{computed:#x}"),
+        expected = %format!("{expected:#x}"),
+        "{field} mismatch"
+    );
+    1
+}
+
+/// Reads the blocks to replay, either from a file with RLP encoded blocks or from a
+/// JSON file with one or more blocks as returned by `eth_getBlockByNumber` with full
+/// transactions.
+fn read_blocks(path: &Path) -> eyre::Result<Vec<Block>> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let value: Value = read_json(path)?;
+        let rpc_blocks: Vec<RpcBlock> = match value {
+            Value::Array(_) => serde_json::from_value(value)?,
+            _ => vec![serde_json::from_value(value)?],
+        };
+        return rpc_blocks
+            .into_iter()
+            .map(|block| block.try_into().map_err(|err: String| eyre!(err)))
+            .collect();
+    }
+    let blocks = decode::chain_file(File::open(path)?)
+        .map_err(|err| eyre!("Failed to decode RLP blocks: {err}"))?;
+    Ok(blocks)
+}
+
+/// Reads a JSON file, accepting both the bare object and a full JSON-RPC response.
+fn read_json<T: DeserializeOwned>(path: &Path) -> eyre::Result<T> {
+    let mut value: Value = serde_json::from_reader(File::open(path)?)?;
+    if let Some(result) = value.get_mut("result") {
+        value = result.take();
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{BufReader, Write},
+        path::PathBuf,
+    };
+
+    use bytes::Bytes;
+    use ethrex_blockchain::{
+        Blockchain,
+        payload::{BuildPayloadArgs, create_payload},
+    };
+    use ethrex_common::{
+        Address, H256, U256,
+        types::{
+            BlockHeader, EIP1559Transaction, Genesis, GenesisAccount, Transaction, TxKind, TxType,
+        },
+    };
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::{PayloadRLPEncode, RLPEncode};
+    use ethrex_storage::{EngineType, Store};
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn signed_transfer(chain_id: u64, key: &SecretKey) -> Transaction {
+        let mut tx = EIP1559Transaction {
+            chain_id,
+            nonce: 0,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_gas: 100_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::from_low_u64_be(1)),
+            value: U256::one(),
+            ..Default::default()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    /// Builds a block with a transfer on top of the execution-api genesis, and writes it
+    /// and its execution witness to files as `ethrex replay` expects them
+    async fn block_and_witness_files() -> (TempDir, Block, PathBuf, ChainConfig) {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(SECP256K1, &key).serialize_uncompressed();
+        let sender = Address::from_slice(&keccak_hash(&public_key[1..])[12..]);
+
+        let file = File::open("../../fixtures/genesis/execution-api.json").unwrap();
+        let mut genesis: Genesis = serde_json::from_reader(BufReader::new(file)).unwrap();
+        genesis.alloc.insert(
+            sender,
+            GenesisAccount {
+                balance: U256::from(10).pow(U256::from(18)),
+                ..Default::default()
+            },
+        );
+        let mut store = Store::new("replay.db", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        let chain_config = store.get_chain_config();
+        let blockchain = Blockchain::default_with_store(store.clone());
+
+        blockchain
+            .add_transaction_to_pool(signed_transfer(chain_config.chain_id, &key))
+            .await
+            .unwrap();
+        let parent = store.get_block_header(0).unwrap().unwrap();
+        let args = BuildPayloadArgs {
+            parent: parent.hash(),
+            timestamp: parent.timestamp + 12,
+            fee_recipient: Address::zero(),
+            random: H256::zero(),
+            withdrawals: Some(Vec::new()),
+            beacon_root: Some(H256::zero()),
+            version: 3,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: parent.gas_limit,
+        };
+        let payload = create_payload(&args, &store, Bytes::new()).unwrap();
+        let block = blockchain.build_payload(payload).unwrap().payload;
+        assert_eq!(block.body.transactions.len(), 1);
+        blockchain.add_block(block.clone()).unwrap();
+        store
+            .forkchoice_update(None, block.header.number, block.hash(), None, None)
+            .await
+            .unwrap();
+
+        let witness = blockchain
+            .generate_witness_for_blocks(std::slice::from_ref(&block))
+            .await
+            .unwrap();
+        let rpc_witness = RpcExecutionWitness::try_from(witness).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let witness_path = dir.path().join("witness.json");
+        serde_json::to_writer(File::create(&witness_path).unwrap(), &rpc_witness).unwrap();
+        (dir, block, witness_path, chain_config)
+    }
+
+    fn write_block(dir: &TempDir, block: &Block) -> PathBuf {
+        let path = dir.path().join("block.rlp");
+        File::create(&path)
+            .unwrap()
+            .write_all(&block.encode_to_vec())
+            .unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn replays_a_block_from_its_witness() {
+        let (dir, block, witness_path, chain_config) = block_and_witness_files().await;
+        let block_path = write_block(&dir, &block);
+
+        replay(
+            &block_path,
+            &witness_path,
+            chain_config,
+            ReplayBackend::Exec,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_a_state_root_mismatch() {
+        let (dir, block, witness_path, chain_config) = block_and_witness_files().await;
+        let tampered = Block {
+            header: BlockHeader {
+                state_root: H256::repeat_byte(1),
+                hash: Default::default(),
+                ..block.header
+            },
+            body: block.body,
+        };
+        let block_path = write_block(&dir, &tampered);
+
+        let err = replay(
+            &block_path,
+            &witness_path,
+            chain_config,
+            ReplayBackend::Exec,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("mismatches"));
+    }
+}
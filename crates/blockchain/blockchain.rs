@@ -1,18 +1,23 @@
+pub mod bundle;
 pub mod constants;
 pub mod error;
 pub mod fork_choice;
+pub mod history_expiry;
 pub mod mempool;
 pub mod payload;
 mod smoke_test;
+pub mod trace_index;
 pub mod tracing;
 pub mod vm;
 
 use ::tracing::{debug, info, instrument, trace};
+use bundle::{Bundle, BundlePool, MAX_BUNDLE_BLOCKS_AHEAD};
 use constants::{MAX_INITCODE_SIZE, MAX_TRANSACTION_DATA_SIZE, POST_OSAKA_GAS_LIMIT_CAP};
 use error::MempoolError;
 use error::{ChainError, InvalidBlockError};
 use ethrex_common::constants::{
-    EMPTY_TRIE_HASH, GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE, MIN_BASE_FEE_PER_BLOB_GAS,
+    EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH, GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE,
+    MIN_BASE_FEE_PER_BLOB_GAS,
 };
 use ethrex_common::types::block_execution_witness::ExecutionWitness;
 use ethrex_common::types::fee_config::FeeConfig;
@@ -82,6 +87,9 @@ pub struct L2Config {
 pub struct Blockchain {
     storage: Store,
     pub mempool: Mempool,
+    /// Bundles submitted through `eth_sendBundle`, included ahead of the mempool transactions
+    /// when building payloads
+    pub bundle_pool: BundlePool,
     /// Whether the node's chain is in or out of sync with the current chain
     /// This will be set to true once the initial sync has taken place and wont be set to false after
     /// This does not reflect whether there is an ongoing sync process
@@ -139,6 +147,7 @@ impl Blockchain {
         Self {
             storage: store,
             mempool: Mempool::new(blockchain_opts.max_mempool_size),
+            bundle_pool: BundlePool::new(),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
             options: blockchain_opts,
@@ -149,6 +158,7 @@ impl Blockchain {
         Self {
             storage: store,
             mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT),
+            bundle_pool: BundlePool::new(),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
             options: BlockchainOptions::default(),
@@ -679,6 +689,19 @@ impl Blockchain {
         &self,
         blocks: &[Block],
         fee_configs: Option<&[FeeConfig]>,
+    ) -> Result<ExecutionWitness, ChainError> {
+        self.generate_witness_for_blocks_with_final_accounts(blocks, fee_configs, &[])
+            .await
+    }
+
+    /// Same as [`Self::generate_witness_for_blocks_with_fee_configs`], but the witness also
+    /// proves the state of `final_accounts` after executing the last block, even if the
+    /// blocks don't access them.
+    pub async fn generate_witness_for_blocks_with_final_accounts(
+        &self,
+        blocks: &[Block],
+        fee_configs: Option<&[FeeConfig]>,
+        final_accounts: &[Address],
     ) -> Result<ExecutionWitness, ChainError> {
         let first_block_header = &blocks
             .first()
@@ -896,6 +919,37 @@ impl Blockchain {
             current_trie_witness = new_state_trie_witness;
         }
 
+        // Access the accounts required from the final state, recording the nodes of the
+        // last trie used to do so, along with their code
+        if !final_accounts.is_empty() {
+            for address in final_accounts {
+                let encoded_account = trie.get(&hash_address(address)).map_err(|_e| {
+                    ChainError::WitnessGeneration("Failed to access account from trie".to_string())
+                })?;
+                touched_account_storage_slots.entry(*address).or_default();
+                let Some(encoded_account) = encoded_account else {
+                    continue;
+                };
+                let code_hash = AccountState::decode(&encoded_account)?.code_hash;
+                if code_hash != *EMPTY_KECCACK_HASH {
+                    let code = self.storage.get_account_code(code_hash)?.ok_or(
+                        ChainError::WitnessGeneration("Failed to get account code".to_string()),
+                    )?;
+                    codes.push(code.bytecode.to_vec());
+                }
+            }
+            for state_trie_witness in current_trie_witness
+                .lock()
+                .map_err(|_| {
+                    ChainError::WitnessGeneration("Failed to lock state trie witness".to_string())
+                })?
+                .iter()
+            {
+                accumulated_state_trie_witness
+                    .insert(*state_trie_witness.0, state_trie_witness.1.clone());
+            }
+        }
+
         used_trie_nodes.extend_from_slice(&Vec::from_iter(
             accumulated_state_trie_witness.into_values(),
         ));
@@ -1074,6 +1128,17 @@ impl Blockchain {
         result
     }
 
+    #[instrument(
+        level = "info",
+        name = "Add block",
+        skip_all,
+        fields(
+            namespace = "block_execution",
+            block.number = block.header.number,
+            block.hash = ?block.hash(),
+            block.tx_count = block.body.transactions.len(),
+        )
+    )]
     pub fn add_block_pipeline(&self, block: Block) -> Result<(), ChainError> {
         let (res, account_updates_list, merkle_queue_length, instants) =
             self.execute_block_pipeline(&block)?;
@@ -1395,7 +1460,7 @@ impl Blockchain {
 
         // Validate transaction
         if let Some(tx_to_replace) = self.validate_transaction(&transaction, sender).await? {
-            self.remove_transaction_from_pool(&tx_to_replace)?;
+            self.mempool.replace_transaction(&tx_to_replace, hash)?;
         }
 
         // Add transaction and blobs bundle to storage
@@ -1421,7 +1486,7 @@ impl Blockchain {
         let sender = transaction.sender()?;
         // Validate transaction
         if let Some(tx_to_replace) = self.validate_transaction(&transaction, sender).await? {
-            self.remove_transaction_from_pool(&tx_to_replace)?;
+            self.mempool.replace_transaction(&tx_to_replace, hash)?;
         }
 
         // Add transaction to storage
@@ -1431,17 +1496,83 @@ impl Blockchain {
         Ok(hash)
     }
 
+    /// Validates the transactions of a bundle and adds it to the bundle pool, returning its hash.
+    /// Their nonces and balances aren't checked here, as they may depend on the transactions
+    /// before them, but when the bundle is run for inclusion.
+    pub async fn add_bundle_to_pool(
+        &self,
+        transactions: Vec<Transaction>,
+        block_number: BlockNumber,
+        min_timestamp: Option<u64>,
+        max_timestamp: Option<u64>,
+        reverting_tx_hashes: Vec<H256>,
+    ) -> Result<H256, MempoolError> {
+        if transactions.is_empty() {
+            return Err(MempoolError::InvalidBundle(
+                "bundle has no transactions".to_string(),
+            ));
+        }
+        let latest_block_number = self.storage.get_latest_block_number().await?;
+        if block_number <= latest_block_number {
+            return Err(MempoolError::InvalidBundle(format!(
+                "block {block_number} was already added"
+            )));
+        }
+        if block_number > latest_block_number.saturating_add(MAX_BUNDLE_BLOCKS_AHEAD) {
+            return Err(MempoolError::InvalidBundle(format!(
+                "block {block_number} is more than {MAX_BUNDLE_BLOCKS_AHEAD} blocks ahead of the latest one"
+            )));
+        }
+        let chain_id = self.storage.get_chain_config().chain_id;
+        let mut bundle_transactions = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            if matches!(
+                transaction,
+                Transaction::EIP4844Transaction(_) | Transaction::PrivilegedL2Transaction(_)
+            ) {
+                return Err(MempoolError::InvalidBundle(format!(
+                    "unsupported transaction type {}",
+                    transaction.tx_type()
+                )));
+            }
+            if transaction
+                .chain_id()
+                .is_some_and(|tx_chain_id| tx_chain_id != chain_id)
+            {
+                return Err(MempoolError::InvalidChainId(chain_id));
+            }
+            let sender = transaction.sender()?;
+            bundle_transactions.push(MempoolTransaction::new(transaction, sender));
+        }
+
+        self.bundle_pool.remove_stale_bundles(latest_block_number)?;
+        self.bundle_pool.add_bundle(Bundle {
+            transactions: bundle_transactions,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+        })
+    }
+
     /// Remove a transaction from the mempool
     pub fn remove_transaction_from_pool(&self, hash: &H256) -> Result<(), StoreError> {
         self.mempool.remove_transaction(hash)
     }
 
-    /// Remove all transactions in the executed block from the pool (if we have them)
+    /// Remove a transaction that can't be included anymore from the pool, recording why
+    pub fn drop_invalid_transaction_from_pool(
+        &self,
+        hash: &H256,
+        reason: String,
+    ) -> Result<(), StoreError> {
+        self.mempool.drop_invalid_transaction(hash, reason)
+    }
+
+    /// Remove all transactions in the executed block from the pool (if we have them), along with
+    /// the ones their nonces invalidated
     pub fn remove_block_transactions_from_pool(&self, block: &Block) -> Result<(), StoreError> {
-        for tx in &block.body.transactions {
-            self.mempool.remove_transaction(&tx.hash())?;
-        }
-        Ok(())
+        self.mempool.remove_block_transactions(block)
     }
 
     /*
@@ -0,0 +1,218 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockNumber, MempoolTransaction},
+    utils::keccak,
+};
+use ethrex_storage::error::StoreError;
+
+use crate::error::MempoolError;
+
+/// Maximum amount of bundles kept in the pool
+pub const MAX_BUNDLE_POOL_SIZE: usize = 1024;
+
+/// Bundles can only target blocks up to this many blocks after the latest one, so the pool can't
+/// be filled with bundles that would stay in it for long
+pub const MAX_BUNDLE_BLOCKS_AHEAD: u64 = 25;
+
+/// A list of transactions submitted through `eth_sendBundle` to be included in a given block,
+/// in order and ahead of the mempool transactions, either all of them or none
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub transactions: Vec<MempoolTransaction>,
+    /// Number of the only block the bundle can be included in
+    pub block_number: BlockNumber,
+    pub min_timestamp: Option<u64>,
+    pub max_timestamp: Option<u64>,
+    /// Transactions that are allowed to revert without invalidating the bundle
+    pub reverting_tx_hashes: Vec<H256>,
+}
+
+impl Bundle {
+    /// Hash identifying the bundle: the keccak of its concatenated transaction hashes
+    pub fn hash(&self) -> H256 {
+        bundle_hash(self.transactions.iter().map(|tx| tx.hash()))
+    }
+
+    /// Returns true if the bundle can be included in a block with the given number and timestamp
+    pub fn targets(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+        self.block_number == block_number
+            && self.min_timestamp.is_none_or(|min| timestamp >= min)
+            && self.max_timestamp.is_none_or(|max| timestamp <= max)
+    }
+
+    pub fn may_revert(&self, tx_hash: &H256) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+}
+
+pub fn bundle_hash(tx_hashes: impl IntoIterator<Item = H256>) -> H256 {
+    let concatenated: Vec<u8> = tx_hashes
+        .into_iter()
+        .flat_map(|hash| hash.to_fixed_bytes())
+        .collect();
+    keccak(concatenated)
+}
+
+/// Outcome of running a bundle on top of a payload
+#[derive(Debug, Clone, Default)]
+pub struct BundleExecution {
+    pub results: Vec<BundleTxResult>,
+    pub gas_used: u64,
+    /// Priority fees paid by the bundle's transactions
+    pub gas_fees: U256,
+    /// Increase of the fee recipient's balance, including the priority fees and any direct payment
+    pub coinbase_diff: U256,
+}
+
+impl BundleExecution {
+    /// Profit of the fee recipient per unit of gas, used to rank bundles against each other
+    pub fn effective_gas_price(&self) -> U256 {
+        self.coinbase_diff
+            .checked_div(U256::from(self.gas_used))
+            .unwrap_or_default()
+    }
+}
+
+/// Outcome of running one of the transactions of a bundle
+#[derive(Debug, Clone)]
+pub struct BundleTxResult {
+    pub tx_hash: H256,
+    pub sender: Address,
+    pub gas_used: u64,
+    pub gas_fees: U256,
+    pub coinbase_diff: U256,
+    pub succeeded: bool,
+}
+
+/// Pool of the bundles waiting to be included, keyed by their hash
+#[derive(Debug, Default)]
+pub struct BundlePool {
+    bundles: RwLock<HashMap<H256, Bundle>>,
+}
+
+impl BundlePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, HashMap<H256, Bundle>>, StoreError> {
+        self.bundles
+            .write()
+            .map_err(|error| StoreError::MempoolWriteLock(error.to_string()))
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, HashMap<H256, Bundle>>, StoreError> {
+        self.bundles
+            .read()
+            .map_err(|error| StoreError::MempoolReadLock(error.to_string()))
+    }
+
+    /// Adds a bundle to the pool without doing validity checks, returning its hash
+    pub fn add_bundle(&self, bundle: Bundle) -> Result<H256, MempoolError> {
+        let hash = bundle.hash();
+        let mut bundles = self.write()?;
+        if bundles.len() >= MAX_BUNDLE_POOL_SIZE && !bundles.contains_key(&hash) {
+            return Err(MempoolError::BundlePoolFull);
+        }
+        bundles.insert(hash, bundle);
+        Ok(hash)
+    }
+
+    /// Returns the bundles that can be included in a block with the given number and timestamp
+    pub fn get_bundles(
+        &self,
+        block_number: BlockNumber,
+        timestamp: u64,
+    ) -> Result<Vec<Bundle>, StoreError> {
+        Ok(self
+            .read()?
+            .values()
+            .filter(|bundle| bundle.targets(block_number, timestamp))
+            .cloned()
+            .collect())
+    }
+
+    /// Removes the bundles targeting blocks up to `block_number`, which can't be included anymore
+    pub fn remove_stale_bundles(&self, block_number: BlockNumber) -> Result<(), StoreError> {
+        self.write()?
+            .retain(|_, bundle| bundle.block_number > block_number);
+        Ok(())
+    }
+
+    pub fn len(&self) -> Result<usize, StoreError> {
+        Ok(self.read()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool, StoreError> {
+        Ok(self.read()?.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_common::types::{EIP1559Transaction, Transaction};
+
+    use super::*;
+
+    fn bundle(block_number: BlockNumber, nonces: &[u64]) -> Bundle {
+        Bundle {
+            transactions: nonces
+                .iter()
+                .map(|nonce| {
+                    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+                        nonce: *nonce,
+                        ..Default::default()
+                    });
+                    MempoolTransaction::new(tx, Address::zero())
+                })
+                .collect(),
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn bundle_hash_depends_on_transaction_order() {
+        let a = bundle(1, &[0, 1]);
+        let b = bundle(1, &[1, 0]);
+        assert_ne!(a.hash(), b.hash());
+        assert_eq!(a.hash(), bundle(2, &[0, 1]).hash());
+    }
+
+    #[test]
+    fn bundles_are_only_returned_for_their_target() {
+        let pool = BundlePool::new();
+        let mut timed = bundle(10, &[0]);
+        timed.min_timestamp = Some(100);
+        timed.max_timestamp = Some(200);
+        pool.add_bundle(timed).unwrap();
+        pool.add_bundle(bundle(11, &[1])).unwrap();
+
+        assert_eq!(pool.get_bundles(10, 150).unwrap().len(), 1);
+        assert!(pool.get_bundles(10, 99).unwrap().is_empty());
+        assert!(pool.get_bundles(10, 201).unwrap().is_empty());
+        assert_eq!(pool.get_bundles(11, 0).unwrap().len(), 1);
+
+        pool.remove_stale_bundles(10).unwrap();
+        assert_eq!(pool.len().unwrap(), 1);
+        assert!(pool.get_bundles(10, 150).unwrap().is_empty());
+    }
+
+    #[test]
+    fn pool_rejects_bundles_when_full() {
+        let pool = BundlePool::new();
+        for nonce in 0..MAX_BUNDLE_POOL_SIZE as u64 {
+            pool.add_bundle(bundle(1, &[nonce])).unwrap();
+        }
+        // Resubmitting a known bundle is fine
+        pool.add_bundle(bundle(1, &[0])).unwrap();
+        assert!(matches!(
+            pool.add_bundle(bundle(1, &[u64::MAX])),
+            Err(MempoolError::BundlePoolFull)
+        ));
+    }
+}
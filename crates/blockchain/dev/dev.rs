@@ -1 +0,0 @@
-pub mod block_producer;
@@ -142,6 +142,10 @@ pub enum MempoolError {
     InvalidTxSender(#[from] ethrex_common::EcdsaError),
     #[error("Attempted to replace a pooled transaction with an underpriced transaction")]
     UnderpricedReplacement,
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+    #[error("Bundle pool is full")]
+    BundlePoolFull,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,130 @@
+use std::{str::FromStr, time::Duration};
+
+use ethrex_common::types::BlockNumber;
+use ethrex_storage::{Store, error::StoreError};
+use tracing::{error, info, warn};
+
+/// Interval between runs of the history pruner
+const HISTORY_PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How much block history (bodies and receipts) the node keeps, as described by EIP-4444.
+/// Headers are always kept so the chain can still be verified from genesis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryExpiry {
+    /// Keep the whole history since genesis
+    #[default]
+    KeepAll,
+    /// Drop the history before the merge block
+    PostMerge,
+    /// Keep only the history of the given amount of most recent blocks
+    Window(u64),
+}
+
+impl HistoryExpiry {
+    /// Returns the first block whose history must be kept, or None if nothing should be pruned.
+    /// The boundary never goes past `finalized`, so blocks that can still be reorged are kept.
+    pub fn boundary(
+        &self,
+        head: BlockNumber,
+        finalized: BlockNumber,
+        merge_block: Option<BlockNumber>,
+    ) -> Option<BlockNumber> {
+        let boundary = match self {
+            HistoryExpiry::KeepAll => return None,
+            HistoryExpiry::PostMerge => merge_block?,
+            HistoryExpiry::Window(blocks) => head.saturating_sub(*blocks),
+        };
+        Some(boundary.min(finalized))
+    }
+}
+
+impl FromStr for HistoryExpiry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(HistoryExpiry::KeepAll),
+            "postmerge" => Ok(HistoryExpiry::PostMerge),
+            window => window.parse().map(HistoryExpiry::Window).map_err(|_| {
+                format!(
+                    "Invalid history expiry {window}, expected \"all\", \"postmerge\" or an amount of blocks"
+                )
+            }),
+        }
+    }
+}
+
+/// Prunes the history that expired according to `history_expiry` once.
+/// Returns the amount of blocks whose bodies and receipts were removed.
+pub async fn prune_expired_history(
+    store: &Store,
+    history_expiry: HistoryExpiry,
+    merge_block: Option<BlockNumber>,
+) -> Result<u64, StoreError> {
+    let head = store.get_latest_block_number().await?;
+    let finalized = store.get_finalized_block_number().await?.unwrap_or(head);
+    let Some(boundary) = history_expiry.boundary(head, finalized, merge_block) else {
+        return Ok(0);
+    };
+    store.prune_history(boundary).await
+}
+
+/// Periodically prunes the history that expired according to `history_expiry`
+pub async fn periodically_prune_expired_history(
+    store: Store,
+    history_expiry: HistoryExpiry,
+    merge_block: Option<BlockNumber>,
+) {
+    if history_expiry == HistoryExpiry::KeepAll {
+        return;
+    }
+    if history_expiry == HistoryExpiry::PostMerge && merge_block.is_none() {
+        warn!("Unknown merge block for this network, history won't be expired");
+        return;
+    }
+    let mut interval = tokio::time::interval(HISTORY_PRUNING_INTERVAL);
+    loop {
+        interval.tick().await;
+        match prune_expired_history(&store, history_expiry, merge_block).await {
+            Ok(0) => {}
+            Ok(pruned) => info!("Pruned the bodies and receipts of {pruned} expired blocks"),
+            Err(err) => error!("Failed to prune expired history: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_all_never_prunes() {
+        assert_eq!(HistoryExpiry::KeepAll.boundary(1_000, 900, Some(10)), None);
+    }
+
+    #[test]
+    fn post_merge_prunes_up_to_merge_block() {
+        let expiry = HistoryExpiry::PostMerge;
+        assert_eq!(expiry.boundary(1_000, 900, Some(10)), Some(10));
+        // Unknown merge block for this network
+        assert_eq!(expiry.boundary(1_000, 900, None), None);
+        // The merge block isn't finalized yet
+        assert_eq!(expiry.boundary(1_000, 5, Some(10)), Some(5));
+    }
+
+    #[test]
+    fn window_keeps_recent_blocks() {
+        let expiry = HistoryExpiry::Window(100);
+        assert_eq!(expiry.boundary(1_000, 1_000, None), Some(900));
+        assert_eq!(expiry.boundary(1_000, 850, None), Some(850));
+        assert_eq!(expiry.boundary(50, 50, None), Some(0));
+    }
+
+    #[test]
+    fn parse_history_expiry() {
+        assert_eq!("all".parse(), Ok(HistoryExpiry::KeepAll));
+        assert_eq!("postmerge".parse(), Ok(HistoryExpiry::PostMerge));
+        assert_eq!("8192".parse(), Ok(HistoryExpiry::Window(8192)));
+        assert!("forever".parse::<HistoryExpiry>().is_err());
+    }
+}
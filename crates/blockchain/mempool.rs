@@ -1,6 +1,7 @@
 use std::{
-    collections::{BTreeMap, HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque, hash_map::Entry},
     sync::RwLock,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
@@ -13,13 +14,85 @@ use crate::{
 use ethrex_common::{
     Address, H160, H256, U256,
     types::{
-        BlobsBundle, BlockHeader, ChainConfig, Fork::*, MempoolTransaction, Transaction, TxType,
+        BlobsBundle, Block, BlockHash, BlockHeader, BlockNumber, ChainConfig, Fork::*,
+        MempoolTransaction, Transaction, TxType,
     },
 };
 use ethrex_storage::error::StoreError;
 use std::collections::HashSet;
 use tracing::warn;
 
+/// Maximum number of transactions whose lifecycle is remembered, the oldest ones are forgotten first
+pub const MAX_TX_LIFECYCLE_ENTRIES: usize = 65536;
+/// Maximum number of events remembered for a single transaction
+const MAX_TX_LIFECYCLE_EVENTS: usize = 16;
+
+/// Something that happened to a transaction known by the mempool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxLifecycleEvent {
+    /// Added to the mempool
+    Received,
+    /// Removed in favour of a transaction with the same sender and nonce paying higher fees
+    ReplacedBy(H256),
+    /// Removed to make room for newer transactions when the mempool was full
+    Evicted,
+    IncludedIn {
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+    },
+    /// Removed because it can't be included anymore, e.g. its nonce was used by another transaction
+    DroppedInvalid(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxLifecycleRecord {
+    pub event: TxLifecycleEvent,
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+}
+
+/// Bounded log of the events of the transactions that went through the mempool
+#[derive(Debug, Default)]
+struct TxLifecycleLog {
+    records: HashMap<H256, Vec<TxLifecycleRecord>>,
+    order: VecDeque<H256>,
+}
+
+impl TxLifecycleLog {
+    fn record(&mut self, hash: H256, event: TxLifecycleEvent) {
+        let record = TxLifecycleRecord {
+            event,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or_default(),
+        };
+        match self.records.entry(hash) {
+            Entry::Occupied(mut entry) => {
+                let records = entry.get_mut();
+                if records.len() >= MAX_TX_LIFECYCLE_EVENTS {
+                    records.remove(0);
+                }
+                records.push(record);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(vec![record]);
+                self.order.push_back(hash);
+            }
+        }
+        while self.records.len() > MAX_TX_LIFECYCLE_ENTRIES {
+            let Some(oldest_hash) = self.order.pop_front() else {
+                break;
+            };
+            self.records.remove(&oldest_hash);
+        }
+    }
+
+    fn contains(&self, hash: &H256) -> bool {
+        self.records.contains_key(hash)
+    }
+}
+
 #[derive(Debug, Default)]
 struct MempoolInner {
     broadcast_pool: HashSet<H256>,
@@ -30,6 +103,7 @@ struct MempoolInner {
     max_mempool_size: usize,
     // Max number of transactions to let the mempool order queue grow before pruning it
     mempool_prune_threshold: usize,
+    lifecycle: TxLifecycleLog,
 }
 
 impl MempoolInner {
@@ -63,6 +137,10 @@ impl MempoolInner {
         // Remove elements from the order queue until one is present in the pool
         while self.transaction_pool.len() >= self.max_mempool_size {
             if let Some(oldest_hash) = self.txs_order.pop_front() {
+                if self.transaction_pool.contains_key(&oldest_hash) {
+                    self.lifecycle
+                        .record(oldest_hash, TxLifecycleEvent::Evicted);
+                }
                 self.remove_transaction_with_lock(&oldest_hash)?;
             } else {
                 warn!(
@@ -123,6 +201,7 @@ impl Mempool {
             .insert((transaction.sender(), transaction.nonce()), hash);
         inner.transaction_pool.insert(hash, transaction);
         inner.broadcast_pool.insert(hash);
+        inner.lifecycle.record(hash, TxLifecycleEvent::Received);
 
         Ok(())
     }
@@ -172,6 +251,106 @@ impl Mempool {
         Ok(())
     }
 
+    /// Remove a transaction from the pool in favour of `new_hash`
+    pub fn replace_transaction(&self, hash: &H256, new_hash: H256) -> Result<(), StoreError> {
+        let mut inner = self.write()?;
+        if inner.transaction_pool.contains_key(hash) {
+            inner
+                .lifecycle
+                .record(*hash, TxLifecycleEvent::ReplacedBy(new_hash));
+        }
+        inner.remove_transaction_with_lock(hash)
+    }
+
+    /// Remove a transaction that can't be included anymore from the pool
+    pub fn drop_invalid_transaction(&self, hash: &H256, reason: String) -> Result<(), StoreError> {
+        let mut inner = self.write()?;
+        if inner.transaction_pool.contains_key(hash) {
+            inner
+                .lifecycle
+                .record(*hash, TxLifecycleEvent::DroppedInvalid(reason));
+        }
+        inner.remove_transaction_with_lock(hash)
+    }
+
+    /// Remove the transactions included in the block from the pool, along with the ones that
+    /// became invalid because they used a nonce already consumed by the block's transactions
+    pub fn remove_block_transactions(&self, block: &Block) -> Result<(), StoreError> {
+        let block_number = block.header.number;
+        let block_hash = block.hash();
+        let hashes: Vec<H256> = block
+            .body
+            .transactions
+            .iter()
+            .map(Transaction::hash)
+            .collect();
+        // The senders are needed to find the pool transactions using the same nonce. Recovering
+        // the ones of the transactions we didn't have is expensive, so it's done before taking
+        // the write lock
+        let pooled_senders: Vec<Option<Address>> = {
+            let inner = self.read()?;
+            if inner.transaction_pool.is_empty() {
+                Vec::new()
+            } else {
+                hashes
+                    .iter()
+                    .map(|hash| inner.transaction_pool.get(hash).map(|tx| tx.sender()))
+                    .collect()
+            }
+        };
+        let senders: Vec<Option<Address>> = block
+            .body
+            .transactions
+            .iter()
+            .zip(pooled_senders)
+            .map(|(tx, pooled_sender)| pooled_sender.or_else(|| tx.sender().ok()))
+            .collect();
+
+        let mut inner = self.write()?;
+        for (index, (tx, hash)) in block.body.transactions.iter().zip(hashes).enumerate() {
+            if inner.transaction_pool.contains_key(&hash) || inner.lifecycle.contains(&hash) {
+                inner.lifecycle.record(
+                    hash,
+                    TxLifecycleEvent::IncludedIn {
+                        block_number,
+                        block_hash,
+                    },
+                );
+            }
+            inner.remove_transaction_with_lock(&hash)?;
+
+            if inner.transaction_pool.is_empty() {
+                continue;
+            }
+            let Some(sender) = senders.get(index).copied().flatten() else {
+                continue;
+            };
+            let conflicting: Vec<H256> = inner
+                .txs_by_sender_nonce
+                .range((sender, 0)..=(sender, tx.nonce()))
+                .map(|(_, conflicting_hash)| *conflicting_hash)
+                .collect();
+            for conflicting_hash in conflicting {
+                inner.lifecycle.record(
+                    conflicting_hash,
+                    TxLifecycleEvent::DroppedInvalid(format!(
+                        "nonce already used by transaction {hash:#x}"
+                    )),
+                );
+                inner.remove_transaction_with_lock(&conflicting_hash)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the recorded events of the transaction, oldest first
+    pub fn get_transaction_lifecycle(
+        &self,
+        hash: &H256,
+    ) -> Result<Option<Vec<TxLifecycleRecord>>, StoreError> {
+        Ok(self.read()?.lifecycle.records.get(hash).cloned())
+    }
+
     /// Applies the filter and returns a set of suitable transactions from the mempool.
     /// These transactions will be grouped by sender and sorted by nonce
     pub fn filter_transactions(
@@ -231,6 +410,19 @@ impl Mempool {
         Ok(txs_by_sender)
     }
 
+    /// Gets all the transactions in the mempool sent by the given address, sorted by nonce
+    pub fn get_txs_by_sender(
+        &self,
+        sender: Address,
+    ) -> Result<Vec<MempoolTransaction>, StoreError> {
+        let inner = self.read()?;
+        Ok(inner
+            .txs_by_sender_nonce
+            .range((sender, 0)..=(sender, u64::MAX))
+            .filter_map(|(_, hash)| inner.transaction_pool.get(hash).cloned())
+            .collect())
+    }
+
     /// Applies the filter and returns a set of suitable transactions from the mempool.
     /// These transactions will be grouped by sender and sorted by nonce
     pub fn filter_transactions_with_filter_fn(
@@ -320,7 +512,8 @@ impl Mempool {
     }
 
     /// Returns the status of the mempool, which is the number of transactions currently in
-    /// the pool. Until we add "queue" transactions.
+    /// the pool. Splitting them into pending and queued requires the account nonces, which
+    /// live in the store, so that is left to the caller (see `get_all_txs_by_sender`).
     pub fn status(&self) -> Result<u64, MempoolError> {
         let pool_lock = &self.read()?.transaction_pool;
 
@@ -473,17 +666,21 @@ mod tests {
     use crate::mempool::{
         Mempool, TX_ACCESS_LIST_ADDRESS_GAS, TX_ACCESS_LIST_STORAGE_KEY_GAS, TX_CREATE_GAS_COST,
         TX_DATA_NON_ZERO_GAS, TX_DATA_ZERO_GAS_COST, TX_GAS_COST, TX_INIT_CODE_WORD_GAS_COST,
+        TxLifecycleEvent,
     };
     use std::collections::HashMap;
 
     use super::transaction_intrinsic_gas;
     use ethrex_common::types::{
-        BYTES_PER_BLOB, BlobsBundle, BlockHeader, ChainConfig, EIP1559Transaction,
-        EIP4844Transaction, MempoolTransaction, Transaction, TxKind,
+        BYTES_PER_BLOB, BlobsBundle, Block, BlockBody, BlockHeader, ChainConfig,
+        EIP1559Transaction, EIP4844Transaction, MempoolTransaction, Transaction, TxKind, TxType,
     };
     use ethrex_common::{Address, Bytes, H256, U256};
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::PayloadRLPEncode;
     use ethrex_storage::EngineType;
     use ethrex_storage::{Store, error::StoreError};
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
 
     const MEMPOOL_MAX_SIZE_TEST: usize = 10_000;
 
@@ -829,6 +1026,198 @@ mod tests {
         assert_eq!(txs, HashMap::from([(blob_tx.sender(), vec![blob_tx])]));
     }
 
+    #[test]
+    fn transaction_lifecycle_is_recorded() {
+        let tx = Transaction::decode_canonical(&hex::decode("f86d80843baa0c4082f618946177843db3138ae69679a54b95cf345ed759450d870aa87bee538000808360306ba0151ccc02146b9b11adf516e6787b59acae3e76544fdcd75e77e67c6b598ce65da064c5dd5aae2fbb535830ebbdad0234975cd7ece3562013b63ea18cc0df6c97d4").unwrap()).unwrap();
+        let sender = tx.sender().unwrap();
+        let tx = MempoolTransaction::new(tx, sender);
+        let hash = tx.hash();
+        let replacement_hash = H256::random();
+        let mempool = Mempool::new(MEMPOOL_MAX_SIZE_TEST);
+
+        assert_eq!(mempool.get_transaction_lifecycle(&hash).unwrap(), None);
+        mempool.add_transaction(hash, tx).unwrap();
+        mempool
+            .replace_transaction(&hash, replacement_hash)
+            .unwrap();
+        // Removing it again doesn't record anything as it's not pooled anymore
+        mempool
+            .drop_invalid_transaction(&hash, "nonce too low".to_string())
+            .unwrap();
+
+        let events: Vec<_> = mempool
+            .get_transaction_lifecycle(&hash)
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                TxLifecycleEvent::Received,
+                TxLifecycleEvent::ReplacedBy(replacement_hash)
+            ]
+        );
+        assert!(mempool.get_transaction_by_hash(hash).unwrap().is_none());
+    }
+
+    fn unsigned_tx(nonce: u64, value: u64) -> Transaction {
+        Transaction::EIP1559Transaction(EIP1559Transaction {
+            nonce,
+            value: U256::from(value),
+            ..Default::default()
+        })
+    }
+
+    fn signed_tx(key: &SecretKey, nonce: u64, value: u64) -> Transaction {
+        let Transaction::EIP1559Transaction(mut tx) = unsigned_tx(nonce, value) else {
+            unreachable!()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn address(key: &SecretKey) -> Address {
+        let public_key = PublicKey::from_secret_key(SECP256K1, key).serialize_uncompressed();
+        Address::from_slice(&keccak_hash(&public_key[1..])[12..])
+    }
+
+    /// Adds the transaction to the pool as sent by `sender`, returning its hash
+    fn add(mempool: &Mempool, tx: Transaction, sender: Address) -> H256 {
+        let tx = MempoolTransaction::new(tx, sender);
+        let hash = tx.hash();
+        mempool.add_transaction(hash, tx).unwrap();
+        hash
+    }
+
+    fn block_with(transactions: Vec<Transaction>) -> Block {
+        Block::new(
+            BlockHeader {
+                number: 7,
+                ..Default::default()
+            },
+            BlockBody {
+                transactions,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn events(mempool: &Mempool, hash: &H256) -> Vec<TxLifecycleEvent> {
+        mempool
+            .get_transaction_lifecycle(hash)
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|record| record.event)
+            .collect()
+    }
+
+    #[test]
+    fn oldest_transaction_is_evicted_when_full() {
+        let mempool = Mempool::new(1);
+        let oldest = add(&mempool, unsigned_tx(0, 1), Address::zero());
+        let newest = add(&mempool, unsigned_tx(1, 1), Address::zero());
+
+        assert_eq!(
+            events(&mempool, &oldest),
+            vec![TxLifecycleEvent::Received, TxLifecycleEvent::Evicted]
+        );
+        assert_eq!(events(&mempool, &newest), vec![TxLifecycleEvent::Received]);
+        assert!(mempool.get_transaction_by_hash(oldest).unwrap().is_none());
+        assert!(mempool.get_transaction_by_hash(newest).unwrap().is_some());
+    }
+
+    #[test]
+    fn included_transactions_are_removed() {
+        let mempool = Mempool::new(MEMPOOL_MAX_SIZE_TEST);
+        let pooled_tx = unsigned_tx(0, 1);
+        let hash = add(&mempool, pooled_tx.clone(), Address::zero());
+        // Unknown to the mempool, and unsigned so its sender can't be recovered
+        let unknown_tx = unsigned_tx(5, 1);
+        let block = block_with(vec![unknown_tx.clone(), pooled_tx]);
+
+        mempool.remove_block_transactions(&block).unwrap();
+
+        assert_eq!(
+            events(&mempool, &hash),
+            vec![
+                TxLifecycleEvent::Received,
+                TxLifecycleEvent::IncludedIn {
+                    block_number: 7,
+                    block_hash: block.hash(),
+                }
+            ]
+        );
+        assert!(mempool.get_transaction_by_hash(hash).unwrap().is_none());
+        assert_eq!(
+            mempool
+                .get_transaction_lifecycle(&unknown_tx.hash())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn transactions_using_an_included_nonce_are_dropped() {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let sender = address(&key);
+        let mempool = Mempool::new(MEMPOOL_MAX_SIZE_TEST);
+        let conflicting = add(&mempool, unsigned_tx(0, 1), sender);
+        let next_nonce = add(&mempool, unsigned_tx(1, 1), sender);
+        let other_sender = add(&mempool, unsigned_tx(0, 3), Address::zero());
+        // Same sender and nonce as `conflicting`, but a different transaction the mempool didn't have
+        let included_tx = signed_tx(&key, 0, 2);
+
+        mempool
+            .remove_block_transactions(&block_with(vec![included_tx.clone()]))
+            .unwrap();
+
+        assert_eq!(
+            events(&mempool, &conflicting),
+            vec![
+                TxLifecycleEvent::Received,
+                TxLifecycleEvent::DroppedInvalid(format!(
+                    "nonce already used by transaction {:#x}",
+                    included_tx.hash()
+                ))
+            ]
+        );
+        assert!(
+            mempool
+                .get_transaction_by_hash(conflicting)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            mempool
+                .get_transaction_by_hash(next_nonce)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            mempool
+                .get_transaction_by_hash(other_sender)
+                .unwrap()
+                .is_some()
+        );
+        // The included transaction was never in the mempool
+        assert_eq!(
+            mempool
+                .get_transaction_lifecycle(&included_tx.hash())
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn blobs_bundle_loadtest() {
         // Write a bundle of 6 blobs 10 times
@@ -1,4 +1,4 @@
-use prometheus::{Encoder, Gauge, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use prometheus::{Encoder, Gauge, IntCounter, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
 use std::sync::LazyLock;
 
 use crate::MetricsError;
@@ -18,6 +18,8 @@ pub struct Metrics {
     batch_commitment_gas: IntGaugeVec,
     batch_commitment_blob_gas: IntGaugeVec,
     batch_tx_count: IntGaugeVec,
+    preconfirmations_issued: IntCounter,
+    preconfirmations_broken: IntCounter,
 }
 
 impl Default for Metrics {
@@ -108,6 +110,16 @@ impl Metrics {
                 &["batch_number"],
             )
             .unwrap(),
+            preconfirmations_issued: IntCounter::new(
+                "l2_preconfirmations_issued",
+                "Amount of transaction preconfirmations signed by the sequencer",
+            )
+            .unwrap(),
+            preconfirmations_broken: IntCounter::new(
+                "l2_preconfirmations_broken",
+                "Amount of preconfirmed transactions that weren't included in the promised block and position",
+            )
+            .unwrap(),
         }
     }
 
@@ -237,6 +249,14 @@ impl Metrics {
         Ok(())
     }
 
+    pub fn inc_preconfirmations_issued(&self) {
+        self.preconfirmations_issued.inc();
+    }
+
+    pub fn inc_preconfirmations_broken(&self, amount: u64) {
+        self.preconfirmations_broken.inc_by(amount);
+    }
+
     pub fn gather_metrics(&self) -> Result<String, MetricsError> {
         let r = Registry::new();
 
@@ -264,6 +284,10 @@ impl Metrics {
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.batch_tx_count.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.preconfirmations_issued.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.preconfirmations_broken.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
 
         let encoder = TextEncoder::new();
         let metric_families = r.gather();
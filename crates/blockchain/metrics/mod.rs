@@ -4,6 +4,8 @@ pub mod api;
 pub mod blocks;
 #[cfg(feature = "api")]
 pub mod l2;
+#[cfg(feature = "api")]
+pub mod otel;
 #[cfg(any(feature = "api", feature = "metrics"))]
 pub mod process;
 #[cfg(feature = "api")]
@@ -71,6 +73,8 @@ pub enum MetricsError {
     TryInto(#[from] std::num::TryFromIntError),
     #[error("MetricsL2Error {0}")]
     FromUtf8Error(#[from] std::string::FromUtf8Error),
+    #[error("OtlpError: {0}")]
+    OtlpError(String),
 }
 
 #[cfg(feature = "api")]
@@ -0,0 +1,401 @@
+//! Export of the node's tracing spans to an OpenTelemetry collector through OTLP.
+//!
+//! The configuration types are always available so they can be part of the CLI, the exporter
+//! itself is only built with the `otel` feature.
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "otel")]
+pub(crate) use exporter::set_remote_parent;
+#[cfg(feature = "otel")]
+pub use exporter::{is_enabled, otlp_layer, shutdown_tracer_provider};
+
+/// Encoding used to send spans to the collector
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    #[default]
+    HttpBinary,
+    HttpJson,
+}
+
+impl FromStr for OtlpProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "http/protobuf" => Ok(Self::HttpBinary),
+            "http/json" => Ok(Self::HttpJson),
+            other => Err(format!(
+                "Invalid OTLP protocol {other}, expected `http/protobuf` or `http/json`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OtlpProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HttpBinary => write!(f, "http/protobuf"),
+            Self::HttpJson => write!(f, "http/json"),
+        }
+    }
+}
+
+/// Format used to read the trace context of incoming RPC requests, named as in `OTEL_PROPAGATORS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagator {
+    /// W3C `traceparent` and `tracestate` headers
+    TraceContext,
+    /// W3C `baggage` header
+    Baggage,
+}
+
+impl FromStr for Propagator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tracecontext" => Ok(Self::TraceContext),
+            "baggage" => Ok(Self::Baggage),
+            other => Err(format!(
+                "Invalid propagator {other}, expected `tracecontext`, `baggage` or `none`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Propagator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TraceContext => write!(f, "tracecontext"),
+            Self::Baggage => write!(f, "baggage"),
+        }
+    }
+}
+
+/// Comma separated list of propagators, where `none` disables propagation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Propagators(pub Vec<Propagator>);
+
+impl Default for Propagators {
+    fn default() -> Self {
+        Self(vec![Propagator::TraceContext, Propagator::Baggage])
+    }
+}
+
+impl FromStr for Propagators {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "none" {
+            return Ok(Self(Vec::new()));
+        }
+        s.split(',')
+            .map(|name| name.trim().parse())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for Propagators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+        let names: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", names.join(","))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Base URL of the collector's OTLP/HTTP receiver, e.g. `http://localhost:4318`
+    pub endpoint: String,
+    pub protocol: OtlpProtocol,
+    /// Reported as the `service.name` resource attribute
+    pub service_name: String,
+    pub propagators: Propagators,
+}
+
+impl OtlpConfig {
+    /// URL spans are sent to, the endpoint with the `/v1/traces` path appended if missing
+    pub fn traces_endpoint(&self) -> String {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{endpoint}/v1/traces")
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+mod exporter {
+    use std::sync::OnceLock;
+
+    use axum::http::HeaderMap;
+    use opentelemetry::{
+        global,
+        propagation::{Extractor, TextMapCompositePropagator, TextMapPropagator},
+        trace::TracerProvider as _,
+    };
+    use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{
+        Resource,
+        propagation::{BaggagePropagator, TraceContextPropagator},
+        trace::SdkTracerProvider,
+    };
+    use tracing::{Level, Span, Subscriber};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::{Layer, filter::Targets, registry::LookupSpan};
+
+    use super::{OtlpConfig, OtlpProtocol, Propagator};
+    use crate::MetricsError;
+
+    static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+    /// Builds the OTLP exporter and returns a layer that sends the spans of the ethrex crates to
+    /// it. Spans are exported in batches from a background thread.
+    ///
+    /// This also installs the configured propagators globally, and can only be called once.
+    pub fn otlp_layer<S>(config: &OtlpConfig) -> Result<impl Layer<S>, MetricsError>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let protocol = match config.protocol {
+            OtlpProtocol::HttpBinary => Protocol::HttpBinary,
+            OtlpProtocol::HttpJson => Protocol::HttpJson,
+        };
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_protocol(protocol)
+            .with_endpoint(config.traces_endpoint())
+            .build()
+            .map_err(|err| MetricsError::OtlpError(err.to_string()))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(config.service_name.clone())
+                    .build(),
+            )
+            .build();
+        let tracer = provider.tracer("ethrex");
+        TRACER_PROVIDER
+            .set(provider)
+            .map_err(|_| MetricsError::OtlpError("Exporter already initialized".to_string()))?;
+
+        let propagators = config
+            .propagators
+            .0
+            .iter()
+            .map(|propagator| -> Box<dyn TextMapPropagator + Send + Sync> {
+                match propagator {
+                    Propagator::TraceContext => Box::new(TraceContextPropagator::new()),
+                    Propagator::Baggage => Box::new(BaggagePropagator::new()),
+                }
+            })
+            .collect();
+        global::set_text_map_propagator(TextMapCompositePropagator::new(propagators));
+
+        // Dependencies' spans and the events logged at debug level are left out
+        let filter = Targets::new().with_target("ethrex", Level::INFO);
+        Ok(tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(filter))
+    }
+
+    /// Returns whether the exporter was initialized with [`otlp_layer`]
+    pub fn is_enabled() -> bool {
+        TRACER_PROVIDER.get().is_some()
+    }
+
+    /// Exports the spans that are still buffered and stops the exporter
+    pub fn shutdown_tracer_provider() {
+        if let Some(provider) = TRACER_PROVIDER.get()
+            && let Err(err) = provider.shutdown()
+        {
+            tracing::error!("Failed to shut down the OTLP exporter: {err}");
+        }
+    }
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|key| key.as_str()).collect()
+        }
+    }
+
+    /// Makes the trace context found in the request headers the parent of `span`
+    pub(crate) fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+        let context = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(headers))
+        });
+        span.set_parent(context);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::{TcpListener, TcpStream},
+            sync::mpsc::{Sender, channel},
+            thread,
+            time::Duration,
+        };
+
+        use axum::http::{HeaderMap, HeaderValue};
+        use serde_json::Value;
+        use tracing_subscriber::{Registry, layer::SubscriberExt};
+
+        use super::*;
+        use crate::otel::Propagators;
+
+        const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+        const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+        /// Answers every OTLP/HTTP request with an empty success response, sending its body through
+        /// the channel
+        fn serve_collector(stream: TcpStream, requests: Sender<String>) {
+            let mut reader = BufReader::new(stream);
+            loop {
+                let mut content_length = 0;
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                        return;
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                requests.send(String::from_utf8(body).unwrap()).unwrap();
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\n\r\n{}")
+                    .unwrap();
+            }
+        }
+
+        fn attribute<'a>(span: &'a Value, key: &str) -> Option<&'a Value> {
+            span["attributes"]
+                .as_array()?
+                .iter()
+                .find(|attribute| attribute["key"] == key)
+                .map(|attribute| &attribute["value"])
+        }
+
+        #[test]
+        fn exports_spans_to_collector() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let address = listener.local_addr().unwrap();
+            let (sender, receiver) = channel();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let sender = sender.clone();
+                    thread::spawn(move || serve_collector(stream, sender));
+                }
+            });
+
+            let config = OtlpConfig {
+                endpoint: format!("http://{address}"),
+                protocol: OtlpProtocol::HttpJson,
+                service_name: "ethrex-test".to_string(),
+                propagators: Propagators::default(),
+            };
+            let subscriber = Registry::default().with(otlp_layer(&config).unwrap());
+            tracing::subscriber::with_default(subscriber, || {
+                tracing::info_span!(
+                    target: "ethrex_blockchain::blockchain",
+                    "Add block",
+                    block.number = 42,
+                    block.hash = "0x01",
+                    block.tx_count = 3
+                )
+                .in_scope(|| {});
+
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    "traceparent",
+                    HeaderValue::from_str(&format!("00-{TRACE_ID}-{PARENT_SPAN_ID}-01")).unwrap(),
+                );
+                let span = tracing::info_span!(target: "ethrex_rpc::rpc", "RPC call");
+                set_remote_parent(&span, &headers);
+                span.in_scope(|| {});
+
+                tracing::info_span!(target: "hyper::client", "Dependency span").in_scope(|| {});
+            });
+            shutdown_tracer_provider();
+
+            let mut spans = Vec::new();
+            while let Ok(body) = receiver.recv_timeout(Duration::from_secs(5)) {
+                let request: Value = serde_json::from_str(&body).unwrap();
+                for resource_spans in request["resourceSpans"].as_array().unwrap() {
+                    let service_name = resource_spans["resource"]["attributes"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .find(|attribute| attribute["key"] == "service.name")
+                        .map(|attribute| attribute["value"]["stringValue"].clone());
+                    assert_eq!(service_name, Some(Value::from("ethrex-test")));
+                    for scope_spans in resource_spans["scopeSpans"].as_array().unwrap() {
+                        spans.extend(scope_spans["spans"].as_array().unwrap().iter().cloned());
+                    }
+                }
+            }
+            assert_eq!(spans.len(), 2, "unexpected spans exported: {spans:?}");
+
+            let block_span = spans
+                .iter()
+                .find(|span| span["name"] == "Add block")
+                .unwrap();
+            assert!(attribute(block_span, "block.number").is_some());
+            assert!(attribute(block_span, "block.hash").is_some());
+            assert!(attribute(block_span, "block.tx_count").is_some());
+
+            let rpc_span = spans
+                .iter()
+                .find(|span| span["name"] == "RPC call")
+                .unwrap();
+            assert_eq!(rpc_span["traceId"], TRACE_ID);
+            assert_eq!(rpc_span["parentSpanId"], PARENT_SPAN_ID);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cli_values() {
+        assert_eq!("none".parse(), Ok(Propagators(Vec::new())));
+        assert_eq!("tracecontext, baggage".parse(), Ok(Propagators::default()));
+        assert_eq!(Propagators::default().to_string(), "tracecontext,baggage");
+        assert!("b3".parse::<Propagators>().is_err());
+        assert_eq!(
+            "http/json".parse::<OtlpProtocol>().unwrap(),
+            OtlpProtocol::HttpJson
+        );
+
+        let config = OtlpConfig {
+            endpoint: "http://localhost:4318/".to_string(),
+            protocol: OtlpProtocol::HttpBinary,
+            service_name: "ethrex".to_string(),
+            propagators: Propagators(Vec::new()),
+        };
+        assert_eq!(config.traces_endpoint(), "http://localhost:4318/v1/traces");
+    }
+}
@@ -84,15 +84,16 @@ where
             }
             let timer = {
                 let extensions = span.extensions();
-                let namespace = extensions
-                    .get::<Namespace>()
-                    .map(|ns| ns.0.as_str())
-                    .unwrap_or("default");
+                // Spans without a namespace are only meant for tracing, e.g. the async spans
+                // exported through OTLP, which are entered and exited on every poll.
+                let Some(Namespace(namespace)) = extensions.get::<Namespace>() else {
+                    return;
+                };
 
                 let function_name = span.metadata().name();
 
                 METRICS_BLOCK_PROCESSING_PROFILE
-                    .with_label_values(&[namespace, function_name])
+                    .with_label_values(&[namespace.as_str(), function_name])
                     .start_timer()
             };
 
@@ -1,3 +1,4 @@
+use axum::{extract::Request, middleware::Next, response::Response};
 use prometheus::{CounterVec, HistogramVec, register_counter_vec, register_histogram_vec};
 use std::{future::Future, sync::LazyLock};
 
@@ -83,3 +84,23 @@ where
     timer.observe_duration();
     output
 }
+
+/// Axum middleware that runs each request inside a span whose parent is the trace context sent
+/// by the caller, so that the RPC spans are exported as part of the caller's trace.
+///
+/// Requests are passed through untouched unless the OTLP exporter was initialized.
+pub async fn propagate_trace_context(request: Request, next: Next) -> Response {
+    #[cfg(feature = "otel")]
+    if crate::otel::is_enabled() {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "HTTP request",
+            otel.kind = "server",
+            url.path = request.uri().path()
+        );
+        crate::otel::set_remote_parent(&span, request.headers());
+        return next.run(request).instrument(span).await;
+    }
+    next.run(request).await
+}
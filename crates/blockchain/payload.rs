@@ -1,6 +1,6 @@
 use std::{
     cmp::{Ordering, max},
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::Div,
     sync::Arc,
     time::{Duration, Instant},
@@ -11,7 +11,7 @@ use ethrex_common::{
     constants::{DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH, GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE},
     types::{
         AccountUpdate, BlobsBundle, Block, BlockBody, BlockHash, BlockHeader, BlockNumber,
-        ChainConfig,
+        ChainConfig, ELASTICITY_MULTIPLIER,
         Fork::*,
         MempoolTransaction, Receipt, Transaction, TxType, Withdrawal, bloom_from_logs,
         calc_excess_blob_gas, calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
@@ -36,6 +36,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
     Blockchain, BlockchainType, MAX_PAYLOADS,
+    bundle::{Bundle, BundleExecution, BundleTxResult},
     constants::{GAS_LIMIT_BOUND_DIVISOR, MIN_GAS_LIMIT, TX_GAS_COST},
     error::{ChainError, InvalidBlockError},
     mempool::PendingTxFilter,
@@ -461,6 +462,16 @@ impl Blockchain {
     pub fn fetch_mempool_transactions(
         &self,
         context: &mut PayloadBuildContext,
+    ) -> Result<(TransactionQueue, TransactionQueue), ChainError> {
+        self.fetch_mempool_transactions_with_ordering(context, Arc::new(TipOrdering))
+    }
+
+    /// Same as [Blockchain::fetch_mempool_transactions] but the returned queues yield the
+    /// transactions following the given `ordering` policy
+    pub fn fetch_mempool_transactions_with_ordering(
+        &self,
+        context: &mut PayloadBuildContext,
+        ordering: Arc<dyn TransactionOrdering>,
     ) -> Result<(TransactionQueue, TransactionQueue), ChainError> {
         let tx_filter = PendingTxFilter {
             /*TODO(https://github.com/lambdaclass/ethrex/issues/680): add tip filter */
@@ -481,11 +492,13 @@ impl Blockchain {
             TransactionQueue::new(
                 self.mempool.filter_transactions(&plain_tx_filter)?,
                 context.base_fee_per_gas(),
+                ordering.clone(),
             )?,
             // Blob txs
             TransactionQueue::new(
                 self.mempool.filter_transactions(&blob_tx_filter)?,
                 context.base_fee_per_gas(),
+                ordering,
             )?,
         ))
     }
@@ -499,6 +512,9 @@ impl Blockchain {
             .map(|schedule| schedule.max)
             .unwrap_or_default() as usize;
 
+        // Bundles go first, so their transactions run on the state they were simulated against
+        let bundle_txs = self.apply_bundles(context)?;
+
         debug!("Fetching transactions from mempool");
         // Fetch mempool transactions
         let (mut plain_txs, mut blob_txs) = self.fetch_mempool_transactions(context)?;
@@ -529,6 +545,12 @@ impl Blockchain {
                 &mut plain_txs
             };
 
+            // Skip transactions already included as part of a bundle
+            if bundle_txs.contains(&head_tx.tx.hash()) {
+                txs.shift()?;
+                continue;
+            }
+
             // Check if we have enough gas to run the transaction
             if context.remaining_gas < head_tx.tx.gas_limit() {
                 debug!("Skipping transaction: {}, no gas left", head_tx.tx.hash());
@@ -578,6 +600,86 @@ impl Blockchain {
         Ok(())
     }
 
+    /// Includes the bundles targeting the payload's block, ranked by how much each one pays the
+    /// fee recipient per unit of gas when run on its own. Each bundle is run again on top of the
+    /// previously included ones, and only kept if all of its transactions can be included
+    /// without reverting, unless allowed to.
+    /// Returns the hashes of the included transactions
+    fn apply_bundles(
+        &self,
+        context: &mut PayloadBuildContext,
+    ) -> Result<HashSet<H256>, ChainError> {
+        let mut included_txs = HashSet::new();
+        self.bundle_pool
+            .remove_stale_bundles(context.block_number().saturating_sub(1))?;
+        let bundles = self
+            .bundle_pool
+            .get_bundles(context.block_number(), context.payload.header.timestamp)?;
+        if bundles.is_empty() {
+            return Ok(included_txs);
+        }
+
+        debug!("Simulating {} bundles", bundles.len());
+        let mut ranked_bundles: Vec<(U256, Bundle)> = bundles
+            .into_iter()
+            .filter_map(|bundle| {
+                let mut simulation = context.clone();
+                let execution = run_bundle(&bundle, &mut simulation)
+                    .inspect_err(|e| {
+                        debug!("Discarding bundle {:#x}: {e}", bundle.hash());
+                        metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                    })
+                    .ok()?;
+                Some((execution.effective_gas_price(), bundle))
+            })
+            .collect();
+        ranked_bundles.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        for (_, bundle) in ranked_bundles {
+            let mut attempt = context.clone();
+            match run_bundle(&bundle, &mut attempt) {
+                Ok(_) => {
+                    debug!("Adding bundle: {:#x} to payload", bundle.hash());
+                    *context = attempt;
+                    for tx in &bundle.transactions {
+                        metrics!(METRICS_TX.inc_tx_with_type(MetricsTxType(tx.tx_type())));
+                        included_txs.insert(tx.hash());
+                    }
+                }
+                Err(e) => {
+                    debug!("Failed to include bundle {:#x}: {e}", bundle.hash());
+                    metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                }
+            }
+        }
+        Ok(included_txs)
+    }
+
+    /// Simulates the bundle in a block built on top of `parent`, as done by `eth_callBundle`.
+    /// Unlike when building payloads, reverted transactions don't make the simulation fail.
+    pub fn simulate_bundle(
+        &self,
+        bundle: &Bundle,
+        parent: &BlockHeader,
+        timestamp: u64,
+        fee_recipient: Address,
+    ) -> Result<BundleExecution, ChainError> {
+        let args = BuildPayloadArgs {
+            parent: parent.hash(),
+            timestamp,
+            fee_recipient,
+            random: parent.prev_randao,
+            withdrawals: None,
+            beacon_root: parent.parent_beacon_block_root,
+            version: 0,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: parent.gas_limit,
+        };
+        let payload = create_payload(&args, &self.storage, Bytes::new())?;
+        let mut context = PayloadBuildContext::new(payload, &self.storage, &self.options.r#type)?;
+        execute_bundle(bundle, &mut context)
+    }
+
     /// Executes the transaction, updates gas-related context values & return the receipt
     /// The payload build context should have enough remaining gas to cover the transaction's gas_limit
     fn apply_transaction(
@@ -689,21 +791,115 @@ pub fn apply_plain_transaction(
     Ok(report)
 }
 
+/// Runs a bundle for inclusion, failing if any of its transactions reverts without the bundle
+/// allowing it. The context must be discarded on failure.
+fn run_bundle(
+    bundle: &Bundle,
+    context: &mut PayloadBuildContext,
+) -> Result<BundleExecution, ChainError> {
+    let execution = execute_bundle(bundle, context)?;
+    if let Some(reverted) = execution
+        .results
+        .iter()
+        .find(|result| !result.succeeded && !bundle.may_revert(&result.tx_hash))
+    {
+        return Err(ChainError::InvalidTransaction(format!(
+            "bundle transaction {:#x} reverted",
+            reverted.tx_hash
+        )));
+    }
+    Ok(execution)
+}
+
+/// Runs the transactions of the bundle in order and adds them to the payload.
+/// Fails if any of them can't be included, in which case the context must be discarded.
+pub fn execute_bundle(
+    bundle: &Bundle,
+    context: &mut PayloadBuildContext,
+) -> Result<BundleExecution, ChainError> {
+    let coinbase = context.payload.header.coinbase;
+    let is_osaka = context
+        .chain_config()
+        .is_fork_activated(Osaka, context.payload.header.timestamp);
+    let mut execution = BundleExecution::default();
+    for tx in &bundle.transactions {
+        let tx_hash = tx.hash();
+        if context.remaining_gas < tx.gas_limit() {
+            return Err(ChainError::InvalidTransaction(format!(
+                "no gas left for bundle transaction {tx_hash:#x}"
+            )));
+        }
+        let tip = tx
+            .effective_gas_tip(context.base_fee_per_gas())
+            .ok_or_else(|| {
+                ChainError::InvalidTransaction(format!(
+                    "bundle transaction {tx_hash:#x} doesn't cover the base fee"
+                ))
+            })?;
+        let payload_size = context.payload_size + tx.encode_canonical_to_vec().len() as u64;
+        if is_osaka && payload_size > MAX_RLP_BLOCK_SIZE {
+            return Err(ChainError::InvalidTransaction(
+                "bundle exceeds the block size limit".to_string(),
+            ));
+        }
+
+        let head = HeadTransaction {
+            tx: tx.clone(),
+            tip,
+            included_from_sender: 0,
+        };
+        let balance_before = coinbase_balance(context, coinbase)?;
+        let remaining_gas = context.remaining_gas;
+        let receipt = apply_plain_transaction(&head, context)?;
+        let gas_used = remaining_gas.saturating_sub(context.remaining_gas);
+        let gas_fees = U256::from(gas_used) * tip;
+        let coinbase_diff = coinbase_balance(context, coinbase)?.saturating_sub(balance_before);
+
+        execution.results.push(BundleTxResult {
+            tx_hash,
+            sender: tx.sender(),
+            gas_used,
+            gas_fees,
+            coinbase_diff,
+            succeeded: receipt.succeeded,
+        });
+        execution.gas_used += gas_used;
+        execution.gas_fees += gas_fees;
+        execution.coinbase_diff += coinbase_diff;
+
+        context.payload_size = payload_size;
+        context.payload.body.transactions.push(head.into());
+        context.receipts.push(receipt);
+    }
+    Ok(execution)
+}
+
+fn coinbase_balance(
+    context: &mut PayloadBuildContext,
+    coinbase: Address,
+) -> Result<U256, EvmError> {
+    Ok(context.vm.db.get_account(coinbase)?.info.balance)
+}
+
 /// A struct representing suitable mempool transactions waiting to be included in a block
 // TODO: Consider using VecDequeue instead of Vec
 pub struct TransactionQueue {
-    // The first transaction for each account along with its tip, sorted by highest tip
+    // The first transaction for each account along with its tip, sorted by the ordering policy
     heads: Vec<HeadTransaction>,
     // The remaining txs grouped by account and sorted by nonce
     txs: HashMap<Address, Vec<MempoolTransaction>>,
     // Base Fee stored for tip calculations
     base_fee: Option<u64>,
+    // Policy deciding which head transaction goes first
+    ordering: Arc<dyn TransactionOrdering>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct HeadTransaction {
     pub tx: MempoolTransaction,
     pub tip: u64,
+    /// Amount of transactions from the same sender already taken from the queue
+    pub included_from_sender: u64,
 }
 
 impl std::ops::Deref for HeadTransaction {
@@ -725,6 +921,7 @@ impl TransactionQueue {
     fn new(
         mut txs: HashMap<Address, Vec<MempoolTransaction>>,
         base_fee: Option<u64>,
+        ordering: Arc<dyn TransactionOrdering>,
     ) -> Result<Self, ChainError> {
         let mut heads = Vec::with_capacity(100);
         for (_, txs) in txs.iter_mut() {
@@ -739,14 +936,15 @@ impl TransactionQueue {
                         InvalidBlockError::InvalidTransaction("Attempted to add an invalid transaction to the block. The transaction filter must have failed.".to_owned()),
                     ))?,
                 tx: head_tx,
+                included_from_sender: 0,
             });
         }
-        // Sort heads by higest tip (and lowest timestamp if tip is equal)
-        heads.sort();
+        heads.sort_by(|a, b| compare_heads(ordering.as_ref(), a, b));
         Ok(TransactionQueue {
             heads,
             txs,
             base_fee,
+            ordering,
         })
     }
 
@@ -761,8 +959,7 @@ impl TransactionQueue {
         self.heads.is_empty()
     }
 
-    /// Returns the head transaction with the highest tip
-    /// If there is more than one transaction with the highest tip, return the one with the lowest timestamp
+    /// Returns the head transaction that goes first according to the ordering policy
     pub fn peek(&self) -> Option<HeadTransaction> {
         self.heads.first().cloned()
     }
@@ -791,9 +988,13 @@ impl TransactionQueue {
                         ),
                     )?,
                     tx: head_tx,
+                    included_from_sender: tx.included_from_sender + 1,
                 };
                 // Insert head into heads list while maintaing order
-                let index = match self.heads.binary_search(&head) {
+                let index = match self
+                    .heads
+                    .binary_search_by(|other| compare_heads(self.ordering.as_ref(), other, &head))
+                {
                     Ok(index) => index, // Same ordering shouldn't be possible when adding timestamps
                     Err(index) => index,
                 };
@@ -804,19 +1005,77 @@ impl TransactionQueue {
     }
 }
 
-// Orders transactions by highest tip, if tip is equal, orders by lowest timestamp
+/// Policy used by a [TransactionQueue] to decide which of the senders' head transactions
+/// is included next. Privileged transactions always go first, in nonce order, regardless of
+/// the policy.
+pub trait TransactionOrdering: std::fmt::Debug + Send + Sync {
+    /// Returns [Ordering::Less] if `a` should be included before `b`
+    fn compare(&self, a: &HeadTransaction, b: &HeadTransaction) -> Ordering;
+}
+
+/// Orders transactions by highest tip, if tip is equal, orders by lowest timestamp
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TipOrdering;
+
+impl TransactionOrdering for TipOrdering {
+    fn compare(&self, a: &HeadTransaction, b: &HeadTransaction) -> Ordering {
+        b.tip
+            .cmp(&a.tip)
+            .then_with(|| a.tx.time().cmp(&b.tx.time()))
+    }
+}
+
+/// Orders transactions by their arrival time to the mempool
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FifoOrdering;
+
+impl TransactionOrdering for FifoOrdering {
+    fn compare(&self, a: &HeadTransaction, b: &HeadTransaction) -> Ordering {
+        a.tx.time()
+            .cmp(&b.tx.time())
+            .then_with(|| b.tip.cmp(&a.tip))
+    }
+}
+
+/// Orders transactions by highest tip, but first serves the senders with the fewest
+/// transactions already included, so a single sender can't fill the block by paying more
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FairTipOrdering;
+
+impl TransactionOrdering for FairTipOrdering {
+    fn compare(&self, a: &HeadTransaction, b: &HeadTransaction) -> Ordering {
+        a.included_from_sender
+            .cmp(&b.included_from_sender)
+            .then_with(|| TipOrdering.compare(a, b))
+    }
+}
+
+fn compare_heads(
+    ordering: &dyn TransactionOrdering,
+    a: &HeadTransaction,
+    b: &HeadTransaction,
+) -> Ordering {
+    match (a.tx_type(), b.tx_type()) {
+        (TxType::Privileged, TxType::Privileged) => a.nonce().cmp(&b.nonce()),
+        (TxType::Privileged, _) => Ordering::Less,
+        (_, TxType::Privileged) => Ordering::Greater,
+        _ => ordering.compare(a, b),
+    }
+}
+
+// Heads are equal when neither goes before the other, to stay consistent with `Ord`
+impl PartialEq for HeadTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeadTransaction {}
+
+// Orders transactions following the default [TipOrdering] policy
 impl Ord for HeadTransaction {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self.tx_type(), other.tx_type()) {
-            (TxType::Privileged, TxType::Privileged) => return self.nonce().cmp(&other.nonce()),
-            (TxType::Privileged, _) => return Ordering::Less,
-            (_, TxType::Privileged) => return Ordering::Greater,
-            _ => (),
-        };
-        match other.tip.cmp(&self.tip) {
-            Ordering::Equal => self.tx.time().cmp(&other.tx.time()),
-            ordering => ordering,
-        }
+        compare_heads(&TipOrdering, self, other)
     }
 }
 
@@ -825,3 +1084,381 @@ impl PartialOrd for HeadTransaction {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use ethrex_common::types::{
+        EIP1559Transaction, Genesis, GenesisAccount, PrivilegedL2Transaction, TxKind,
+    };
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use ethrex_storage::EngineType;
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+
+    use super::*;
+    use crate::{bundle::MAX_BUNDLE_BLOCKS_AHEAD, error::MempoolError};
+
+    const GWEI: u64 = 1_000_000_000;
+    /// Code that always reverts: `PUSH1 0 PUSH1 0 REVERT`
+    const REVERTING_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+    fn key(seed: u8) -> SecretKey {
+        SecretKey::from_slice(&[seed; 32]).unwrap()
+    }
+
+    fn address(key: &SecretKey) -> Address {
+        let public_key = PublicKey::from_secret_key(SECP256K1, key).serialize_uncompressed();
+        Address::from_slice(&keccak_hash(&public_key[1..])[12..])
+    }
+
+    fn reverting_contract() -> Address {
+        Address::from_low_u64_be(0xdead)
+    }
+
+    /// Store with the accounts of the given keys funded and a contract that always reverts
+    async fn test_store(funded_keys: &[SecretKey]) -> Store {
+        let file = File::open("../../fixtures/genesis/execution-api.json").unwrap();
+        let mut genesis: Genesis = serde_json::from_reader(BufReader::new(file)).unwrap();
+        for key in funded_keys {
+            genesis.alloc.insert(
+                address(key),
+                GenesisAccount {
+                    balance: U256::from(10).pow(U256::from(18)),
+                    ..Default::default()
+                },
+            );
+        }
+        genesis.alloc.insert(
+            reverting_contract(),
+            GenesisAccount {
+                code: Bytes::from_static(&REVERTING_CODE),
+                ..Default::default()
+            },
+        );
+        let mut store = Store::new("store.db", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        store
+    }
+
+    fn signed_tx(store: &Store, key: &SecretKey, nonce: u64, tip: u64, to: Address) -> Transaction {
+        let mut tx = EIP1559Transaction {
+            chain_id: store.get_chain_config().chain_id,
+            nonce,
+            max_priority_fee_per_gas: tip,
+            max_fee_per_gas: 100 * GWEI,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            value: U256::one(),
+            ..Default::default()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn payload_args(store: &Store, fee_recipient: Address) -> BuildPayloadArgs {
+        let genesis = store.get_block_header(0).unwrap().unwrap();
+        BuildPayloadArgs {
+            parent: genesis.hash(),
+            timestamp: genesis.timestamp + 12,
+            fee_recipient,
+            random: H256::zero(),
+            withdrawals: Some(Vec::new()),
+            beacon_root: Some(H256::zero()),
+            version: 3,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: genesis.gas_limit,
+        }
+    }
+
+    fn build_block(blockchain: &Blockchain, store: &Store) -> Block {
+        let payload =
+            create_payload(&payload_args(store, Address::zero()), store, Bytes::new()).unwrap();
+        blockchain.build_payload(payload).unwrap().payload
+    }
+
+    fn tx_hashes(block: &Block) -> Vec<H256> {
+        block.body.transactions.iter().map(|tx| tx.hash()).collect()
+    }
+
+    #[tokio::test]
+    async fn execute_bundle_reports_fees_and_coinbase_diff() {
+        let sender = key(1);
+        let store = test_store(&[sender]).await;
+        let fee_recipient = Address::from_low_u64_be(0xfee);
+        let payload =
+            create_payload(&payload_args(&store, fee_recipient), &store, Bytes::new()).unwrap();
+        let base_fee = payload.header.base_fee_per_gas.unwrap();
+        let mut context = PayloadBuildContext::new(payload, &store, &BlockchainType::L1).unwrap();
+
+        let transfer = signed_tx(&store, &sender, 0, 2 * GWEI, Address::from_low_u64_be(1));
+        let reverting = signed_tx(&store, &sender, 1, GWEI, reverting_contract());
+        let bundle = Bundle {
+            transactions: vec![
+                MempoolTransaction::new(transfer.clone(), address(&sender)),
+                MempoolTransaction::new(reverting.clone(), address(&sender)),
+            ],
+            block_number: 1,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        };
+        let execution = execute_bundle(&bundle, &mut context).unwrap();
+
+        let [transfer_result, reverting_result] = execution.results.as_slice() else {
+            panic!("Expected one result per transaction");
+        };
+        assert_eq!(transfer_result.tx_hash, transfer.hash());
+        assert_eq!(transfer_result.sender, address(&sender));
+        assert_eq!(transfer_result.gas_used, TX_GAS_COST);
+        assert!(transfer_result.succeeded);
+        let tip = (2 * GWEI).min(100 * GWEI - base_fee);
+        assert_eq!(
+            transfer_result.gas_fees,
+            U256::from(TX_GAS_COST) * U256::from(tip)
+        );
+        assert_eq!(transfer_result.coinbase_diff, transfer_result.gas_fees);
+        // Reverted transactions are still included by `execute_bundle`
+        assert!(!reverting_result.succeeded);
+
+        assert_eq!(
+            execution.gas_used,
+            transfer_result.gas_used + reverting_result.gas_used
+        );
+        assert_eq!(
+            execution.coinbase_diff,
+            transfer_result.coinbase_diff + reverting_result.coinbase_diff
+        );
+        assert_eq!(
+            tx_hashes(&context.payload),
+            vec![transfer.hash(), reverting.hash()]
+        );
+        assert_eq!(context.receipts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn bundles_are_included_whole_or_not_at_all() {
+        let (funded, unfunded) = (key(1), key(2));
+        let store = test_store(&[funded]).await;
+        let blockchain = Blockchain::default_with_store(store.clone());
+
+        // The second transaction can't pay for its gas, so the first one is left out too
+        let first = signed_tx(&store, &funded, 0, GWEI, Address::from_low_u64_be(1));
+        let second = signed_tx(&store, &unfunded, 0, GWEI, Address::from_low_u64_be(1));
+        blockchain
+            .add_bundle_to_pool(vec![first, second], 1, None, None, Vec::new())
+            .await
+            .unwrap();
+
+        assert!(
+            build_block(&blockchain, &store)
+                .body
+                .transactions
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn bundles_with_reverted_transactions_need_them_allowed() {
+        let (disallowed, allowed) = (key(1), key(2));
+        let store = test_store(&[disallowed, allowed]).await;
+        let blockchain = Blockchain::default_with_store(store.clone());
+
+        let reverting = signed_tx(&store, &disallowed, 0, GWEI, reverting_contract());
+        blockchain
+            .add_bundle_to_pool(vec![reverting], 1, None, None, Vec::new())
+            .await
+            .unwrap();
+        let allowed_reverting = signed_tx(&store, &allowed, 0, GWEI, reverting_contract());
+        blockchain
+            .add_bundle_to_pool(
+                vec![allowed_reverting.clone()],
+                1,
+                None,
+                None,
+                vec![allowed_reverting.hash()],
+            )
+            .await
+            .unwrap();
+
+        let block = build_block(&blockchain, &store);
+        assert_eq!(tx_hashes(&block), vec![allowed_reverting.hash()]);
+    }
+
+    #[tokio::test]
+    async fn bundles_are_ordered_by_profit() {
+        let (low, high) = (key(1), key(2));
+        let store = test_store(&[low, high]).await;
+        let blockchain = Blockchain::default_with_store(store.clone());
+
+        let low_tx = signed_tx(&store, &low, 0, GWEI, Address::from_low_u64_be(1));
+        let high_txs = vec![
+            signed_tx(&store, &high, 0, 3 * GWEI, Address::from_low_u64_be(1)),
+            signed_tx(&store, &high, 1, 3 * GWEI, Address::from_low_u64_be(1)),
+        ];
+        blockchain
+            .add_bundle_to_pool(vec![low_tx.clone()], 1, None, None, Vec::new())
+            .await
+            .unwrap();
+        blockchain
+            .add_bundle_to_pool(high_txs.clone(), 1, None, None, Vec::new())
+            .await
+            .unwrap();
+        // Bundles for other blocks are left for later
+        let later_tx = signed_tx(&store, &low, 1, 10 * GWEI, Address::from_low_u64_be(1));
+        blockchain
+            .add_bundle_to_pool(vec![later_tx], 2, None, None, Vec::new())
+            .await
+            .unwrap();
+
+        let block = build_block(&blockchain, &store);
+        assert_eq!(
+            tx_hashes(&block),
+            vec![high_txs[0].hash(), high_txs[1].hash(), low_tx.hash()]
+        );
+    }
+
+    #[tokio::test]
+    async fn bundles_must_target_the_next_blocks() {
+        let sender = key(1);
+        let store = test_store(&[sender]).await;
+        let blockchain = Blockchain::default_with_store(store.clone());
+        let bundle = |block_number| {
+            blockchain.add_bundle_to_pool(
+                vec![signed_tx(&store, &sender, 0, GWEI, Address::zero())],
+                block_number,
+                None,
+                None,
+                Vec::new(),
+            )
+        };
+
+        assert!(matches!(
+            bundle(0).await,
+            Err(MempoolError::InvalidBundle(_))
+        ));
+        assert!(bundle(MAX_BUNDLE_BLOCKS_AHEAD).await.is_ok());
+        assert!(matches!(
+            bundle(MAX_BUNDLE_BLOCKS_AHEAD + 1).await,
+            Err(MempoolError::InvalidBundle(_))
+        ));
+    }
+
+    /// Head transaction with the given tip, created after the previously created ones
+    fn head(tip: u64, included_from_sender: u64) -> HeadTransaction {
+        // Mempool timestamps have microsecond resolution
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            max_priority_fee_per_gas: tip,
+            ..Default::default()
+        });
+        HeadTransaction {
+            tx: MempoolTransaction::new(tx, Address::zero()),
+            tip,
+            included_from_sender,
+        }
+    }
+
+    fn privileged_head(nonce: u64) -> HeadTransaction {
+        let tx = Transaction::PrivilegedL2Transaction(PrivilegedL2Transaction {
+            nonce,
+            ..Default::default()
+        });
+        HeadTransaction {
+            tx: MempoolTransaction::new(tx, Address::zero()),
+            tip: 0,
+            included_from_sender: 0,
+        }
+    }
+
+    #[test]
+    fn tip_ordering_prefers_higher_tips_then_older_transactions() {
+        let older = head(2, 0);
+        let low_tip = head(1, 0);
+        let newer = head(2, 0);
+
+        assert_eq!(TipOrdering.compare(&older, &low_tip), Ordering::Less);
+        assert_eq!(TipOrdering.compare(&low_tip, &newer), Ordering::Greater);
+        assert_eq!(TipOrdering.compare(&older, &newer), Ordering::Less);
+        assert_eq!(TipOrdering.compare(&older, &older), Ordering::Equal);
+    }
+
+    #[test]
+    fn fifo_ordering_prefers_older_transactions() {
+        let older = head(1, 0);
+        let newer = head(5, 0);
+
+        assert_eq!(FifoOrdering.compare(&older, &newer), Ordering::Less);
+        assert_eq!(FifoOrdering.compare(&newer, &older), Ordering::Greater);
+        // On equal arrival time, the higher tip goes first
+        let mut same_time = newer.clone();
+        same_time.tip = 10;
+        assert_eq!(FifoOrdering.compare(&same_time, &newer), Ordering::Less);
+    }
+
+    #[test]
+    fn fair_tip_ordering_serves_senders_with_fewer_included_transactions_first() {
+        let busy_sender = head(5, 1);
+        let new_sender = head(1, 0);
+        let other_busy_sender = head(3, 1);
+
+        assert_eq!(
+            FairTipOrdering.compare(&new_sender, &busy_sender),
+            Ordering::Less
+        );
+        // Among senders with as many included transactions, the higher tip goes first
+        assert_eq!(
+            FairTipOrdering.compare(&busy_sender, &other_busy_sender),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn compare_heads_puts_privileged_transactions_first_in_nonce_order() {
+        let first = privileged_head(1);
+        let second = privileged_head(2);
+        let regular = head(u64::MAX, 0);
+
+        for ordering in [
+            &TipOrdering as &dyn TransactionOrdering,
+            &FifoOrdering,
+            &FairTipOrdering,
+        ] {
+            assert_eq!(compare_heads(ordering, &first, &regular), Ordering::Less);
+            assert_eq!(
+                compare_heads(ordering, &regular, &second),
+                Ordering::Greater
+            );
+            assert_eq!(compare_heads(ordering, &second, &first), Ordering::Greater);
+        }
+        let older = head(1, 0);
+        let newer = head(2, 0);
+        assert_eq!(compare_heads(&FifoOrdering, &older, &newer), Ordering::Less);
+        assert_eq!(
+            compare_heads(&TipOrdering, &older, &newer),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn head_transaction_equality_matches_its_ordering() {
+        let head = head(1, 0);
+        let mut shifted = head.clone();
+        shifted.included_from_sender = 3;
+
+        assert_eq!(head.cmp(&shifted), Ordering::Equal);
+        assert_eq!(head, shifted);
+        let mut higher_tip = head.clone();
+        higher_tip.tip = 2;
+        assert_ne!(head, higher_tip);
+        assert_eq!(higher_tip.cmp(&head), Ordering::Less);
+    }
+}
@@ -0,0 +1,89 @@
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use ethrex_common::{Address, tracing::CallTraceFrame};
+use tracing::{debug, error};
+
+use crate::{Blockchain, error::ChainError};
+
+/// Interval between runs of the trace indexer
+const TRACE_INDEXING_INTERVAL: Duration = Duration::from_secs(12);
+/// Max amount of blocks re-executed to rebuild the state of a block being indexed
+const TRACE_INDEXING_REEXEC: u32 = 128;
+/// Max amount of time spent tracing each transaction of a block being indexed
+const TRACE_INDEXING_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Blockchain {
+    /// Adds the finalized blocks that weren't indexed yet to the trace index, which keeps the
+    /// addresses appearing in the call traces of each block so `trace_filter` doesn't need to
+    /// re-execute whole block ranges.
+    /// The index starts at the finalized block when it's first built, and restarts from the oldest
+    /// block that can still be traced if it fell behind.
+    /// Returns the amount of blocks indexed.
+    pub async fn index_traces(&self) -> Result<u64, ChainError> {
+        if !self.is_synced() {
+            return Ok(0);
+        }
+        let head = self.storage.get_latest_block_number().await?;
+        let finalized = self
+            .storage
+            .get_finalized_block_number()
+            .await?
+            .unwrap_or(head);
+        let oldest_traceable = finalized.saturating_sub(TRACE_INDEXING_REEXEC as u64);
+        let from = match self.storage.get_trace_indexed_range().await? {
+            Some((_, last)) => (last + 1).max(oldest_traceable),
+            None => finalized,
+        }
+        // The genesis block has no parent state to trace it from
+        .max(1);
+
+        let mut indexed = 0;
+        for block_number in from..=finalized {
+            let Some(block) = self.storage.get_block_by_number(block_number).await? else {
+                return Err(ChainError::Custom(format!(
+                    "Block {block_number} not found"
+                )));
+            };
+            let call_traces = self
+                .trace_block_calls(
+                    block,
+                    TRACE_INDEXING_REEXEC,
+                    TRACE_INDEXING_TIMEOUT,
+                    false,
+                    false,
+                )
+                .await?;
+            let mut addresses = BTreeSet::new();
+            for frame in call_traces.iter().flat_map(|(_, call_trace)| call_trace) {
+                collect_call_addresses(frame, &mut addresses);
+            }
+            self.storage
+                .add_trace_index_entries(block_number, addresses.into_iter().collect())
+                .await?;
+            indexed += 1;
+        }
+        Ok(indexed)
+    }
+}
+
+/// Collects the addresses sending and receiving the call frame and its sub-calls
+fn collect_call_addresses(frame: &CallTraceFrame, addresses: &mut BTreeSet<Address>) {
+    addresses.insert(frame.from);
+    addresses.insert(frame.to);
+    for call in &frame.calls {
+        collect_call_addresses(call, addresses);
+    }
+}
+
+/// Periodically adds the newly finalized blocks to the trace index
+pub async fn periodically_index_traces(blockchain: Arc<Blockchain>) {
+    let mut interval = tokio::time::interval(TRACE_INDEXING_INTERVAL);
+    loop {
+        interval.tick().await;
+        match blockchain.index_traces().await {
+            Ok(0) => {}
+            Ok(indexed) => debug!("Indexed the call traces of {indexed} blocks"),
+            Err(err) => error!("Failed to index call traces: {err}"),
+        }
+    }
+}
@@ -3,7 +3,12 @@ use std::{
     time::Duration,
 };
 
-use ethrex_common::{H256, tracing::CallTrace, types::Block};
+use bytes::Bytes;
+use ethrex_common::{
+    H256,
+    tracing::{CallTrace, StateDiff},
+    types::Block,
+};
 use ethrex_storage::Store;
 use ethrex_vm::{Evm, EvmError};
 
@@ -82,6 +87,74 @@ impl Blockchain {
         Ok(call_traces)
     }
 
+    /// Outputs the return data and the state diff of the given transaction
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    pub async fn trace_transaction_state_diff(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<(Bytes, StateDiff), ChainError> {
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        vm.rerun_block(&block, Some(tx_index))?;
+        timeout_trace_operation(timeout, move || vm.trace_tx_state_diff(&block, tx_index)).await
+    }
+
+    /// Outputs the return data and the state diff of each transaction in the block along with the transaction's hash
+    /// May need to re-execute blocks in order to rebuild the block's prestate, up to the amount given by `reexec`
+    /// Returns transaction state diffs from oldest to newest
+    pub async fn trace_block_transactions_state_diff(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<Vec<(H256, Bytes, StateDiff)>, ChainError> {
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run anything necessary before executing the block's transactions (system calls, etc)
+        vm.rerun_block(&block, Some(0))?;
+        // The whole block is traced in a single blocking task, as each diff depends on the previous transactions
+        timeout_trace_operation(timeout, move || {
+            block
+                .body
+                .transactions
+                .iter()
+                .enumerate()
+                .map(|(index, tx)| {
+                    let (output, state_diff) = vm.trace_tx_state_diff(&block, index)?;
+                    Ok((tx.hash(), output, state_diff))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Outputs the changes made to the state by the whole block, including system calls and withdrawals
+    /// May need to re-execute blocks in order to rebuild the block's prestate, up to the amount given by `reexec`
+    pub async fn trace_block_state_diff(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<StateDiff, ChainError> {
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        timeout_trace_operation(timeout, move || vm.trace_block_state_diff(&block)).await
+    }
+
     /// Rebuild the parent state for a block given its parent hash, returning an `Evm` instance with all changes cached
     /// Will re-execute all ancestor block's which's state is not stored up to a maximum given by `reexec`
     async fn rebuild_parent_state(
@@ -0,0 +1,207 @@
+use std::{fs, path::Path};
+
+use ethrex_common::{
+    Address,
+    types::{BlobSchedule, BlockNumber, Genesis, GenesisError},
+};
+use ethrex_p2p::types::Node;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+pub const GENESIS_FILE: &str = "genesis.json";
+pub const BOOTNODES_FILE: &str = "bootnodes.json";
+pub const ENRS_FILE: &str = "enrs.json";
+pub const DEPOSIT_CONTRACT_FILE: &str = "deposit_contract.json";
+pub const BLOB_SCHEDULE_FILE: &str = "blob_schedule.json";
+
+/// Deposit contract of the network, as consensus clients need to know it along with its deployment block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositContract {
+    pub address: Address,
+    pub block: BlockNumber,
+}
+
+/// Everything needed to join a network, loaded from a chain-spec directory laid out as:
+/// - `genesis.json`: the genesis file, the only required one.
+/// - `bootnodes.json`: a list of enode URLs.
+/// - `enrs.json`: a list of ENRs of the network's nodes, also used as bootnodes.
+/// - `deposit_contract.json`: the deposit contract `address` and deployment `block`, overriding
+///   the genesis config's `depositContractAddress`.
+/// - `blob_schedule.json`: the blob schedule, overriding the genesis config's `blobSchedule`.
+///
+/// The override files are only read, for specs written by other tools. Specs written by
+/// [ChainSpec::write] keep those settings in `genesis.json`, so it is the single source of truth.
+///
+/// The embedded public networks under `cmd/ethrex/networks` follow the same layout.
+#[derive(Debug, Clone)]
+pub struct ChainSpec {
+    pub genesis: Genesis,
+    pub bootnodes: Vec<Node>,
+    pub enrs: Vec<String>,
+    pub deposit_contract: Option<DepositContract>,
+}
+
+impl ChainSpec {
+    pub fn load(dir: &Path) -> Result<Self, GenesisError> {
+        let mut genesis = Genesis::try_from(dir.join(GENESIS_FILE).as_path())?;
+        let bootnodes = read_optional_file(&dir.join(BOOTNODES_FILE))?.unwrap_or_default();
+        let enrs: Vec<String> = read_optional_file(&dir.join(ENRS_FILE))?.unwrap_or_default();
+        // Fail early on invalid records instead of when connecting to the network
+        parse_enrs(&enrs)?;
+
+        let deposit_contract: Option<DepositContract> =
+            read_optional_file(&dir.join(DEPOSIT_CONTRACT_FILE))?;
+        if let Some(deposit_contract) = deposit_contract {
+            genesis.config.deposit_contract_address = deposit_contract.address;
+        }
+        if let Some(blob_schedule) =
+            read_optional_file::<BlobSchedule>(&dir.join(BLOB_SCHEDULE_FILE))?
+        {
+            genesis.config.blob_schedule = blob_schedule;
+        }
+
+        Ok(Self {
+            genesis,
+            bootnodes,
+            enrs,
+            deposit_contract,
+        })
+    }
+
+    /// Writes the spec to `dir`, creating it if needed. The deposit contract address is
+    /// written to the genesis config, and its file is only written to keep the deployment block.
+    /// Override files left by a previous spec in `dir` are removed, so they can't override the
+    /// written genesis.
+    pub fn write(&self, dir: &Path) -> Result<(), GenesisError> {
+        fs::create_dir_all(dir)?;
+        let mut genesis = self.genesis.clone();
+        if let Some(deposit_contract) = self.deposit_contract {
+            genesis.config.deposit_contract_address = deposit_contract.address;
+            write_file(&dir.join(DEPOSIT_CONTRACT_FILE), &deposit_contract)?;
+        } else {
+            remove_file_if_exists(&dir.join(DEPOSIT_CONTRACT_FILE))?;
+        }
+        remove_file_if_exists(&dir.join(BLOB_SCHEDULE_FILE))?;
+        write_file(&dir.join(GENESIS_FILE), &genesis)?;
+        write_file(&dir.join(BOOTNODES_FILE), &self.bootnodes)?;
+        write_file(&dir.join(ENRS_FILE), &self.enrs)
+    }
+
+    /// Returns the nodes to bootstrap from, both the enode and ENR ones
+    pub fn all_bootnodes(&self) -> Result<Vec<Node>, GenesisError> {
+        let mut bootnodes = self.bootnodes.clone();
+        bootnodes.extend(parse_enrs(&self.enrs)?);
+        Ok(bootnodes)
+    }
+}
+
+fn parse_enrs(enrs: &[String]) -> Result<Vec<Node>, GenesisError> {
+    enrs.iter()
+        .map(|enr| {
+            Ok(serde_json::from_value(serde_json::Value::String(
+                enr.clone(),
+            ))?)
+        })
+        .collect()
+}
+
+fn read_optional_file<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, GenesisError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+}
+
+fn write_file<T: Serialize>(path: &Path, contents: &T) -> Result<(), GenesisError> {
+    fs::write(path, serde_json::to_string_pretty(contents)?)?;
+    Ok(())
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<(), GenesisError> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networks::LOCAL_DEVNET_GENESIS_CONTENTS;
+
+    #[test]
+    fn chain_spec_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis: Genesis = serde_json::from_str(LOCAL_DEVNET_GENESIS_CONTENTS).unwrap();
+        let spec = ChainSpec {
+            genesis: genesis.clone(),
+            bootnodes: vec![],
+            enrs: vec!["enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8".to_string()],
+            deposit_contract: Some(DepositContract {
+                address: Address::repeat_byte(0x42),
+                block: 7,
+            }),
+        };
+        spec.write(dir.path()).unwrap();
+
+        let loaded = ChainSpec::load(dir.path()).unwrap();
+        assert_eq!(loaded.deposit_contract, spec.deposit_contract);
+        assert_eq!(
+            loaded.genesis.config.deposit_contract_address,
+            Address::repeat_byte(0x42)
+        );
+        assert_eq!(
+            loaded.genesis.config.blob_schedule,
+            genesis.config.blob_schedule
+        );
+        assert_eq!(loaded.all_bootnodes().unwrap().len(), 1);
+
+        // The written genesis agrees with the deposit contract file
+        let written = Genesis::try_from(dir.path().join(GENESIS_FILE).as_path()).unwrap();
+        assert_eq!(written.config, loaded.genesis.config);
+        assert!(!dir.path().join(BLOB_SCHEDULE_FILE).exists());
+    }
+
+    #[test]
+    fn rewriting_removes_stale_override_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis: Genesis = serde_json::from_str(LOCAL_DEVNET_GENESIS_CONTENTS).unwrap();
+        let mut spec = ChainSpec {
+            genesis: genesis.clone(),
+            bootnodes: vec![],
+            enrs: vec![],
+            deposit_contract: Some(DepositContract {
+                address: Address::repeat_byte(0x42),
+                block: 7,
+            }),
+        };
+        spec.write(dir.path()).unwrap();
+        fs::write(
+            dir.path().join(BLOB_SCHEDULE_FILE),
+            serde_json::to_string(&BlobSchedule::default()).unwrap(),
+        )
+        .unwrap();
+
+        spec.deposit_contract = None;
+        spec.write(dir.path()).unwrap();
+
+        assert!(!dir.path().join(DEPOSIT_CONTRACT_FILE).exists());
+        assert!(!dir.path().join(BLOB_SCHEDULE_FILE).exists());
+        let loaded = ChainSpec::load(dir.path()).unwrap();
+        assert!(loaded.deposit_contract.is_none());
+        assert_eq!(loaded.genesis.config, genesis.config);
+    }
+
+    #[test]
+    fn only_genesis_is_required() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(GENESIS_FILE), LOCAL_DEVNET_GENESIS_CONTENTS).unwrap();
+
+        let spec = ChainSpec::load(dir.path()).unwrap();
+        assert!(spec.all_bootnodes().unwrap().is_empty());
+        assert!(spec.deposit_contract.is_none());
+
+        fs::remove_file(dir.path().join(GENESIS_FILE)).unwrap();
+        assert!(ChainSpec::load(dir.path()).is_err());
+    }
+}
@@ -1 +1,2 @@
+pub mod chain_spec;
 pub mod networks;
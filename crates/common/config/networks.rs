@@ -1,12 +1,14 @@
 use ethrex_p2p::types::Node;
 use std::{
     fmt::{self},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use ethrex_common::types::{ChainConfig, Genesis, GenesisError};
+use ethrex_common::types::{BlockNumber, ChainConfig, Genesis, GenesisError};
 use serde::{Deserialize, Serialize};
 
+use crate::chain_spec::ChainSpec;
+
 //TODO: Look for a better place to move these files
 const MAINNET_BOOTNODES: &str = include_str!("../../../cmd/ethrex/networks/mainnet/bootnodes.json");
 const HOLESKY_BOOTNODES: &str = include_str!("../../../cmd/ethrex/networks/holesky/bootnodes.json");
@@ -33,6 +35,11 @@ pub const HOLESKY_CHAIN_ID: u64 = 0x4268;
 pub const HOODI_CHAIN_ID: u64 = 0x88bb0;
 pub const SEPOLIA_CHAIN_ID: u64 = 0xAA36A7;
 
+// History before these blocks can be dropped with `--history.expiry postmerge`
+// Values taken from geth's history prune points
+pub const MAINNET_MERGE_BLOCK: u64 = 15_537_393;
+pub const SEPOLIA_MERGE_BLOCK: u64 = 1_450_409;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Network {
     PublicNetwork(PublicNetwork),
@@ -41,6 +48,9 @@ pub enum Network {
     L2Chain(u64),
     #[serde(skip)]
     GenesisPath(PathBuf),
+    /// A directory with the genesis file, bootnodes and settings of the network, see [`ChainSpec`]
+    #[serde(skip)]
+    ChainSpec(PathBuf),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -59,6 +69,7 @@ impl From<&str> for Network {
             "mainnet" => Network::PublicNetwork(PublicNetwork::Mainnet),
             "sepolia" => Network::PublicNetwork(PublicNetwork::Sepolia),
             // Note that we don't allow to manually specify the local devnet genesis
+            s if Path::new(s).is_dir() => Network::ChainSpec(PathBuf::from(s)),
             s => Network::GenesisPath(PathBuf::from(s)),
         }
     }
@@ -80,7 +91,11 @@ impl TryFrom<u64> for Network {
 
 impl From<PathBuf> for Network {
     fn from(value: PathBuf) -> Self {
-        Network::GenesisPath(value)
+        if value.is_dir() {
+            Network::ChainSpec(value)
+        } else {
+            Network::GenesisPath(value)
+        }
     }
 }
 
@@ -100,7 +115,9 @@ impl fmt::Display for Network {
             Network::LocalDevnet => write!(f, "local-devnet"),
             Network::LocalDevnetL2 => write!(f, "local-devnet-l2"),
             Network::L2Chain(chain_id) => write!(f, "l2-chain-{}", chain_id),
-            Network::GenesisPath(path_buf) => write!(f, "{path_buf:?}"),
+            Network::GenesisPath(path_buf) | Network::ChainSpec(path_buf) => {
+                write!(f, "{path_buf:?}")
+            }
         }
     }
 }
@@ -126,18 +143,38 @@ impl Network {
                 ..Default::default()
             }),
             Network::GenesisPath(s) => Genesis::try_from(s.as_path()),
+            Network::ChainSpec(dir) => Ok(ChainSpec::load(dir)?.genesis),
         }
     }
 
-    pub fn get_bootnodes(&self) -> Vec<Node> {
+    /// Returns the merge block of the network, before which history can be expired (EIP-4444)
+    pub fn get_merge_block(&self) -> Result<Option<BlockNumber>, GenesisError> {
+        match self {
+            Network::PublicNetwork(PublicNetwork::Mainnet) => Ok(Some(MAINNET_MERGE_BLOCK)),
+            Network::PublicNetwork(PublicNetwork::Sepolia) => Ok(Some(SEPOLIA_MERGE_BLOCK)),
+            // These networks started after the merge
+            Network::PublicNetwork(PublicNetwork::Holesky | PublicNetwork::Hoodi)
+            | Network::LocalDevnet
+            | Network::LocalDevnetL2
+            | Network::L2Chain(_) => Ok(Some(0)),
+            Network::GenesisPath(_) | Network::ChainSpec(_) => {
+                Ok(self.get_genesis()?.config.merge_netsplit_block)
+            }
+        }
+    }
+
+    /// Returns the preset bootnodes of the network, failing only if a chain-spec directory can't
+    /// be loaded
+    pub fn get_bootnodes(&self) -> Result<Vec<Node>, GenesisError> {
         let bootnodes = match self {
             Network::PublicNetwork(PublicNetwork::Holesky) => HOLESKY_BOOTNODES,
             Network::PublicNetwork(PublicNetwork::Hoodi) => HOODI_BOOTNODES,
             Network::PublicNetwork(PublicNetwork::Mainnet) => MAINNET_BOOTNODES,
             Network::PublicNetwork(PublicNetwork::Sepolia) => SEPOLIA_BOOTNODES,
-            _ => return vec![],
+            Network::ChainSpec(dir) => return ChainSpec::load(dir)?.all_bootnodes(),
+            _ => return Ok(vec![]),
         };
-        serde_json::from_str(bootnodes).expect("bootnodes file should be valid JSON")
+        Ok(serde_json::from_str(bootnodes).expect("bootnodes file should be valid JSON"))
     }
 }
 
@@ -205,9 +242,32 @@ mod tests {
 
     #[test]
     fn test_get_bootnodes_works_for_public_networks() {
-        Network::PublicNetwork(PublicNetwork::Holesky).get_bootnodes();
-        Network::PublicNetwork(PublicNetwork::Hoodi).get_bootnodes();
-        Network::PublicNetwork(PublicNetwork::Mainnet).get_bootnodes();
-        Network::PublicNetwork(PublicNetwork::Sepolia).get_bootnodes();
+        Network::PublicNetwork(PublicNetwork::Holesky)
+            .get_bootnodes()
+            .unwrap();
+        Network::PublicNetwork(PublicNetwork::Hoodi)
+            .get_bootnodes()
+            .unwrap();
+        Network::PublicNetwork(PublicNetwork::Mainnet)
+            .get_bootnodes()
+            .unwrap();
+        Network::PublicNetwork(PublicNetwork::Sepolia)
+            .get_bootnodes()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_directories_are_loaded_as_chain_specs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(crate::chain_spec::GENESIS_FILE),
+            LOCAL_DEVNET_GENESIS_CONTENTS,
+        )
+        .unwrap();
+
+        let network = Network::from(dir.path().to_str().unwrap());
+        assert_eq!(network, Network::ChainSpec(dir.path().to_path_buf()));
+        assert_eq!(network.get_genesis().unwrap().config.chain_id, 9);
+        assert!(network.get_bootnodes().unwrap().is_empty());
     }
 }
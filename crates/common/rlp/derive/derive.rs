@@ -0,0 +1,279 @@
+//! Derive macros for the `RLPEncode` and `RLPDecode` traits of `ethrex-rlp`.
+//!
+//! Structs and tuple structs are encoded as an RLP list of their fields, in declaration
+//! order, the same way the `Encoder` and `Decoder` helpers of `ethrex_rlp::structs` do.
+//!
+//! Supported attributes:
+//! - `#[rlp(transparent)]` on a struct with a single field encodes it as that field, without
+//!   wrapping it in a list.
+//! - `#[rlp(optional)]` on an `Option` field encodes it only when it is `Some`, and decodes it
+//!   as `None` if the list ends before it. As with fork-dependent header fields, every field
+//!   after an optional one must also be optional.
+//! - `#[rlp(skip)]` leaves a field out of the encoding, and decodes it as `Default::default()`.
+//!
+//! ```
+//! use ethrex_rlp::{decode::RLPDecode, encode::RLPEncode};
+//! use ethrex_rlp_derive::{RLPDecode, RLPEncode};
+//!
+//! #[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+//! struct Header {
+//!     number: u64,
+//!     #[rlp(skip)]
+//!     cached_size: usize,
+//!     #[rlp(optional)]
+//!     base_fee: Option<u64>,
+//! }
+//!
+//! let header = Header { number: 1, cached_size: 0, base_fee: None };
+//! assert_eq!(Header::decode(&header.encode_to_vec()).unwrap(), header);
+//! ```
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Generics, Ident, Index, Member, Path,
+    parse_macro_input, parse_quote,
+};
+
+#[proc_macro_derive(RLPEncode, attributes(rlp))]
+pub fn derive_rlp_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(RLPDecode, attributes(rlp))]
+pub fn derive_rlp_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Required,
+    Optional,
+    Skip,
+}
+
+struct RlpField {
+    member: Member,
+    name: String,
+    kind: FieldKind,
+}
+
+struct RlpStruct {
+    transparent: bool,
+    fields: Vec<RlpField>,
+}
+
+fn parse_struct(input: &DeriveInput) -> syn::Result<(RlpStruct, &Fields)> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "RLP derives only support structs",
+        ));
+    };
+
+    let mut transparent = false;
+    for attr in rlp_attributes(&input.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                transparent = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown rlp attribute, expected `transparent`"))
+            }
+        })?;
+    }
+
+    let mut fields = Vec::with_capacity(data.fields.len());
+    let mut after_optional = false;
+    for (index, field) in data.fields.iter().enumerate() {
+        let mut kind = FieldKind::Required;
+        for attr in rlp_attributes(&field.attrs) {
+            attr.parse_nested_meta(|meta| {
+                let new_kind = if meta.path.is_ident("optional") {
+                    FieldKind::Optional
+                } else if meta.path.is_ident("skip") {
+                    FieldKind::Skip
+                } else {
+                    return Err(meta.error("unknown rlp attribute, expected `optional` or `skip`"));
+                };
+                if kind != FieldKind::Required {
+                    return Err(meta.error("a field can be either `optional` or `skip`"));
+                }
+                kind = new_kind;
+                Ok(())
+            })?;
+        }
+
+        match kind {
+            FieldKind::Optional => after_optional = true,
+            FieldKind::Required if after_optional => {
+                return Err(Error::new_spanned(
+                    field,
+                    "fields after an `#[rlp(optional)]` field must also be optional",
+                ));
+            }
+            _ => {}
+        }
+
+        let (member, name) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.to_string()),
+            None => (Member::Unnamed(Index::from(index)), index.to_string()),
+        };
+        fields.push(RlpField { member, name, kind });
+    }
+
+    if transparent {
+        let mut encoded_fields = fields.iter().filter(|field| field.kind != FieldKind::Skip);
+        let valid = matches!(
+            (encoded_fields.next(), encoded_fields.next()),
+            (Some(field), None) if field.kind == FieldKind::Required
+        );
+        if !valid {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`#[rlp(transparent)]` requires exactly one field that is neither `optional` nor `skip`",
+            ));
+        }
+    }
+
+    Ok((
+        RlpStruct {
+            transparent,
+            fields,
+        },
+        &data.fields,
+    ))
+}
+
+fn rlp_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("rlp"))
+}
+
+/// Adds a `T: bound` predicate for every type parameter of the struct
+fn add_trait_bounds(generics: &Generics, bound: &Path) -> Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (rlp_struct, _) = parse_struct(input)?;
+    let ident = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::ethrex_rlp::encode::RLPEncode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut encoded_fields = rlp_struct
+        .fields
+        .iter()
+        .filter(|field| field.kind != FieldKind::Skip);
+
+    let body = if rlp_struct.transparent {
+        let Some(RlpField { member, .. }) = encoded_fields.next() else {
+            return Err(Error::new_spanned(ident, "missing transparent field"));
+        };
+        quote! {
+            fn encode(&self, buf: &mut dyn ::ethrex_rlp::__private::BufMut) {
+                ::ethrex_rlp::encode::RLPEncode::encode(&self.#member, buf)
+            }
+
+            fn length(&self) -> usize {
+                ::ethrex_rlp::encode::RLPEncode::length(&self.#member)
+            }
+        }
+    } else {
+        let calls = encoded_fields.map(|RlpField { member, kind, .. }| match kind {
+            FieldKind::Optional => quote!(.encode_optional_field(&self.#member)),
+            _ => quote!(.encode_field(&self.#member)),
+        });
+        quote! {
+            fn encode(&self, buf: &mut dyn ::ethrex_rlp::__private::BufMut) {
+                ::ethrex_rlp::structs::Encoder::new(buf)
+                    #(#calls)*
+                    .finish();
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ethrex_rlp::encode::RLPEncode for #ident #ty_generics #where_clause {
+            #body
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (rlp_struct, fields) = parse_struct(input)?;
+    let ident = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::ethrex_rlp::decode::RLPDecode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let variables: Vec<Ident> = (0..rlp_struct.fields.len())
+        .map(|index| format_ident!("field_{}", index))
+        .collect();
+
+    let decode_fields = rlp_struct.fields.iter().zip(&variables).map(
+        |(RlpField { name, kind, .. }, variable)| match kind {
+            FieldKind::Skip => quote! {
+                let #variable = ::core::default::Default::default();
+            },
+            FieldKind::Required if rlp_struct.transparent => quote! {
+                let (#variable, rest) = ::ethrex_rlp::decode::RLPDecode::decode_unfinished(rlp)?;
+            },
+            FieldKind::Required => quote! {
+                let (#variable, decoder) = decoder.decode_field(#name)?;
+            },
+            FieldKind::Optional => quote! {
+                let (#variable, decoder) = decoder.decode_optional_field();
+            },
+        },
+    );
+
+    let members = rlp_struct.fields.iter().map(|field| &field.member);
+    let value = match fields {
+        Fields::Named(_) => quote!(Self { #(#members: #variables),* }),
+        Fields::Unnamed(_) => quote!(Self( #(#variables),* )),
+        Fields::Unit => quote!(Self),
+    };
+
+    let body = if rlp_struct.transparent {
+        quote! {
+            #(#decode_fields)*
+            ::core::result::Result::Ok((#value, rest))
+        }
+    } else {
+        quote! {
+            let decoder = ::ethrex_rlp::structs::Decoder::new(rlp)?;
+            #(#decode_fields)*
+            ::core::result::Result::Ok((#value, decoder.finish()?))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ethrex_rlp::decode::RLPDecode for #ident #ty_generics #where_clause {
+            fn decode_unfinished(
+                rlp: &[u8],
+            ) -> ::core::result::Result<(Self, &[u8]), ::ethrex_rlp::error::RLPDecodeError> {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,291 @@
+//! Checks that the derived impls encode and decode exactly like the manual ones in
+//! `ethrex-common`.
+use std::fmt::Debug;
+
+use ethrex_common::{
+    Address, Bloom, Bytes, H32, H256, U256,
+    types::{
+        AccountState, BlockBody, BlockHeader, EIP1559Transaction, ForkId, Log, Transaction, TxKind,
+        Withdrawal, requests::EncodedRequests,
+    },
+};
+use ethrex_rlp::{decode::RLPDecode, encode::RLPEncode, error::RLPDecodeError};
+use ethrex_rlp_derive::{RLPDecode, RLPEncode};
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedWithdrawal {
+    index: u64,
+    validator_index: u64,
+    address: Address,
+    amount: u64,
+}
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedAccountState {
+    nonce: u64,
+    balance: U256,
+    storage_root: H256,
+    code_hash: H256,
+}
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedLog {
+    address: Address,
+    topics: Vec<H256>,
+    data: Bytes,
+}
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedForkId(H32, u64);
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+#[rlp(transparent)]
+struct DerivedEncodedRequests(Bytes);
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedBlockBody {
+    transactions: Vec<Transaction>,
+    ommers: Vec<BlockHeader>,
+    #[rlp(optional)]
+    withdrawals: Option<Vec<Withdrawal>>,
+}
+
+#[derive(Debug, PartialEq, RLPEncode, RLPDecode)]
+struct DerivedBlockHeader {
+    #[rlp(skip)]
+    hash: Option<H256>,
+    parent_hash: H256,
+    ommers_hash: H256,
+    coinbase: Address,
+    state_root: H256,
+    transactions_root: H256,
+    receipts_root: H256,
+    logs_bloom: Bloom,
+    difficulty: U256,
+    number: u64,
+    gas_limit: u64,
+    gas_used: u64,
+    timestamp: u64,
+    extra_data: Bytes,
+    prev_randao: H256,
+    nonce: [u8; 8],
+    #[rlp(optional)]
+    base_fee_per_gas: Option<u64>,
+    #[rlp(optional)]
+    withdrawals_root: Option<H256>,
+    #[rlp(optional)]
+    blob_gas_used: Option<u64>,
+    #[rlp(optional)]
+    excess_blob_gas: Option<u64>,
+    #[rlp(optional)]
+    parent_beacon_block_root: Option<H256>,
+    #[rlp(optional)]
+    requests_hash: Option<H256>,
+}
+
+impl From<&BlockHeader> for DerivedBlockHeader {
+    fn from(header: &BlockHeader) -> Self {
+        Self {
+            hash: None,
+            parent_hash: header.parent_hash,
+            ommers_hash: header.ommers_hash,
+            coinbase: header.coinbase,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            difficulty: header.difficulty,
+            number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            extra_data: header.extra_data.clone(),
+            prev_randao: header.prev_randao,
+            nonce: header.nonce.to_be_bytes(),
+            base_fee_per_gas: header.base_fee_per_gas,
+            withdrawals_root: header.withdrawals_root,
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
+            parent_beacon_block_root: header.parent_beacon_block_root,
+            requests_hash: header.requests_hash,
+        }
+    }
+}
+
+/// Asserts that both values have the same encoding and that each one decodes the other's
+fn assert_round_trip<M, D>(manual: &M, derived: &D)
+where
+    M: RLPEncode + RLPDecode + PartialEq + Debug,
+    D: RLPEncode + RLPDecode + PartialEq + Debug,
+{
+    let encoded = manual.encode_to_vec();
+    assert_eq!(derived.encode_to_vec(), encoded);
+    assert_eq!(derived.length(), manual.length());
+    assert_eq!(&D::decode(&encoded).unwrap(), derived);
+    assert_eq!(&M::decode(&derived.encode_to_vec()).unwrap(), manual);
+}
+
+fn example_withdrawal() -> Withdrawal {
+    Withdrawal {
+        index: 7,
+        validator_index: 1234,
+        address: Address::from_low_u64_be(0xbeef),
+        amount: 32_000_000_000,
+    }
+}
+
+fn example_header() -> BlockHeader {
+    BlockHeader {
+        parent_hash: H256::from_low_u64_be(1),
+        coinbase: Address::from_low_u64_be(2),
+        state_root: H256::from_low_u64_be(3),
+        number: 21_000_000,
+        gas_limit: 36_000_000,
+        gas_used: 1_234_567,
+        timestamp: 1_718_232_101,
+        extra_data: Bytes::from_static(b"ethrex"),
+        nonce: 0x42,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn withdrawal() {
+    let withdrawal = example_withdrawal();
+    let derived = DerivedWithdrawal {
+        index: withdrawal.index,
+        validator_index: withdrawal.validator_index,
+        address: withdrawal.address,
+        amount: withdrawal.amount,
+    };
+    assert_round_trip(&withdrawal, &derived);
+}
+
+#[test]
+fn account_state() {
+    let account_state = AccountState {
+        nonce: 3,
+        balance: U256::from(10).pow(U256::from(18)),
+        storage_root: H256::from_low_u64_be(4),
+        code_hash: H256::from_low_u64_be(5),
+    };
+    let derived = DerivedAccountState {
+        nonce: account_state.nonce,
+        balance: account_state.balance,
+        storage_root: account_state.storage_root,
+        code_hash: account_state.code_hash,
+    };
+    assert_round_trip(&account_state, &derived);
+}
+
+#[test]
+fn log() {
+    let log = Log {
+        address: Address::from_low_u64_be(6),
+        topics: vec![H256::from_low_u64_be(7), H256::from_low_u64_be(8)],
+        data: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+    };
+    let derived = DerivedLog {
+        address: log.address,
+        topics: log.topics.clone(),
+        data: log.data.clone(),
+    };
+    assert_round_trip(&log, &derived);
+}
+
+#[test]
+fn tuple_struct() {
+    let fork_id = ForkId {
+        fork_hash: H32::from_low_u64_be(0xfc64ec04),
+        fork_next: 1_150_000,
+    };
+    let derived = DerivedForkId(fork_id.fork_hash, fork_id.fork_next);
+    assert_round_trip(&fork_id, &derived);
+}
+
+#[test]
+fn transparent_newtype() {
+    let requests = EncodedRequests(Bytes::from_static(&[0x00, 0x01, 0x02]));
+    let derived = DerivedEncodedRequests(requests.0.clone());
+
+    let encoded = requests.encode_to_vec();
+    assert_eq!(derived.encode_to_vec(), encoded);
+    assert_eq!(derived.encode_to_vec(), requests.0.encode_to_vec());
+    assert_eq!(DerivedEncodedRequests::decode(&encoded).unwrap(), derived);
+    assert_eq!(
+        EncodedRequests::decode(&derived.encode_to_vec()).unwrap().0,
+        requests.0
+    );
+}
+
+#[test]
+fn optional_trailing_fields() {
+    let transaction = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        nonce: 1,
+        gas_limit: 21_000,
+        to: TxKind::Call(Address::from_low_u64_be(9)),
+        value: U256::one(),
+        ..Default::default()
+    });
+    let with_withdrawals = BlockBody {
+        transactions: vec![transaction],
+        ommers: Vec::new(),
+        withdrawals: Some(vec![example_withdrawal()]),
+    };
+    let without_withdrawals = BlockBody {
+        withdrawals: None,
+        ..with_withdrawals.clone()
+    };
+
+    for body in [with_withdrawals, without_withdrawals] {
+        let derived = DerivedBlockBody {
+            transactions: body.transactions.clone(),
+            ommers: body.ommers.clone(),
+            withdrawals: body.withdrawals.clone(),
+        };
+        assert_round_trip(&body, &derived);
+    }
+}
+
+#[test]
+fn fork_dependent_header_fields() {
+    // Pre-London, Cancun and Prague headers
+    let pre_london = example_header();
+    let cancun = BlockHeader {
+        base_fee_per_gas: Some(7),
+        withdrawals_root: Some(H256::from_low_u64_be(10)),
+        blob_gas_used: Some(131_072),
+        excess_blob_gas: Some(0),
+        parent_beacon_block_root: Some(H256::from_low_u64_be(11)),
+        ..example_header()
+    };
+    let prague = BlockHeader {
+        requests_hash: Some(H256::from_low_u64_be(12)),
+        ..cancun.clone()
+    };
+
+    for header in [pre_london, cancun, prague] {
+        assert_round_trip(&header, &DerivedBlockHeader::from(&header));
+    }
+}
+
+#[test]
+fn skipped_fields_are_not_encoded() {
+    let header = example_header();
+    let derived = DerivedBlockHeader {
+        hash: Some(header.hash()),
+        ..DerivedBlockHeader::from(&header)
+    };
+    assert_eq!(derived.encode_to_vec(), header.encode_to_vec());
+    // Skipped fields are decoded with their default value
+    let decoded = DerivedBlockHeader::decode(&derived.encode_to_vec()).unwrap();
+    assert_eq!(decoded.hash, None);
+}
+
+#[test]
+fn missing_required_field_fails() {
+    let encoded = DerivedForkId(H32::zero(), 0).encode_to_vec();
+    let result = DerivedWithdrawal::decode(&encoded);
+    assert!(matches!(result, Err(RLPDecodeError::Custom(_))));
+}
@@ -3,3 +3,9 @@ pub mod decode;
 pub mod encode;
 pub mod error;
 pub mod structs;
+
+// Used by the code generated by `ethrex-rlp-derive`, not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use bytes::BufMut;
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bytes::Bytes;
 use ethereum_types::H256;
 use ethereum_types::{Address, U256};
@@ -65,3 +67,26 @@ pub struct CallLog {
     pub data: Bytes,
     pub position: u64,
 }
+
+/// Changes made to the state by a transaction or block, indexed by the address of each
+/// changed account
+pub type StateDiff = BTreeMap<Address, AccountStateDiff>;
+
+/// Changes made to a single account, along with the values they replaced
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateDiff {
+    /// Account state before the change, `None` if the account didn't exist
+    pub pre: Option<AccountStateSnapshot>,
+    /// Account state after the change, `None` if the account was removed
+    pub post: Option<AccountStateSnapshot>,
+    /// Storage slots whose value changed, mapped to their value before and after the change
+    pub storage: BTreeMap<H256, (U256, U256)>,
+}
+
+/// Balance, nonce and code of an account at one side of a state diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateSnapshot {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code: Bytes,
+}
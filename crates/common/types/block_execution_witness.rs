@@ -339,6 +339,32 @@ impl GuestProgramState {
         Ok(Some(state))
     }
 
+    /// Retrieves the account state from the state trie, failing if the witness doesn't
+    /// contain the nodes needed to prove the account's state (or its absence).
+    pub fn get_account_state_checked(
+        &mut self,
+        address: Address,
+    ) -> Result<Option<AccountState>, GuestProgramStateError> {
+        let hashed_address = self
+            .account_hashes_by_address
+            .entry(address)
+            .or_insert_with(|| hash_address(&address));
+
+        let Some(encoded_state) = self.state_trie.get(hashed_address).map_err(|_| {
+            GuestProgramStateError::Database(format!(
+                "Account {address:#x} is not proven by the execution witness"
+            ))
+        })?
+        else {
+            return Ok(None);
+        };
+        let state = AccountState::decode(&encoded_state).map_err(|_| {
+            GuestProgramStateError::Database("Failed to get decode account from trie".to_string())
+        })?;
+
+        Ok(Some(state))
+    }
+
     /// Fetches the block hash for a specific block number.
     /// Looks up `self.block_headers` and computes the hash if it is not already computed.
     pub fn get_block_hash(&self, block_number: u64) -> Result<H256, GuestProgramStateError> {
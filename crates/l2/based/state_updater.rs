@@ -16,7 +16,7 @@ use tracing::{debug, error, info, warn};
 use crate::{
     SequencerConfig,
     based::sequencer_state::{SequencerState, SequencerStatus},
-    sequencer::utils::node_is_up_to_date,
+    sequencer::utils::{node_is_up_to_date, track_lead_sequencer},
     utils::parse::hash_to_address,
 };
 
@@ -123,6 +123,8 @@ impl StateUpdater {
                 })?,
         );
 
+        self.track_lead_sequencer(lead_sequencer).await?;
+
         let node_is_up_to_date = node_is_up_to_date::<StateUpdaterError>(
             &self.eth_client,
             self.on_chain_proposer_address,
@@ -173,6 +175,19 @@ impl StateUpdater {
         Ok(())
     }
 
+    /// Records the lead sequencer in charge of the blocks after the last committed batch, so
+    /// the P2P layer can verify the signatures of the blocks and batches it receives.
+    async fn track_lead_sequencer(&self, lead_sequencer: Address) -> Result<(), StateUpdaterError> {
+        let last_committed_batch =
+            get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+        if let Some(first_block) =
+            track_lead_sequencer(&self.rollup_store, last_committed_batch, lead_sequencer).await?
+        {
+            info!("Lead sequencer {lead_sequencer:#x} in charge from block {first_block}");
+        }
+        Ok(())
+    }
+
     /// Reverts state to the last committed batch if known.
     async fn revert_uncommitted_state(&mut self) -> Result<(), StateUpdaterError> {
         let last_l2_committed_batch =
@@ -0,0 +1,421 @@
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use ethrex_common::types::{
+    BlockHeader, ChainConfig, Fork, Transaction, TxKind, TxType, fee_config::FeeConfig,
+};
+use ethrex_common::utils::keccak;
+use ethrex_levm::{
+    constants::{INIT_CODE_MAX_SIZE, POST_OSAKA_GAS_LIMIT_CAP, TX_BASE_COST, WORD_SIZE},
+    gas_cost::{
+        ACCESS_LIST_ADDRESS_COST, ACCESS_LIST_STORAGE_KEY_COST, CREATE_BASE_COST,
+        STANDARD_TOKEN_COST, TOTAL_COST_FLOOR_PER_TOKEN, tx_calldata,
+    },
+    utils::code_has_delegation,
+};
+
+use crate::privileged_transactions::{
+    PrivilegedTransactionError, compute_privileged_transactions_hash,
+};
+
+/// Max forced tx to process per batch
+pub const FORCED_TX_BUDGET: u64 = 100;
+
+/// Intrinsic gas of each EIP-7702 authorization, as charged by the VM
+const PER_AUTHORIZATION_COST: u64 = ethrex_levm::constants::PER_EMPTY_ACCOUNT_COST;
+
+/// Hash under which a forced transaction is queued in the CommonBridge, which is the
+/// L2 transaction hash when the transaction is canonically encoded
+pub fn get_forced_transaction_hash(raw_transaction: &[u8]) -> H256 {
+    keccak(raw_transaction)
+}
+
+/// Versioned hash of the forced transactions processed by a batch, with the same
+/// layout as the one used for privileged transactions:
+/// `amount of transactions (2 bytes) || keccak(hashes)[2..32]`
+pub fn compute_forced_transactions_hash(
+    forced_transaction_hashes: Vec<H256>,
+) -> Result<H256, PrivilegedTransactionError> {
+    compute_privileged_transactions_hash(forced_transaction_hashes)
+}
+
+/// Decodes a forced transaction as it was sent to the CommonBridge and recovers its sender.
+/// Returns `None` if the transaction can never be included in the L2: it isn't canonically
+/// encoded, it's of a type users can't send, it's signed for another chain or its
+/// signature is invalid.
+pub fn decode_forced_transaction(
+    raw_transaction: &[u8],
+    chain_id: u64,
+) -> Option<(Transaction, Address)> {
+    let tx = Transaction::decode_canonical(raw_transaction).ok()?;
+    if tx.encode_canonical_to_vec() != raw_transaction {
+        return None;
+    }
+    if matches!(
+        tx.tx_type(),
+        TxType::EIP4844 | TxType::FeeToken | TxType::Privileged
+    ) {
+        return None;
+    }
+    // Signature values that don't fit in a u64 can't be recovered
+    if let Transaction::LegacyTransaction(legacy_tx) = &tx
+        && legacy_tx.v > U256::from(u32::MAX)
+    {
+        return None;
+    }
+    if tx
+        .chain_id()
+        .is_some_and(|tx_chain_id| tx_chain_id != chain_id)
+    {
+        return None;
+    }
+    let sender = tx.sender().ok()?;
+    Some((tx, sender))
+}
+
+/// State of the sender of a forced transaction on top of which it would be included
+#[derive(Debug, Clone, Default)]
+pub struct ForcedTransactionSender {
+    pub nonce: u64,
+    pub balance: U256,
+    pub code: Bytes,
+}
+
+/// Returns whether a decoded forced transaction can be included in a block with the given
+/// `header`, on top of a state where its sender is `sender`.
+///
+/// This mirrors the validations the VM runs before executing a transaction, so a forced
+/// transaction is includable if and only if executing it can't fail validation. A forced
+/// transaction that isn't includable on top of the final state of a batch can be skipped
+/// by it, as the sender's nonce can't go back and only the sender can spend its balance.
+pub fn is_forced_transaction_includable(
+    tx: &Transaction,
+    sender: &ForcedTransactionSender,
+    header: &BlockHeader,
+    chain_config: &ChainConfig,
+    fee_config: &FeeConfig,
+) -> bool {
+    let fork = chain_config.get_fork(header.timestamp);
+    let gas_limit = tx.gas_limit();
+    let Some(intrinsic_gas) = intrinsic_gas(tx, fork) else {
+        return false;
+    };
+    if fork >= Fork::Prague && floor_gas(tx.data()).is_none_or(|floor| gas_limit < floor) {
+        return false;
+    }
+    if gas_limit < intrinsic_gas
+        || gas_limit > header.gas_limit
+        || (fork >= Fork::Osaka && gas_limit > POST_OSAKA_GAS_LIMIT_CAP)
+    {
+        return false;
+    }
+
+    // The max fee must cover the base fee and the operator fee, and the tip can't exceed it
+    let operator_fee = fee_config
+        .operator_fee_config
+        .map(|config| config.operator_fee_per_gas)
+        .unwrap_or_default();
+    let min_fee_per_gas = U256::from(header.base_fee_per_gas.unwrap_or_default())
+        .saturating_add(U256::from(operator_fee));
+    if tx.gas_price() < min_fee_per_gas
+        || tx.max_priority_fee().unwrap_or_default() > tx.max_fee_per_gas().unwrap_or(u64::MAX)
+    {
+        return false;
+    }
+    let upfront_cost = tx
+        .gas_price()
+        .checked_mul(gas_limit.into())
+        .and_then(|gas_cost| gas_cost.checked_add(tx.value()));
+    if upfront_cost.is_none_or(|cost| cost > sender.balance) {
+        return false;
+    }
+
+    if tx.nonce() != sender.nonce || sender.nonce == u64::MAX {
+        return false;
+    }
+    // EIP-3607, senders with code other than a delegation can't send transactions
+    if !sender.code.is_empty() && !code_has_delegation(&sender.code).unwrap_or(false) {
+        return false;
+    }
+    if tx.is_contract_creation() && fork >= Fork::Shanghai && tx.data().len() > INIT_CODE_MAX_SIZE {
+        return false;
+    }
+    if let Some(authorization_list) = tx.authorization_list()
+        && (fork < Fork::Prague || authorization_list.is_empty())
+    {
+        return false;
+    }
+    true
+}
+
+/// Intrinsic gas charged by the VM before executing `tx`
+fn intrinsic_gas(tx: &Transaction, fork: Fork) -> Option<u64> {
+    let mut gas = TX_BASE_COST.checked_add(tx_calldata(tx.data()).ok()?)?;
+    if matches!(tx.to(), TxKind::Create) {
+        gas = gas.checked_add(CREATE_BASE_COST)?;
+        if fork >= Fork::Shanghai {
+            let words = u64::try_from(tx.data().len().div_ceil(WORD_SIZE)).ok()?;
+            gas = gas.checked_add(words.checked_mul(2)?)?;
+        }
+    }
+    for (_, keys) in tx.access_list() {
+        let keys = u64::try_from(keys.len()).ok()?;
+        gas = gas
+            .checked_add(ACCESS_LIST_ADDRESS_COST)?
+            .checked_add(keys.checked_mul(ACCESS_LIST_STORAGE_KEY_COST)?)?;
+    }
+    let authorizations = u64::try_from(tx.authorization_list().map_or(0, Vec::len)).ok()?;
+    gas.checked_add(authorizations.checked_mul(PER_AUTHORIZATION_COST)?)
+}
+
+/// Minimum gas a transaction with `calldata` consumes since Prague (EIP-7623)
+fn floor_gas(calldata: &Bytes) -> Option<u64> {
+    let tokens = tx_calldata(calldata).ok()? / STANDARD_TOKEN_COST;
+    tokens
+        .checked_mul(TOTAL_COST_FLOOR_PER_TOKEN)?
+        .checked_add(TX_BASE_COST)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::indexing_slicing, clippy::as_conversions)]
+mod tests {
+    use ethrex_common::types::{
+        EIP1559Transaction, EIP7702Transaction, PrivilegedL2Transaction,
+        fee_config::OperatorFeeConfig,
+    };
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 65536999;
+    const GWEI: u64 = 1_000_000_000;
+
+    fn signer() -> (SecretKey, Address) {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(SECP256K1, &key).serialize_uncompressed();
+        let address = Address::from_slice(&keccak(&public_key[1..]).0[12..]);
+        (key, address)
+    }
+
+    fn signed_tx(tx: EIP1559Transaction) -> Transaction {
+        let mut tx = tx;
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak(&payload).0), &signer().0)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn transfer() -> EIP1559Transaction {
+        EIP1559Transaction {
+            chain_id: CHAIN_ID,
+            nonce: 3,
+            max_priority_fee_per_gas: GWEI,
+            max_fee_per_gas: 2 * GWEI,
+            gas_limit: TX_BASE_COST,
+            to: TxKind::Call(Address::repeat_byte(0x42)),
+            value: U256::from(100),
+            ..Default::default()
+        }
+    }
+
+    fn header() -> BlockHeader {
+        BlockHeader {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(GWEI),
+            ..Default::default()
+        }
+    }
+
+    fn sender(nonce: u64, balance: U256) -> ForcedTransactionSender {
+        ForcedTransactionSender {
+            nonce,
+            balance,
+            code: Bytes::new(),
+        }
+    }
+
+    /// Whether `tx` is includable by a sender with nonce 3 and plenty of balance, on Prague
+    fn includable(tx: &Transaction) -> bool {
+        let chain_config = ChainConfig {
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+            prague_time: Some(0),
+            ..Default::default()
+        };
+        is_forced_transaction_includable(
+            tx,
+            &sender(3, U256::MAX >> 1),
+            &header(),
+            &chain_config,
+            &FeeConfig::default(),
+        )
+    }
+
+    #[test]
+    fn decodes_forced_transaction_and_recovers_its_sender() {
+        let tx = signed_tx(transfer());
+        let raw = tx.encode_canonical_to_vec();
+
+        let (decoded, sender) = decode_forced_transaction(&raw, CHAIN_ID).unwrap();
+
+        assert_eq!(decoded.encode_canonical_to_vec(), raw);
+        assert_eq!(sender, signer().1);
+        assert_eq!(get_forced_transaction_hash(&raw), tx.hash());
+    }
+
+    #[test]
+    fn rejects_forced_transactions_that_can_never_be_included() {
+        let raw = signed_tx(transfer()).encode_canonical_to_vec();
+        // Garbage and trailing bytes
+        assert!(decode_forced_transaction(&[0x02, 0x01], CHAIN_ID).is_none());
+        assert!(
+            decode_forced_transaction(&[raw.as_slice(), &[0u8][..]].concat(), CHAIN_ID).is_none()
+        );
+        // Signed for another chain
+        assert!(decode_forced_transaction(&raw, CHAIN_ID + 1).is_none());
+        // Invalid signature
+        let mut tx = transfer();
+        tx.signature_r = U256::MAX;
+        tx.signature_s = U256::MAX;
+        let raw = Transaction::EIP1559Transaction(tx).encode_canonical_to_vec();
+        assert!(decode_forced_transaction(&raw, CHAIN_ID).is_none());
+        // Transaction types users can't send
+        let privileged = Transaction::PrivilegedL2Transaction(PrivilegedL2Transaction {
+            chain_id: CHAIN_ID,
+            ..Default::default()
+        });
+        assert!(
+            decode_forced_transaction(&privileged.encode_canonical_to_vec(), CHAIN_ID).is_none()
+        );
+    }
+
+    #[test]
+    fn forced_transaction_is_includable_with_the_right_nonce_and_enough_balance() {
+        let tx = signed_tx(transfer());
+        let cost = tx.cost_without_base_fee().unwrap();
+        let chain_config = ChainConfig::default();
+        let fee_config = FeeConfig::default();
+        let is_includable = |sender: ForcedTransactionSender| {
+            is_forced_transaction_includable(&tx, &sender, &header(), &chain_config, &fee_config)
+        };
+
+        assert!(is_includable(sender(3, cost)));
+        assert!(!is_includable(sender(2, cost)));
+        assert!(!is_includable(sender(4, cost)));
+        assert!(!is_includable(sender(3, cost - 1)));
+        // EIP-3607, only senders without code or with a delegation can send transactions
+        let delegation = [&[0xef, 0x01, 0x00][..], &[0x42; 20][..]].concat();
+        assert!(is_includable(ForcedTransactionSender {
+            code: delegation.into(),
+            ..sender(3, cost)
+        }));
+        assert!(!is_includable(ForcedTransactionSender {
+            code: Bytes::from_static(&[0x60, 0x00]),
+            ..sender(3, cost)
+        }));
+    }
+
+    #[test]
+    fn forced_transaction_is_not_includable_with_invalid_gas() {
+        assert!(includable(&signed_tx(transfer())));
+        let below_base_fee = EIP1559Transaction {
+            max_fee_per_gas: GWEI - 1,
+            max_priority_fee_per_gas: 0,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(below_base_fee)));
+        let tip_above_max_fee = EIP1559Transaction {
+            max_priority_fee_per_gas: 3 * GWEI,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(tip_above_max_fee)));
+        let not_enough_gas = EIP1559Transaction {
+            gas_limit: TX_BASE_COST - 1,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(not_enough_gas)));
+        let above_block_gas_limit = EIP1559Transaction {
+            gas_limit: header().gas_limit + 1,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(above_block_gas_limit)));
+    }
+
+    #[test]
+    fn forced_transaction_is_not_includable_below_its_intrinsic_gas() {
+        // 100 non-zero bytes cost 1600 gas of calldata, and 4000 of floor gas since Prague
+        let calldata = EIP1559Transaction {
+            data: Bytes::from(vec![1; 100]),
+            gas_limit: TX_BASE_COST + 1_600,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(calldata.clone())));
+        assert!(includable(&signed_tx(EIP1559Transaction {
+            gas_limit: TX_BASE_COST + 4_000,
+            ..calldata
+        })));
+
+        let access_list = EIP1559Transaction {
+            access_list: vec![(Address::repeat_byte(1), vec![H256::zero()])],
+            gas_limit: TX_BASE_COST + ACCESS_LIST_ADDRESS_COST,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(access_list.clone())));
+        assert!(includable(&signed_tx(EIP1559Transaction {
+            gas_limit: TX_BASE_COST + ACCESS_LIST_ADDRESS_COST + ACCESS_LIST_STORAGE_KEY_COST,
+            ..access_list
+        })));
+
+        let creation = EIP1559Transaction {
+            to: TxKind::Create,
+            gas_limit: TX_BASE_COST,
+            ..transfer()
+        };
+        assert!(!includable(&signed_tx(creation.clone())));
+        assert!(includable(&signed_tx(EIP1559Transaction {
+            gas_limit: TX_BASE_COST + CREATE_BASE_COST,
+            ..creation.clone()
+        })));
+        let init_code_too_large = EIP1559Transaction {
+            data: Bytes::from(vec![0; INIT_CODE_MAX_SIZE + 1]),
+            gas_limit: 10_000_000,
+            ..creation
+        };
+        assert!(!includable(&signed_tx(init_code_too_large)));
+    }
+
+    #[test]
+    fn forced_transaction_is_not_includable_without_authorizations_or_operator_fee() {
+        let set_code = Transaction::EIP7702Transaction(EIP7702Transaction {
+            chain_id: CHAIN_ID,
+            nonce: 3,
+            max_priority_fee_per_gas: GWEI,
+            max_fee_per_gas: 2 * GWEI,
+            gas_limit: 100_000,
+            to: Address::repeat_byte(0x42),
+            ..Default::default()
+        });
+        assert!(!includable(&set_code));
+
+        let tx = signed_tx(transfer());
+        let fee_config = FeeConfig {
+            operator_fee_config: Some(OperatorFeeConfig {
+                operator_fee_vault: Address::zero(),
+                operator_fee_per_gas: GWEI + 1,
+            }),
+            ..Default::default()
+        };
+        assert!(!is_forced_transaction_includable(
+            &tx,
+            &sender(3, U256::MAX >> 1),
+            &header(),
+            &ChainConfig::default(),
+            &fee_config
+        ));
+    }
+}
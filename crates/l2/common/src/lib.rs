@@ -1,4 +1,5 @@
 pub mod calldata;
+pub mod forced_transactions;
 pub mod l1_messages;
 pub mod merkle_tree;
 pub mod privileged_transactions;
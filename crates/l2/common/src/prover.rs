@@ -19,6 +19,8 @@ pub struct ProverInputData {
     #[serde_as(as = "[_; 48]")]
     pub blob_proof: blobs_bundle::Proof,
     pub fee_configs: Vec<FeeConfig>,
+    /// Forced transactions processed by the batch, in CommonBridge queue order
+    pub forced_transactions: Vec<Vec<u8>>,
 }
 
 /// Enum used to identify the different proving systems.
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC1155/ERC1155.sol";
+
+/// @title Example L1-side ERC1155 token, used to test NFT bridging
+/// @author LambdaClass
+contract TestMultiToken is ERC1155 {
+    constructor() ERC1155("") {}
+
+    // Mint a free amount of the given token for whoever
+    // calls the function
+    function freeMint(uint256 id, uint256 amount) public {
+        _mint(msg.sender, id, amount, "");
+    }
+}
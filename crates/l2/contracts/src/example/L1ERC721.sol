@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC721/ERC721.sol";
+
+/// @title Example L1-side ERC721 token, used to test NFT bridging
+/// @author LambdaClass
+contract TestNFT is ERC721 {
+    constructor() ERC721("TestNFT", "TNFT") {}
+
+    // Mint the given token for whoever
+    // calls the function
+    function freeMint(uint256 tokenId) public {
+        _mint(msg.sender, tokenId);
+    }
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC1155/ERC1155.sol";
+import "../l2/interfaces/IERC1155L2.sol";
+
+/// @title Example L2-side bridgeable ERC1155 token
+/// @author LambdaClass
+contract TestMultiTokenL2 is ERC1155, IERC1155L2 {
+    address public L1_TOKEN = address(0);
+    address public constant BRIDGE =  0x000000000000000000000000000000000000FFff;
+
+    constructor(address l1Addr) ERC1155("") {
+        L1_TOKEN = l1Addr;
+    }
+
+    modifier onlyBridge() {
+        require(msg.sender == BRIDGE, "TestMultiToken: not authorized to mint");
+        _;
+    }
+
+    function l1Address() external view returns (address) {
+        return L1_TOKEN;
+    }
+
+    function crosschainMint(address destination, uint256 id, uint256 amount) external onlyBridge {
+        _mint(destination, id, amount, "");
+    }
+
+    function crosschainBurn(address from, uint256 id, uint256 amount) external onlyBridge {
+        _burn(from, id, amount);
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC721/ERC721.sol";
+import "../l2/interfaces/IERC721L2.sol";
+
+/// @title Example L2-side bridgeable ERC721 token
+/// @author LambdaClass
+contract TestNFTL2 is ERC721, IERC721L2 {
+    address public L1_TOKEN = address(0);
+    address public constant BRIDGE =  0x000000000000000000000000000000000000FFff;
+
+    constructor(address l1Addr) ERC721("TestNFTL2", "TNFT") {
+        L1_TOKEN = l1Addr;
+    }
+
+    modifier onlyBridge() {
+        require(msg.sender == BRIDGE, "TestNFT: not authorized to mint");
+        _;
+    }
+
+    function l1Address() external view returns (address) {
+        return L1_TOKEN;
+    }
+
+    function crosschainMint(address destination, uint256 tokenId) external onlyBridge {
+        _mint(destination, tokenId);
+    }
+
+    function crosschainBurn(address from, uint256 tokenId) external onlyBridge {
+        require(_ownerOf(tokenId) == from, "TestNFT: token not owned by sender");
+        _burn(tokenId);
+    }
+}
@@ -8,6 +8,9 @@ import "@openzeppelin/contracts-upgradeable/utils/PausableUpgradeable.sol";
 import "@openzeppelin/contracts-upgradeable/utils/ReentrancyGuardUpgradeable.sol";
 import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
 import "@openzeppelin/contracts/token/ERC20/utils/SafeERC20.sol";
+import "@openzeppelin/contracts/token/ERC721/IERC721.sol";
+import "@openzeppelin/contracts/token/ERC1155/IERC1155.sol";
+import "@openzeppelin/contracts/token/ERC1155/IERC1155Receiver.sol";
 import "@openzeppelin/contracts/proxy/transparent/TransparentUpgradeableProxy.sol";
 import {MerkleProof} from "@openzeppelin/contracts/utils/cryptography/MerkleProof.sol";
 
@@ -101,6 +104,41 @@ contract CommonBridge is
     /// @dev Index pointing to the first unprocessed privileged transaction in the queue.
     uint256 private pendingPrivilegedTxIndex;
 
+    /// @notice Array of hashes of the forced transactions, which are the keccak of the signed transactions.
+    bytes32[] public forcedTxHashes;
+
+    /// @notice Deadline for the sequencer to process each forced transaction, by queue position.
+    uint256[] public forcedTxDeadlines;
+
+    /// @dev Index pointing to the first forced transaction not processed by a committed batch.
+    uint256 private pendingForcedTxIndex;
+
+    /// @notice Maximum size of a forced transaction, in bytes.
+    /// @dev Keeps forced transactions small enough to fit in a block.
+    uint256 public constant MAX_FORCED_TX_SIZE = 32 * 1024;
+
+    /// @notice How much of each L1 NFT was deposited to each L2 NFT.
+    /// @dev Stored as L1 -> L2 -> token id -> amount
+    /// @dev ERC721 tokens are accounted as an amount of 1
+    mapping(address => mapping(address => mapping(uint256 => uint256)))
+        public nftDeposits;
+
+    /// @notice Gas limit of the L2 transaction that mints a bridged NFT.
+    /// @dev Higher than the ERC20 one, since minting an ERC1155 calls into the receiver.
+    uint256 public constant NFT_DEPOSIT_GAS_LIMIT = 21000 * 10;
+
+    /// @notice L1 block in which each forced transaction was queued.
+    /// @dev Lets the L1Watcher fetch the transaction from the logs of a single block.
+    mapping(bytes32 => uint256) public forcedTxL1Block;
+
+    /// @notice Fee charged for each forced transaction, in wei.
+    /// @dev Every queued transaction takes up processing budget of the batches until
+    /// it's processed, so flooding the queue must be costly.
+    uint256 public constant FORCED_TX_FEE = 0.001 ether;
+
+    /// @notice Forced transaction fees not withdrawn by the owner yet.
+    uint256 public forcedTxFees;
+
     modifier onlyOnChainProposer() {
         require(
             msg.sender == ON_CHAIN_PROPOSER,
@@ -268,6 +306,76 @@ contract CommonBridge is
         _sendToL2(L2_BRIDGE_ADDRESS, sendValues);
     }
 
+    /// @inheritdoc ICommonBridge
+    function depositERC721(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId
+    ) external override whenNotPaused {
+        nftDeposits[tokenL1][tokenL2][tokenId] += 1;
+        IERC721(tokenL1).transferFrom(msg.sender, address(this), tokenId);
+
+        bytes memory callData = abi.encodeCall(
+            ICommonBridgeL2.mintERC721,
+            (tokenL1, tokenL2, destination, tokenId)
+        );
+        SendValues memory sendValues = SendValues({
+            to: L2_BRIDGE_ADDRESS,
+            gasLimit: NFT_DEPOSIT_GAS_LIMIT,
+            value: 0,
+            data: callData
+        });
+        _sendToL2(L2_BRIDGE_ADDRESS, sendValues);
+    }
+
+    /// @inheritdoc ICommonBridge
+    function depositERC1155(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) external override whenNotPaused {
+        require(amount > 0, "CommonBridge: amount to deposit is zero");
+        nftDeposits[tokenL1][tokenL2][tokenId] += amount;
+        IERC1155(tokenL1).safeTransferFrom(
+            msg.sender,
+            address(this),
+            tokenId,
+            amount,
+            ""
+        );
+
+        bytes memory callData = abi.encodeCall(
+            ICommonBridgeL2.mintERC1155,
+            (tokenL1, tokenL2, destination, tokenId, amount)
+        );
+        SendValues memory sendValues = SendValues({
+            to: L2_BRIDGE_ADDRESS,
+            gasLimit: NFT_DEPOSIT_GAS_LIMIT,
+            value: 0,
+            data: callData
+        });
+        _sendToL2(L2_BRIDGE_ADDRESS, sendValues);
+    }
+
+    /// @notice Accepts ERC1155 tokens transferred by the bridge itself.
+    /// @dev Tokens sent directly to the bridge would not be credited on the L2, so they are rejected.
+    function onERC1155Received(
+        address operator,
+        address,
+        uint256,
+        uint256,
+        bytes calldata
+    ) external view returns (bytes4) {
+        require(
+            operator == address(this),
+            "CommonBridge: ERC1155 tokens must be sent through depositERC1155()"
+        );
+        return IERC1155Receiver.onERC1155Received.selector;
+    }
+
     /// @inheritdoc ICommonBridge
     function getPendingTransactionsVersionedHash(
         uint16 number
@@ -313,6 +421,116 @@ contract CommonBridge is
             privilegedTxDeadline[pendingTxHashes[pendingPrivilegedTxIndex]];
     }
 
+    /// @inheritdoc ICommonBridge
+    function forceTransaction(
+        bytes calldata transaction
+    ) public payable override whenNotPaused {
+        require(
+            msg.value == FORCED_TX_FEE,
+            "CommonBridge: forced transaction fee not paid"
+        );
+        require(
+            transaction.length > 0,
+            "CommonBridge: forced transaction is empty"
+        );
+        require(
+            transaction.length <= MAX_FORCED_TX_SIZE,
+            "CommonBridge: forced transaction is too big"
+        );
+
+        bytes32 txHash = keccak256(transaction);
+        uint256 deadline = block.timestamp +
+            PRIVILEGED_TX_MAX_WAIT_BEFORE_INCLUSION;
+        forcedTxHashes.push(txHash);
+        forcedTxDeadlines.push(deadline);
+        forcedTxL1Block[txHash] = block.number;
+        forcedTxFees += msg.value;
+
+        emit ForcedTransactionSent(txHash, transaction, deadline);
+    }
+
+    /// @inheritdoc ICommonBridge
+    function withdrawForcedTransactionFees(
+        address to
+    ) public onlyOwner nonReentrant {
+        uint256 amount = forcedTxFees;
+        forcedTxFees = 0;
+        (bool success, ) = payable(to).call{value: amount}("");
+        require(
+            success,
+            "CommonBridge: failed to withdraw forced transaction fees"
+        );
+    }
+
+    /// @inheritdoc ICommonBridge
+    function getPendingForcedTransactionHashes()
+        public
+        view
+        returns (bytes32[] memory)
+    {
+        bytes32[] memory buffer = new bytes32[](pendingForcedTxHashesLength());
+        for (uint256 i = 0; i < pendingForcedTxHashesLength(); i++) {
+            buffer[i] = forcedTxHashes[i + pendingForcedTxIndex];
+        }
+
+        return buffer;
+    }
+
+    /// @inheritdoc ICommonBridge
+    function getPendingForcedTransactionsVersionedHash(
+        uint16 number
+    ) public view returns (bytes32) {
+        require(number > 0, "CommonBridge: number is zero (get forced)");
+        require(
+            uint256(number) <= pendingForcedTxHashesLength(),
+            "CommonBridge: number is greater than the length of pending forced transactions (get)"
+        );
+
+        bytes memory hashes;
+        for (uint i = 0; i < number; i++) {
+            hashes = bytes.concat(
+                hashes,
+                forcedTxHashes[i + pendingForcedTxIndex]
+            );
+        }
+
+        return
+            bytes32(bytes2(number)) |
+            bytes32(uint256(uint240(uint256(keccak256(hashes)))));
+    }
+
+    /// @inheritdoc ICommonBridge
+    function commitForcedTransactions(
+        uint16 number
+    ) public onlyOnChainProposer {
+        require(
+            number <= pendingForcedTxHashesLength(),
+            "CommonBridge: number is greater than the length of pending forced transactions (commit)"
+        );
+
+        pendingForcedTxIndex += number;
+    }
+
+    /// @inheritdoc ICommonBridge
+    function revertForcedTransactions(
+        uint16 number
+    ) public onlyOnChainProposer {
+        require(
+            number <= pendingForcedTxIndex,
+            "CommonBridge: number is greater than the amount of processed forced transactions"
+        );
+
+        pendingForcedTxIndex -= number;
+    }
+
+    /// @inheritdoc ICommonBridge
+    function hasExpiredForcedTransactions() public view returns (bool) {
+        if (pendingForcedTxHashesLength() == 0) {
+            return false;
+        }
+        return block.timestamp > forcedTxDeadlines[pendingForcedTxIndex];
+    }
+
     /// @inheritdoc ICommonBridge
     function getWithdrawalLogsMerkleRoot(
         uint256 blockNumber
@@ -382,6 +600,55 @@ contract CommonBridge is
         IERC20(tokenL1).safeTransfer(msg.sender, claimedAmount);
     }
 
+    /// @inheritdoc ICommonBridge
+    function claimWithdrawalERC721(
+        address tokenL1,
+        address tokenL2,
+        uint256 tokenId,
+        uint256 withdrawalBatchNumber,
+        uint256 withdrawalMessageId,
+        bytes32[] calldata withdrawalProof
+    ) public override nonReentrant whenNotPaused {
+        _claimNFTWithdrawal(
+            tokenL1,
+            tokenL2,
+            tokenId,
+            1,
+            withdrawalBatchNumber,
+            withdrawalMessageId,
+            withdrawalProof
+        );
+        IERC721(tokenL1).transferFrom(address(this), msg.sender, tokenId);
+    }
+
+    /// @inheritdoc ICommonBridge
+    function claimWithdrawalERC1155(
+        address tokenL1,
+        address tokenL2,
+        uint256 tokenId,
+        uint256 claimedAmount,
+        uint256 withdrawalBatchNumber,
+        uint256 withdrawalMessageId,
+        bytes32[] calldata withdrawalProof
+    ) public override nonReentrant whenNotPaused {
+        _claimNFTWithdrawal(
+            tokenL1,
+            tokenL2,
+            tokenId,
+            claimedAmount,
+            withdrawalBatchNumber,
+            withdrawalMessageId,
+            withdrawalProof
+        );
+        IERC1155(tokenL1).safeTransferFrom(
+            address(this),
+            msg.sender,
+            tokenId,
+            claimedAmount,
+            ""
+        );
+    }
+
     function _claimWithdrawal(
         address tokenL1,
         address tokenL2,
@@ -398,6 +665,51 @@ contract CommonBridge is
         bytes32 msgHash = keccak256(
             abi.encodePacked(tokenL1, tokenL2, msg.sender, claimedAmount)
         );
+        _claimMessage(
+            msgHash,
+            withdrawalBatchNumber,
+            withdrawalMessageId,
+            withdrawalProof
+        );
+    }
+
+    function _claimNFTWithdrawal(
+        address tokenL1,
+        address tokenL2,
+        uint256 tokenId,
+        uint256 claimedAmount,
+        uint256 withdrawalBatchNumber,
+        uint256 withdrawalMessageId,
+        bytes32[] calldata withdrawalProof
+    ) private {
+        require(
+            nftDeposits[tokenL1][tokenL2][tokenId] >= claimedAmount,
+            "CommonBridge: trying to withdraw more NFTs than were deposited"
+        );
+        nftDeposits[tokenL1][tokenL2][tokenId] -= claimedAmount;
+        bytes32 msgHash = keccak256(
+            abi.encodePacked(
+                tokenL1,
+                tokenL2,
+                msg.sender,
+                tokenId,
+                claimedAmount
+            )
+        );
+        _claimMessage(
+            msgHash,
+            withdrawalBatchNumber,
+            withdrawalMessageId,
+            withdrawalProof
+        );
+    }
+
+    function _claimMessage(
+        bytes32 msgHash,
+        uint256 withdrawalBatchNumber,
+        uint256 withdrawalMessageId,
+        bytes32[] calldata withdrawalProof
+    ) private {
         require(
             batchWithdrawalLogsMerkleRoots[withdrawalBatchNumber] != bytes32(0),
             "CommonBridge: the batch that emitted the withdrawal logs was not committed"
@@ -445,6 +757,10 @@ contract CommonBridge is
         return pendingTxHashes.length - pendingPrivilegedTxIndex;
     }
 
+    function pendingForcedTxHashesLength() private view returns (uint256) {
+        return forcedTxHashes.length - pendingForcedTxIndex;
+    }
+
     function upgradeL2Contract(
         address l2Contract,
         address newImplementation,
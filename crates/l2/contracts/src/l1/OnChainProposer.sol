@@ -29,12 +29,16 @@ contract OnChainProposer is
     /// pendingTxHashes queue of the CommonBridge contract.
     /// @dev withdrawalsLogsMerkleRoot is the Merkle root of the Merkle tree containing
     /// all the withdrawals that were processed in the batch being committed
+    /// @dev processedForcedTransactionsRollingHash is the versioned hash of the forced
+    /// transactions processed by the batch, which are taken out of the CommonBridge queue
+    /// when the batch is committed.
     struct BatchCommitmentInfo {
         bytes32 newStateRoot;
         bytes32 blobKZGVersionedHash;
         bytes32 processedPrivilegedTransactionsRollingHash;
         bytes32 withdrawalsLogsMerkleRoot;
         bytes32 lastBlockHash;
+        bytes32 processedForcedTransactionsRollingHash;
     }
 
     /// @notice The commitments of the committed batches.
@@ -95,6 +99,13 @@ contract OnChainProposer is
     /// @notice True if verification is done through Aligned Layer instead of smart contract verifiers.
     bool public ALIGNED_MODE;
 
+    /// @notice Verification key of the SP1 program that aggregates batch proofs.
+    bytes32 public SP1_AGGREGATION_VERIFICATION_KEY;
+    /// @notice Digest of SP1_VERIFICATION_KEY's program, as committed by the SP1 aggregation program.
+    bytes32 public SP1_PROGRAM_VK_DIGEST;
+    /// @notice Image ID of the RISC0 program that aggregates batch proofs.
+    bytes32 public RISC0_AGGREGATION_VERIFICATION_KEY;
+
     modifier onlySequencer() {
         require(
             authorizedSequencerAddresses[msg.sender],
@@ -185,12 +196,27 @@ contract OnChainProposer is
         emit VerificationKeyUpgraded("RISC0", new_vk);
     }
 
+    /// @inheritdoc IOnChainProposer
+    function upgradeAggregationVerificationKeys(
+        bytes32 sp1AggregationVk,
+        bytes32 sp1ProgramVkDigest,
+        bytes32 risc0AggregationVk
+    ) public onlyOwner {
+        SP1_AGGREGATION_VERIFICATION_KEY = sp1AggregationVk;
+        SP1_PROGRAM_VK_DIGEST = sp1ProgramVkDigest;
+        RISC0_AGGREGATION_VERIFICATION_KEY = risc0AggregationVk;
+        emit VerificationKeyUpgraded("SP1_AGGREGATION", sp1AggregationVk);
+        emit VerificationKeyUpgraded("SP1_PROGRAM_DIGEST", sp1ProgramVkDigest);
+        emit VerificationKeyUpgraded("RISC0_AGGREGATION", risc0AggregationVk);
+    }
+
     /// @inheritdoc IOnChainProposer
     function commitBatch(
         uint256 batchNumber,
         bytes32 newStateRoot,
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
+        bytes32 processedForcedTransactionsRollingHash,
         bytes32 lastBlockHash
     ) external override onlySequencer whenNotPaused {
         // TODO: Refactor validation
@@ -218,6 +244,23 @@ contract OnChainProposer is
                 "OnChainProposer: invalid privileged transaction logs"
             );
         }
+        if (processedForcedTransactionsRollingHash != bytes32(0)) {
+            uint16 forcedTransactionCount = uint16(
+                bytes2(processedForcedTransactionsRollingHash)
+            );
+            bytes32 claimedForcedTransactions = ICommonBridge(BRIDGE)
+                .getPendingForcedTransactionsVersionedHash(
+                    forcedTransactionCount
+                );
+            require(
+                claimedForcedTransactions ==
+                    processedForcedTransactionsRollingHash,
+                "OnChainProposer: invalid forced transactions"
+            );
+            ICommonBridge(BRIDGE).commitForcedTransactions(
+                forcedTransactionCount
+            );
+        }
         if (withdrawalsLogsMerkleRoot != bytes32(0)) {
             ICommonBridge(BRIDGE).publishWithdrawals(
                 batchNumber,
@@ -244,7 +287,8 @@ contract OnChainProposer is
             blobVersionedHash,
             processedPrivilegedTransactionsRollingHash,
             withdrawalsLogsMerkleRoot,
-            lastBlockHash
+            lastBlockHash,
+            processedForcedTransactionsRollingHash
         );
         emit BatchCommitted(newStateRoot);
 
@@ -471,16 +515,178 @@ contract OnChainProposer is
         emit BatchVerified(lastVerifiedBatch);
     }
 
+    /// @inheritdoc IOnChainProposer
+    function verifyBatchesAggregated(
+        uint256 firstBatchNumber,
+        uint256 lastBatchNumber,
+        //risc0
+        bytes memory risc0AggregatedProof,
+        bytes calldata risc0Journal,
+        //sp1
+        bytes calldata sp1PublicValues,
+        bytes memory sp1ProofBytes
+    ) external override onlySequencer whenNotPaused {
+        require(
+            !ALIGNED_MODE,
+            "Batch verification should be done via Aligned Layer. Call verifyBatchesAligned() instead."
+        );
+        require(
+            !REQUIRE_TDX_PROOF,
+            "OnChainProposer: TDX proofs can't be aggregated. Call verifyBatch() instead."
+        );
+        require(
+            firstBatchNumber == lastVerifiedBatch + 1,
+            "OnChainProposer: incorrect first batch number"
+        );
+        require(
+            lastBatchNumber >= firstBatchNumber,
+            "OnChainProposer: incorrect last batch number"
+        );
+
+        uint256 batchCount = lastBatchNumber - firstBatchNumber + 1;
+        if (REQUIRE_RISC0_PROOF) {
+            string memory reason = _verifyAggregatedPublicData(
+                batchCount,
+                risc0Journal,
+                RISC0_VERIFICATION_KEY
+            );
+            if (bytes(reason).length != 0) {
+                revert(
+                    string.concat(
+                        "OnChainProposer: Invalid RISC0 proof: ",
+                        reason
+                    )
+                );
+            }
+        }
+        if (REQUIRE_SP1_PROOF) {
+            string memory reason = _verifyAggregatedPublicData(
+                batchCount,
+                sp1PublicValues,
+                SP1_PROGRAM_VK_DIGEST
+            );
+            if (bytes(reason).length != 0) {
+                revert(
+                    string.concat("OnChainProposer: Invalid SP1 proof: ", reason)
+                );
+            }
+        }
+
+        for (uint256 i = 0; i < batchCount; i++) {
+            uint256 batchNumber = firstBatchNumber + i;
+            require(
+                batchCommitments[batchNumber].newStateRoot != bytes32(0),
+                "OnChainProposer: cannot verify an uncommitted batch"
+            );
+
+            // The first 2 bytes are the number of privileged transactions.
+            uint16 privileged_transaction_count = uint16(
+                bytes2(
+                    batchCommitments[batchNumber]
+                        .processedPrivilegedTransactionsRollingHash
+                )
+            );
+            if (privileged_transaction_count > 0) {
+                ICommonBridge(BRIDGE).removePendingTransactionHashes(
+                    privileged_transaction_count
+                );
+            }
+
+            // The public values of each batch follow the 32 bytes of the program key.
+            uint256 offset = 32 + 288 * i;
+            if (REQUIRE_RISC0_PROOF) {
+                string memory reason = _verifyPublicData(
+                    batchNumber,
+                    risc0Journal[offset:offset + 288]
+                );
+                if (bytes(reason).length != 0) {
+                    revert(
+                        string.concat(
+                            "OnChainProposer: Invalid RISC0 proof: ",
+                            reason
+                        )
+                    );
+                }
+            }
+            if (REQUIRE_SP1_PROOF) {
+                string memory reason = _verifyPublicData(
+                    batchNumber,
+                    sp1PublicValues[offset:offset + 288]
+                );
+                if (bytes(reason).length != 0) {
+                    revert(
+                        string.concat(
+                            "OnChainProposer: Invalid SP1 proof: ",
+                            reason
+                        )
+                    );
+                }
+            }
+
+            // Remove previous batch commitment as it is no longer needed.
+            delete batchCommitments[batchNumber - 1];
+        }
+
+        if (REQUIRE_RISC0_PROOF) {
+            try
+                IRiscZeroVerifier(RISC0_VERIFIER_ADDRESS).verify(
+                    risc0AggregatedProof,
+                    RISC0_AGGREGATION_VERIFICATION_KEY,
+                    sha256(risc0Journal)
+                )
+            {} catch {
+                revert(
+                    "OnChainProposer: Invalid RISC0 proof failed proof verification"
+                );
+            }
+        }
+        if (REQUIRE_SP1_PROOF) {
+            try
+                ISP1Verifier(SP1_VERIFIER_ADDRESS).verifyProof(
+                    SP1_AGGREGATION_VERIFICATION_KEY,
+                    sp1PublicValues,
+                    sp1ProofBytes
+                )
+            {} catch {
+                revert(
+                    "OnChainProposer: Invalid SP1 proof failed proof verification"
+                );
+            }
+        }
+
+        lastVerifiedBatch = lastBatchNumber;
+
+        emit BatchVerified(lastVerifiedBatch);
+    }
+
+    /// @dev Checks the layout of the public values of an aggregated proof: the key of the
+    /// aggregated program followed by the 288 bytes of public values of each batch.
+    function _verifyAggregatedPublicData(
+        uint256 batchCount,
+        bytes calldata publicData,
+        bytes32 programKey
+    ) internal pure returns (string memory) {
+        if (publicData.length != 32 + 288 * batchCount) {
+            return "invalid aggregated public data length";
+        }
+        if (bytes32(publicData[0:32]) != programKey) {
+            return "batch proofs were not generated by the sequencer's program";
+        }
+        return "";
+    }
+
+    /// @dev The initial state root is checked against the previous batch, which
+    /// must be the last verified one or belong to the same verified range.
     function _verifyPublicData(
         uint256 batchNumber,
         bytes calldata publicData
     ) internal view returns (string memory) {
-        if (publicData.length != 256) {
+        if (publicData.length != 288) {
             return "invalid public data length";
         }
         bytes32 initialStateRoot = bytes32(publicData[0:32]);
         if (
-            batchCommitments[lastVerifiedBatch].newStateRoot != initialStateRoot
+            batchCommitments[batchNumber - 1].newStateRoot != initialStateRoot
         ) {
             return
                 "initial state root public inputs don't match with initial state root";
@@ -507,7 +713,16 @@ contract OnChainProposer is
             return
                 "privileged transactions hash public input does not match with committed transactions";
         }
-        bytes32 blobVersionedHash = bytes32(publicData[128:160]);
+        bytes32 forcedTransactionsHash = bytes32(publicData[128:160]);
+        if (
+            batchCommitments[batchNumber]
+                .processedForcedTransactionsRollingHash !=
+            forcedTransactionsHash
+        ) {
+            return
+                "forced transactions hash public input does not match with committed transactions";
+        }
+        bytes32 blobVersionedHash = bytes32(publicData[160:192]);
         if (
             batchCommitments[batchNumber].blobKZGVersionedHash !=
             blobVersionedHash
@@ -515,17 +730,17 @@ contract OnChainProposer is
             return
                 "blob versioned hash public input does not match with committed hash";
         }
-        bytes32 lastBlockHash = bytes32(publicData[160:192]);
+        bytes32 lastBlockHash = bytes32(publicData[192:224]);
         if (batchCommitments[batchNumber].lastBlockHash != lastBlockHash) {
             return
                 "last block hash public inputs don't match with last block hash";
         }
-        uint256 chainId = uint256(bytes32(publicData[192:224]));
+        uint256 chainId = uint256(bytes32(publicData[224:256]));
         if (chainId != CHAIN_ID) {
             return ("given chain id does not correspond to this network");
         }
         uint256 nonPrivilegedTransactions = uint256(
-            bytes32(publicData[224:256])
+            bytes32(publicData[256:288])
         );
         if (
             ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactions() &&
@@ -534,6 +749,13 @@ contract OnChainProposer is
             return
                 "exceeded privileged transaction inclusion deadline, can't include non-privileged transactions";
         }
+        if (
+            ICommonBridge(BRIDGE).hasExpiredForcedTransactions() &&
+            nonPrivilegedTransactions != 0
+        ) {
+            return
+                "exceeded forced transaction inclusion deadline, batch skips expired forced transactions";
+        }
         return "";
     }
 
@@ -574,9 +796,17 @@ contract OnChainProposer is
             "OnChainProposer: no batches are being reverted"
         );
 
-        // Remove old batches
-        for (uint256 i = batchNumber; i < lastCommittedBatch; i++) {
-            delete batchCommitments[i + 1];
+        // Remove old batches, giving their forced transactions back to the bridge queue
+        for (uint256 i = lastCommittedBatch; i > batchNumber; i--) {
+            uint16 forcedTransactionCount = uint16(
+                bytes2(batchCommitments[i].processedForcedTransactionsRollingHash)
+            );
+            if (forcedTransactionCount > 0) {
+                ICommonBridge(BRIDGE).revertForcedTransactions(
+                    forcedTransactionCount
+                );
+            }
+            delete batchCommitments[i];
         }
 
         lastCommittedBatch = batchNumber;
@@ -489,7 +489,7 @@ contract OnChainProposer is
         uint256 batchNumber,
         bytes calldata publicData
     ) internal view returns (string memory) {
-        if (publicData.length != 256) {
+        if (publicData.length != 288) {
             return "invalid public data length";
         }
         bytes32 initialStateRoot = bytes32(publicData[0:32]);
@@ -521,17 +521,31 @@ contract OnChainProposer is
             return
                 "privileged transactions hash public input does not match with committed transactions";
         }
-        bytes32 lastBlockHash = bytes32(publicData[128:160]);
+        // Forced transactions are not supported in based mode, so batches can't process any
+        bytes32 forcedTransactionsHash = bytes32(publicData[128:160]);
+        if (forcedTransactionsHash != bytes32(0)) {
+            return
+                "forced transactions hash public input must be zero in based mode";
+        }
+        bytes32 blobVersionedHash = bytes32(publicData[160:192]);
+        if (
+            batchCommitments[batchNumber].blobVersionedHash !=
+            blobVersionedHash
+        ) {
+            return
+                "blob versioned hash public input does not match with committed hash";
+        }
+        bytes32 lastBlockHash = bytes32(publicData[192:224]);
         if (batchCommitments[batchNumber].lastBlockHash != lastBlockHash) {
             return
                 "last block hash public inputs don't match with last block hash";
         }
-        uint256 chainId = uint256(bytes32(publicData[160:182]));
+        uint256 chainId = uint256(bytes32(publicData[224:256]));
         if (chainId != CHAIN_ID) {
             return "given chain id does not correspond to this network";
         }
         uint256 nonPrivilegedTransactions = uint256(
-            bytes32(publicData[192:224])
+            bytes32(publicData[256:288])
         );
         if (
             ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactions() &&
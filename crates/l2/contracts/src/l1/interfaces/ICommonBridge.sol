@@ -25,6 +25,18 @@ interface ICommonBridge {
         bytes data
     );
 
+    /// @notice A signed L2 transaction was queued to be forcibly included.
+    /// @dev Event emitted when a forced transaction is sent. This event will later be
+    /// intercepted by the L2 operator, which has to include the transaction before its deadline.
+    /// @param txHash the keccak of the transaction, which is its L2 hash.
+    /// @param transaction the signed transaction, canonically encoded.
+    /// @param deadline the timestamp until which the sequencer can process the transaction.
+    event ForcedTransactionSent(
+        bytes32 indexed txHash,
+        bytes transaction,
+        uint256 deadline
+    );
+
     /// @notice L2 withdrawals have been published on L1.
     /// @dev Event emitted when the L2 withdrawals are published on L1.
     /// @param withdrawalLogsBatchNumber the batch number where the withdrawal logs were emitted.
@@ -68,6 +80,36 @@ interface ICommonBridge {
     /// @param l2Recipient the address on L2 that will receive the deposit.
     function deposit(address l2Recipient) external payable;
 
+    /// @notice Method that starts an L2 ERC721 deposit process.
+    /// @dev The token is held by the bridge until it's withdrawn back from the L2.
+    /// The bridge must be approved to transfer it.
+    /// @param tokenL1 Address of the token on the L1
+    /// @param tokenL2 Address of the token on the L2
+    /// @param destination Address on the L2 that should receive the token
+    /// @param tokenId Id of the token to deposit
+    function depositERC721(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId
+    ) external;
+
+    /// @notice Method that starts an L2 ERC1155 deposit process.
+    /// @dev The tokens are held by the bridge until they're withdrawn back from the L2.
+    /// The bridge must be approved to transfer them.
+    /// @param tokenL1 Address of the token on the L1
+    /// @param tokenL2 Address of the token on the L2
+    /// @param destination Address on the L2 that should receive the tokens
+    /// @param tokenId Id of the token to deposit
+    /// @param amount Amount of tokens to deposit
+    function depositERC1155(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) external;
+
     /// @notice Method to retrieve the versioned hash of the first `number`
     /// pending privileged transactions.
     /// @param number of pending privileged transaction to retrieve the versioned hash.
@@ -146,9 +188,85 @@ interface ICommonBridge {
         bytes32[] calldata withdrawalProof
     ) external;
 
+    /// @notice Claims an ERC721 withdrawal
+    /// @param tokenL1 Address of the token on the L1
+    /// @param tokenL2 Address of the token on the L2
+    /// @param tokenId Id of the token that will be claimed.
+    /// @param withdrawalProof the merkle path to the withdrawal log.
+    /// @param withdrawalLogIndex the index of the message log in the batch.
+    /// @param l2WithdrawalBatchNumber the batch number where the withdrawal log
+    /// was emitted.
+    function claimWithdrawalERC721(
+        address tokenL1,
+        address tokenL2,
+        uint256 tokenId,
+        uint256 l2WithdrawalBatchNumber,
+        uint256 withdrawalLogIndex,
+        bytes32[] calldata withdrawalProof
+    ) external;
+
+    /// @notice Claims an ERC1155 withdrawal
+    /// @param tokenL1 Address of the token on the L1
+    /// @param tokenL2 Address of the token on the L2
+    /// @param tokenId Id of the token that will be claimed.
+    /// @param claimedAmount the amount that will be claimed.
+    /// @param withdrawalProof the merkle path to the withdrawal log.
+    /// @param withdrawalLogIndex the index of the message log in the batch.
+    /// @param l2WithdrawalBatchNumber the batch number where the withdrawal log
+    /// was emitted.
+    function claimWithdrawalERC1155(
+        address tokenL1,
+        address tokenL2,
+        uint256 tokenId,
+        uint256 claimedAmount,
+        uint256 l2WithdrawalBatchNumber,
+        uint256 withdrawalLogIndex,
+        bytes32[] calldata withdrawalProof
+    ) external;
+
     /// @notice Checks if the sequencer has exceeded it's processing deadlines
     function hasExpiredPrivilegedTransactions() external view returns (bool);
 
+    /// @notice Queues a signed L2 transaction that the sequencer must process before
+    /// its deadline, so users can't be censored.
+    /// @dev The transaction is either included in a batch or, if it can't be executed
+    /// (e.g. wrong nonce or not enough balance), skipped. The guest program checks
+    /// which is the case.
+    /// Requires paying `FORCED_TX_FEE`, so the queue can't be flooded for free.
+    /// @param transaction the signed L2 transaction, canonically encoded.
+    function forceTransaction(bytes calldata transaction) external payable;
+
+    /// @notice Sends the collected forced transaction fees to `to`.
+    /// @dev Only callable by the owner.
+    function withdrawForcedTransactionFees(address to) external;
+
+    /// @notice Method to retrieve the hashes of the forced transactions that
+    /// weren't processed by any committed batch yet.
+    function getPendingForcedTransactionHashes()
+        external
+        view
+        returns (bytes32[] memory);
+
+    /// @notice Method to retrieve the versioned hash of the first `number`
+    /// pending forced transactions.
+    /// @param number of forced transactions to hash.
+    function getPendingForcedTransactionsVersionedHash(
+        uint16 number
+    ) external view returns (bytes32);
+
+    /// @notice Marks the first `number` pending forced transactions as processed
+    /// by a committed batch.
+    /// @dev Only callable by the OnChainProposer.
+    function commitForcedTransactions(uint16 number) external;
+
+    /// @notice Marks the last `number` processed forced transactions as pending
+    /// again, because the batches processing them were reverted.
+    /// @dev Only callable by the OnChainProposer.
+    function revertForcedTransactions(uint16 number) external;
+
+    /// @notice Checks if the sequencer has exceeded the deadline of a forced transaction
+    function hasExpiredForcedTransactions() external view returns (bool);
+
     /// @notice Allows the owner to pause the contract
     function pause() external;
 
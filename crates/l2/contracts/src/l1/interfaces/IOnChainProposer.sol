@@ -48,6 +48,17 @@ interface IOnChainProposer {
     /// @param new_vk new verification key for RISC0 verifier
     function upgradeRISC0VerificationKey(bytes32 new_vk) external;
 
+    /// @notice Upgrades the verification keys used to verify aggregated proofs.
+    /// @param sp1AggregationVk verification key of the SP1 aggregation program
+    /// @param sp1ProgramVkDigest digest of the SP1 verification key that represents the
+    /// sequencer's code, as committed by the SP1 aggregation program
+    /// @param risc0AggregationVk image id of the RISC0 aggregation program
+    function upgradeAggregationVerificationKeys(
+        bytes32 sp1AggregationVk,
+        bytes32 sp1ProgramVkDigest,
+        bytes32 risc0AggregationVk
+    ) external;
+
     /// @notice Commits to a batch of L2 blocks.
     /// @dev Committing to an L2 batch means to store the batch's commitment
     /// and to publish withdrawals if any.
@@ -57,12 +68,15 @@ interface IOnChainProposer {
     /// of the batch to be committed.
     /// @param processedPrivilegedTransactionsRollingHash the rolling hash of the processed
     /// privileged transactions of the batch to be committed.
+    /// @param processedForcedTransactionsRollingHash the rolling hash of the forced
+    /// transactions processed by the batch to be committed.
     /// @param lastBlockHash the hash of the last block of the batch to be committed.
     function commitBatch(
         uint256 batchNumber,
         bytes32 newStateRoot,
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
+        bytes32 processedForcedTransactionsRollingHash,
         bytes32 lastBlockHash
     ) external;
 
@@ -108,6 +122,23 @@ interface IOnChainProposer {
         bytes32[][] calldata risc0MerkleProofsList
     ) external;
 
+    /// @notice Method used to verify a range of L2 batches with a single recursive proof per
+    /// prover, aggregating the proofs of every batch in the range.
+    /// @dev The public values of an aggregated proof are the verification key of the
+    /// aggregated program followed by the public values of each batch, in order.
+    /// @param firstBatchNumber The first batch of the range. Must be `lastVerifiedBatch + 1`.
+    /// @param lastBatchNumber The last batch of the range.
+    function verifyBatchesAggregated(
+        uint256 firstBatchNumber,
+        uint256 lastBatchNumber,
+        //risc0
+        bytes memory risc0AggregatedProof,
+        bytes calldata risc0Journal,
+        //sp1
+        bytes calldata sp1PublicValues,
+        bytes memory sp1ProofBytes
+    ) external;
+
     /// @notice Allows unverified batches to be reverted
     function revertBatch(uint256 batchNumber) external;
 
@@ -4,6 +4,8 @@ pragma solidity =0.8.29;
 import "./interfaces/ICommonBridgeL2.sol";
 import "./interfaces/IL2ToL1Messenger.sol";
 import "./interfaces/IERC20L2.sol";
+import "./interfaces/IERC721L2.sol";
+import "./interfaces/IERC1155L2.sol";
 
 /// @title CommonBridge L2 contract.
 /// @author LambdaClass
@@ -89,6 +91,102 @@ contract CommonBridgeL2 is ICommonBridgeL2 {
         _withdraw(tokenL1, tokenL2, destination, amount);
     }
 
+    function mintERC721(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId
+    ) external onlySelf {
+        (bool success, ) = address(this).call(
+            abi.encodeCall(
+                this.tryMintERC721,
+                (tokenL1, tokenL2, destination, tokenId)
+            )
+        );
+        if (!success) {
+            _withdrawNFT(tokenL1, tokenL2, destination, tokenId, 1);
+        }
+        emit ERC721DepositProcessed(tokenL1, tokenL2, destination, tokenId);
+    }
+
+    function tryMintERC721(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId
+    ) external onlySelf {
+        IERC721L2 token = IERC721L2(tokenL2);
+        require(token.l1Address() == tokenL1);
+        token.crosschainMint(destination, tokenId);
+    }
+
+    function withdrawERC721(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId
+    ) external {
+        IERC721L2(tokenL2).crosschainBurn(msg.sender, tokenId);
+        emit ERC721WithdrawalInitiated(tokenL1, tokenL2, destination, tokenId);
+        _withdrawNFT(tokenL1, tokenL2, destination, tokenId, 1);
+    }
+
+    function mintERC1155(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) external onlySelf {
+        (bool success, ) = address(this).call(
+            abi.encodeCall(
+                this.tryMintERC1155,
+                (tokenL1, tokenL2, destination, tokenId, amount)
+            )
+        );
+        if (!success) {
+            _withdrawNFT(tokenL1, tokenL2, destination, tokenId, amount);
+        }
+        emit ERC1155DepositProcessed(
+            tokenL1,
+            tokenL2,
+            destination,
+            tokenId,
+            amount
+        );
+    }
+
+    function tryMintERC1155(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) external onlySelf {
+        IERC1155L2 token = IERC1155L2(tokenL2);
+        require(token.l1Address() == tokenL1);
+        token.crosschainMint(destination, tokenId, amount);
+    }
+
+    function withdrawERC1155(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) external {
+        require(amount > 0, "Withdrawal amount must be positive");
+        IERC1155L2(tokenL2).crosschainBurn(msg.sender, tokenId, amount);
+        emit ERC1155WithdrawalInitiated(
+            tokenL1,
+            tokenL2,
+            destination,
+            tokenId,
+            amount
+        );
+        _withdrawNFT(tokenL1, tokenL2, destination, tokenId, amount);
+    }
+
     function _withdraw(
         address tokenL1,
         address tokenL2,
@@ -99,4 +197,18 @@ contract CommonBridgeL2 is ICommonBridgeL2 {
             keccak256(abi.encodePacked(tokenL1, tokenL2, destination, amount))
         );
     }
+
+    function _withdrawNFT(
+        address tokenL1,
+        address tokenL2,
+        address destination,
+        uint256 tokenId,
+        uint256 amount
+    ) private {
+        IL2ToL1Messenger(L1_MESSENGER).sendMessageToL1(
+            keccak256(
+                abi.encodePacked(tokenL1, tokenL2, destination, tokenId, amount)
+            )
+        );
+    }
 }
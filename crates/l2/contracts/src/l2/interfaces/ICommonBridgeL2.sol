@@ -51,6 +51,60 @@ interface ICommonBridgeL2 {
         uint256 amount
     );
 
+    /// @notice An ERC721 token deposit was successfully processed
+    /// @dev Event emitted when an ERC721 deposit is processed.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param receiver the address that received the token
+    /// @param tokenId the id of the token being deposited
+    event ERC721DepositProcessed(
+        address indexed tokenL1,
+        address indexed tokenL2,
+        address indexed receiver,
+        uint256 tokenId
+    );
+    /// @notice An ERC721 token withdrawal has initiated
+    /// @dev Event emitted when an ERC721 withdrawal is initiated.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param receiverOnL1 the address on L1 that will receive the token back.
+    /// @param tokenId the id of the token being withdrawn.
+    event ERC721WithdrawalInitiated(
+        address indexed tokenL1,
+        address indexed tokenL2,
+        address indexed receiverOnL1,
+        uint256 tokenId
+    );
+
+    /// @notice An ERC1155 token deposit was successfully processed
+    /// @dev Event emitted when an ERC1155 deposit is processed.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param receiver the address that received the tokens
+    /// @param tokenId the id of the tokens being deposited
+    /// @param amount the amount of tokens being deposited
+    event ERC1155DepositProcessed(
+        address indexed tokenL1,
+        address indexed tokenL2,
+        address indexed receiver,
+        uint256 tokenId,
+        uint256 amount
+    );
+    /// @notice An ERC1155 token withdrawal has initiated
+    /// @dev Event emitted when an ERC1155 withdrawal is initiated.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param receiverOnL1 the address on L1 that will receive the tokens back.
+    /// @param tokenId the id of the tokens being withdrawn.
+    /// @param amount the amount of tokens being withdrawn.
+    event ERC1155WithdrawalInitiated(
+        address indexed tokenL1,
+        address indexed tokenL2,
+        address indexed receiverOnL1,
+        uint256 tokenId,
+        uint256 amount
+    );
+
     /// @notice Initiates the withdrawal of funds to the L1.
     /// @dev This is the first step in the two step process of a user withdrawal.
     /// @dev It burns funds on L2 and sends a message to the L1 so users
@@ -82,4 +136,42 @@ interface ICommonBridgeL2 {
     /// @param destination Address on L1 that should receive the tokens
     /// @param amount Amount of tokens to withdraw
     function withdrawERC20(address tokenL1, address tokenL2, address destination, uint256 amount) external;
+
+    /// @notice Tries to deposit an ERC721 token
+    /// @dev The msg.sender must be the bridge itself, using a privileged transaction
+    /// @dev If the token can't be minted, a withdrawal is initiated.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param destination Address that should receive the token
+    /// @param tokenId Id of the token to give
+    function mintERC721(address tokenL1, address tokenL2, address destination, uint256 tokenId) external;
+
+    /// @notice Initiates the withdrawal of an ERC721 token to the L1.
+    /// @dev It burns the token on L2 and sends a message to the L1 so users
+    /// @dev can claim it on L1.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param destination Address on L1 that should receive the token
+    /// @param tokenId Id of the token to withdraw
+    function withdrawERC721(address tokenL1, address tokenL2, address destination, uint256 tokenId) external;
+
+    /// @notice Tries to deposit ERC1155 tokens
+    /// @dev The msg.sender must be the bridge itself, using a privileged transaction
+    /// @dev If the tokens can't be minted, a withdrawal is initiated.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param destination Address that should receive the tokens
+    /// @param tokenId Id of the tokens to give
+    /// @param amount Amount of tokens to give
+    function mintERC1155(address tokenL1, address tokenL2, address destination, uint256 tokenId, uint256 amount) external;
+
+    /// @notice Initiates the withdrawal of ERC1155 tokens to the L1.
+    /// @dev It burns tokens on L2 and sends a message to the L1 so users
+    /// @dev can claim those tokens on L1.
+    /// @param tokenL1 Address of the token on L1
+    /// @param tokenL2 Address of the token on L2
+    /// @param destination Address on L1 that should receive the tokens
+    /// @param tokenId Id of the tokens to withdraw
+    /// @param amount Amount of tokens to withdraw
+    function withdrawERC1155(address tokenL1, address tokenL2, address destination, uint256 tokenId, uint256 amount) external;
 }
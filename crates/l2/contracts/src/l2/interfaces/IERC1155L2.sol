@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC1155/IERC1155.sol";
+
+/// @title Interface for an L2-capable ERC1155 token.
+/// @author LambdaClass
+/// @dev Mirrors the IERC20L2 interface, with an amount for each token id
+interface IERC1155L2 is IERC1155 {
+    /// @notice Returns the address of the token on the L1
+    /// @dev Used to verify token reception.
+    function l1Address() external returns (address);
+
+    /// @notice Mints tokens with the given id to the given address
+    /// @dev Should be callable by the bridge
+    function crosschainMint(address to, uint256 id, uint256 amount) external;
+
+    /// @notice Burns tokens with the given id from the given address
+    /// @dev Should be callable by the bridge
+    function crosschainBurn(address from, uint256 id, uint256 amount) external;
+}
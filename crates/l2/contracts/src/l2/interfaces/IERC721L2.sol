@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "@openzeppelin/contracts/token/ERC721/IERC721.sol";
+
+/// @title Interface for an L2-capable ERC721 token.
+/// @author LambdaClass
+/// @dev Mirrors the IERC20L2 interface, with token ids instead of amounts
+interface IERC721L2 is IERC721 {
+    /// @notice Returns the address of the token on the L1
+    /// @dev Used to verify token reception.
+    function l1Address() external returns (address);
+
+    /// @notice Mints the given token to the given address
+    /// @dev Should be callable by the bridge
+    function crosschainMint(address to, uint256 tokenId) external;
+
+    /// @notice Burns the given token, which must be owned by the given address
+    /// @dev Should be callable by the bridge
+    function crosschainBurn(address from, uint256 tokenId) external;
+}
@@ -43,6 +43,24 @@ const WITHDRAWAL_ERC20_TOKEN_L1_TOPIC_IDX: usize = 1;
 const WITHDRAWAL_ERC20_TOKEN_L2_TOPIC_IDX: usize = 2;
 const WITHDRAWAL_ERC20_RECEIVER_TOPIC_IDX: usize = 3;
 /*
+event ERC721WithdrawalInitiated(
+    address indexed tokenL1,      => topic 1
+    address indexed tokenL2,      => topic 2
+    address indexed receiverOnL1, => topic 3
+    uint256 tokenId               => data 0..32
+);
+event ERC1155WithdrawalInitiated(
+    address indexed tokenL1,      => topic 1
+    address indexed tokenL2,      => topic 2
+    address indexed receiverOnL1, => topic 3
+    uint256 tokenId,              => data 0..32
+    uint256 amount                => data 32..64
+);
+*/
+const WITHDRAWAL_NFT_TOKEN_L1_TOPIC_IDX: usize = 1;
+const WITHDRAWAL_NFT_TOKEN_L2_TOPIC_IDX: usize = 2;
+const WITHDRAWAL_NFT_RECEIVER_TOPIC_IDX: usize = 3;
+/*
 event L1Message(
     address indexed senderOnL2,   => topic 1
     bytes32 indexed data,         => topic 2
@@ -120,6 +138,8 @@ impl Display for L2ToL1MessageStatus {
 pub enum L2ToL1MessageKind {
     ETHWithdraw,
     ERC20Withdraw,
+    ERC721Withdraw,
+    ERC1155Withdraw,
     Message,
 }
 
@@ -128,6 +148,8 @@ impl Display for L2ToL1MessageKind {
         match self {
             L2ToL1MessageKind::ETHWithdraw => write!(f, "Withdraw (ETH)"),
             L2ToL1MessageKind::ERC20Withdraw => write!(f, "Withdraw (ERC20)"),
+            L2ToL1MessageKind::ERC721Withdraw => write!(f, "Withdraw (ERC721)"),
+            L2ToL1MessageKind::ERC1155Withdraw => write!(f, "Withdraw (ERC1155)"),
             L2ToL1MessageKind::Message => write!(f, "Message"),
         }
     }
@@ -141,6 +163,8 @@ pub struct L2ToL1MessageRow {
     pub value: U256,
     pub token_l1: Address,
     pub token_l2: Address,
+    /// Id of the withdrawn token, only set for NFT withdrawals
+    pub token_id: U256,
     pub l2_tx_hash: H256,
 }
 
@@ -228,6 +252,10 @@ impl L2ToL1MessagesTable {
         let eth_withdrawal_topic = keccak(b"WithdrawalInitiated(address,address,uint256)");
         let erc20_withdrawal_topic =
             keccak(b"ERC20WithdrawalInitiated(address,address,address,uint256)");
+        let erc721_withdrawal_topic =
+            keccak(b"ERC721WithdrawalInitiated(address,address,address,uint256)");
+        let erc1155_withdrawal_topic =
+            keccak(b"ERC1155WithdrawalInitiated(address,address,address,uint256,uint256)");
 
         for log in logs {
             let withdrawal_status = match L2ToL1MessageStatus::for_tx(
@@ -263,6 +291,7 @@ impl L2ToL1MessagesTable {
                         ),
                         token_l1: Address::default(),
                         token_l2: Address::default(),
+                        token_id: U256::zero(),
                         l2_tx_hash: log.transaction_hash,
                     });
                 }
@@ -300,6 +329,37 @@ impl L2ToL1MessagesTable {
                                 ))?
                                 .as_fixed_bytes()[12..],
                         ),
+                        token_id: U256::zero(),
+                        l2_tx_hash: log.transaction_hash,
+                    });
+                }
+                topic if topic == erc721_withdrawal_topic => {
+                    processed_logs.push(L2ToL1MessageRow {
+                        kind: L2ToL1MessageKind::ERC721Withdraw,
+                        status: withdrawal_status,
+                        receiver: address_from_topic(log, WITHDRAWAL_NFT_RECEIVER_TOPIC_IDX)?,
+                        value: U256::one(),
+                        token_l1: address_from_topic(log, WITHDRAWAL_NFT_TOKEN_L1_TOPIC_IDX)?,
+                        token_l2: address_from_topic(log, WITHDRAWAL_NFT_TOKEN_L2_TOPIC_IDX)?,
+                        token_id: U256::from_big_endian(
+                            log.log.data.get(0..32).ok_or(MonitorError::LogsData(32))?,
+                        ),
+                        l2_tx_hash: log.transaction_hash,
+                    });
+                }
+                topic if topic == erc1155_withdrawal_topic => {
+                    processed_logs.push(L2ToL1MessageRow {
+                        kind: L2ToL1MessageKind::ERC1155Withdraw,
+                        status: withdrawal_status,
+                        receiver: address_from_topic(log, WITHDRAWAL_NFT_RECEIVER_TOPIC_IDX)?,
+                        value: U256::from_big_endian(
+                            log.log.data.get(32..64).ok_or(MonitorError::LogsData(64))?,
+                        ),
+                        token_l1: address_from_topic(log, WITHDRAWAL_NFT_TOKEN_L1_TOPIC_IDX)?,
+                        token_l2: address_from_topic(log, WITHDRAWAL_NFT_TOKEN_L2_TOPIC_IDX)?,
+                        token_id: U256::from_big_endian(
+                            log.log.data.get(0..32).ok_or(MonitorError::LogsData(32))?,
+                        ),
                         l2_tx_hash: log.transaction_hash,
                     });
                 }
@@ -313,6 +373,15 @@ impl L2ToL1MessagesTable {
     }
 }
 
+fn address_from_topic(log: &RpcLog, topic_idx: usize) -> Result<Address, MonitorError> {
+    let topic = log
+        .log
+        .topics
+        .get(topic_idx)
+        .ok_or(MonitorError::LogsTopics(topic_idx))?;
+    Ok(Address::from_slice(&topic.as_fixed_bytes()[12..]))
+}
+
 impl StatefulWidget for &mut L2ToL1MessagesTable {
     type State = TableState;
 
@@ -321,12 +390,13 @@ impl StatefulWidget for &mut L2ToL1MessagesTable {
         Self: Sized,
     {
         let constraints = vec![
-            Constraint::Length(16),
+            Constraint::Length(18),
             Constraint::Length(9),
             Constraint::Length(ADDRESS_LENGTH_IN_DIGITS),
             Constraint::Length(NUMBER_LENGTH_IN_DIGITS),
             Constraint::Length(ADDRESS_LENGTH_IN_DIGITS),
             Constraint::Length(ADDRESS_LENGTH_IN_DIGITS),
+            Constraint::Length(NUMBER_LENGTH_IN_DIGITS),
             Constraint::Length(HASH_LENGTH_IN_DIGITS),
         ];
 
@@ -338,6 +408,7 @@ impl StatefulWidget for &mut L2ToL1MessagesTable {
                 Span::styled(row.value.to_string(), Style::default()),
                 Span::styled(format!("{:#x}", row.token_l1), Style::default()),
                 Span::styled(format!("{:#x}", row.token_l2), Style::default()),
+                Span::styled(row.token_id.to_string(), Style::default()),
                 Span::styled(format!("{:#x}", row.l2_tx_hash), Style::default()),
             ])
         });
@@ -351,6 +422,7 @@ impl StatefulWidget for &mut L2ToL1MessagesTable {
                     "Value",
                     "Token L1",
                     "Token L2",
+                    "Token ID",
                     "L2 Tx Hash",
                 ])
                 .style(Style::default()),
@@ -2,4 +2,5 @@ pub mod batch;
 pub mod execution_witness;
 pub mod fees;
 pub mod l1_message;
+pub mod preconfirmation;
 pub mod transaction;
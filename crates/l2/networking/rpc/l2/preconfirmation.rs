@@ -0,0 +1,35 @@
+use ethrex_common::H256;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+
+pub struct GetPreconfirmationRequest {
+    pub tx_hash: H256,
+}
+
+impl RpcHandler for GetPreconfirmationRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<GetPreconfirmationRequest, RpcErr> {
+        let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+            "No params provided".to_owned(),
+        ))?;
+        if params.len() != 1 {
+            return Err(ethrex_rpc::RpcErr::BadParams("Expected 1 param".to_owned()))?;
+        };
+        let tx_hash = serde_json::from_value(params[0].clone())?;
+
+        Ok(GetPreconfirmationRequest { tx_hash })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        debug!(
+            "Requested preconfirmation for transaction {:#x}",
+            self.tx_hash
+        );
+        let preconfirmation = context.preconfirmations.get(&self.tx_hash);
+        Ok(serde_json::to_value(preconfirmation)?)
+    }
+}
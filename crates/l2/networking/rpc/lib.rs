@@ -1,5 +1,6 @@
 pub mod clients;
 pub mod l2;
+pub mod preconfirmations;
 mod rpc;
 pub mod signer;
 pub mod utils;
@@ -0,0 +1,249 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use ethereum_types::Signature;
+use ethrex_common::{Address, H256, U256, serde_utils};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Number of blocks for which settled preconfirmations are kept around to be queried.
+const SETTLED_PRECONFIRMATIONS_RETENTION: u64 = 128;
+
+/// Capacity of the channel used to stream preconfirmations to subscribers.
+/// Slow subscribers lagging behind this amount will miss preconfirmations.
+const PRECONFIRMATIONS_CHANNEL_CAPACITY: usize = 4096;
+
+/// Domain tag prefixed to the signed message, so a preconfirmation signature can't be
+/// replayed as a signature over any other 128 bytes message signed with the same key.
+pub const PRECONFIRMATION_DOMAIN: &[u8] = b"ethrex-preconfirmation-v1";
+
+/// Promise made by the sequencer that a transaction will be included in a block
+/// at a given position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preconfirmation {
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub chain_id: u64,
+    pub tx_hash: H256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub block_number: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub transaction_index: u64,
+}
+
+impl Preconfirmation {
+    /// Message signed by the sequencer:
+    /// `PRECONFIRMATION_DOMAIN || chain_id (32 bytes) || tx_hash (32 bytes) || block_number (32 bytes) || transaction_index (32 bytes)`
+    pub fn encode(&self) -> Bytes {
+        let mut encoded = Vec::with_capacity(PRECONFIRMATION_DOMAIN.len() + 128);
+        encoded.extend_from_slice(PRECONFIRMATION_DOMAIN);
+        encoded.extend_from_slice(&U256::from(self.chain_id).to_big_endian());
+        encoded.extend_from_slice(self.tx_hash.as_bytes());
+        encoded.extend_from_slice(&U256::from(self.block_number).to_big_endian());
+        encoded.extend_from_slice(&U256::from(self.transaction_index).to_big_endian());
+        encoded.into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedPreconfirmation {
+    #[serde(flatten)]
+    pub preconfirmation: Preconfirmation,
+    pub signer: Address,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Default)]
+struct PreconfirmationsInner {
+    /// Preconfirmations for the block that is being built.
+    pending: HashMap<H256, SignedPreconfirmation>,
+    /// Preconfirmations that were honored, kept for [SETTLED_PRECONFIRMATIONS_RETENTION] blocks.
+    settled: HashMap<H256, SignedPreconfirmation>,
+    settled_by_block: BTreeMap<u64, Vec<H256>>,
+}
+
+/// Registry of the preconfirmations issued by the block producer, shared with the RPC
+/// so they can be queried and streamed to the users.
+#[derive(Debug, Clone)]
+pub struct Preconfirmations {
+    inner: Arc<Mutex<PreconfirmationsInner>>,
+    sender: broadcast::Sender<SignedPreconfirmation>,
+}
+
+impl Default for Preconfirmations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Preconfirmations {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(PRECONFIRMATIONS_CHANNEL_CAPACITY);
+        Self {
+            inner: Arc::new(Mutex::new(PreconfirmationsInner::default())),
+            sender,
+        }
+    }
+
+    /// Registers a new preconfirmation and streams it to the subscribers.
+    pub fn publish(&self, preconfirmation: SignedPreconfirmation) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.pending.insert(
+                preconfirmation.preconfirmation.tx_hash,
+                preconfirmation.clone(),
+            );
+        }
+        // There may be no subscribers, in which case the preconfirmation is only stored.
+        let _ = self.sender.send(preconfirmation);
+    }
+
+    pub fn get(&self, tx_hash: &H256) -> Option<SignedPreconfirmation> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .pending
+            .get(tx_hash)
+            .or_else(|| inner.settled.get(tx_hash))
+            .cloned()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SignedPreconfirmation> {
+        self.sender.subscribe()
+    }
+
+    /// Settles the pending preconfirmations issued for the stored block `block_number`
+    /// against its transactions, returning the amount of broken promises. Promises for
+    /// other blocks are kept pending.
+    pub fn settle(&self, block_number: u64, transactions: &[H256]) -> u64 {
+        let Ok(mut inner) = self.inner.lock() else {
+            return 0;
+        };
+        let (to_settle, pending) =
+            std::mem::take(&mut inner.pending)
+                .into_iter()
+                .partition(|(_, preconfirmation)| {
+                    preconfirmation.preconfirmation.block_number == block_number
+                });
+        inner.pending = pending;
+        let mut broken = 0;
+        let mut settled = Vec::new();
+        for (tx_hash, preconfirmation) in to_settle {
+            let promise = &preconfirmation.preconfirmation;
+            let honored = usize::try_from(promise.transaction_index)
+                .ok()
+                .and_then(|index| transactions.get(index))
+                == Some(&tx_hash);
+            if honored {
+                settled.push(tx_hash);
+                inner.settled.insert(tx_hash, preconfirmation);
+            } else {
+                broken += 1;
+            }
+        }
+        inner.settled_by_block.insert(block_number, settled);
+
+        // Forget preconfirmations of old blocks
+        let first_kept = block_number.saturating_sub(SETTLED_PRECONFIRMATIONS_RETENTION);
+        let kept = inner.settled_by_block.split_off(&first_kept);
+        for tx_hash in std::mem::replace(&mut inner.settled_by_block, kept)
+            .into_values()
+            .flatten()
+        {
+            inner.settled.remove(&tx_hash);
+        }
+        broken
+    }
+
+    /// Drops the pending preconfirmations because the block they were issued for
+    /// couldn't be stored, returning the amount of broken promises.
+    pub fn break_pending(&self) -> u64 {
+        let Ok(mut inner) = self.inner.lock() else {
+            return 0;
+        };
+        let broken = inner.pending.len();
+        inner.pending.clear();
+        broken.try_into().unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preconfirmation(tx_hash: H256, block_number: u64, index: u64) -> SignedPreconfirmation {
+        SignedPreconfirmation {
+            preconfirmation: Preconfirmation {
+                chain_id: 1729,
+                tx_hash,
+                block_number,
+                transaction_index: index,
+            },
+            signer: Address::zero(),
+            signature: Signature::zero(),
+        }
+    }
+
+    #[test]
+    fn signed_message_is_domain_separated() {
+        let promise = preconfirmation(H256::repeat_byte(1), 10, 2).preconfirmation;
+        let encoded = promise.encode();
+        let (domain, fields) = encoded.split_at(PRECONFIRMATION_DOMAIN.len());
+        assert_eq!(domain, PRECONFIRMATION_DOMAIN);
+        assert_eq!(fields.len(), 128);
+        assert_eq!(&fields[32..64], promise.tx_hash.as_bytes());
+    }
+
+    #[test]
+    fn settle_counts_broken_promises() {
+        let preconfirmations = Preconfirmations::new();
+        let (a, b, c) = (
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            H256::repeat_byte(3),
+        );
+        preconfirmations.publish(preconfirmation(a, 10, 0));
+        preconfirmations.publish(preconfirmation(b, 10, 1));
+        preconfirmations.publish(preconfirmation(c, 10, 2));
+
+        // `b` is included at a different position and `c` is not included at all
+        let broken = preconfirmations.settle(10, &[a, H256::zero(), b]);
+        assert_eq!(broken, 2);
+        assert!(preconfirmations.get(&a).is_some());
+        assert!(preconfirmations.get(&b).is_none());
+        assert!(preconfirmations.get(&c).is_none());
+
+        // Settled preconfirmations are forgotten after the retention period
+        preconfirmations.settle(10 + SETTLED_PRECONFIRMATIONS_RETENTION + 1, &[]);
+        assert!(preconfirmations.get(&a).is_none());
+    }
+
+    #[test]
+    fn settle_keeps_promises_for_other_blocks_pending() {
+        let preconfirmations = Preconfirmations::new();
+        let (a, b) = (H256::repeat_byte(1), H256::repeat_byte(2));
+        preconfirmations.publish(preconfirmation(a, 10, 0));
+        preconfirmations.publish(preconfirmation(b, 11, 0));
+
+        assert_eq!(preconfirmations.settle(10, &[a]), 0);
+        assert!(preconfirmations.get(&b).is_some());
+
+        assert_eq!(preconfirmations.settle(11, &[b]), 0);
+        assert!(preconfirmations.get(&a).is_some());
+        assert!(preconfirmations.get(&b).is_some());
+    }
+
+    #[test]
+    fn pending_preconfirmations_are_streamed_and_broken() {
+        let preconfirmations = Preconfirmations::new();
+        let mut subscription = preconfirmations.subscribe();
+        let promise = preconfirmation(H256::repeat_byte(1), 5, 0);
+        preconfirmations.publish(promise.clone());
+
+        assert_eq!(subscription.try_recv().ok(), Some(promise));
+        assert_eq!(preconfirmations.break_pending(), 1);
+        assert!(preconfirmations.get(&H256::repeat_byte(1)).is_none());
+    }
+}
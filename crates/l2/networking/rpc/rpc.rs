@@ -5,8 +5,11 @@ use crate::l2::fees::{
     GetOperatorFeeVaultAddress,
 };
 use crate::l2::l1_message::GetL1MessageProof;
+use crate::l2::preconfirmation::GetPreconfirmationRequest;
+use crate::preconfirmations::{Preconfirmations, SignedPreconfirmation};
 use crate::utils::{RpcErr, RpcNamespace, resolve_namespace};
-use axum::extract::State;
+use axum::extract::ws::WebSocket;
+use axum::extract::{State, WebSocketUpgrade};
 use axum::{Json, Router, http::StatusCode, routing::post};
 use bytes::Bytes;
 use ethrex_blockchain::Blockchain;
@@ -18,7 +21,7 @@ use ethrex_p2p::types::NodeRecord;
 use ethrex_rpc::RpcHandler as L1RpcHandler;
 use ethrex_rpc::debug::execution_witness::ExecutionWitnessRequest;
 use ethrex_rpc::{
-    GasTipEstimator, NodeData, RpcRequestWrapper,
+    GasTipEstimator, NodeData, RpcRequestWrapper, SendRawTransactionSyncRequest,
     types::transaction::SendRawTransactionRequest,
     utils::{RpcRequest, RpcRequestId},
 };
@@ -28,12 +31,18 @@ use std::{
     collections::HashMap,
     future::IntoFuture,
     net::SocketAddr,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
     time::Duration,
 };
-use tokio::{net::TcpListener, sync::Mutex as TokioMutex};
+use tokio::{
+    net::TcpListener,
+    sync::{Mutex as TokioMutex, broadcast},
+};
 use tower_http::cors::CorsLayer;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use tracing_subscriber::{EnvFilter, Registry, reload};
 
 use crate::l2::transaction::SponsoredTx;
@@ -47,6 +56,7 @@ pub struct RpcApiContext {
     pub valid_delegation_addresses: Vec<Address>,
     pub sponsor_pk: SecretKey,
     pub rollup_store: StoreRollup,
+    pub preconfirmations: Preconfirmations,
 }
 
 pub trait RpcHandler: Sized {
@@ -71,6 +81,7 @@ pub const FILTER_DURATION: Duration = {
 #[expect(clippy::too_many_arguments)]
 pub async fn start_api(
     http_addr: SocketAddr,
+    ws_addr: Option<SocketAddr>,
     authrpc_addr: SocketAddr,
     storage: Store,
     blockchain: Arc<Blockchain>,
@@ -85,6 +96,7 @@ pub async fn start_api(
     rollup_store: StoreRollup,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     gas_ceil: u64,
+    preconfirmations: Preconfirmations,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
@@ -108,10 +120,12 @@ pub async fn start_api(
             log_filter_handler,
             gas_ceil,
             block_worker_channel,
+            dev: None,
         },
         valid_delegation_addresses,
         sponsor_pk,
         rollup_store,
+        preconfirmations,
     };
 
     // Periodically clean up the active filters for the filters endpoints.
@@ -134,7 +148,10 @@ pub async fn start_api(
 
     let http_router = Router::new()
         .route("/", post(handle_http_request))
-        .layer(cors)
+        .layer(axum::middleware::from_fn(
+            ethrex_rpc::propagate_trace_context,
+        ))
+        .layer(cors.clone())
         .with_state(service_context.clone());
     let http_listener = TcpListener::bind(http_addr)
         .await
@@ -146,8 +163,28 @@ pub async fn start_api(
 
     info!("Not starting Auth-RPC server. The address passed as argument is {authrpc_addr}");
 
-    let _ =
-        tokio::try_join!(http_server).inspect_err(|e| info!("Error shutting down servers: {e:?}"));
+    if let Some(address) = ws_addr {
+        let ws_handler = |ws: WebSocketUpgrade, ctx| async {
+            ws.on_upgrade(|socket| handle_websocket(socket, ctx))
+        };
+        let ws_router = Router::new()
+            .route("/", axum::routing::any(ws_handler))
+            .layer(cors)
+            .with_state(service_context);
+        let ws_listener = TcpListener::bind(address)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        let ws_server = axum::serve(ws_listener, ws_router)
+            .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
+            .into_future();
+        info!("Starting WS server at {address}");
+
+        let _ = tokio::try_join!(http_server, ws_server)
+            .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
+    } else {
+        let _ = tokio::try_join!(http_server)
+            .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
+    }
 
     Ok(())
 }
@@ -182,6 +219,135 @@ async fn handle_http_request(
     Ok(Json(res))
 }
 
+/// Identifier of the next preconfirmations subscription, unique across connections.
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Handles websocket connections. Besides regular requests, a connection can call
+/// `ethrex_subscribe` with `["preconfirmations"]` to receive every preconfirmation
+/// issued by the sequencer as an `ethrex_subscription` notification.
+async fn handle_websocket(mut socket: WebSocket, state: State<RpcApiContext>) {
+    let mut subscription: Option<(String, broadcast::Receiver<SignedPreconfirmation>)> = None;
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let Some(Ok(body)) = message.map(|msg| msg.and_then(|msg| msg.into_text())) else {
+                    return;
+                };
+                let Some(response) =
+                    handle_websocket_request(body.to_string(), &state, &mut subscription).await
+                else {
+                    return;
+                };
+                if socket.send(response.to_string().into()).await.is_err() {
+                    return;
+                }
+            }
+            preconfirmation = next_preconfirmation(&mut subscription) => {
+                let Some(notification) =
+                    preconfirmation_notification(&mut subscription, preconfirmation)
+                else {
+                    continue;
+                };
+                if socket.send(notification.to_string().into()).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn handle_websocket_request(
+    body: String,
+    state: &State<RpcApiContext>,
+    subscription: &mut Option<(String, broadcast::Receiver<SignedPreconfirmation>)>,
+) -> Option<Value> {
+    match serde_json::from_str::<RpcRequest>(&body) {
+        Ok(request) if request.method == "ethrex_subscribe" => {
+            let res = subscribe(&request, &state.preconfirmations).map(|(id, receiver)| {
+                *subscription = Some((id.clone(), receiver));
+                Value::String(id)
+            });
+            ethrex_rpc::rpc_response(request.id, res).ok()
+        }
+        Ok(request) if request.method == "ethrex_unsubscribe" => {
+            let unsubscribed = request
+                .params
+                .as_ref()
+                .and_then(|params| params.first())
+                .and_then(Value::as_str)
+                .is_some_and(|id| {
+                    subscription
+                        .as_ref()
+                        .is_some_and(|(current, _)| current == id)
+                });
+            if unsubscribed {
+                *subscription = None;
+            }
+            ethrex_rpc::rpc_response::<RpcErr>(request.id, Ok(Value::Bool(unsubscribed))).ok()
+        }
+        // ok-clone: increase arc reference count
+        _ => handle_http_request(state.clone(), body)
+            .await
+            .ok()
+            .map(|Json(response)| response),
+    }
+}
+
+fn subscribe(
+    request: &RpcRequest,
+    preconfirmations: &Preconfirmations,
+) -> Result<(String, broadcast::Receiver<SignedPreconfirmation>), RpcErr> {
+    let topic = request
+        .params
+        .as_ref()
+        .and_then(|params| params.first())
+        .and_then(Value::as_str);
+    if topic != Some("preconfirmations") {
+        return Err(ethrex_rpc::RpcErr::BadParams(
+            "Only the \"preconfirmations\" subscription is supported".to_owned(),
+        ))?;
+    }
+    let id = format!(
+        "{:#x}",
+        NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    Ok((id, preconfirmations.subscribe()))
+}
+
+fn preconfirmation_notification(
+    subscription: &mut Option<(String, broadcast::Receiver<SignedPreconfirmation>)>,
+    preconfirmation: Result<SignedPreconfirmation, broadcast::error::RecvError>,
+) -> Option<Value> {
+    let (id, _) = subscription.as_ref()?;
+    match preconfirmation {
+        Ok(preconfirmation) => Some(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "ethrex_subscription",
+            "params": {
+                "subscription": id,
+                "result": preconfirmation,
+            }
+        })),
+        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+            warn!("Websocket subscriber lagged behind, skipped {skipped} preconfirmations");
+            None
+        }
+        Err(broadcast::error::RecvError::Closed) => {
+            *subscription = None;
+            None
+        }
+    }
+}
+
+async fn next_preconfirmation(
+    subscription: &mut Option<(String, broadcast::Receiver<SignedPreconfirmation>)>,
+) -> Result<SignedPreconfirmation, broadcast::error::RecvError> {
+    match subscription {
+        Some((_, receiver)) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Handle requests that can come from either clients or other users
 pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match resolve_namespace(&req.method) {
@@ -198,20 +364,19 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
 pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
         "eth_sendRawTransaction" => {
-            let tx = SendRawTransactionRequest::parse(&req.params)?;
-            if let SendRawTransactionRequest::EIP4844(wrapped_blob_tx) = tx {
-                debug!(
-                    "EIP-4844 transaction are not supported in the L2: {:#x}",
-                    Transaction::EIP4844Transaction(wrapped_blob_tx.tx).hash()
-                );
-                return Err(RpcErr::InvalidEthrexL2Message(
-                    "EIP-4844 transactions are not supported in the L2".to_string(),
-                ));
-            }
+            reject_blob_transaction(SendRawTransactionRequest::parse(&req.params)?)?;
             SendRawTransactionRequest::call(req, context.l1_ctx)
                 .await
                 .map_err(RpcErr::L1RpcErr)
         }
+        "eth_sendRawTransactionSync" => {
+            reject_blob_transaction(
+                SendRawTransactionSyncRequest::parse(&req.params)?.transaction,
+            )?;
+            SendRawTransactionSyncRequest::call(req, context.l1_ctx)
+                .await
+                .map_err(RpcErr::L1RpcErr)
+        }
         "debug_executionWitness" => {
             let request = ExecutionWitnessRequest::parse(&req.params)?;
             handle_execution_witness(&request, context)
@@ -224,6 +389,19 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
     }
 }
 
+fn reject_blob_transaction(tx: SendRawTransactionRequest) -> Result<(), RpcErr> {
+    if let SendRawTransactionRequest::EIP4844(wrapped_blob_tx) = tx {
+        debug!(
+            "EIP-4844 transaction are not supported in the L2: {:#x}",
+            Transaction::EIP4844Transaction(wrapped_blob_tx.tx).hash()
+        );
+        return Err(RpcErr::InvalidEthrexL2Message(
+            "EIP-4844 transactions are not supported in the L2".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 pub async fn map_l2_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
         "ethrex_sendTransaction" => SponsoredTx::call(req, context).await,
@@ -235,8 +413,10 @@ pub async fn map_l2_requests(req: &RpcRequest, context: RpcApiContext) -> Result
         "ethrex_getOperatorFee" => GetOperatorFee::call(req, context).await,
         "ethrex_getL1FeeVaultAddress" => GetL1FeeVaultAddress::call(req, context).await,
         "ethrex_getL1BlobBaseFee" => GetL1BlobBaseFeeRequest::call(req, context).await,
-        unknown_ethrex_l2_method => {
-            Err(ethrex_rpc::RpcErr::MethodNotFound(unknown_ethrex_l2_method.to_owned()).into())
-        }
+        "ethrex_getPreconfirmation" => GetPreconfirmationRequest::call(req, context).await,
+        // Methods of the namespace shared with L1 nodes
+        _other_ethrex_method => ethrex_rpc::map_ethrex_requests(req, context.l1_ctx)
+            .await
+            .map_err(RpcErr::L1RpcErr),
     }
 }
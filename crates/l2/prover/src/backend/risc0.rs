@@ -3,8 +3,9 @@ use ethrex_l2_common::{
     prover::{BatchProof, ProofBytes, ProofCalldata, ProofFormat, ProverType},
 };
 use guest_program::{
+    aggregation::AggregationInput,
     input::ProgramInput,
-    methods::{ZKVM_RISC0_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ID},
+    methods::{ZKVM_RISC0_AGGREGATION_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ID},
 };
 use risc0_zkp::verify::VerificationError;
 use risc0_zkvm::{
@@ -29,6 +30,8 @@ pub enum Error {
     ZkvmDyn(#[from] anyhow::Error),
     #[error("bincode error: {0}")]
     Bincode(#[from] Box<bincode::ErrorKind>),
+    #[error("only compressed proofs can be aggregated")]
+    AggregateNonCompressedProof,
 }
 
 pub fn execute(input: ProgramInput) -> Result<(), Box<dyn std::error::Error>> {
@@ -40,10 +43,13 @@ pub fn execute(input: ProgramInput) -> Result<(), Box<dyn std::error::Error>> {
     let executor = default_executor();
 
     let now = Instant::now();
-    let _session_info = executor.execute(env, ZKVM_RISC0_PROGRAM_ELF)?;
+    let session_info = executor.execute(env, ZKVM_RISC0_PROGRAM_ELF)?;
     let elapsed = now.elapsed();
 
-    info!("Successfully executed RISC0 program in {elapsed:.2?}");
+    info!(
+        cycles = session_info.cycles(),
+        "Successfully executed RISC0 program in {elapsed:.2?}"
+    );
 
     Ok(())
 }
@@ -76,6 +82,53 @@ pub fn prove(
     Ok(prove_info.receipt)
 }
 
+/// Generates a recursive proof of the given compressed batch proofs, which must belong
+/// to consecutive batches and be ordered by batch number.
+pub fn prove_aggregation(
+    batch_proofs: Vec<BatchProof>,
+    format: ProofFormat,
+) -> Result<Receipt, Box<dyn std::error::Error>> {
+    let mut receipts = Vec::with_capacity(batch_proofs.len());
+    let mut public_values = Vec::with_capacity(batch_proofs.len());
+    for batch_proof in batch_proofs {
+        let Some(proof) = batch_proof.compressed() else {
+            return Err(Error::AggregateNonCompressedProof.into());
+        };
+        let inner: InnerReceipt = bincode::deserialize(&proof)?;
+        let journal = batch_proof.public_values();
+        receipts.push(Receipt::new(inner, journal.clone()));
+        public_values.push(journal);
+    }
+
+    let input = AggregationInput {
+        program_vk: ZKVM_RISC0_PROGRAM_ID,
+        public_values,
+    };
+    let bytes = rkyv::to_bytes::<RkyvError>(&input)?;
+    let mut env = ExecutorEnv::builder();
+    for receipt in receipts {
+        // Each receipt resolves one of the `env::verify` calls of the guest
+        env.add_assumption(receipt);
+    }
+    let env = env.write_slice(bytes.as_slice()).build()?;
+
+    let prover = default_prover();
+
+    let prover_opts = match format {
+        ProofFormat::Compressed => ProverOpts::succinct(),
+        ProofFormat::Groth16 => ProverOpts::groth16(),
+    };
+
+    let now = Instant::now();
+    let prove_info =
+        prover.prove_with_opts(env, ZKVM_RISC0_AGGREGATION_PROGRAM_ELF, &prover_opts)?;
+    let elapsed = now.elapsed();
+
+    info!("Successfully proved RISC0 aggregation program in {elapsed:.2?}");
+
+    Ok(prove_info.receipt)
+}
+
 pub fn verify(receipt: &Receipt) -> Result<(), Error> {
     receipt.verify(ZKVM_RISC0_PROGRAM_ID)?;
     Ok(())
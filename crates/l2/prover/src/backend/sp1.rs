@@ -2,7 +2,10 @@ use ethrex_l2_common::{
     calldata::Value,
     prover::{BatchProof, ProofBytes, ProofCalldata, ProofFormat, ProverType},
 };
-use guest_program::{ZKVM_SP1_PROGRAM_ELF, input::ProgramInput};
+use guest_program::{
+    ZKVM_SP1_AGGREGATION_PROGRAM_ELF, ZKVM_SP1_PROGRAM_ELF, aggregation::AggregationInput,
+    input::ProgramInput,
+};
 use rkyv::rancor::Error;
 use sp1_prover::components::CpuProverComponents;
 #[cfg(not(feature = "gpu"))]
@@ -10,7 +13,7 @@ use sp1_sdk::CpuProver;
 #[cfg(feature = "gpu")]
 use sp1_sdk::cuda::builder::CudaProverBuilder;
 use sp1_sdk::{
-    HashableKey, Prover, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin,
+    HashableKey, Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin,
     SP1VerifyingKey,
 };
 use std::{fmt::Debug, sync::OnceLock, time::Instant};
@@ -21,6 +24,8 @@ pub struct ProverSetup {
     client: Box<dyn Prover<CpuProverComponents>>,
     pk: SP1ProvingKey,
     vk: SP1VerifyingKey,
+    aggregation_pk: SP1ProvingKey,
+    aggregation_vk: SP1VerifyingKey,
 }
 
 pub static PROVER_SETUP: OnceLock<ProverSetup> = OnceLock::new();
@@ -45,11 +50,14 @@ pub fn init_prover_setup(_endpoint: Option<Url>) -> ProverSetup {
     #[cfg(not(feature = "gpu"))]
     let client = { CpuProver::new() };
     let (pk, vk) = client.setup(ZKVM_SP1_PROGRAM_ELF);
+    let (aggregation_pk, aggregation_vk) = client.setup(ZKVM_SP1_AGGREGATION_PROGRAM_ELF);
 
     ProverSetup {
         client: Box::new(client),
         pk,
         vk,
+        aggregation_pk,
+        aggregation_vk,
     }
 }
 pub struct ProveOutput {
@@ -85,10 +93,13 @@ pub fn execute(input: ProgramInput) -> Result<(), Box<dyn std::error::Error>> {
     let setup = PROVER_SETUP.get_or_init(|| init_prover_setup(None));
 
     let now = Instant::now();
-    setup.client.execute(ZKVM_SP1_PROGRAM_ELF, &stdin)?;
+    let (_, report) = setup.client.execute(ZKVM_SP1_PROGRAM_ELF, &stdin)?;
     let elapsed = now.elapsed();
 
-    info!("Successfully executed SP1 program in {elapsed:.2?}");
+    info!(
+        cycles = report.total_instruction_count(),
+        "Successfully executed SP1 program in {elapsed:.2?}"
+    );
 
     Ok(())
 }
@@ -118,6 +129,53 @@ pub fn prove(
     Ok(ProveOutput::new(proof, setup.vk.clone()))
 }
 
+/// Generates a recursive proof of the given compressed batch proofs, which must belong
+/// to consecutive batches and be ordered by batch number.
+pub fn prove_aggregation(
+    batch_proofs: Vec<BatchProof>,
+    format: ProofFormat,
+) -> Result<ProveOutput, Box<dyn std::error::Error>> {
+    let setup = PROVER_SETUP.get_or_init(|| init_prover_setup(None));
+
+    let mut stdin = SP1Stdin::new();
+    let mut reduce_proofs = Vec::with_capacity(batch_proofs.len());
+    let mut public_values = Vec::with_capacity(batch_proofs.len());
+    for batch_proof in batch_proofs {
+        let Some(proof) = batch_proof.compressed() else {
+            return Err("Only compressed proofs can be aggregated".into());
+        };
+        let proof: SP1ProofWithPublicValues = bincode::deserialize(&proof)?;
+        let SP1Proof::Compressed(reduce_proof) = proof.proof else {
+            return Err("Only compressed proofs can be aggregated".into());
+        };
+        reduce_proofs.push(*reduce_proof);
+        public_values.push(proof.public_values.to_vec());
+    }
+
+    let input = AggregationInput {
+        program_vk: setup.vk.hash_u32(),
+        public_values,
+    };
+    let bytes = rkyv::to_bytes::<Error>(&input)?;
+    stdin.write_slice(bytes.as_slice());
+    for reduce_proof in reduce_proofs {
+        stdin.write_proof(reduce_proof, setup.vk.vk.clone());
+    }
+
+    let format = match format {
+        ProofFormat::Compressed => SP1ProofMode::Compressed,
+        ProofFormat::Groth16 => SP1ProofMode::Groth16,
+    };
+
+    let now = Instant::now();
+    let proof = setup.client.prove(&setup.aggregation_pk, &stdin, format)?;
+    let elapsed = now.elapsed();
+
+    info!("Successfully proved SP1 aggregation program in {elapsed:.2?}");
+
+    Ok(ProveOutput::new(proof, setup.aggregation_vk.clone()))
+}
+
 pub fn verify(output: &ProveOutput) -> Result<(), Box<dyn std::error::Error>> {
     let setup = PROVER_SETUP.get_or_init(|| init_prover_setup(None));
     setup.client.verify(&output.proof, &output.vk)?;
@@ -42,24 +42,35 @@ fn build_risc0_program() {
             .unwrap()
     };
 
-    let built_guests = embed_methods_with_options(std::collections::HashMap::from([(
-        "zkvm-risc0-program",
-        guest_options,
-    )]));
-    let elf = built_guests[0].elf.clone();
-    let image_id = built_guests[0].image_id;
-
-    // this errs if the dir already exists, so we don't handle an error.
-    let _ = std::fs::create_dir("./src/risc0/out");
-
-    std::fs::write("./src/risc0/out/riscv32im-risc0-elf", &elf)
-        .expect("could not write Risc0 elf to file");
-
-    std::fs::write(
-        "./src/risc0/out/riscv32im-risc0-vk",
-        format!("0x{}\n", hex::encode(image_id.as_bytes())),
-    )
-    .expect("could not write Risc0 vk to file");
+    let built_guests = embed_methods_with_options(std::collections::HashMap::from([
+        ("zkvm-risc0-program", guest_options.clone()),
+        ("zkvm-risc0-aggregation-program", guest_options),
+    ]));
+
+    for (guest_name, out_dir) in [
+        ("zkvm-risc0-program", "./src/risc0/out"),
+        (
+            "zkvm-risc0-aggregation-program",
+            "./src/risc0_aggregation/out",
+        ),
+    ] {
+        let guest = built_guests
+            .iter()
+            .find(|guest| guest.name == guest_name)
+            .expect("Risc0 guest was not built");
+
+        // this errs if the dir already exists, so we don't handle an error.
+        let _ = std::fs::create_dir(out_dir);
+
+        std::fs::write(format!("{out_dir}/riscv32im-risc0-elf"), &guest.elf)
+            .expect("could not write Risc0 elf to file");
+
+        std::fs::write(
+            format!("{out_dir}/riscv32im-risc0-vk"),
+            format!("0x{}\n", hex::encode(guest.image_id.as_bytes())),
+        )
+        .expect("could not write Risc0 vk to file");
+    }
 }
 
 #[cfg(all(not(clippy), feature = "sp1"))]
@@ -73,36 +84,41 @@ fn build_sp1_program() {
         vec![]
     };
 
-    sp1_build::build_program_with_args(
-        "./src/sp1",
-        sp1_build::BuildArgs {
-            output_directory: Some("./src/sp1/out".to_string()),
-            elf_name: Some("riscv32im-succinct-zkvm-elf".to_string()),
-            features,
-            docker: option_env!("PROVER_REPRODUCIBLE_BUILD").is_some(),
-            tag: "v5.0.8".to_string(),
-            workspace_directory: Some(format!("{}/../../../../../", env!("CARGO_MANIFEST_DIR"))),
-            ..Default::default()
-        },
-    );
-
-    // Get verification key
-    // ref: https://github.com/succinctlabs/sp1/blob/dev/crates/cli/src/commands/vkey.rs
-    let elf = std::fs::read("./src/sp1/out/riscv32im-succinct-zkvm-elf")
-        .expect("could not read SP1 elf file");
-    let prover = ProverClient::from_env();
-    let (_, vk) = prover.setup(&elf);
-
-    std::fs::write(
-        "./src/sp1/out/riscv32im-succinct-zkvm-vk-bn254",
-        format!("{}\n", vk.vk.bytes32()),
-    )
-    .expect("could not write SP1 vk-bn254 to file");
-    std::fs::write(
-        "./src/sp1/out/riscv32im-succinct-zkvm-vk-u32",
-        format!("0x{}\n", hex::encode(vk.vk.hash_bytes())),
-    )
-    .expect("could not write SP1 vk-u32 to file");
+    for program_dir in ["./src/sp1", "./src/sp1_aggregation"] {
+        sp1_build::build_program_with_args(
+            program_dir,
+            sp1_build::BuildArgs {
+                output_directory: Some(format!("{program_dir}/out")),
+                elf_name: Some("riscv32im-succinct-zkvm-elf".to_string()),
+                features: features.clone(),
+                docker: option_env!("PROVER_REPRODUCIBLE_BUILD").is_some(),
+                tag: "v5.0.8".to_string(),
+                workspace_directory: Some(format!(
+                    "{}/../../../../../",
+                    env!("CARGO_MANIFEST_DIR")
+                )),
+                ..Default::default()
+            },
+        );
+
+        // Get verification key
+        // ref: https://github.com/succinctlabs/sp1/blob/dev/crates/cli/src/commands/vkey.rs
+        let elf = std::fs::read(format!("{program_dir}/out/riscv32im-succinct-zkvm-elf"))
+            .expect("could not read SP1 elf file");
+        let prover = ProverClient::from_env();
+        let (_, vk) = prover.setup(&elf);
+
+        std::fs::write(
+            format!("{program_dir}/out/riscv32im-succinct-zkvm-vk-bn254"),
+            format!("{}\n", vk.vk.bytes32()),
+        )
+        .expect("could not write SP1 vk-bn254 to file");
+        std::fs::write(
+            format!("{program_dir}/out/riscv32im-succinct-zkvm-vk-u32"),
+            format!("0x{}\n", hex::encode(vk.vk.hash_bytes())),
+        )
+        .expect("could not write SP1 vk-u32 to file");
+    }
 }
 
 #[cfg(all(not(clippy), feature = "zisk"))]
@@ -0,0 +1,153 @@
+use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::output::ProgramOutput;
+
+/// Private input variables passed into the zkVM aggregation program.
+///
+/// The batch proofs themselves are handed to the zkVM separately, as they are
+/// verified with the recursion primitives of each backend.
+#[derive(Serialize, Deserialize, RDeserialize, RSerialize, Archive)]
+pub struct AggregationInput {
+    /// verification key of the execution program that generated the batch proofs
+    pub program_vk: [u32; 8],
+    /// public values of the batch proofs, ordered by batch number
+    pub public_values: Vec<Vec<u8>>,
+}
+
+/// Public output of the aggregation program.
+pub struct AggregationOutput {
+    /// verification key of the execution program, as committed by the backend
+    pub program_vk: [u8; 32],
+    /// public values of the aggregated batch proofs, ordered by batch number
+    pub public_values: Vec<Vec<u8>>,
+}
+
+impl AggregationOutput {
+    /// Encodes the output as the verification key followed by the public values of
+    /// every batch, which is the layout expected by `OnChainProposer.verifyBatchesAggregated`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded =
+            Vec::with_capacity(32 + self.public_values.len() * ProgramOutput::ENCODED_LEN);
+        encoded.extend_from_slice(&self.program_vk);
+        for public_values in &self.public_values {
+            encoded.extend_from_slice(public_values);
+        }
+        encoded
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AggregationError {
+    #[error("No batch proofs to aggregate")]
+    EmptyAggregation,
+    #[error("Invalid public values length for batch proof {0}")]
+    InvalidPublicValuesLength(usize),
+    #[error("Batch proof {0} doesn't start from the final state of the previous one")]
+    StateRootMismatch(usize),
+    #[error("Batch proof {0} was generated for a different chain")]
+    ChainIdMismatch(usize),
+}
+
+/// Checks that the public values of consecutive batch proofs form a chain, i.e. every
+/// batch starts from the state the previous one ended at, and builds the aggregated output.
+///
+/// The batch proofs must be verified by the caller before committing the output.
+pub fn aggregation_program(
+    public_values: Vec<Vec<u8>>,
+    program_vk: [u8; 32],
+) -> Result<AggregationOutput, AggregationError> {
+    if public_values.is_empty() {
+        return Err(AggregationError::EmptyAggregation);
+    }
+    for (index, values) in public_values.iter().enumerate() {
+        if values.len() != ProgramOutput::ENCODED_LEN {
+            return Err(AggregationError::InvalidPublicValuesLength(index));
+        }
+    }
+    for (index, pair) in public_values.windows(2).enumerate() {
+        let (previous, current) = (&pair[0], &pair[1]);
+        // The initial state root is the first word and the final state root is the second one.
+        if current[..32] != previous[32..64] {
+            return Err(AggregationError::StateRootMismatch(index + 1));
+        }
+        // The chain id is the second to last word.
+        let chain_id = ProgramOutput::ENCODED_LEN - 64..ProgramOutput::ENCODED_LEN - 32;
+        if current[chain_id.clone()] != previous[chain_id] {
+            return Err(AggregationError::ChainIdMismatch(index + 1));
+        }
+    }
+    Ok(AggregationOutput {
+        program_vk,
+        public_values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_VK: [u8; 32] = [7; 32];
+
+    fn public_values(initial_state_root: u8, final_state_root: u8, chain_id: u8) -> Vec<u8> {
+        let mut values = vec![0; ProgramOutput::ENCODED_LEN];
+        values[..32].fill(initial_state_root);
+        values[32..64].fill(final_state_root);
+        values[ProgramOutput::ENCODED_LEN - 64..ProgramOutput::ENCODED_LEN - 32].fill(chain_id);
+        values
+    }
+
+    #[test]
+    fn chained_batches_are_aggregated() {
+        let batches = vec![public_values(1, 2, 9), public_values(2, 3, 9)];
+
+        let output = aggregation_program(batches.clone(), PROGRAM_VK).unwrap();
+
+        assert_eq!(
+            output.encode(),
+            [PROGRAM_VK.to_vec(), batches.concat()].concat()
+        );
+    }
+
+    #[test]
+    fn empty_aggregation_is_rejected() {
+        assert!(matches!(
+            aggregation_program(vec![], PROGRAM_VK),
+            Err(AggregationError::EmptyAggregation)
+        ));
+    }
+
+    #[test]
+    fn invalid_public_values_length_is_rejected() {
+        let batches = vec![public_values(1, 2, 9), vec![0; 32]];
+
+        assert!(matches!(
+            aggregation_program(batches, PROGRAM_VK),
+            Err(AggregationError::InvalidPublicValuesLength(1))
+        ));
+    }
+
+    #[test]
+    fn unchained_state_roots_are_rejected() {
+        let batches = vec![
+            public_values(1, 2, 9),
+            public_values(2, 3, 9),
+            public_values(4, 5, 9),
+        ];
+
+        assert!(matches!(
+            aggregation_program(batches, PROGRAM_VK),
+            Err(AggregationError::StateRootMismatch(2))
+        ));
+    }
+
+    #[test]
+    fn different_chain_ids_are_rejected() {
+        let batches = vec![public_values(1, 2, 9), public_values(2, 3, 10)];
+
+        assert!(matches!(
+            aggregation_program(batches, PROGRAM_VK),
+            Err(AggregationError::ChainIdMismatch(1))
+        ));
+    }
+}
@@ -24,11 +24,15 @@ use std::collections::{BTreeMap, HashMap};
 use ethrex_common::types::ELASTICITY_MULTIPLIER;
 #[cfg(feature = "l2")]
 use ethrex_common::types::{
-    BlobsBundleError, Commitment, PrivilegedL2Transaction, Proof, Receipt, blob_from_bytes,
-    kzg_commitment_to_versioned_hash,
+    BlobsBundleError, ChainConfig, Commitment, PrivilegedL2Transaction, Proof, Receipt,
+    blob_from_bytes, kzg_commitment_to_versioned_hash,
 };
 #[cfg(feature = "l2")]
 use ethrex_l2_common::{
+    forced_transactions::{
+        ForcedTransactionSender, compute_forced_transactions_hash, decode_forced_transaction,
+        get_forced_transaction_hash, is_forced_transaction_includable,
+    },
     l1_messages::{L1Message, get_block_l1_messages},
     privileged_transactions::{PrivilegedTransactionError, compute_privileged_transactions_hash},
 };
@@ -60,6 +64,9 @@ pub enum StatelessExecutionError {
     #[cfg(feature = "l2")]
     #[error("FeeConfig not provided for L2 execution")]
     FeeConfigNotFound,
+    #[cfg(feature = "l2")]
+    #[error("Forced transaction {0:#x} was skipped while it could be included")]
+    ForcedTransactionNotIncluded(H256),
     #[error("Batch has no blocks")]
     EmptyBatchError,
     #[error("Invalid database")]
@@ -100,6 +107,8 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Stateless
         blob_commitment,
         #[cfg(feature = "l2")]
         blob_proof,
+        #[cfg(feature = "l2")]
+        forced_transactions,
     } = input;
 
     let chain_id = execution_witness.chain_config.chain_id;
@@ -113,6 +122,7 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Stateless
             _fee_configs,
             blob_commitment,
             blob_proof,
+            &forced_transactions,
             chain_id,
         );
     }
@@ -269,6 +279,8 @@ pub fn stateless_validation_l1(
         #[cfg(feature = "l2")]
         privileged_transactions_hash: H256::zero(),
         #[cfg(feature = "l2")]
+        forced_transactions_hash: H256::zero(),
+        #[cfg(feature = "l2")]
         blob_versioned_hash: H256::zero(),
         last_block_hash: last_block.header.hash(),
         chain_id: chain_id.into(),
@@ -284,6 +296,7 @@ pub fn stateless_validation_l2(
     fee_configs: Option<Vec<FeeConfig>>,
     blob_commitment: Commitment,
     blob_proof: Proof,
+    forced_transactions: &[Vec<u8>],
     chain_id: u64,
 ) -> Result<ProgramOutput, StatelessExecutionError> {
     let StatelessResult {
@@ -292,6 +305,7 @@ pub fn stateless_validation_l2(
         final_state_hash,
         last_block_hash,
         non_privileged_count,
+        final_state,
     } = execute_stateless(
         blocks,
         execution_witness,
@@ -308,6 +322,31 @@ pub fn stateless_validation_l2(
             &privileged_transactions,
         )?;
 
+    let last_fee_config = fee_configs
+        .as_ref()
+        .and_then(|fee_configs| fee_configs.last())
+        .ok_or(StatelessExecutionError::FeeConfigNotFound)?;
+    let chain_config = final_state.lock_mutex()?.get_chain_config()?;
+    let forced_transactions_hash = compute_forced_transactions_digest(
+        blocks,
+        forced_transactions,
+        &chain_config,
+        last_fee_config,
+        |address| {
+            let Some(account) = final_state.get_account_state_checked(address)? else {
+                return Ok(ForcedTransactionSender::default());
+            };
+            Ok(ForcedTransactionSender {
+                nonce: account.nonce,
+                balance: account.balance,
+                code: final_state
+                    .lock_mutex()?
+                    .get_account_code(account.code_hash)?
+                    .bytecode,
+            })
+        },
+    )?;
+
     // TODO: this could be replaced with something like a ProverConfig in the future.
     let validium = (blob_commitment, &blob_proof) == ([0; 48], &[0; 48]);
 
@@ -324,6 +363,7 @@ pub fn stateless_validation_l2(
         final_state_hash,
         l1messages_merkle_root,
         privileged_transactions_hash,
+        forced_transactions_hash,
         blob_versioned_hash,
         last_block_hash,
         chain_id: chain_id.into(),
@@ -338,6 +378,8 @@ struct StatelessResult {
     final_state_hash: H256,
     last_block_hash: H256,
     non_privileged_count: U256,
+    /// state after executing the whole batch
+    final_state: GuestProgramStateWrapper,
 }
 
 fn execute_stateless(
@@ -471,6 +513,7 @@ fn execute_stateless(
         final_state_hash,
         last_block_hash,
         non_privileged_count: non_privileged_count.into(),
+        final_state: wrapped_db,
     })
 }
 
@@ -513,6 +556,52 @@ fn compute_l1messages_and_privileged_transactions_digests(
     Ok((l1message_merkle_root, privileged_transactions_hash))
 }
 
+/// Checks that every forced transaction processed by the batch was either included in one
+/// of its blocks or can't be included on top of its final state, whose senders are read
+/// through `get_sender`, and returns the hash committed for them.
+#[cfg(feature = "l2")]
+fn compute_forced_transactions_digest(
+    blocks: &[Block],
+    forced_transactions: &[Vec<u8>],
+    chain_config: &ChainConfig,
+    last_fee_config: &FeeConfig,
+    get_sender: impl Fn(Address) -> Result<ForcedTransactionSender, GuestProgramStateError>,
+) -> Result<H256, StatelessExecutionError> {
+    use std::collections::HashSet;
+
+    let last_header = &blocks
+        .last()
+        .ok_or(StatelessExecutionError::EmptyBatchError)?
+        .header;
+    let included: HashSet<H256> = blocks
+        .iter()
+        .flat_map(|block| block.body.transactions.iter().map(|tx| tx.hash()))
+        .collect();
+
+    let mut forced_transaction_hashes = Vec::with_capacity(forced_transactions.len());
+    for raw_transaction in forced_transactions {
+        let hash = get_forced_transaction_hash(raw_transaction);
+        if !included.contains(&hash)
+            && let Some((tx, sender)) =
+                decode_forced_transaction(raw_transaction, chain_config.chain_id)
+        {
+            let sender = get_sender(sender)?;
+            if is_forced_transaction_includable(
+                &tx,
+                &sender,
+                last_header,
+                chain_config,
+                last_fee_config,
+            ) {
+                return Err(StatelessExecutionError::ForcedTransactionNotIncluded(hash));
+            }
+        }
+        forced_transaction_hashes.push(hash);
+    }
+
+    Ok(compute_forced_transactions_hash(forced_transaction_hashes)?)
+}
+
 #[cfg(feature = "l2")]
 fn verify_blob(
     blocks: &[Block],
@@ -544,3 +633,180 @@ fn verify_blob(
 
     Ok(kzg_commitment_to_versioned_hash(&commitment))
 }
+
+#[cfg(all(test, feature = "l2"))]
+mod tests {
+    use ethrex_common::types::{
+        BlockBody, BlockHeader, EIP1559Transaction, Transaction, TxKind, TxType,
+    };
+    use ethrex_common::utils::keccak;
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 65536999;
+    const GWEI: u64 = 1_000_000_000;
+
+    fn key() -> SecretKey {
+        SecretKey::from_slice(&[1; 32]).unwrap()
+    }
+
+    fn sender() -> Address {
+        let public_key = PublicKey::from_secret_key(SECP256K1, &key()).serialize_uncompressed();
+        Address::from_slice(&keccak(&public_key[1..]).0[12..])
+    }
+
+    fn forced_transfer(nonce: u64, value: u64) -> Transaction {
+        sign(EIP1559Transaction {
+            chain_id: CHAIN_ID,
+            nonce,
+            max_priority_fee_per_gas: GWEI,
+            max_fee_per_gas: 2 * GWEI,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::repeat_byte(0x42)),
+            value: value.into(),
+            ..Default::default()
+        })
+    }
+
+    fn sign(mut tx: EIP1559Transaction) -> Transaction {
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak(&payload).0), &key())
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn batch(transactions: Vec<Transaction>) -> Vec<Block> {
+        let header = BlockHeader {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(GWEI),
+            ..Default::default()
+        };
+        let body = BlockBody {
+            transactions,
+            ..Default::default()
+        };
+        vec![Block::new(header, body)]
+    }
+
+    /// Final state where the sender has sent `nonce` transactions and has plenty of balance
+    fn final_state(
+        nonce: u64,
+    ) -> impl Fn(Address) -> Result<ForcedTransactionSender, GuestProgramStateError> {
+        move |address| {
+            if address != sender() {
+                return Ok(ForcedTransactionSender::default());
+            }
+            Ok(ForcedTransactionSender {
+                nonce,
+                balance: U256::from(10).pow(U256::from(18)),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn chain_config(chain_id: u64) -> ChainConfig {
+        ChainConfig {
+            chain_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn forced_transactions_digest_accepts_included_and_skippable_transactions() {
+        let included = forced_transfer(0, 1);
+        let stale_nonce = forced_transfer(0, 2).encode_canonical_to_vec();
+        let undecodable = vec![0x02, 0x01];
+        let forced_transactions = vec![
+            included.encode_canonical_to_vec(),
+            stale_nonce.clone(),
+            undecodable.clone(),
+        ];
+
+        let digest = compute_forced_transactions_digest(
+            &batch(vec![included.clone()]),
+            &forced_transactions,
+            &chain_config(CHAIN_ID),
+            &FeeConfig::default(),
+            final_state(1),
+        )
+        .unwrap();
+
+        let expected = compute_forced_transactions_hash(vec![
+            included.hash(),
+            keccak(&stale_nonce),
+            keccak(&undecodable),
+        ])
+        .unwrap();
+        assert_eq!(digest, expected);
+        assert_eq!(
+            compute_forced_transactions_digest(
+                &batch(vec![]),
+                &[],
+                &chain_config(CHAIN_ID),
+                &FeeConfig::default(),
+                final_state(0),
+            )
+            .unwrap(),
+            H256::zero()
+        );
+    }
+
+    #[test]
+    fn forced_transactions_digest_rejects_skipped_includable_transactions() {
+        let skipped = forced_transfer(1, 1);
+
+        let result = compute_forced_transactions_digest(
+            &batch(vec![forced_transfer(0, 1)]),
+            &[skipped.encode_canonical_to_vec()],
+            &chain_config(CHAIN_ID),
+            &FeeConfig::default(),
+            final_state(1),
+        );
+
+        assert!(matches!(
+            result,
+            Err(StatelessExecutionError::ForcedTransactionNotIncluded(hash)) if hash == skipped.hash()
+        ));
+        // Transactions signed for another chain can always be skipped
+        assert!(
+            compute_forced_transactions_digest(
+                &batch(vec![]),
+                &[skipped.encode_canonical_to_vec()],
+                &chain_config(CHAIN_ID + 1),
+                &FeeConfig::default(),
+                final_state(1),
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn forced_transactions_digest_accepts_skipped_transactions_below_their_intrinsic_gas() {
+        let Transaction::EIP1559Transaction(transfer) = forced_transfer(0, 1) else {
+            unreachable!()
+        };
+        // The calldata costs gas on top of the 21000 of a transfer, so it can't be executed
+        let under_gassed = sign(EIP1559Transaction {
+            data: vec![1; 100].into(),
+            ..transfer
+        });
+
+        assert!(
+            compute_forced_transactions_digest(
+                &batch(vec![]),
+                &[under_gassed.encode_canonical_to_vec()],
+                &chain_config(CHAIN_ID),
+                &FeeConfig::default(),
+                final_state(0),
+            )
+            .is_ok()
+        );
+    }
+}
@@ -28,6 +28,10 @@ pub struct ProgramInput {
     /// KZG opening for a challenge over the blob commitment
     #[serde_as(as = "[_; 48]")]
     pub blob_proof: blobs_bundle::Proof,
+    #[cfg(feature = "l2")]
+    /// forced transactions sent through the CommonBridge that the batch processes, in
+    /// queue order, as they were sent to L1
+    pub forced_transactions: Vec<Vec<u8>>,
 }
 
 impl Default for ProgramInput {
@@ -41,6 +45,8 @@ impl Default for ProgramInput {
             blob_commitment: [0; 48],
             #[cfg(feature = "l2")]
             blob_proof: [0u8; 48],
+            #[cfg(feature = "l2")]
+            forced_transactions: Vec::new(),
         }
     }
 }
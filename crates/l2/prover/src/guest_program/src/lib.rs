@@ -1,3 +1,4 @@
+pub mod aggregation;
 pub mod execution;
 pub mod input;
 pub mod methods;
@@ -10,6 +11,12 @@ pub static ZKVM_SP1_PROGRAM_ELF: &[u8] = include_bytes!("./sp1/out/riscv32im-suc
 #[cfg(any(clippy, not(feature = "sp1")))]
 pub const ZKVM_SP1_PROGRAM_ELF: &[u8] = &[];
 
+#[cfg(all(not(clippy), feature = "sp1"))]
+pub static ZKVM_SP1_AGGREGATION_PROGRAM_ELF: &[u8] =
+    include_bytes!("./sp1_aggregation/out/riscv32im-succinct-zkvm-elf");
+#[cfg(any(clippy, not(feature = "sp1")))]
+pub const ZKVM_SP1_AGGREGATION_PROGRAM_ELF: &[u8] = &[];
+
 #[cfg(all(not(clippy), feature = "risc0"))]
 pub static ZKVM_RISC0_PROGRAM_VK: &str = include_str!(concat!("./risc0/out/riscv32im-risc0-vk"));
 #[cfg(any(clippy, not(feature = "risc0")))]
@@ -16,6 +16,9 @@ pub struct ProgramOutput {
     /// hash of all the privileged transactions made in a batch
     pub privileged_transactions_hash: H256,
     #[cfg(feature = "l2")]
+    /// hash of all the forced transactions processed in a batch
+    pub forced_transactions_hash: H256,
+    #[cfg(feature = "l2")]
     /// blob commitment versioned hash
     pub blob_versioned_hash: H256,
     /// hash of the last block in a batch
@@ -27,6 +30,12 @@ pub struct ProgramOutput {
 }
 
 impl ProgramOutput {
+    /// Length in bytes of the encoded output, see [`ProgramOutput::encode`]
+    #[cfg(feature = "l2")]
+    pub const ENCODED_LEN: usize = 9 * 32;
+    #[cfg(not(feature = "l2"))]
+    pub const ENCODED_LEN: usize = 5 * 32;
+
     pub fn encode(&self) -> Vec<u8> {
         [
             self.initial_state_hash.to_fixed_bytes(),
@@ -36,6 +45,8 @@ impl ProgramOutput {
             #[cfg(feature = "l2")]
             self.privileged_transactions_hash.to_fixed_bytes(),
             #[cfg(feature = "l2")]
+            self.forced_transactions_hash.to_fixed_bytes(),
+            #[cfg(feature = "l2")]
             self.blob_versioned_hash.to_fixed_bytes(),
             self.last_block_hash.to_fixed_bytes(),
             self.chain_id.to_big_endian(),
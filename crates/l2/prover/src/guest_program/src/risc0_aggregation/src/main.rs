@@ -0,0 +1,29 @@
+use std::io::Read;
+
+use guest_program::aggregation::{AggregationInput, aggregation_program};
+use risc0_zkvm::{guest::env, sha::Digest};
+use rkyv::rancor::Error;
+
+fn main() {
+    let mut input = Vec::new();
+    env::stdin().read_to_end(&mut input).unwrap();
+    let input = rkyv::from_bytes::<AggregationInput, Error>(&input).unwrap();
+
+    // The batch receipts are added as assumptions by the host, so verifying them
+    // here resolves each assumption when the receipt is compressed.
+    println!("start verifying batch proofs");
+    let start = env::cycle_count();
+    let program_id = Digest::new(input.program_vk);
+    for public_values in &input.public_values {
+        env::verify(program_id, public_values.as_slice()).unwrap();
+    }
+    let end = env::cycle_count();
+    println!("end verifying batch proofs, cycles: {}", end - start);
+
+    let output = aggregation_program(
+        input.public_values,
+        program_id.as_bytes().try_into().unwrap(),
+    )
+    .unwrap();
+    env::commit_slice(&output.encode());
+}
@@ -0,0 +1,32 @@
+#![no_main]
+
+use guest_program::aggregation::{AggregationInput, aggregation_program};
+use rkyv::rancor::Error;
+use sha2::{Digest, Sha256};
+
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    let input = sp1_zkvm::io::read_vec();
+    let input = rkyv::from_bytes::<AggregationInput, Error>(&input).unwrap();
+
+    // The batch proofs are written to the stdin by the host, in the same order as
+    // their public values. Each call consumes the next one.
+    println!("cycle-tracker-report-start: verify_batch_proofs");
+    for public_values in &input.public_values {
+        let public_values_digest: [u8; 32] = Sha256::digest(public_values).into();
+        sp1_zkvm::lib::verify::verify_sp1_proof(&input.program_vk, &public_values_digest);
+    }
+    println!("cycle-tracker-report-end: verify_batch_proofs");
+
+    let program_vk: Vec<u8> = input
+        .program_vk
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect();
+    let output = aggregation_program(input.public_values, program_vk.try_into().unwrap()).unwrap();
+
+    println!("cycle-tracker-report-start: commit_public_inputs");
+    sp1_zkvm::io::commit_slice(&output.encode());
+    println!("cycle-tracker-report-end: commit_public_inputs");
+}
@@ -3,7 +3,7 @@ pub mod prover;
 
 pub mod config;
 use config::ProverConfig;
-use ethrex_l2_common::prover::{BatchProof, ProofFormat};
+use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use guest_program::input::ProgramInput;
 use tracing::warn;
 
@@ -48,6 +48,37 @@ pub fn prove(
     }
 }
 
+/// Prover type of the aggregated proofs generated by the specified backend, if it
+/// supports recursive aggregation of batch proofs.
+pub fn aggregation_prover_type(backend: Backend) -> Option<ProverType> {
+    match backend {
+        #[cfg(feature = "sp1")]
+        Backend::SP1 => Some(ProverType::SP1),
+        #[cfg(feature = "risc0")]
+        Backend::RISC0 => Some(ProverType::RISC0),
+        _ => None,
+    }
+}
+
+/// Generate a recursive proof of consecutive batch proofs using the specified backend.
+pub fn prove_aggregation(
+    backend: Backend,
+    _batch_proofs: Vec<BatchProof>,
+    _format: ProofFormat,
+) -> Result<ProveOutput, Box<dyn std::error::Error>> {
+    match backend {
+        #[cfg(feature = "sp1")]
+        Backend::SP1 => {
+            backend::sp1::prove_aggregation(_batch_proofs, _format).map(ProveOutput::SP1)
+        }
+        #[cfg(feature = "risc0")]
+        Backend::RISC0 => {
+            backend::risc0::prove_aggregation(_batch_proofs, _format).map(ProveOutput::RISC0)
+        }
+        _ => Err(format!("{backend:?} backend doesn't support proof aggregation").into()),
+    }
+}
+
 pub fn to_batch_proof(
     proof: ProveOutput,
     format: ProofFormat,
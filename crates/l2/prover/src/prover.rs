@@ -1,6 +1,9 @@
-use crate::{backend::Backend, config::ProverConfig, prove, to_batch_proof};
+use crate::{
+    aggregation_prover_type, backend::Backend, config::ProverConfig, prove, prove_aggregation,
+    to_batch_proof,
+};
 use ethrex_l2::sequencer::{proof_coordinator::ProofData, utils::get_git_commit_hash};
-use ethrex_l2_common::prover::{BatchProof, ProofFormat};
+use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use guest_program::input::ProgramInput;
 use std::time::Duration;
 use tokio::{
@@ -22,6 +25,13 @@ struct ProverData {
     format: ProofFormat,
 }
 
+struct AggregationData {
+    first_batch: u64,
+    last_batch: u64,
+    batch_proofs: Vec<BatchProof>,
+    format: ProofFormat,
+}
+
 struct Prover {
     backend: Backend,
     proof_coordinator_endpoints: Vec<Url>,
@@ -62,12 +72,18 @@ impl Prover {
             sleep(Duration::from_millis(self.proving_time_ms)).await;
 
             for endpoint in &self.proof_coordinator_endpoints {
-                let Ok(Some(prover_data)) = self
-                    .request_new_input(endpoint)
-                    .await
-                    .inspect_err(|e| error!(%endpoint, "Failed to request new data from: {e}"))
-                else {
-                    continue;
+                let prover_data = match self.request_new_input(endpoint).await {
+                    Ok(Some(prover_data)) => prover_data,
+                    Ok(None) => {
+                        // There are no batches left to prove, but the proofs of the
+                        // proven ones may be ready to be aggregated
+                        self.aggregate_proofs(endpoint).await;
+                        continue;
+                    }
+                    Err(e) => {
+                        error!(%endpoint, "Failed to request new data from: {e}");
+                        continue;
+                    }
                 };
 
                 // If we get the input
@@ -89,6 +105,39 @@ impl Prover {
         }
     }
 
+    async fn aggregate_proofs(&self, endpoint: &Url) {
+        let Some(prover_type) = aggregation_prover_type(self.backend) else {
+            return;
+        };
+
+        let Ok(Some(aggregation_data)) = self
+            .request_aggregation_input(endpoint, prover_type)
+            .await
+            .inspect_err(|e| error!(%endpoint, "Failed to request aggregation data from: {e}"))
+        else {
+            return;
+        };
+
+        let format = aggregation_data.format;
+        let Ok(batch_proof) =
+            prove_aggregation(self.backend, aggregation_data.batch_proofs, format)
+                .and_then(|output| to_batch_proof(output, format))
+                .inspect_err(|e| error!("{}", e.to_string()))
+        else {
+            return;
+        };
+
+        let _ = self
+            .submit_aggregated_proof(
+                endpoint,
+                aggregation_data.first_batch,
+                aggregation_data.last_batch,
+                batch_proof,
+            )
+            .await
+            .inspect_err(|e| warn!(%endpoint, "Failed to submit aggregated proof: {e}"));
+    }
+
     async fn request_new_input(&self, endpoint: &Url) -> Result<Option<ProverData>, String> {
         // Request the input with the correct batch_number
         let request = ProofData::batch_request(self.commit_hash.clone());
@@ -131,6 +180,8 @@ impl Prover {
                 #[cfg(feature = "l2")]
                 blob_proof: input.blob_proof,
                 fee_configs: Some(input.fee_configs),
+                #[cfg(feature = "l2")]
+                forced_transactions: input.forced_transactions,
             },
             format,
         }))
@@ -155,6 +206,65 @@ impl Prover {
         info!(%endpoint, "Received submit ack for batch_number: {batch_number}");
         Ok(())
     }
+
+    async fn request_aggregation_input(
+        &self,
+        endpoint: &Url,
+        prover_type: ProverType,
+    ) -> Result<Option<AggregationData>, String> {
+        let request = ProofData::aggregation_request(self.commit_hash.clone(), prover_type);
+        let response = connect_to_prover_server_wr(endpoint, &request)
+            .await
+            .map_err(|e| format!("Failed to get Response: {e}"))?;
+
+        let ProofData::AggregationResponse {
+            first_batch,
+            last_batch,
+            batch_proofs,
+            format,
+        } = response
+        else {
+            return Err("Expecting ProofData::AggregationResponse".to_owned());
+        };
+
+        let (Some(first_batch), Some(last_batch), Some(batch_proofs), Some(format)) =
+            (first_batch, last_batch, batch_proofs, format)
+        else {
+            debug!(%endpoint, "Received Empty AggregationResponse, there are no proofs to aggregate");
+            return Ok(None);
+        };
+
+        info!(%endpoint, "Received AggregationResponse for batches {first_batch} to {last_batch}");
+        Ok(Some(AggregationData {
+            first_batch,
+            last_batch,
+            batch_proofs,
+            format,
+        }))
+    }
+
+    async fn submit_aggregated_proof(
+        &self,
+        endpoint: &Url,
+        first_batch: u64,
+        last_batch: u64,
+        batch_proof: BatchProof,
+    ) -> Result<(), String> {
+        let submit = ProofData::aggregated_proof_submit(first_batch, last_batch, batch_proof);
+
+        let ProofData::AggregatedProofSubmitACK {
+            first_batch,
+            last_batch,
+        } = connect_to_prover_server_wr(endpoint, &submit)
+            .await
+            .map_err(|e| format!("Failed to get AggregatedProofSubmitACK: {e}"))?
+        else {
+            return Err("Expecting ProofData::AggregatedProofSubmitACK".to_owned());
+        };
+
+        info!(%endpoint, "Received submit ack for batches {first_batch} to {last_batch}");
+        Ok(())
+    }
 }
 
 async fn connect_to_prover_server_wr(
@@ -1,5 +1,5 @@
 use crate::{
-    build_generic_tx,
+    COMMON_BRIDGE_L2_ADDRESS, build_generic_tx,
     calldata::{self},
     send_generic_transaction,
 };
@@ -14,6 +14,9 @@ use ethrex_rpc::{
 };
 use secp256k1::SecretKey;
 
+/// Gas limit the `CommonBridge` gives to the privileged transactions that mint bridged NFTs.
+pub const NFT_DEPOSIT_GAS_LIMIT: u64 = 21000 * 10;
+
 #[derive(Debug)]
 pub struct L1ToL2TransactionData {
     pub to: Address,
@@ -40,6 +43,71 @@ impl L1ToL2TransactionData {
         }
     }
 
+    /// Creates the privileged transaction the `CommonBridge` sends to L2 for an ERC721 deposit.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_l1` - The address of the token on L1.
+    /// * `token_l2` - The address of the token on L2.
+    /// * `destination` - The address that receives the token on L2.
+    /// * `token_id` - The id of the deposited token.
+    pub fn new_erc721_deposit(
+        token_l1: Address,
+        token_l2: Address,
+        destination: Address,
+        token_id: U256,
+    ) -> Result<Self, CalldataEncodeError> {
+        let calldata = calldata::encode_calldata(
+            "mintERC721(address,address,address,uint256)",
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(destination),
+                Value::Uint(token_id),
+            ],
+        )?;
+        Ok(Self::new(
+            COMMON_BRIDGE_L2_ADDRESS,
+            NFT_DEPOSIT_GAS_LIMIT,
+            U256::zero(),
+            calldata.into(),
+        ))
+    }
+
+    /// Creates the privileged transaction the `CommonBridge` sends to L2 for an ERC1155 deposit.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_l1` - The address of the token on L1.
+    /// * `token_l2` - The address of the token on L2.
+    /// * `destination` - The address that receives the tokens on L2.
+    /// * `token_id` - The id of the deposited tokens.
+    /// * `amount` - The amount of deposited tokens.
+    pub fn new_erc1155_deposit(
+        token_l1: Address,
+        token_l2: Address,
+        destination: Address,
+        token_id: U256,
+        amount: U256,
+    ) -> Result<Self, CalldataEncodeError> {
+        let calldata = calldata::encode_calldata(
+            "mintERC1155(address,address,address,uint256,uint256)",
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(destination),
+                Value::Uint(token_id),
+                Value::Uint(amount),
+            ],
+        )?;
+        Ok(Self::new(
+            COMMON_BRIDGE_L2_ADDRESS,
+            NFT_DEPOSIT_GAS_LIMIT,
+            U256::zero(),
+            calldata.into(),
+        ))
+    }
+
     /// Encodes the `L1ToL2TransactionData` into a calldata.
     pub fn to_calldata(&self) -> Result<Vec<u8>, CalldataEncodeError> {
         let values = vec![Value::Tuple(vec![
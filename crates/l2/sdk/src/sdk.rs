@@ -70,6 +70,11 @@ pub const ADDRESS_ALIASING: Address = H160([
 
 pub const L2_WITHDRAW_SIGNATURE: &str = "withdraw(address)";
 
+pub const L2_WITHDRAW_ERC721_SIGNATURE: &str = "withdrawERC721(address,address,address,uint256)";
+
+pub const L2_WITHDRAW_ERC1155_SIGNATURE: &str =
+    "withdrawERC1155(address,address,address,uint256,uint256)";
+
 pub const REGISTER_FEE_TOKEN_SIGNATURE: &str = "registerNewFeeToken(address)";
 
 /// Bytecode of the OpenZeppelin's ERC1967Proxy contract.
@@ -341,6 +346,241 @@ pub async fn deposit_erc20(
     send_generic_transaction(eth_client, deposit_tx, from_signer).await
 }
 
+pub async fn deposit_erc721(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    from: Address,
+    from_signer: &Signer,
+    eth_client: &EthClient,
+) -> Result<H256, EthClientError> {
+    println!("Depositing ERC721 token {token_id} from {from:#x}");
+
+    const DEPOSIT_ERC721_SIGNATURE: &str = "depositERC721(address,address,address,uint256)";
+
+    let calldata_values = vec![
+        Value::Address(token_l1),
+        Value::Address(token_l2),
+        Value::Address(from),
+        Value::Uint(token_id),
+    ];
+
+    let deposit_data = encode_calldata(DEPOSIT_ERC721_SIGNATURE, &calldata_values)?;
+
+    let mut deposit_tx = build_generic_tx(
+        eth_client,
+        TxType::EIP1559,
+        bridge_address().map_err(|err| EthClientError::Custom(err.to_string()))?,
+        from,
+        deposit_data.into(),
+        Overrides {
+            from: Some(from),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    deposit_tx.gas = deposit_tx.gas.map(|gas| gas * 2); // tx reverts in some cases otherwise
+
+    send_generic_transaction(eth_client, deposit_tx, from_signer).await
+}
+
+pub async fn deposit_erc1155(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    amount: U256,
+    from: Address,
+    from_signer: &Signer,
+    eth_client: &EthClient,
+) -> Result<H256, EthClientError> {
+    println!("Depositing {amount} of ERC1155 token {token_id} from {from:#x}");
+
+    const DEPOSIT_ERC1155_SIGNATURE: &str =
+        "depositERC1155(address,address,address,uint256,uint256)";
+
+    let calldata_values = vec![
+        Value::Address(token_l1),
+        Value::Address(token_l2),
+        Value::Address(from),
+        Value::Uint(token_id),
+        Value::Uint(amount),
+    ];
+
+    let deposit_data = encode_calldata(DEPOSIT_ERC1155_SIGNATURE, &calldata_values)?;
+
+    let mut deposit_tx = build_generic_tx(
+        eth_client,
+        TxType::EIP1559,
+        bridge_address().map_err(|err| EthClientError::Custom(err.to_string()))?,
+        from,
+        deposit_data.into(),
+        Overrides {
+            from: Some(from),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    deposit_tx.gas = deposit_tx.gas.map(|gas| gas * 2); // tx reverts in some cases otherwise
+
+    send_generic_transaction(eth_client, deposit_tx, from_signer).await
+}
+
+pub async fn withdraw_erc721(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    from: Address,
+    from_signer: &Signer,
+    proposer_client: &EthClient,
+) -> Result<H256, EthClientError> {
+    let withdraw_transaction = build_generic_tx(
+        proposer_client,
+        TxType::EIP1559,
+        COMMON_BRIDGE_L2_ADDRESS,
+        from,
+        Bytes::from(encode_calldata(
+            L2_WITHDRAW_ERC721_SIGNATURE,
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(from),
+                Value::Uint(token_id),
+            ],
+        )?),
+        Overrides::default(),
+    )
+    .await?;
+
+    send_generic_transaction(proposer_client, withdraw_transaction, from_signer).await
+}
+
+pub async fn withdraw_erc1155(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    amount: U256,
+    from: Address,
+    from_signer: &Signer,
+    proposer_client: &EthClient,
+) -> Result<H256, EthClientError> {
+    let withdraw_transaction = build_generic_tx(
+        proposer_client,
+        TxType::EIP1559,
+        COMMON_BRIDGE_L2_ADDRESS,
+        from,
+        Bytes::from(encode_calldata(
+            L2_WITHDRAW_ERC1155_SIGNATURE,
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(from),
+                Value::Uint(token_id),
+                Value::Uint(amount),
+            ],
+        )?),
+        Overrides::default(),
+    )
+    .await?;
+
+    send_generic_transaction(proposer_client, withdraw_transaction, from_signer).await
+}
+
+pub async fn claim_erc721withdraw(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    from_signer: &Signer,
+    eth_client: &EthClient,
+    message_proof: &L1MessageProof,
+) -> Result<H256, EthClientError> {
+    let from = from_signer.address();
+    const CLAIM_WITHDRAWAL_ERC721_SIGNATURE: &str =
+        "claimWithdrawalERC721(address,address,uint256,uint256,uint256,bytes32[])";
+
+    let calldata_values = vec![
+        Value::Address(token_l1),
+        Value::Address(token_l2),
+        Value::Uint(token_id),
+        Value::Uint(U256::from(message_proof.batch_number)),
+        Value::Uint(message_proof.message_id),
+        Value::Array(
+            message_proof
+                .merkle_proof
+                .iter()
+                .map(|v| Value::Uint(U256::from_big_endian(v.as_bytes())))
+                .collect(),
+        ),
+    ];
+
+    let claim_withdrawal_data =
+        encode_calldata(CLAIM_WITHDRAWAL_ERC721_SIGNATURE, &calldata_values)?;
+
+    let claim_tx = build_generic_tx(
+        eth_client,
+        TxType::EIP1559,
+        bridge_address().map_err(|err| EthClientError::Custom(err.to_string()))?,
+        from,
+        claim_withdrawal_data.into(),
+        Overrides {
+            from: Some(from),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    send_generic_transaction(eth_client, claim_tx, from_signer).await
+}
+
+pub async fn claim_erc1155withdraw(
+    token_l1: Address,
+    token_l2: Address,
+    token_id: U256,
+    amount: U256,
+    from_signer: &Signer,
+    eth_client: &EthClient,
+    message_proof: &L1MessageProof,
+) -> Result<H256, EthClientError> {
+    let from = from_signer.address();
+    const CLAIM_WITHDRAWAL_ERC1155_SIGNATURE: &str =
+        "claimWithdrawalERC1155(address,address,uint256,uint256,uint256,uint256,bytes32[])";
+
+    let calldata_values = vec![
+        Value::Address(token_l1),
+        Value::Address(token_l2),
+        Value::Uint(token_id),
+        Value::Uint(amount),
+        Value::Uint(U256::from(message_proof.batch_number)),
+        Value::Uint(message_proof.message_id),
+        Value::Array(
+            message_proof
+                .merkle_proof
+                .iter()
+                .map(|v| Value::Uint(U256::from_big_endian(v.as_bytes())))
+                .collect(),
+        ),
+    ];
+
+    let claim_withdrawal_data =
+        encode_calldata(CLAIM_WITHDRAWAL_ERC1155_SIGNATURE, &calldata_values)?;
+
+    let claim_tx = build_generic_tx(
+        eth_client,
+        TxType::EIP1559,
+        bridge_address().map_err(|err| EthClientError::Custom(err.to_string()))?,
+        from,
+        claim_withdrawal_data.into(),
+        Overrides {
+            from: Some(from),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    send_generic_transaction(eth_client, claim_tx, from_signer).await
+}
+
 pub fn secret_key_deserializer<'de, D>(deserializer: D) -> Result<SecretKey, D::Error>
 where
     D: Deserializer<'de>,
@@ -1103,6 +1343,33 @@ pub async fn get_last_verified_batch(
     _call_u64_variable(client, b"lastVerifiedBatch()", on_chain_proposer_address).await
 }
 
+/// Returns the `newStateRoot` committed on L1 for the given batch.
+/// A zero hash means the batch was not committed (or was reverted).
+pub async fn get_batch_committed_state_root(
+    client: &EthClient,
+    on_chain_proposer_address: Address,
+    batch_number: u64,
+) -> Result<H256, EthClientError> {
+    let calldata = encode_calldata(
+        "batchCommitments(uint256)",
+        &[Value::Uint(U256::from(batch_number))],
+    )?;
+    let hex_string = client
+        .call(
+            on_chain_proposer_address,
+            calldata.into(),
+            Overrides::default(),
+        )
+        .await?;
+    let bytes = hex::decode(hex_string.trim_start_matches("0x"))
+        .map_err(|e| EthClientError::Custom(format!("Failed to decode hex string: {e}")))?;
+    // newStateRoot is the first word of the returned BatchCommitmentInfo tuple
+    let state_root = bytes.get(..32).ok_or(EthClientError::Custom(
+        "batchCommitments returned less than 32 bytes".to_owned(),
+    ))?;
+    Ok(H256::from_slice(state_root))
+}
+
 pub async fn get_sp1_vk(
     client: &EthClient,
     on_chain_proposer_address: Address,
@@ -1142,6 +1409,48 @@ pub async fn get_pending_privileged_transactions(
     from_hex_string_to_h256_array(&response)
 }
 
+pub async fn get_pending_forced_transactions(
+    client: &EthClient,
+    common_bridge_address: Address,
+) -> Result<Vec<H256>, EthClientError> {
+    let response = _generic_call(
+        client,
+        b"getPendingForcedTransactionHashes()",
+        common_bridge_address,
+    )
+    .await?;
+    from_hex_string_to_h256_array(&response)
+}
+
+pub async fn get_forced_transaction_fee(
+    client: &EthClient,
+    common_bridge_address: Address,
+) -> Result<U256, EthClientError> {
+    let hex_string = _generic_call(client, b"FORCED_TX_FEE()", common_bridge_address).await?;
+    Ok(U256::from_str_radix(
+        hex_string.trim_start_matches("0x"),
+        16,
+    )?)
+}
+
+/// Returns the L1 block in which the forced transaction `tx_hash` was queued, or 0 if it's unknown
+pub async fn get_forced_transaction_l1_block(
+    client: &EthClient,
+    common_bridge_address: Address,
+    tx_hash: H256,
+) -> Result<u64, EthClientError> {
+    let calldata = encode_calldata(
+        "forcedTxL1Block(bytes32)",
+        &[Value::FixedBytes(tx_hash.0.to_vec().into())],
+    )?;
+    let hex_string = client
+        .call(common_bridge_address, calldata.into(), Overrides::default())
+        .await?;
+    U256::from_str_radix(hex_string.trim_start_matches("0x"), 16)?
+        .try_into()
+        .map_err(|_| EthClientError::Custom("Forced transaction L1 block is too big".to_owned()))
+}
+
 // TODO: This is a work around for now, issue: https://github.com/lambdaclass/ethrex/issues/4828
 pub async fn get_l1_active_fork(
     client: &EthClient,
@@ -10,6 +10,9 @@ use crate::sequencer::l1_proof_sender::{
 use crate::sequencer::l1_watcher::{
     CallMessage as WatcherCallMessage, L1Watcher, OutMessage as WatcherOutMessage,
 };
+use crate::sequencer::leader_election::{
+    CallMessage as LeaderElectorCallMessage, LeaderElector, OutMessage as LeaderElectorOutMessage,
+};
 #[cfg(feature = "metrics")]
 use crate::sequencer::metrics::{
     CallMessage as MetricsCallMessage, MetricsGatherer, OutMessage as MetricsOutMessage,
@@ -38,6 +41,7 @@ pub struct Admin {
     pub l1_watcher: Option<GenServerHandle<L1Watcher>>,
     pub l1_proof_sender: Option<GenServerHandle<L1ProofSender>>,
     pub block_producer: Option<GenServerHandle<BlockProducer>>,
+    pub leader_elector: Option<GenServerHandle<LeaderElector>>,
     #[cfg(feature = "metrics")]
     pub metrics_gatherer: Option<GenServerHandle<MetricsGatherer>>,
 }
@@ -74,6 +78,7 @@ pub async fn start_api(
     l1_watcher: Option<GenServerHandle<L1Watcher>>,
     l1_proof_sender: Option<GenServerHandle<L1ProofSender>>,
     block_producer: Option<GenServerHandle<BlockProducer>>,
+    leader_elector: Option<GenServerHandle<LeaderElector>>,
     #[cfg(feature = "metrics")] metrics_gatherer: Option<GenServerHandle<MetricsGatherer>>,
 ) -> Result<WithGracefulShutdown<TcpListener, Router, Router, impl Future<Output = ()>>, AdminError>
 {
@@ -82,6 +87,7 @@ pub async fn start_api(
         l1_watcher,
         l1_proof_sender,
         block_producer,
+        leader_elector,
         #[cfg(feature = "metrics")]
         metrics_gatherer,
     };
@@ -204,6 +210,22 @@ async fn health(
         .await,
     );
 
+    // Only present in active/standby mode, where it tells whether this node is the leader
+    if admin.leader_elector.is_some() {
+        response.insert(
+            "leader_election".to_string(),
+            genserver_health(
+                admin.leader_elector,
+                LeaderElectorCallMessage::Health,
+                |msg| {
+                    let LeaderElectorOutMessage::Health(h) = msg;
+                    Some(h)
+                },
+            )
+            .await,
+        );
+    }
+
     #[cfg(feature = "metrics")]
     {
         response.insert(
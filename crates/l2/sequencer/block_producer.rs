@@ -1,4 +1,5 @@
 mod payload_builder;
+pub mod preconfirmer;
 use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -9,24 +10,27 @@ use ethrex_blockchain::{
     Blockchain, BlockchainType,
     error::ChainError,
     fork_choice::apply_fork_choice,
-    payload::{BuildPayloadArgs, create_payload},
+    payload::{BuildPayloadArgs, TransactionOrdering, create_payload},
     validate_block,
 };
 use ethrex_common::Address;
 use ethrex_common::H256;
+use ethrex_l2_rpc::preconfirmations::Preconfirmations;
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use ethrex_vm::BlockExecutionResult;
 pub use payload_builder::build_payload;
+use preconfirmer::Preconfirmer;
 use serde::Serialize;
 use spawned_concurrency::tasks::{
     CallResponse, CastResponse, GenServer, GenServerHandle, send_after,
 };
-use tracing::{debug, error, info, warn};
+use tracing::{Span, debug, error, field, info, instrument, warn};
 
 use crate::{
     BlockProducerConfig, SequencerConfig,
     based::sequencer_state::{SequencerState, SequencerStatus},
+    sequencer::forced_transactions::ForcedTransactions,
 };
 
 use super::errors::BlockProducerError;
@@ -63,6 +67,10 @@ pub struct BlockProducer {
     // Needed to ensure privileged tx nonces are sequential
     last_privileged_nonce: Option<u64>,
     block_gas_limit: u64,
+    transaction_ordering: Arc<dyn TransactionOrdering>,
+    preconfirmer: Option<Preconfirmer>,
+    // Transactions sent through the CommonBridge that must be included
+    forced_transactions: ForcedTransactions,
 }
 
 #[derive(Clone, Serialize)]
@@ -75,11 +83,13 @@ pub struct BlockProducerHealth {
 
 impl BlockProducer {
     pub fn new(
-        config: &BlockProducerConfig,
+        config: &SequencerConfig,
         store: Store,
         rollup_store: StoreRollup,
         blockchain: Arc<Blockchain>,
         sequencer_state: SequencerState,
+        preconfirmations: Preconfirmations,
+        forced_transactions: ForcedTransactions,
     ) -> Self {
         let BlockProducerConfig {
             block_time_ms,
@@ -88,7 +98,9 @@ impl BlockProducer {
             operator_fee_vault_address,
             elasticity_multiplier,
             block_gas_limit,
-        } = config;
+            transaction_ordering,
+            preconfirmations: preconfirmations_enabled,
+        } = &config.block_producer;
 
         if base_fee_vault_address.is_some_and(|base_fee_vault| base_fee_vault == *coinbase_address)
         {
@@ -104,6 +116,16 @@ impl BlockProducer {
             );
         }
 
+        // Preconfirmations are signed with the same key the sequencer uses to commit batches,
+        // so users can check them against the committer address registered on L1.
+        let preconfirmer = preconfirmations_enabled.then(|| {
+            Preconfirmer::new(
+                config.l1_committer.signer.clone(),
+                store.get_chain_config().chain_id,
+                preconfirmations,
+            )
+        });
+
         Self {
             store,
             blockchain,
@@ -115,6 +137,9 @@ impl BlockProducer {
             // FIXME: Initialize properly to the last privileged nonce in the chain
             last_privileged_nonce: None,
             block_gas_limit: *block_gas_limit,
+            transaction_ordering: transaction_ordering.clone(),
+            preconfirmer,
+            forced_transactions,
         }
     }
 
@@ -124,13 +149,17 @@ impl BlockProducer {
         blockchain: Arc<Blockchain>,
         cfg: SequencerConfig,
         sequencer_state: SequencerState,
+        preconfirmations: Preconfirmations,
+        forced_transactions: ForcedTransactions,
     ) -> Result<GenServerHandle<BlockProducer>, BlockProducerError> {
         let mut block_producer = Self::new(
-            &cfg.block_producer,
+            &cfg,
             store,
             rollup_store,
             blockchain,
             sequencer_state,
+            preconfirmations,
+            forced_transactions,
         )
         .start_blocking();
         block_producer
@@ -140,6 +169,16 @@ impl BlockProducer {
         Ok(block_producer)
     }
 
+    #[instrument(
+        level = "info",
+        name = "Produce block",
+        skip_all,
+        fields(
+            block.number = field::Empty,
+            block.hash = field::Empty,
+            block.tx_count = field::Empty,
+        )
+    )]
     pub async fn produce_block(&mut self) -> Result<(), BlockProducerError> {
         let version = 3;
         let head_header = {
@@ -178,6 +217,9 @@ impl BlockProducer {
             &self.store,
             &mut self.last_privileged_nonce,
             self.block_gas_limit,
+            &self.forced_transactions,
+            self.transaction_ordering.clone(),
+            self.preconfirmer.as_ref(),
         )
         .await?;
         info!(
@@ -210,13 +252,30 @@ impl BlockProducer {
         let transactions_count = block.body.transactions.len();
         let block_number = block.header.number;
         let block_hash = block.hash();
+        Span::current()
+            .record("block.number", block_number)
+            .record("block.hash", field::debug(block_hash))
+            .record("block.tx_count", transactions_count);
+        // Needed to settle the preconfirmations once the block is stored
+        let transaction_hashes: Vec<H256> = if self.preconfirmer.is_some() {
+            block.body.transactions.iter().map(|tx| tx.hash()).collect()
+        } else {
+            Vec::new()
+        };
         self.store_fee_config_by_block(block.header.number).await?;
+        // The transactions were already taken from the mempool while building the payload,
+        // this records their inclusion for `ethrex_getTransactionStatus`
+        self.blockchain
+            .remove_block_transactions_from_pool(&block)?;
         self.blockchain
             .store_block(block, account_updates_list, execution_result)?;
         info!(
             "Stored new block {:x}, transaction_count {}",
             block_hash, transactions_count
         );
+        if let Some(preconfirmer) = &self.preconfirmer {
+            preconfirmer.settle(block_number, transaction_hashes);
+        }
         // WARN: We're not storing the payload into the Store because there's no use to it by the L2 for now.
 
         self.rollup_store
@@ -267,10 +326,13 @@ impl GenServer for BlockProducer {
         match message {
             InMessage::Produce => {
                 if let SequencerStatus::Sequencing = self.sequencer_state.status().await {
-                    let _ = self
-                        .produce_block()
-                        .await
-                        .inspect_err(|e| error!("Block Producer Error: {e}"));
+                    let _ = self.produce_block().await.inspect_err(|e| {
+                        error!("Block Producer Error: {e}");
+                        // The block the pending preconfirmations were issued for won't be stored
+                        if let Some(preconfirmer) = &self.preconfirmer {
+                            preconfirmer.abort();
+                        }
+                    });
                 }
                 send_after(
                     Duration::from_millis(self.block_time_ms),
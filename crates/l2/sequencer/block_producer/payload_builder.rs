@@ -1,13 +1,25 @@
-use crate::sequencer::errors::BlockProducerError;
+use crate::sequencer::{
+    block_producer::preconfirmer::Preconfirmer, errors::BlockProducerError,
+    forced_transactions::ForcedTransactions,
+};
 use ethrex_blockchain::{
     Blockchain,
     constants::TX_GAS_COST,
-    payload::{PayloadBuildContext, PayloadBuildResult, TransactionQueue, apply_plain_transaction},
+    payload::{
+        HeadTransaction, PayloadBuildContext, PayloadBuildResult, TransactionOrdering,
+        TransactionQueue, apply_plain_transaction,
+    },
 };
+use ethrex_common::Address;
 use ethrex_common::types::{
-    Block, EIP1559_DEFAULT_SERIALIZED_LENGTH, SAFE_BYTES_PER_BLOB, Transaction,
+    Block, EIP1559_DEFAULT_SERIALIZED_LENGTH, MempoolTransaction, SAFE_BYTES_PER_BLOB, Transaction,
+};
+use ethrex_l2_common::{
+    forced_transactions::{
+        ForcedTransactionSender, decode_forced_transaction, is_forced_transaction_includable,
+    },
+    privileged_transactions::PRIVILEGED_TX_BUDGET,
 };
-use ethrex_l2_common::privileged_transactions::PRIVILEGED_TX_BUDGET;
 use ethrex_levm::vm::VMType;
 use ethrex_metrics::metrics;
 #[cfg(feature = "metrics")]
@@ -17,20 +29,25 @@ use ethrex_metrics::{
 };
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::Store;
+use ethrex_vm::EvmError;
 use std::ops::Div;
 use std::sync::Arc;
 use tokio::time::Instant;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// L2 payload builder
 /// Completes the payload building process, return the block value
 /// Same as `blockchain::build_payload` without applying system operations and using a different `fill_transactions`
+#[expect(clippy::too_many_arguments)]
 pub async fn build_payload(
     blockchain: Arc<Blockchain>,
     payload: Block,
     store: &Store,
     last_privileged_nonce: &mut Option<u64>,
     block_gas_limit: u64,
+    forced_transactions: &ForcedTransactions,
+    ordering: Arc<dyn TransactionOrdering>,
+    preconfirmer: Option<&Preconfirmer>,
 ) -> Result<PayloadBuildResult, BlockProducerError> {
     let since = Instant::now();
     let gas_limit = payload.header.gas_limit;
@@ -44,6 +61,9 @@ pub async fn build_payload(
         store,
         last_privileged_nonce,
         block_gas_limit,
+        forced_transactions,
+        ordering,
+        preconfirmer,
     )
     .await?;
     blockchain.finalize_payload(&mut context)?;
@@ -88,12 +108,19 @@ pub async fn build_payload(
 /// does not exceed `SAFE_BYTES_PER_BLOB`.
 /// Also, uses a configured `block_gas_limit` to limit the gas used in the block,
 /// which can be lower than the block gas limit specified in the payload header.
+/// Pending `forced_transactions` go first, then transactions are taken from the mempool
+/// following the given `ordering` policy.
+/// If a `preconfirmer` is given, every transaction added to the payload is preconfirmed.
+#[expect(clippy::too_many_arguments)]
 pub async fn fill_transactions(
     blockchain: Arc<Blockchain>,
     context: &mut PayloadBuildContext,
     store: &Store,
     last_privileged_nonce: &mut Option<u64>,
     configured_block_gas_limit: u64,
+    forced_transactions: &ForcedTransactions,
+    ordering: Arc<dyn TransactionOrdering>,
+    preconfirmer: Option<&Preconfirmer>,
 ) -> Result<(), BlockProducerError> {
     let mut privileged_tx_count = 0;
     let VMType::L2(fee_config) = context.vm.vm_type else {
@@ -102,10 +129,21 @@ pub async fn fill_transactions(
     let mut acc_encoded_size = context.payload.encode_to_vec().len();
     let fee_config_len = fee_config.to_vec().len();
 
+    fill_forced_transactions(
+        context,
+        store,
+        forced_transactions,
+        &mut acc_encoded_size,
+        fee_config_len,
+        configured_block_gas_limit,
+        preconfirmer,
+    )
+    .await?;
+
     debug!("Fetching transactions from mempool");
     // Fetch mempool transactions
     let latest_block_number = store.get_latest_block_number().await?;
-    let mut txs = fetch_mempool_transactions(blockchain.as_ref(), context)?;
+    let mut txs = fetch_mempool_transactions(blockchain.as_ref(), context, ordering)?;
 
     // Execute and add transactions to payload (if suitable)
     loop {
@@ -185,7 +223,7 @@ pub async fn fill_transactions(
         {
             debug!("Removing transaction with nonce too low from mempool: {tx_hash:#x}");
             txs.pop();
-            blockchain.remove_transaction_from_pool(&tx_hash)?;
+            blockchain.drop_invalid_transaction_from_pool(&tx_hash, "nonce too low".to_string())?;
             continue;
         }
 
@@ -219,6 +257,16 @@ pub async fn fill_transactions(
 
         // Save receipt for hash calculation
         context.receipts.push(receipt);
+
+        // Promise the inclusion of the transaction at its position in the payload
+        if let Some(preconfirmer) = preconfirmer {
+            let transaction_index = context.payload.body.transactions.len() - 1;
+            preconfirmer.preconfirm(
+                tx_hash,
+                context.payload.header.number,
+                transaction_index.try_into()?,
+            );
+        }
     } // end loop
 
     metrics!(
@@ -233,15 +281,140 @@ pub async fn fill_transactions(
     Ok(())
 }
 
+/// Adds the forced transactions pending in the CommonBridge that aren't in the chain yet,
+/// so they are included before their deadline whatever the mempool contents are.
+/// The ones that aren't includable on top of the payload state are skipped, batches are
+/// allowed to skip them.
+async fn fill_forced_transactions(
+    context: &mut PayloadBuildContext,
+    store: &Store,
+    forced_transactions: &ForcedTransactions,
+    acc_encoded_size: &mut usize,
+    fee_config_len: usize,
+    configured_block_gas_limit: u64,
+    preconfirmer: Option<&Preconfirmer>,
+) -> Result<(), BlockProducerError> {
+    let chain_config = store.get_chain_config();
+    let VMType::L2(fee_config) = context.vm.vm_type else {
+        return Err(BlockProducerError::Custom("invalid VM type".to_string()));
+    };
+
+    for forced_tx in forced_transactions.pending() {
+        if store
+            .get_transaction_location(forced_tx.hash)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+        let Some((tx, sender)) = decode_forced_transaction(&forced_tx.raw, chain_config.chain_id)
+        else {
+            debug!(
+                "Skipping undecodable forced transaction: {:#x}",
+                forced_tx.hash
+            );
+            continue;
+        };
+
+        if context.remaining_gas < tx.gas_limit()
+            || context.gas_used() + tx.gas_limit() >= configured_block_gas_limit
+        {
+            debug!(
+                "Skipping forced transaction: {:#x}, no gas left",
+                forced_tx.hash
+            );
+            continue;
+        }
+        let tx_size = tx.encode_to_vec().len();
+        if *acc_encoded_size + fee_config_len + tx_size > SAFE_BYTES_PER_BLOB {
+            debug!("No more blob space to run forced transactions");
+            break;
+        }
+        let sender_state = forced_transaction_sender(context, sender)?;
+        if !is_forced_transaction_includable(
+            &tx,
+            &sender_state,
+            &context.payload.header,
+            &chain_config,
+            &fee_config,
+        ) {
+            debug!(
+                "Skipping forced transaction: {:#x}, not includable",
+                forced_tx.hash
+            );
+            continue;
+        }
+        let Some(tip) = tx.effective_gas_tip(context.payload.header.base_fee_per_gas) else {
+            debug!(
+                "Skipping forced transaction: {:#x}, fee too low",
+                forced_tx.hash
+            );
+            continue;
+        };
+
+        let head_tx = HeadTransaction {
+            tx: MempoolTransaction::new(tx.clone(), sender),
+            tip,
+            included_from_sender: 0,
+        };
+        let receipt = match apply_plain_transaction(&head_tx, context) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                // Batches can't skip it, so it will be retried in the next blocks
+                warn!(
+                    "Failed to execute includable forced transaction: {:#x}, {e}",
+                    forced_tx.hash
+                );
+                continue;
+            }
+        };
+
+        *acc_encoded_size += tx_size;
+        context.payload.body.transactions.push(tx);
+        context.receipts.push(receipt);
+
+        if let Some(preconfirmer) = preconfirmer {
+            let transaction_index = context.payload.body.transactions.len() - 1;
+            preconfirmer.preconfirm(
+                forced_tx.hash,
+                context.payload.header.number,
+                transaction_index.try_into()?,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the state of the sender of a forced transaction on top of the transactions
+/// already added to the payload
+fn forced_transaction_sender(
+    context: &mut PayloadBuildContext,
+    address: Address,
+) -> Result<ForcedTransactionSender, EvmError> {
+    let info = context.vm.db.get_account(address)?.info.clone();
+    let code = context.vm.db.get_code(info.code_hash)?.bytecode.clone();
+    Ok(ForcedTransactionSender {
+        nonce: info.nonce,
+        balance: info.balance,
+        code,
+    })
+}
+
 // TODO: Once #2857 is implemented, we can completely ignore the blobs pool.
 fn fetch_mempool_transactions(
     blockchain: &Blockchain,
     context: &mut PayloadBuildContext,
+    ordering: Arc<dyn TransactionOrdering>,
 ) -> Result<TransactionQueue, BlockProducerError> {
-    let (plain_txs, mut blob_txs) = blockchain.fetch_mempool_transactions(context)?;
+    let (plain_txs, mut blob_txs) =
+        blockchain.fetch_mempool_transactions_with_ordering(context, ordering)?;
     while let Some(blob_tx) = blob_txs.peek() {
         let tx_hash = blob_tx.hash();
-        blockchain.remove_transaction_from_pool(&tx_hash)?;
+        blockchain.drop_invalid_transaction_from_pool(
+            &tx_hash,
+            "blob transactions are not supported in the L2".to_string(),
+        )?;
         blob_txs.pop();
     }
     Ok(plain_txs)
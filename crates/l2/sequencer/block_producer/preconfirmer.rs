@@ -0,0 +1,120 @@
+use ethrex_common::H256;
+use ethrex_l2_rpc::{
+    preconfirmations::{Preconfirmation, Preconfirmations, SignedPreconfirmation},
+    signer::Signer,
+};
+#[cfg(feature = "metrics")]
+use ethrex_metrics::l2::metrics::METRICS;
+use ethrex_metrics::metrics;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::warn;
+
+enum PreconfirmerMessage {
+    Preconfirm(Preconfirmation),
+    Settle {
+        block_number: u64,
+        transactions: Vec<H256>,
+    },
+    Abort,
+}
+
+/// Signs an inclusion promise for every transaction added to the block being built
+/// and publishes it to the [Preconfirmations] shared with the RPC.
+///
+/// Promises are signed by a background task, so building a block never waits for the
+/// signer. Settling goes through the same task, which guarantees every promise issued
+/// for a block is signed and published before the block is settled.
+pub struct Preconfirmer {
+    chain_id: u64,
+    sender: UnboundedSender<PreconfirmerMessage>,
+}
+
+impl Preconfirmer {
+    /// Creates the preconfirmer and spawns its signing task, so it must be called
+    /// from within a tokio runtime.
+    pub fn new(signer: Signer, chain_id: u64, preconfirmations: Preconfirmations) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_preconfirmer(signer, preconfirmations, receiver));
+        Self { chain_id, sender }
+    }
+
+    /// Promises that `tx_hash` will be included in block `block_number` at position
+    /// `transaction_index`. Failing to sign doesn't abort the block building, the
+    /// transaction is just not preconfirmed.
+    pub fn preconfirm(&self, tx_hash: H256, block_number: u64, transaction_index: u64) {
+        self.send(PreconfirmerMessage::Preconfirm(Preconfirmation {
+            chain_id: self.chain_id,
+            tx_hash,
+            block_number,
+            transaction_index,
+        }));
+    }
+
+    /// Checks the preconfirmations issued for the stored block `block_number` against
+    /// its transactions, recording the broken ones.
+    pub fn settle(&self, block_number: u64, transactions: Vec<H256>) {
+        self.send(PreconfirmerMessage::Settle {
+            block_number,
+            transactions,
+        });
+    }
+
+    /// Breaks every preconfirmation issued for a block that couldn't be stored.
+    pub fn abort(&self) {
+        self.send(PreconfirmerMessage::Abort);
+    }
+
+    fn send(&self, message: PreconfirmerMessage) {
+        if self.sender.send(message).is_err() {
+            warn!("Preconfirmer task stopped, preconfirmations are not being issued");
+        }
+    }
+}
+
+async fn run_preconfirmer(
+    signer: Signer,
+    preconfirmations: Preconfirmations,
+    mut receiver: UnboundedReceiver<PreconfirmerMessage>,
+) {
+    while let Some(message) = receiver.recv().await {
+        match message {
+            PreconfirmerMessage::Preconfirm(preconfirmation) => {
+                sign_and_publish(&signer, &preconfirmations, preconfirmation).await
+            }
+            PreconfirmerMessage::Settle {
+                block_number,
+                transactions,
+            } => record_broken(preconfirmations.settle(block_number, &transactions)),
+            PreconfirmerMessage::Abort => record_broken(preconfirmations.break_pending()),
+        }
+    }
+}
+
+async fn sign_and_publish(
+    signer: &Signer,
+    preconfirmations: &Preconfirmations,
+    preconfirmation: Preconfirmation,
+) {
+    let tx_hash = preconfirmation.tx_hash;
+    let signature = match signer.sign(preconfirmation.encode()).await {
+        Ok(signature) => signature,
+        Err(err) => {
+            warn!("Failed to sign preconfirmation for transaction {tx_hash:#x}: {err}");
+            return;
+        }
+    };
+    preconfirmations.publish(SignedPreconfirmation {
+        preconfirmation,
+        signer: signer.address(),
+        signature,
+    });
+    metrics!(METRICS.inc_preconfirmations_issued());
+}
+
+fn record_broken(broken: u64) {
+    if broken == 0 {
+        return;
+    }
+    warn!("Broke {broken} preconfirmation promises");
+    metrics!(METRICS.inc_preconfirmations_broken(broken));
+}
@@ -1,9 +1,10 @@
 use aligned_sdk::common::types::Network;
+use ethrex_blockchain::payload::TransactionOrdering;
 use ethrex_common::{Address, U256};
 use ethrex_l2_rpc::signer::Signer;
 use reqwest::Url;
 use secp256k1::SecretKey;
-use std::net::IpAddr;
+use std::{net::IpAddr, path::PathBuf, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub struct SequencerConfig {
@@ -13,12 +14,12 @@ pub struct SequencerConfig {
     pub l1_watcher: L1WatcherConfig,
     pub proof_coordinator: ProofCoordinatorConfig,
     pub based: BasedConfig,
+    pub high_availability: HighAvailabilityConfig,
     pub aligned: AlignedConfig,
     pub monitor: MonitorConfig,
     pub admin_server: AdminConfig,
 }
 
-// TODO: Move to blockchain/dev
 #[derive(Clone, Debug)]
 pub struct BlockProducerConfig {
     pub block_time_ms: u64,
@@ -27,6 +28,10 @@ pub struct BlockProducerConfig {
     pub operator_fee_vault_address: Option<Address>,
     pub elasticity_multiplier: u64,
     pub block_gas_limit: u64,
+    /// Policy used to pick the mempool transactions included in each block.
+    /// Any [TransactionOrdering] implementation can be plugged in.
+    pub transaction_ordering: Arc<dyn TransactionOrdering>,
+    pub preconfirmations: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -59,6 +64,8 @@ pub struct L1WatcherConfig {
     pub max_block_step: U256,
     pub watcher_block_delay: u64,
     pub l1_blob_base_fee_update_interval: u64,
+    /// When set, L1 logs are received through WebSocket subscriptions instead of polling
+    pub ws_url: Option<Url>,
 }
 
 #[derive(Clone, Debug)]
@@ -70,6 +77,8 @@ pub struct ProofCoordinatorConfig {
     pub validium: bool,
     pub tdx_private_key: Option<SecretKey>,
     pub qpl_tool_path: Option<String>,
+    /// Amount of consecutive batches verified with a single aggregated proof, if enabled.
+    pub aggregation_size: Option<u64>,
 }
 
 #[derive(Clone, Debug)]
@@ -91,6 +100,17 @@ pub struct BlockFetcherConfig {
     pub fetch_block_step: u64,
 }
 
+#[derive(Clone, Debug)]
+pub struct HighAvailabilityConfig {
+    pub enabled: bool,
+    /// Identifier of this node in the sequencer lease, unique within the deployment.
+    pub node_id: String,
+    /// When set, the lease is kept in this file instead of the rollup store.
+    pub lease_file: Option<PathBuf>,
+    pub lease_duration_ms: u64,
+    pub renew_interval_ms: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct AlignedConfig {
     pub aligned_mode: bool,
@@ -39,6 +39,8 @@ pub enum SequencerError {
     EthClientError(#[from] EthClientError),
     #[error("Failed to start StateUpdater: {0}")]
     StateUpdaterError(#[from] StateUpdaterError),
+    #[error("Failed to start LeaderElector: {0}")]
+    LeaderElectionError(#[from] LeaderElectionError),
     #[error("Failed to start BlockFetcher: {0}")]
     BlockFetcherError(#[from] BlockFetcherError),
     #[error("Failed to access Store: {0}")]
@@ -55,6 +57,28 @@ pub enum SequencerError {
     GasLimitError,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum LeaderElectionError {
+    #[error("LeaderElector failed because of an EthClient error: {0}")]
+    EthClientError(#[from] EthClientError),
+    #[error("LeaderElector failed to access Store: {0}")]
+    FailedAccessingStore(#[from] StoreError),
+    #[error("LeaderElector failed to access RollupStore: {0}")]
+    FailedAccessingRollUpStore(#[from] RollupStoreError),
+    #[error("LeaderElector failed to access the lease file: {0}")]
+    LeaseFileError(#[from] std::io::Error),
+    #[error("LeaderElector failed to parse the lease file: {0}")]
+    LeaseFileParseError(#[from] serde_json::Error),
+    #[error("LeaderElector failed to resume the L1 committer: {0}")]
+    CommitterError(String),
+    #[error("LeaderElector failed to get the system time")]
+    SystemTimeError,
+    #[error("Missing data: {0}")]
+    MissingData(String),
+    #[error("Internal Error: {0}")]
+    InternalError(#[from] GenServerError),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum L1WatcherError {
     #[error("L1Watcher error: {0}")]
@@ -0,0 +1,52 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use ethrex_common::H256;
+
+/// Transaction sent to the CommonBridge to be force-included in the L2
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForcedTransaction {
+    /// Hash under which the transaction is queued in the CommonBridge
+    pub hash: H256,
+    /// Transaction as it was sent to L1
+    pub raw: Bytes,
+}
+
+/// Forced transactions still pending in the CommonBridge, in queue order.
+///
+/// It is filled by the L1Watcher and shared with the block producer, which includes
+/// them in its blocks, and with the committer, which processes them in its batches.
+#[derive(Debug, Clone, Default)]
+pub struct ForcedTransactions {
+    inner: Arc<Mutex<Vec<ForcedTransaction>>>,
+}
+
+impl ForcedTransactions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the raw transaction queued under `hash`, if it's known.
+    pub fn get(&self, hash: &H256) -> Option<Bytes> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .iter()
+            .find(|forced_tx| forced_tx.hash == *hash)
+            .map(|forced_tx| forced_tx.raw.clone())
+    }
+
+    /// Returns the pending forced transactions, in queue order.
+    pub fn pending(&self) -> Vec<ForcedTransaction> {
+        self.inner
+            .lock()
+            .map(|inner| inner.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the pending forced transactions with the given queue.
+    pub fn replace(&self, pending: Vec<ForcedTransaction>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            *inner = pending;
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     based::sequencer_state::{SequencerState, SequencerStatus},
     sequencer::{
         errors::CommitterError,
+        forced_transactions::ForcedTransactions,
         utils::{
             self, batch_checkpoint_name, fetch_blocks_with_respective_fee_configs,
             get_git_commit_hash, system_now_ms,
@@ -23,6 +24,10 @@ use ethrex_common::{
 };
 use ethrex_l2_common::{
     calldata::Value,
+    forced_transactions::{
+        FORCED_TX_BUDGET, ForcedTransactionSender, compute_forced_transactions_hash,
+        decode_forced_transaction, get_forced_transaction_hash, is_forced_transaction_includable,
+    },
     l1_messages::{get_block_l1_messages, get_l1_message_hash},
     merkle_tree::compute_merkle_root,
     privileged_transactions::{
@@ -34,7 +39,7 @@ use ethrex_l2_common::{
 use ethrex_l2_rpc::signer::{Signer, SignerHealth};
 use ethrex_l2_sdk::{
     build_generic_tx, calldata::encode_calldata, get_l1_active_fork, get_last_committed_batch,
-    send_tx_bump_gas_exponential_backoff,
+    get_pending_forced_transactions, send_tx_bump_gas_exponential_backoff,
 };
 #[cfg(feature = "metrics")]
 use ethrex_metrics::l2::metrics::{METRICS, MetricsBlockType};
@@ -51,13 +56,13 @@ use ethrex_vm::{BlockExecutionResult, Evm};
 use rand::Rng;
 use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
     fs::remove_dir_all,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, trace, warn};
+use tracing::{Span, debug, error, field, info, instrument, trace, warn};
 
 use super::{errors::BlobEstimationError, utils::random_duration};
 use spawned_concurrency::tasks::{
@@ -66,7 +71,8 @@ use spawned_concurrency::tasks::{
 
 const COMMIT_FUNCTION_SIGNATURE_BASED: &str =
     "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32,bytes[])";
-const COMMIT_FUNCTION_SIGNATURE: &str = "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32)";
+const COMMIT_FUNCTION_SIGNATURE: &str =
+    "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32,bytes32)";
 /// Default wake up time for the committer to check if it should send a commit tx
 const COMMITTER_DEFAULT_WAKE_TIME_MS: u64 = 60_000;
 
@@ -76,6 +82,9 @@ pub enum CallMessage {
     /// time to wait in ms before sending commit
     Start(u64),
     Health,
+    /// Takes over committing from the last batch recorded on L1, e.g. after another
+    /// sequencer failed. The node's head must be the last block of that batch.
+    Resume,
 }
 
 #[derive(Clone)]
@@ -131,6 +140,11 @@ pub struct L1Committer {
     genesis: Genesis,
     /// Directory where checkpoints are stored.
     checkpoints_dir: PathBuf,
+    /// Address of the CommonBridge, where forced transactions are queued
+    bridge_address: Address,
+    /// Forced transactions pending in the CommonBridge, fetched by the L1Watcher.
+    /// Not used in based mode.
+    forced_transactions: Option<ForcedTransactions>,
 }
 
 #[derive(Clone, Serialize)]
@@ -162,6 +176,8 @@ impl L1Committer {
         sequencer_state: SequencerState,
         genesis: Genesis,
         checkpoints_dir: PathBuf,
+        bridge_address: Address,
+        forced_transactions: Option<ForcedTransactions>,
     ) -> Result<Self, CommitterError> {
         let eth_client = EthClient::new_with_config(
             eth_config.rpc_url.clone(),
@@ -209,9 +225,12 @@ impl L1Committer {
             current_checkpoint_store,
             genesis,
             checkpoints_dir,
+            bridge_address,
+            forced_transactions,
         })
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub async fn spawn(
         store: Store,
         blockchain: Arc<Blockchain>,
@@ -220,7 +239,9 @@ impl L1Committer {
         sequencer_state: SequencerState,
         genesis: Genesis,
         checkpoints_dir: PathBuf,
+        forced_transactions: ForcedTransactions,
     ) -> Result<GenServerHandle<L1Committer>, CommitterError> {
+        let forced_transactions = (!cfg.based.enabled).then_some(forced_transactions);
         let state = Self::new(
             &cfg.l1_committer,
             &cfg.block_producer,
@@ -232,6 +253,8 @@ impl L1Committer {
             sequencer_state,
             genesis,
             checkpoints_dir,
+            cfg.l1_watcher.bridge_address,
+            forced_transactions,
         )
         .await?;
         // NOTE: we spawn as blocking due to `generate_blobs_bundle` and
@@ -250,12 +273,19 @@ impl L1Committer {
         }
     }
 
+    #[instrument(
+        level = "info",
+        name = "Commit batch",
+        skip_all,
+        fields(batch.number = field::Empty)
+    )]
     async fn commit_next_batch_to_l1(&mut self) -> Result<(), CommitterError> {
         info!("Running committer main loop");
         // Get the batch to commit
         let last_committed_batch_number =
             get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
         let batch_to_commit = last_committed_batch_number + 1;
+        Span::current().record("batch.number", batch_to_commit);
 
         let l1_fork = get_l1_active_fork(&self.eth_client, self.osaka_activation_time)
             .await
@@ -577,7 +607,16 @@ impl L1Committer {
             batch.number,
         );
 
-        let batch_prover_input = self.generate_batch_prover_input(&batch).await?;
+        let forced_transactions = self
+            .get_batch_forced_transactions(&batch, &one_time_checkpoint_store)
+            .await
+            .inspect_err(|_| {
+                let _ = self.remove_one_time_checkpoint(&one_time_checkpoint_path);
+            })?;
+
+        let batch_prover_input = self
+            .generate_batch_prover_input(&batch, forced_transactions)
+            .await?;
 
         self.rollup_store
             .seal_batch_with_prover_input(batch.clone(), &self.git_commit_hash, batch_prover_input)
@@ -905,9 +944,102 @@ impl L1Committer {
         )))
     }
 
+    /// Returns the forced transactions pending in the CommonBridge that the batch processes,
+    /// in queue order. A forced transaction is processed if it's included in the batch or
+    /// if it can't be included on top of the batch final state, which `checkpoint_store`
+    /// must hold. The first one that the batch can't process, or whose transaction isn't
+    /// known yet, ends the list.
+    async fn get_batch_forced_transactions(
+        &self,
+        batch: &Batch,
+        checkpoint_store: &Store,
+    ) -> Result<Vec<Vec<u8>>, CommitterError> {
+        let Some(known_forced_transactions) = &self.forced_transactions else {
+            return Ok(Vec::new());
+        };
+        let pending_hashes =
+            get_pending_forced_transactions(&self.eth_client, self.bridge_address).await?;
+        if pending_hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (blocks, fee_configs) = fetch_blocks_with_respective_fee_configs::<CommitterError>(
+            batch,
+            &self.store,
+            &self.rollup_store,
+        )
+        .await?;
+        let last_fee_config = fee_configs.last().ok_or(CommitterError::Unreachable(
+            "There should always be fee configs".to_string(),
+        ))?;
+        let last_header = &blocks
+            .last()
+            .ok_or(CommitterError::Unreachable(
+                "There should always be blocks".to_string(),
+            ))?
+            .header;
+        let included: HashSet<H256> = blocks
+            .iter()
+            .flat_map(|block| block.body.transactions.iter().map(|tx| tx.hash()))
+            .collect();
+        let chain_config = self.store.get_chain_config();
+
+        let mut forced_transactions = Vec::new();
+        for hash in pending_hashes {
+            if u64::try_from(forced_transactions.len())? >= FORCED_TX_BUDGET {
+                break;
+            }
+            let Some(raw_transaction) = known_forced_transactions.get(&hash) else {
+                debug!("Forced transaction {hash:#x} is not known yet");
+                break;
+            };
+            if !included.contains(&hash)
+                && let Some((tx, sender)) =
+                    decode_forced_transaction(&raw_transaction, chain_config.chain_id)
+            {
+                let sender = match checkpoint_store
+                    .get_account_info(batch.last_block, sender)
+                    .await?
+                {
+                    Some(account) => ForcedTransactionSender {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        code: checkpoint_store
+                            .get_account_code(account.code_hash)?
+                            .map(|code| code.bytecode)
+                            .unwrap_or_default(),
+                    },
+                    None => ForcedTransactionSender::default(),
+                };
+                if is_forced_transaction_includable(
+                    &tx,
+                    &sender,
+                    last_header,
+                    &chain_config,
+                    last_fee_config,
+                ) {
+                    debug!(
+                        "Forced transaction {hash:#x} is not included in batch {}",
+                        batch.number
+                    );
+                    break;
+                }
+            }
+            forced_transactions.push(raw_transaction.to_vec());
+        }
+
+        info!(
+            "Processed {} forced transactions in the batch",
+            forced_transactions.len()
+        );
+
+        Ok(forced_transactions)
+    }
+
     async fn generate_batch_prover_input(
         &self,
         batch: &Batch,
+        forced_transactions: Vec<Vec<u8>>,
     ) -> Result<ProverInputData, CommitterError> {
         if let Some(prover_input) = self
             .rollup_store
@@ -931,8 +1063,21 @@ impl L1Committer {
         let (one_time_checkpoint_path, _, one_time_checkpoint_blockchain) =
             self.generate_one_time_checkpoint(batch.number).await?;
 
+        // The prover reads the senders of the forced transactions from the batch final
+        // state to check the skipped ones can't be included
+        let chain_id = self.store.get_chain_config().chain_id;
+        let forced_transaction_senders: Vec<Address> = forced_transactions
+            .iter()
+            .filter_map(|raw_transaction| decode_forced_transaction(raw_transaction, chain_id))
+            .map(|(_, sender)| sender)
+            .collect();
+
         let result = one_time_checkpoint_blockchain
-            .generate_witness_for_blocks_with_fee_configs(&blocks, Some(&fee_configs))
+            .generate_witness_for_blocks_with_final_accounts(
+                &blocks,
+                Some(&fee_configs),
+                &forced_transaction_senders,
+            )
             .await
             .map_err(CommitterError::FailedToGenerateBatchWitness);
 
@@ -991,6 +1136,7 @@ impl L1Committer {
             blob_commitment,
             blob_proof,
             fee_configs,
+            forced_transactions,
         };
 
         Ok(prover_input)
@@ -1059,6 +1205,25 @@ impl L1Committer {
         Ok((checkpoint_store, checkpoint_blockchain))
     }
 
+    /// Hash of the forced transactions processed by the batch, taken from the prover
+    /// input generated for it.
+    async fn get_forced_transactions_hash(&self, batch: &Batch) -> Result<H256, CommitterError> {
+        let prover_input = self
+            .rollup_store
+            .get_prover_input_by_batch_and_version(batch.number, &self.git_commit_hash)
+            .await?
+            .ok_or(CommitterError::RetrievalError(format!(
+                "Prover input for batch {} and version {} is missing",
+                batch.number, self.git_commit_hash
+            )))?;
+        let forced_transaction_hashes = prover_input
+            .forced_transactions
+            .iter()
+            .map(|raw_transaction| get_forced_transaction_hash(raw_transaction))
+            .collect();
+        Ok(compute_forced_transactions_hash(forced_transaction_hashes)?)
+    }
+
     async fn send_commitment(&mut self, batch: &Batch) -> Result<H256, CommitterError> {
         let messages_merkle_root = compute_merkle_root(&batch.message_hashes);
         let last_block_hash = get_last_block_hash(&self.store, batch.last_block)?;
@@ -1068,10 +1233,11 @@ impl L1Committer {
             Value::FixedBytes(batch.state_root.0.to_vec().into()),
             Value::FixedBytes(messages_merkle_root.0.to_vec().into()),
             Value::FixedBytes(batch.privileged_transactions_hash.0.to_vec().into()),
-            Value::FixedBytes(last_block_hash.0.to_vec().into()),
         ];
 
         let (commit_function_signature, values) = if self.based {
+            calldata_values.push(Value::FixedBytes(last_block_hash.0.to_vec().into()));
+
             let mut encoded_blocks: Vec<Bytes> = Vec::new();
 
             let (blocks, _) = fetch_blocks_with_respective_fee_configs::<CommitterError>(
@@ -1091,6 +1257,12 @@ impl L1Committer {
 
             (COMMIT_FUNCTION_SIGNATURE_BASED, calldata_values)
         } else {
+            let forced_transactions_hash = self.get_forced_transactions_hash(batch).await?;
+            calldata_values.push(Value::FixedBytes(
+                forced_transactions_hash.0.to_vec().into(),
+            ));
+            calldata_values.push(Value::FixedBytes(last_block_hash.0.to_vec().into()));
+
             (COMMIT_FUNCTION_SIGNATURE, calldata_values)
         };
 
@@ -1208,6 +1380,38 @@ impl L1Committer {
         self.cancellation_token = Some(handle.cancellation_token);
     }
 
+    /// Moves the committer to the last batch committed on L1, which may have been committed
+    /// by another sequencer. As the node's head is at the last block of that batch, its state
+    /// is used as the checkpoint when there isn't one already.
+    async fn resume_from_l1(&mut self) -> Result<(), CommitterError> {
+        let last_committed_batch =
+            get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+        let checkpoint_path = self
+            .checkpoints_dir
+            .join(batch_checkpoint_name(last_committed_batch));
+
+        if self.current_checkpoint_store.get_store_directory()? != checkpoint_path {
+            if !checkpoint_path.exists() {
+                info!(
+                    "Creating checkpoint for batch {last_committed_batch} from the current state"
+                );
+                self.store.create_checkpoint(&checkpoint_path).await?;
+            }
+            let (checkpoint_store, _) = Self::get_checkpoint_from_path(
+                self.genesis.clone(),
+                self.blockchain.options.clone(),
+                &checkpoint_path,
+                &self.rollup_store,
+            )
+            .await?;
+            self.current_checkpoint_store = checkpoint_store;
+        }
+
+        info!("L1 committer resuming after batch {last_committed_batch}");
+        self.last_committed_batch = last_committed_batch;
+        Ok(())
+    }
+
     async fn health(&mut self) -> CallResponse<Self> {
         let rpc_urls = self.eth_client.test_urls().await;
         let signer_status = self.signer.health().await;
@@ -1316,6 +1520,10 @@ impl GenServer for L1Committer {
             CallMessage::Stop => self.stop_committer(),
             CallMessage::Start(delay) => self.start_committer(handle.clone(), delay),
             CallMessage::Health => self.health().await,
+            CallMessage::Resume => match self.resume_from_l1().await {
+                Ok(()) => CallResponse::Reply(OutMessage::Done),
+                Err(err) => CallResponse::Reply(OutMessage::Error(err.to_string())),
+            },
         }
     }
 }
@@ -24,7 +24,7 @@ use serde::Serialize;
 use spawned_concurrency::tasks::{
     CallResponse, CastResponse, GenServer, GenServerHandle, send_after,
 };
-use tracing::{error, info, warn};
+use tracing::{Span, error, field, info, instrument, warn};
 
 use super::{
     configs::AlignedConfig,
@@ -47,6 +47,8 @@ use aligned_sdk::{
 use ethers::signers::{Signer as EthersSigner, Wallet};
 
 const VERIFY_FUNCTION_SIGNATURE: &str = "verifyBatch(uint256,bytes,bytes,bytes,bytes,bytes,bytes)";
+const VERIFY_AGGREGATED_FUNCTION_SIGNATURE: &str =
+    "verifyBatchesAggregated(uint256,uint256,bytes,bytes,bytes,bytes)";
 
 #[derive(Clone)]
 pub enum InMessage {
@@ -78,6 +80,8 @@ pub struct L1ProofSender {
     /// Directory where checkpoints are stored.
     checkpoints_dir: PathBuf,
     aligned_mode: bool,
+    /// Amount of batches verified with each aggregated proof, if aggregation is enabled.
+    aggregation_size: Option<u64>,
 }
 
 #[derive(Clone, Serialize)]
@@ -133,6 +137,7 @@ impl L1ProofSender {
             fee_estimate,
             checkpoints_dir,
             aligned_mode: aligned_cfg.aligned_mode,
+            aggregation_size: cfg.aggregation_size,
         })
     }
 
@@ -162,6 +167,12 @@ impl L1ProofSender {
         Ok(l1_proof_sender)
     }
 
+    #[instrument(
+        level = "info",
+        name = "Send proof",
+        skip_all,
+        fields(batch.number = field::Empty)
+    )]
     async fn verify_and_send_proof(&self) -> Result<(), ProofSenderError> {
         let last_verified_batch =
             get_last_verified_batch(&self.eth_client, self.on_chain_proposer_address).await?;
@@ -178,6 +189,7 @@ impl L1ProofSender {
             }
             last_verified_batch + 1
         };
+        Span::current().record("batch.number", batch_to_send);
 
         let last_committed_batch =
             get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
@@ -187,6 +199,12 @@ impl L1ProofSender {
             return Ok(());
         }
 
+        if self.aggregation_size.is_some() {
+            return self
+                .send_aggregated_proof(batch_to_send, last_committed_batch)
+                .await;
+        }
+
         let mut proofs = HashMap::new();
         let mut missing_proof_types = Vec::new();
         for proof_type in &self.needed_proof_types {
@@ -212,19 +230,7 @@ impl L1ProofSender {
                 .set_latest_sent_batch_proof(batch_to_send)
                 .await?;
 
-            // Remove checkpoint from batch sent - 1.
-            // That checkpoint was needed to generate the proof for the batch we just sent.
-            // The checkpoint for the batch we have just sent is needed for the next batch.
-            let checkpoint_path = self
-                .checkpoints_dir
-                .join(batch_checkpoint_name(batch_to_send - 1));
-            if checkpoint_path.exists() {
-                let _ = remove_dir_all(&checkpoint_path).inspect_err(|e| {
-                    error!(
-                        "Failed to remove checkpoint directory at path {checkpoint_path:?}. Should be removed manually. Error: {e}"
-                    )
-                });
-            }
+            self.remove_previous_checkpoint(batch_to_send);
         } else {
             let missing_proof_types: Vec<String> = missing_proof_types
                 .iter()
@@ -240,6 +246,96 @@ impl L1ProofSender {
         Ok(())
     }
 
+    /// Sends the aggregated proofs of the range of batches starting at `first_batch`,
+    /// verifying the whole range in a single transaction.
+    async fn send_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_committed_batch: u64,
+    ) -> Result<(), ProofSenderError> {
+        let mut last_batch = None;
+        for (range_first_batch, range_last_batch) in
+            self.rollup_store.get_aggregated_ranges().await?
+        {
+            if range_first_batch < first_batch {
+                // The range starts at an already verified batch, it can't be sent anymore
+                self.rollup_store
+                    .delete_aggregated_proofs(range_first_batch, range_last_batch)
+                    .await?;
+            } else if range_first_batch == first_batch && last_batch.is_none() {
+                last_batch = Some(range_last_batch);
+            }
+        }
+
+        let Some(last_batch) = last_batch else {
+            info!(
+                ?first_batch,
+                "No aggregated proof for the next batch to send"
+            );
+            return Ok(());
+        };
+
+        if last_committed_batch < last_batch {
+            info!("Last batch of the aggregated range ({last_batch}) is not yet committed");
+            return Ok(());
+        }
+
+        let mut proofs = HashMap::new();
+        let mut missing_proof_types = Vec::new();
+        for proof_type in &self.needed_proof_types {
+            if let Some(proof) = self
+                .rollup_store
+                .get_aggregated_proof(first_batch, last_batch, *proof_type)
+                .await?
+            {
+                proofs.insert(*proof_type, proof);
+            } else {
+                missing_proof_types.push(format!("{proof_type:?}"));
+            }
+        }
+
+        if !missing_proof_types.is_empty() {
+            info!(
+                ?missing_proof_types,
+                ?first_batch,
+                ?last_batch,
+                "Missing aggregated proof(s), will not send",
+            );
+            return Ok(());
+        }
+
+        self.send_aggregated_proof_to_contract(first_batch, last_batch, proofs)
+            .await?;
+        self.rollup_store
+            .set_latest_sent_batch_proof(last_batch)
+            .await?;
+        self.rollup_store
+            .delete_aggregated_proofs(first_batch, last_batch)
+            .await?;
+
+        for batch_number in first_batch..=last_batch {
+            self.remove_previous_checkpoint(batch_number);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the checkpoint of the batch previous to the one sent.
+    /// That checkpoint was needed to generate the proof for the batch we just sent.
+    /// The checkpoint for the batch we have just sent is needed for the next batch.
+    fn remove_previous_checkpoint(&self, sent_batch: u64) {
+        let checkpoint_path = self
+            .checkpoints_dir
+            .join(batch_checkpoint_name(sent_batch - 1));
+        if checkpoint_path.exists() {
+            let _ = remove_dir_all(&checkpoint_path).inspect_err(|e| {
+                error!(
+                    "Failed to remove checkpoint directory at path {checkpoint_path:?}. Should be removed manually. Error: {e}"
+                )
+            });
+        }
+    }
+
     async fn send_proof_to_aligned(
         &self,
         batch_number: u64,
@@ -451,6 +547,83 @@ impl L1ProofSender {
         Ok(())
     }
 
+    pub async fn send_aggregated_proof_to_contract(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proofs: HashMap<ProverType, BatchProof>,
+    ) -> Result<(), ProofSenderError> {
+        info!(
+            ?first_batch,
+            ?last_batch,
+            "Sending aggregated verification transaction to L1"
+        );
+
+        let calldata_values = [
+            &[
+                Value::Uint(U256::from(first_batch)),
+                Value::Uint(U256::from(last_batch)),
+            ],
+            proofs
+                .get(&ProverType::RISC0)
+                .map(|proof| proof.calldata())
+                .unwrap_or(ProverType::RISC0.empty_calldata())
+                .as_slice(),
+            proofs
+                .get(&ProverType::SP1)
+                .map(|proof| proof.calldata())
+                .unwrap_or(ProverType::SP1.empty_calldata())
+                .as_slice(),
+        ]
+        .concat();
+
+        let calldata = encode_calldata(VERIFY_AGGREGATED_FUNCTION_SIGNATURE, &calldata_values)?;
+
+        let send_verify_tx_result = send_verify_tx(
+            calldata,
+            &self.eth_client,
+            self.on_chain_proposer_address,
+            &self.signer,
+        )
+        .await;
+
+        if let Err(EthClientError::EstimateGasError(EstimateGasError::RPCError(error))) =
+            send_verify_tx_result.as_ref()
+        {
+            // The range is aggregated again from the stored batch proofs
+            let invalid_proof_type = if error.contains("Invalid RISC0 proof") {
+                Some(ProverType::RISC0)
+            } else if error.contains("Invalid SP1 proof") {
+                Some(ProverType::SP1)
+            } else {
+                None
+            };
+            if let Some(proof_type) = invalid_proof_type {
+                warn!("Deleting invalid aggregated {proof_type} proof");
+                self.rollup_store
+                    .delete_aggregated_proofs(first_batch, last_batch)
+                    .await?;
+            }
+        }
+
+        let verify_tx_hash = send_verify_tx_result?;
+
+        for batch_number in first_batch..=last_batch {
+            self.rollup_store
+                .store_verify_tx_by_batch(batch_number, verify_tx_hash)
+                .await?;
+        }
+
+        info!(
+            ?first_batch,
+            ?last_batch,
+            ?verify_tx_hash,
+            "Sent aggregated verification transaction to L1"
+        );
+
+        Ok(())
+    }
+
     async fn health(&self) -> CallResponse<Self> {
         let rpc_healthcheck = self.eth_client.test_urls().await;
         let signer_status = self.signer.health().await;
@@ -20,7 +20,7 @@ use ethrex_rpc::{
 };
 use ethrex_storage_rollup::StoreRollup;
 use reqwest::Url;
-use tracing::{error, info, warn};
+use tracing::{Span, error, field, info, instrument, warn};
 
 use crate::{
     CommitterConfig, EthConfig, ProofCoordinatorConfig, SequencerConfig,
@@ -115,9 +115,16 @@ impl L1ProofVerifier {
         }
     }
 
+    #[instrument(
+        level = "info",
+        name = "Verify proofs",
+        skip_all,
+        fields(batch.number = field::Empty)
+    )]
     async fn main_logic(&self) -> Result<(), ProofVerifierError> {
         let first_batch_to_verify =
             1 + get_last_verified_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+        Span::current().record("batch.number", first_batch_to_verify);
 
         match self
             .verify_proofs_aggregation(first_batch_to_verify)
@@ -1,5 +1,6 @@
 use super::utils::random_duration;
 use crate::based::sequencer_state::{SequencerState, SequencerStatus};
+use crate::sequencer::forced_transactions::{ForcedTransaction, ForcedTransactions};
 use crate::{EthConfig, L1WatcherConfig, SequencerConfig};
 use crate::{sequencer::errors::L1WatcherError, utils::parse::hash_to_address};
 use bytes::Bytes;
@@ -9,9 +10,11 @@ use ethrex_common::types::{PrivilegedL2Transaction, TxType};
 use ethrex_common::utils::keccak;
 use ethrex_common::{H160, types::Transaction};
 use ethrex_l2_sdk::{
-    build_generic_tx, get_last_fetched_l1_block, get_pending_privileged_transactions,
+    build_generic_tx, get_forced_transaction_l1_block, get_last_fetched_l1_block,
+    get_pending_forced_transactions, get_pending_privileged_transactions,
 };
 use ethrex_rpc::clients::EthClientError;
+use ethrex_rpc::clients::eth::subscription::LogFilter;
 use ethrex_rpc::types::block_identifier::{BlockIdentifier, BlockTag};
 use ethrex_rpc::types::receipt::RpcLog;
 use ethrex_rpc::{
@@ -27,7 +30,7 @@ use spawned_concurrency::tasks::{
 use std::collections::BTreeMap;
 use std::time::Duration;
 use std::{cmp::min, sync::Arc};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 #[derive(Clone)]
 pub enum CallMessage {
@@ -38,6 +41,10 @@ pub enum CallMessage {
 pub enum InMessage {
     WatchLogs,
     UpdateL1BlobBaseFee,
+    /// A log received through the L1 logs subscription
+    NewLog(Box<RpcLog>),
+    /// A new L1 head received through the L1 new heads subscription
+    NewHead(u64),
 }
 
 #[derive(Clone)]
@@ -59,6 +66,13 @@ pub struct L1Watcher {
     pub l1_block_delay: u64,
     pub sequencer_state: SequencerState,
     pub l1_blob_base_fee_update_interval: u64,
+    /// L1 WebSocket URL, when set logs are received through subscriptions instead of polling
+    pub ws_url: Option<Url>,
+    /// Logs received through the subscription that aren't `l1_block_delay` blocks deep yet
+    pub pending_logs: Vec<RpcLog>,
+    /// Forced transactions pending in the CommonBridge, shared with the block producer
+    /// and the committer. Not used in based mode.
+    pub forced_transactions: Option<ForcedTransactions>,
 }
 
 #[derive(Clone, Serialize)]
@@ -80,6 +94,7 @@ impl L1Watcher {
         eth_config: &EthConfig,
         watcher_config: &L1WatcherConfig,
         sequencer_state: SequencerState,
+        forced_transactions: Option<ForcedTransactions>,
     ) -> Result<Self, L1WatcherError> {
         let eth_client = EthClient::new_with_multiple_urls(eth_config.rpc_url.clone())?;
         // TODO: De-hardcode the rollup client URL
@@ -100,6 +115,9 @@ impl L1Watcher {
             l1_block_delay: watcher_config.watcher_block_delay,
             sequencer_state,
             l1_blob_base_fee_update_interval: watcher_config.l1_blob_base_fee_update_interval,
+            ws_url: watcher_config.ws_url.clone(),
+            pending_logs: Vec::new(),
+            forced_transactions,
         })
     }
 
@@ -108,17 +126,21 @@ impl L1Watcher {
         blockchain: Arc<Blockchain>,
         cfg: SequencerConfig,
         sequencer_state: SequencerState,
+        forced_transactions: ForcedTransactions,
     ) -> Result<GenServerHandle<Self>, L1WatcherError> {
+        let forced_transactions = (!cfg.based.enabled).then_some(forced_transactions);
         let state = Self::new(
             store,
             blockchain,
             &cfg.eth,
             &cfg.l1_watcher,
             sequencer_state,
+            forced_transactions,
         )?;
         Ok(state.start())
     }
 
+    #[instrument(level = "info", name = "Watch L1", skip_all)]
     async fn watch(&mut self) {
         let Ok(logs) = self
             .get_privileged_transactions()
@@ -135,6 +157,11 @@ impl L1Watcher {
                 .await
                 .inspect_err(|err| error!("L1 Watcher Error: {}", err));
         };
+
+        let _ = self
+            .sync_forced_transactions()
+            .await
+            .inspect_err(|err| error!("L1 Watcher Error: {}", err));
     }
 
     pub async fn get_privileged_transactions(&mut self) -> Result<Vec<RpcLog>, L1WatcherError> {
@@ -182,17 +209,13 @@ impl L1Watcher {
             self.last_block_fetched, new_last_block
         );
 
-        // Matches the event PrivilegedTxSent from ICommonBridge.sol
-        let topic =
-            keccak(b"PrivilegedTxSent(address,address,address,uint256,uint256,uint256,bytes)");
-
         let logs = self
             .eth_client
             .get_logs(
                 self.last_block_fetched + 1,
                 new_last_block,
                 self.address,
-                vec![topic],
+                vec![privileged_tx_sent_topic()],
             )
             .await?;
 
@@ -205,6 +228,86 @@ impl L1Watcher {
         Ok(logs)
     }
 
+    /// Subscribes to the privileged transaction logs and the new heads of the L1, and
+    /// forwards them to the watcher. Logs are processed once they are `l1_block_delay`
+    /// blocks deep, see [`Self::handle_new_head`].
+    async fn subscribe(
+        &mut self,
+        ws_url: Url,
+        handle: &GenServerHandle<Self>,
+    ) -> Result<(), L1WatcherError> {
+        if self.last_block_fetched.is_zero() {
+            self.last_block_fetched = get_last_fetched_l1_block(&self.eth_client, self.address)
+                .await?
+                .into();
+        }
+        let from_block = self.last_block_fetched.as_u64() + 1;
+        let mut logs = self.eth_client.subscribe_logs(
+            ws_url.clone(),
+            from_block,
+            LogFilter {
+                address: self.address,
+                topics: vec![privileged_tx_sent_topic()],
+            },
+        );
+        let mut heads = self.eth_client.subscribe_new_heads(ws_url);
+        info!("L1 Watcher subscribed to L1 logs from block {from_block}");
+
+        let mut handle = handle.clone();
+        tokio::spawn(async move {
+            loop {
+                let message = tokio::select! {
+                    Some(log) = logs.next() => InMessage::NewLog(Box::new(log)),
+                    Some(head) = heads.next() => InMessage::NewHead(head.number),
+                    else => break,
+                };
+                if handle.cast(message).await.is_err() {
+                    break;
+                }
+            }
+            error!("L1 Watcher subscriptions stopped");
+        });
+        Ok(())
+    }
+
+    fn handle_new_log(&mut self, log: RpcLog) {
+        if log.removed {
+            self.pending_logs.retain(|pending| {
+                pending.block_hash != log.block_hash || pending.log_index != log.log_index
+            });
+            return;
+        }
+        self.pending_logs.push(log);
+    }
+
+    /// Processes the pending logs that are at least `l1_block_delay` blocks deep
+    async fn handle_new_head(&mut self, head: u64) {
+        let Some(latest_block_to_check) = head.checked_sub(self.l1_block_delay) else {
+            return;
+        };
+        if U256::from(latest_block_to_check) <= self.last_block_fetched {
+            return;
+        }
+        let (logs, pending_logs): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_logs)
+            .into_iter()
+            .partition(|log| log.block_number <= latest_block_to_check);
+        self.pending_logs = pending_logs;
+
+        debug!("Processing logs up to block {latest_block_to_check:#x}");
+        self.last_block_fetched = latest_block_to_check.into();
+        if !logs.is_empty() {
+            let _ = self
+                .process_privileged_transactions(logs)
+                .await
+                .inspect_err(|err| error!("L1 Watcher Error: {}", err));
+        }
+
+        let _ = self
+            .sync_forced_transactions()
+            .await
+            .inspect_err(|err| error!("L1 Watcher Error: {}", err));
+    }
+
     pub async fn process_privileged_transactions(
         &mut self,
         logs: Vec<RpcLog>,
@@ -265,6 +368,63 @@ impl L1Watcher {
         Ok(privileged_txs)
     }
 
+    /// Updates the forced transactions with the queue pending in the CommonBridge, fetching
+    /// the transactions that aren't known yet from their `ForcedTransactionSent` log.
+    ///
+    /// Unlike privileged transactions, forced transactions are not added to the mempool,
+    /// the block producer includes them on its own.
+    async fn sync_forced_transactions(&mut self) -> Result<(), L1WatcherError> {
+        let Some(forced_transactions) = self.forced_transactions.clone() else {
+            return Ok(());
+        };
+
+        let pending_hashes =
+            get_pending_forced_transactions(&self.eth_client, self.address).await?;
+        let mut pending = Vec::with_capacity(pending_hashes.len());
+        for hash in pending_hashes {
+            let raw = match forced_transactions.get(&hash) {
+                Some(raw) => raw,
+                None => {
+                    let Some(raw) = self.fetch_forced_transaction(hash).await? else {
+                        warn!(
+                            "Could not find the transaction of pending forced transaction {hash:#x}"
+                        );
+                        continue;
+                    };
+                    info!("Forced transaction {hash:#x} queued in the CommonBridge");
+                    raw
+                }
+            };
+            pending.push(ForcedTransaction { hash, raw });
+        }
+        forced_transactions.replace(pending);
+        Ok(())
+    }
+
+    /// Looks for the `ForcedTransactionSent` log of the forced transaction `hash`, which
+    /// may have been emitted before the watcher started. Only the logs of the L1 block the
+    /// CommonBridge recorded for it are queried.
+    async fn fetch_forced_transaction(&self, hash: H256) -> Result<Option<Bytes>, L1WatcherError> {
+        let l1_block =
+            get_forced_transaction_l1_block(&self.eth_client, self.address, hash).await?;
+        if l1_block == 0 {
+            return Ok(None);
+        }
+        let logs = self
+            .eth_client
+            .get_logs(
+                l1_block.into(),
+                l1_block.into(),
+                self.address,
+                vec![forced_tx_sent_topic(), hash],
+            )
+            .await?;
+        let Some(log) = logs.into_iter().find(|log| !log.removed) else {
+            return Ok(None);
+        };
+        Ok(Some(forced_transaction_from_log(&log.log)?))
+    }
+
     async fn privileged_transaction_already_processed(
         &mut self,
         tx_hash: H256,
@@ -309,13 +469,21 @@ impl GenServer for L1Watcher {
     type OutMsg = OutMessage;
     type Error = L1WatcherError;
 
-    async fn init(self, handle: &GenServerHandle<Self>) -> Result<InitResult<Self>, Self::Error> {
-        // Perform the check and suscribe a periodic Watch.
-        handle
-            .clone()
-            .cast(Self::CastMsg::WatchLogs)
-            .await
-            .map_err(Self::Error::InternalError)?;
+    async fn init(
+        mut self,
+        handle: &GenServerHandle<Self>,
+    ) -> Result<InitResult<Self>, Self::Error> {
+        if let Some(ws_url) = self.ws_url.clone() {
+            // Receive the logs from the L1 subscriptions.
+            self.subscribe(ws_url, handle).await?;
+        } else {
+            // Perform the check and suscribe a periodic Watch.
+            handle
+                .clone()
+                .cast(Self::CastMsg::WatchLogs)
+                .await
+                .map_err(Self::Error::InternalError)?;
+        }
 
         // Perform the first L1 blob base fee update and schedule periodic updates.
         handle
@@ -381,6 +549,17 @@ impl GenServer for L1Watcher {
                 send_after(interval, handle.clone(), Self::CastMsg::UpdateL1BlobBaseFee);
                 CastResponse::NoReply
             }
+            Self::CastMsg::NewLog(log) => {
+                self.handle_new_log(*log);
+                CastResponse::NoReply
+            }
+            Self::CastMsg::NewHead(head) => {
+                // Logs are kept pending while not sequencing, like polling stops meanwhile
+                if let SequencerStatus::Sequencing = self.sequencer_state.status().await {
+                    self.handle_new_head(head).await;
+                }
+                CastResponse::NoReply
+            }
         }
     }
 
@@ -395,6 +574,48 @@ impl GenServer for L1Watcher {
     }
 }
 
+/// Matches the event PrivilegedTxSent from ICommonBridge.sol
+fn privileged_tx_sent_topic() -> H256 {
+    keccak(b"PrivilegedTxSent(address,address,address,uint256,uint256,uint256,bytes)")
+}
+
+/// Matches the event ForcedTransactionSent from ICommonBridge.sol
+fn forced_tx_sent_topic() -> H256 {
+    keccak(b"ForcedTransactionSent(bytes32,bytes,uint256)")
+}
+
+fn forced_transaction_from_log(log: &RpcLogInfo) -> Result<Bytes, L1WatcherError> {
+    /*
+        event ForcedTransactionSent (
+            bytes32 indexed txHash, => part of topics, not data
+            bytes transaction, => offset_transaction => 0..32
+            uint256 deadline => 32..64
+        );
+        The transaction follows the head of the data:
+            => length_transaction => 64..96
+            => transaction => 96..
+    */
+    let transaction_len: usize = U256::from_big_endian(log.data.get(64..96).ok_or(
+        L1WatcherError::FailedToDeserializeLog(
+            "Failed to parse transaction_len from log: log.data[64..96] out of bounds".to_owned(),
+        ),
+    )?)
+    .try_into()
+    .map_err(|_| {
+        L1WatcherError::FailedToDeserializeLog("Forced transaction length is too big".to_owned())
+    })?;
+
+    let transaction = 96_usize
+        .checked_add(transaction_len)
+        .and_then(|end| log.data.get(96..end))
+        .ok_or(L1WatcherError::FailedToDeserializeLog(
+            "Failed to parse transaction from log: log.data[96..96 + transaction_len] out of bounds"
+                .to_owned(),
+        ))?;
+
+    Ok(Bytes::copy_from_slice(transaction))
+}
+
 pub struct PrivilegedTransactionData {
     pub value: U256,
     pub to_address: H160,
@@ -514,3 +735,69 @@ impl PrivilegedTransactionData {
         Ok(generic_tx.try_into()?)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// ABI encodes the data of a `ForcedTransactionSent` log
+    fn forced_tx_sent_data(transaction: &[u8], deadline: u64) -> Vec<u8> {
+        let padding = vec![0; transaction.len().next_multiple_of(32) - transaction.len()];
+        [
+            U256::from(64).to_big_endian().as_slice(),
+            U256::from(deadline).to_big_endian().as_slice(),
+            U256::from(transaction.len()).to_big_endian().as_slice(),
+            transaction,
+            padding.as_slice(),
+        ]
+        .concat()
+    }
+
+    fn forced_tx_sent_log(data: Vec<u8>) -> RpcLogInfo {
+        RpcLogInfo {
+            address: Address::zero(),
+            topics: vec![forced_tx_sent_topic(), H256::repeat_byte(1)],
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn parses_the_transaction_of_forced_tx_sent_logs() {
+        for transaction in [vec![0x02; 1], vec![0x02; 32], vec![0x01; 100]] {
+            let log = forced_tx_sent_log(forced_tx_sent_data(&transaction, 1_000));
+
+            assert_eq!(
+                forced_transaction_from_log(&log).unwrap(),
+                Bytes::from(transaction)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_forced_tx_sent_logs() {
+        let data = forced_tx_sent_data(&[0x02; 40], 1_000);
+        // Truncated length and transaction
+        for len in [80, 96 + 39] {
+            let log = forced_tx_sent_log(data.get(..len).unwrap().to_vec());
+            assert!(matches!(
+                forced_transaction_from_log(&log),
+                Err(L1WatcherError::FailedToDeserializeLog(_))
+            ));
+        }
+        // Length that doesn't fit in memory
+        let mut data = data;
+        data.splice(64..96, [0xff; 32]);
+        assert!(matches!(
+            forced_transaction_from_log(&forced_tx_sent_log(data)),
+            Err(L1WatcherError::FailedToDeserializeLog(_))
+        ));
+        // usize::MAX overflows the end of the transaction
+        let mut data = forced_tx_sent_data(&[0x02; 40], 1_000);
+        data.splice(64..96, U256::from(usize::MAX).to_big_endian());
+        assert!(matches!(
+            forced_transaction_from_log(&forced_tx_sent_log(data)),
+            Err(L1WatcherError::FailedToDeserializeLog(_))
+        ));
+    }
+}
@@ -0,0 +1,614 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use ethrex_blockchain::Blockchain;
+use ethrex_common::{
+    Address,
+    types::{Block, Transaction},
+};
+use ethrex_l2_sdk::get_last_committed_batch;
+use ethrex_rpc::EthClient;
+use ethrex_storage::Store;
+use ethrex_storage_rollup::{SequencerLease, StoreRollup};
+use serde::{Deserialize, Serialize};
+use spawned_concurrency::tasks::{
+    CallResponse, CastResponse, GenServer, GenServerHandle, send_after,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    SequencerConfig,
+    based::sequencer_state::{SequencerState, SequencerStatus},
+    sequencer::{
+        errors::LeaderElectionError,
+        l1_committer::{
+            CallMessage as CommitterCallMessage, L1Committer, OutMessage as CommitterOutMessage,
+        },
+        utils::{node_is_up_to_date, system_now_ms, track_lead_sequencer},
+    },
+};
+
+/// Where the sequencer lease is kept.
+#[derive(Clone, Debug)]
+pub enum LeaseStore {
+    /// The rollup store, shared by every node of the deployment (e.g. a Postgres database).
+    RollupStore(StoreRollup),
+    /// A local file. Takeovers are not atomic, so it is only meant for testing several
+    /// nodes running on the same host.
+    File(PathBuf),
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileLease {
+    holder: String,
+    sequencer_address: Address,
+    expires_at: u64,
+    #[serde(default)]
+    epoch: u64,
+}
+
+impl LeaseStore {
+    async fn try_acquire(
+        &self,
+        holder: &str,
+        sequencer_address: Address,
+        now: u64,
+        expires_at: u64,
+    ) -> Result<SequencerLease, LeaderElectionError> {
+        match self {
+            Self::RollupStore(rollup_store) => Ok(rollup_store
+                .try_acquire_sequencer_lease(holder, sequencer_address, now, expires_at)
+                .await?),
+            Self::File(path) => {
+                let current = self.get().await?;
+                if let Some(lease) = &current
+                    && lease.holder != holder
+                    && lease.expires_at > now
+                {
+                    return Ok(lease.clone());
+                }
+                let epoch = match current {
+                    Some(lease) if lease.holder == holder => lease.epoch,
+                    Some(lease) => lease.epoch + 1,
+                    None => 1,
+                };
+                let lease = FileLease {
+                    holder: holder.to_owned(),
+                    sequencer_address,
+                    expires_at,
+                    epoch,
+                };
+                // Replace the file at once so other nodes never read a partial lease
+                let tmp_path = path.with_extension("tmp");
+                std::fs::write(&tmp_path, serde_json::to_vec(&lease)?)?;
+                std::fs::rename(&tmp_path, path)?;
+                Ok(SequencerLease {
+                    holder: lease.holder,
+                    sequencer_address: lease.sequencer_address,
+                    expires_at: lease.expires_at,
+                    epoch: lease.epoch,
+                })
+            }
+        }
+    }
+
+    async fn get(&self) -> Result<Option<SequencerLease>, LeaderElectionError> {
+        match self {
+            Self::RollupStore(rollup_store) => Ok(rollup_store.get_sequencer_lease().await?),
+            Self::File(path) => {
+                if !path.exists() {
+                    return Ok(None);
+                }
+                let lease: FileLease = serde_json::from_slice(&std::fs::read(path)?)?;
+                Ok(Some(SequencerLease {
+                    holder: lease.holder,
+                    sequencer_address: lease.sequencer_address,
+                    expires_at: lease.expires_at,
+                    epoch: lease.epoch,
+                }))
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::RollupStore(_) => "rollup_store".to_string(),
+            Self::File(path) => format!("file:{}", path.display()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum InMessage {
+    Elect,
+}
+
+#[derive(Clone)]
+pub enum CallMessage {
+    Health,
+}
+
+#[derive(Clone)]
+pub enum OutMessage {
+    Health(Box<LeaderElectorHealth>),
+}
+
+#[derive(Clone, Serialize)]
+pub struct LeaderElectorHealth {
+    node_id: String,
+    role: String,
+    sequencer_state: String,
+    lease_store: String,
+    lease_holder: Option<String>,
+    lease_sequencer_address: Option<Address>,
+    lease_expires_at: Option<u64>,
+    lease_duration_ms: u64,
+    renew_interval_ms: u64,
+}
+
+/// Runs the active/standby mode of the sequencer.
+///
+/// Every node periodically tries to take or renew a lease, and only the holder is
+/// `Sequencing`. Standbys are `Following`, receiving the leader's blocks and batches over
+/// P2P, and only compete for the lease once they have the last batch committed on L1.
+pub struct LeaderElector {
+    node_id: String,
+    sequencer_address: Address,
+    lease_store: LeaseStore,
+    lease_duration_ms: u64,
+    renew_interval_ms: u64,
+    /// Last lease seen, renewed or not
+    lease: Option<SequencerLease>,
+    on_chain_proposer_address: Address,
+    eth_client: EthClient,
+    store: Store,
+    rollup_store: StoreRollup,
+    blockchain: Arc<Blockchain>,
+    sequencer_state: SequencerState,
+    l1_committer: Option<GenServerHandle<L1Committer>>,
+}
+
+impl LeaderElector {
+    pub fn new(
+        cfg: &SequencerConfig,
+        sequencer_state: SequencerState,
+        blockchain: Arc<Blockchain>,
+        store: Store,
+        rollup_store: StoreRollup,
+        l1_committer: Option<GenServerHandle<L1Committer>>,
+    ) -> Result<Self, LeaderElectionError> {
+        let lease_store = match &cfg.high_availability.lease_file {
+            Some(path) => LeaseStore::File(path.clone()),
+            None => LeaseStore::RollupStore(rollup_store.clone()),
+        };
+        Ok(Self {
+            node_id: cfg.high_availability.node_id.clone(),
+            sequencer_address: cfg.l1_committer.signer.address(),
+            lease_store,
+            lease_duration_ms: cfg.high_availability.lease_duration_ms,
+            renew_interval_ms: cfg.high_availability.renew_interval_ms,
+            lease: None,
+            on_chain_proposer_address: cfg.l1_committer.on_chain_proposer_address,
+            eth_client: EthClient::new_with_multiple_urls(cfg.eth.rpc_url.clone())?,
+            store,
+            rollup_store,
+            blockchain,
+            sequencer_state,
+            l1_committer,
+        })
+    }
+
+    pub async fn spawn(
+        cfg: &SequencerConfig,
+        sequencer_state: SequencerState,
+        blockchain: Arc<Blockchain>,
+        store: Store,
+        rollup_store: StoreRollup,
+        l1_committer: Option<GenServerHandle<L1Committer>>,
+    ) -> Result<GenServerHandle<LeaderElector>, LeaderElectionError> {
+        // Nodes start as standbys, which need to process the blocks received over P2P
+        blockchain.set_synced();
+        let mut leader_elector = Self::new(
+            cfg,
+            sequencer_state,
+            blockchain,
+            store,
+            rollup_store,
+            l1_committer,
+        )?
+        .start();
+        leader_elector
+            .cast(InMessage::Elect)
+            .await
+            .map_err(LeaderElectionError::InternalError)?;
+        Ok(leader_elector)
+    }
+
+    async fn elect(&mut self) -> Result<(), LeaderElectionError> {
+        let now = now_ms()?;
+        let current_status = self.sequencer_state.status().await;
+        // The leader keeps renewing even if L1 can't be reached
+        let can_lead = current_status == SequencerStatus::Sequencing
+            || node_is_up_to_date::<LeaderElectionError>(
+                &self.eth_client,
+                self.on_chain_proposer_address,
+                &self.rollup_store,
+            )
+            .await?;
+
+        let lease = if can_lead {
+            Some(
+                self.lease_store
+                    .try_acquire(
+                        &self.node_id,
+                        self.sequencer_address,
+                        now,
+                        now + self.lease_duration_ms,
+                    )
+                    .await?,
+            )
+        } else {
+            self.lease_store.get().await?
+        };
+        self.lease = lease.clone();
+
+        let Some(lease) = lease.filter(|lease| lease.expires_at > now) else {
+            // Nobody is leading and we can't take over yet
+            return Ok(());
+        };
+
+        let transition = role_transition(
+            &self.node_id,
+            current_status == SequencerStatus::Sequencing,
+            &lease,
+        );
+        if transition == RoleTransition::StepDown {
+            warn!(
+                "Sequencer lease taken by {}, stepping down as leader",
+                lease.holder
+            );
+            self.step_down().await?;
+        }
+        // Fenced after stepping down, so our uncommitted state is reverted while we can still write
+        self.fence_writes(&lease).await?;
+        if transition == RoleTransition::TakeOver {
+            self.take_over(&lease).await?;
+        } else {
+            self.track_lead_sequencer(lease.sequencer_address).await?;
+        }
+        Ok(())
+    }
+
+    /// Makes the rollup store reject our writes once another node takes the lease over, so a
+    /// leader that didn't notice it lost the lease (e.g. because of clock skew) can't keep
+    /// writing. Only a lease kept in the rollup store can fence its writes.
+    async fn fence_writes(&self, lease: &SequencerLease) -> Result<(), LeaderElectionError> {
+        if let LeaseStore::RollupStore(rollup_store) = &self.lease_store {
+            rollup_store
+                .set_sequencer_lease_fence(Some(lease.epoch))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Starts sequencing on top of the last batch committed on L1, discarding any block
+    /// produced after it by the previous leader. Their transactions are put back in the
+    /// mempool, so the ones already preconfirmed are included again.
+    async fn take_over(&mut self, lease: &SequencerLease) -> Result<(), LeaderElectionError> {
+        info!("Acquired the sequencer lease, taking over as leader");
+        let transactions = self.revert_uncommitted_state(true).await?;
+        self.track_lead_sequencer(lease.sequencer_address).await?;
+        self.reinject_transactions(transactions).await;
+
+        if let Some(l1_committer) = &mut self.l1_committer {
+            match l1_committer.call(CommitterCallMessage::Resume).await? {
+                CommitterOutMessage::Done => {}
+                CommitterOutMessage::Error(err) => {
+                    return Err(LeaderElectionError::CommitterError(err));
+                }
+                _ => {
+                    return Err(LeaderElectionError::CommitterError(
+                        "Unexpected response to resume message".to_string(),
+                    ));
+                }
+            }
+        }
+
+        self.sequencer_state
+            .new_status(SequencerStatus::Sequencing)
+            .await;
+        info!("Node is now the leader sequencer");
+        Ok(())
+    }
+
+    async fn step_down(&mut self) -> Result<(), LeaderElectionError> {
+        self.sequencer_state
+            .new_status(SequencerStatus::Following)
+            .await;
+        // Blocks produced after the last commitment will be discarded by the new leader.
+        // A shared rollup store already belongs to the new leader, so only the local chain is
+        // reverted then
+        self.revert_uncommitted_state(!self.rollup_store.is_shared())
+            .await?;
+        self.blockchain.set_synced();
+        info!("Node is now a standby sequencer following the leader");
+        Ok(())
+    }
+
+    /// Records the lease holder as the lead sequencer of the blocks after the last committed
+    /// batch, where it starts sequencing, so the P2P layer accepts the blocks and batches it
+    /// signs.
+    async fn track_lead_sequencer(
+        &self,
+        lead_sequencer: Address,
+    ) -> Result<(), LeaderElectionError> {
+        let last_committed_batch =
+            get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+        if let Some(first_block) =
+            track_lead_sequencer(&self.rollup_store, last_committed_batch, lead_sequencer).await?
+        {
+            info!("Lead sequencer {lead_sequencer:#x} in charge from block {first_block}");
+        }
+        Ok(())
+    }
+
+    /// Reverts the chain, and the rollup store if `revert_rollup_store` is set, to the last
+    /// batch committed on L1. Returns the transactions of the blocks discarded.
+    async fn revert_uncommitted_state(
+        &self,
+        revert_rollup_store: bool,
+    ) -> Result<Vec<Transaction>, LeaderElectionError> {
+        let last_committed_batch =
+            get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+
+        let last_committed_block = self
+            .rollup_store
+            .get_block_numbers_by_batch(last_committed_batch)
+            .await?
+            .and_then(|blocks| blocks.last().copied())
+            .ok_or(LeaderElectionError::MissingData(format!(
+                "No blocks found for the last committed batch {last_committed_batch}"
+            )))?;
+
+        let header = self.store.get_block_header(last_committed_block)?.ok_or(
+            LeaderElectionError::MissingData(format!(
+                "No block header found for block {last_committed_block}"
+            )),
+        )?;
+        let body = self
+            .store
+            .get_block_body(last_committed_block)
+            .await?
+            .ok_or(LeaderElectionError::MissingData(format!(
+                "No block body found for block {last_committed_block}"
+            )))?;
+        let block_hash = Block::new(header, body).hash();
+
+        let mut transactions = Vec::new();
+        let latest_block = self.store.get_latest_block_number().await?;
+        for block_number in (last_committed_block + 1)..=latest_block {
+            if let Some(body) = self.store.get_block_body(block_number).await? {
+                transactions.extend(body.transactions);
+            }
+        }
+
+        info!(
+            "Reverting uncommitted state to block {last_committed_block} of batch {last_committed_batch}"
+        );
+        if revert_rollup_store {
+            self.rollup_store
+                .revert_to_batch(last_committed_batch)
+                .await?;
+        }
+        self.store
+            .forkchoice_update(None, last_committed_block, block_hash, None, None)
+            .await?;
+        Ok(transactions)
+    }
+
+    /// Puts the transactions of discarded blocks back in the mempool to be included again.
+    async fn reinject_transactions(&self, transactions: Vec<Transaction>) {
+        if !transactions.is_empty() {
+            info!(
+                "Re-injecting {} transactions of discarded blocks into the mempool",
+                transactions.len()
+            );
+        }
+        for transaction in transactions {
+            let hash = transaction.hash();
+            if let Err(err) = self.blockchain.add_transaction_to_pool(transaction).await {
+                warn!("Failed to re-inject transaction {hash:#x} into the mempool: {err}");
+            }
+        }
+    }
+
+    /// Stops sequencing if our lease expired while the lease store couldn't be reached, as
+    /// another node may have taken over.
+    async fn step_down_if_lease_expired(&mut self) {
+        if self.sequencer_state.status().await != SequencerStatus::Sequencing {
+            return;
+        }
+        if lease_expired(self.lease.as_ref(), now_ms().ok()) {
+            warn!("Sequencer lease expired without being renewed, stepping down as leader");
+            self.sequencer_state
+                .new_status(SequencerStatus::Following)
+                .await;
+            self.blockchain.set_synced();
+        }
+    }
+
+    async fn health(&self) -> CallResponse<Self> {
+        let status = self.sequencer_state.status().await;
+        let role = if status == SequencerStatus::Sequencing {
+            "leader"
+        } else {
+            "standby"
+        };
+        CallResponse::Reply(OutMessage::Health(Box::new(LeaderElectorHealth {
+            node_id: self.node_id.clone(),
+            role: role.to_string(),
+            sequencer_state: status.to_string(),
+            lease_store: self.lease_store.describe(),
+            lease_holder: self.lease.as_ref().map(|lease| lease.holder.clone()),
+            lease_sequencer_address: self.lease.as_ref().map(|lease| lease.sequencer_address),
+            lease_expires_at: self.lease.as_ref().map(|lease| lease.expires_at),
+            lease_duration_ms: self.lease_duration_ms,
+            renew_interval_ms: self.renew_interval_ms,
+        })))
+    }
+}
+
+/// Change of role a node has to make given the current lease
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RoleTransition {
+    TakeOver,
+    StepDown,
+    Keep,
+}
+
+fn role_transition(node_id: &str, is_sequencing: bool, lease: &SequencerLease) -> RoleTransition {
+    match (is_sequencing, lease.holder == node_id) {
+        (false, true) => RoleTransition::TakeOver,
+        (true, false) => RoleTransition::StepDown,
+        _ => RoleTransition::Keep,
+    }
+}
+
+/// A lease is considered expired if it's unknown or the time can't be read
+fn lease_expired(lease: Option<&SequencerLease>, now: Option<u64>) -> bool {
+    match (lease, now) {
+        (Some(lease), Some(now)) => lease.expires_at <= now,
+        _ => true,
+    }
+}
+
+fn now_ms() -> Result<u64, LeaderElectionError> {
+    system_now_ms()
+        .and_then(|now| u64::try_from(now).ok())
+        .ok_or(LeaderElectionError::SystemTimeError)
+}
+
+impl GenServer for LeaderElector {
+    type CallMsg = CallMessage;
+    type CastMsg = InMessage;
+    type OutMsg = OutMessage;
+    type Error = LeaderElectionError;
+
+    async fn handle_cast(
+        &mut self,
+        _message: Self::CastMsg,
+        handle: &GenServerHandle<Self>,
+    ) -> CastResponse {
+        if let Err(err) = self.elect().await {
+            error!("Leader Elector Error: {err}");
+            self.step_down_if_lease_expired().await;
+        }
+        send_after(
+            Duration::from_millis(self.renew_interval_ms),
+            handle.clone(),
+            Self::CastMsg::Elect,
+        );
+        CastResponse::NoReply
+    }
+
+    async fn handle_call(
+        &mut self,
+        message: Self::CallMsg,
+        _handle: &GenServerHandle<Self>,
+    ) -> CallResponse<Self> {
+        match message {
+            CallMessage::Health => self.health().await,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ethrex_storage_rollup::EngineTypeRollup;
+
+    const LEASE_DURATION_MS: u64 = 1000;
+
+    fn lease_stores(dir: &tempfile::TempDir) -> Vec<LeaseStore> {
+        vec![
+            LeaseStore::File(dir.path().join("lease.json")),
+            LeaseStore::RollupStore(
+                StoreRollup::new(dir.path(), EngineTypeRollup::InMemory).unwrap(),
+            ),
+        ]
+    }
+
+    async fn try_acquire(lease_store: &LeaseStore, holder: &str, now: u64) -> SequencerLease {
+        let address = Address::from_low_u64_be(if holder == "a" { 1 } else { 2 });
+        lease_store
+            .try_acquire(holder, address, now, now + LEASE_DURATION_MS)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lease_acquire_renew_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        for lease_store in lease_stores(&dir) {
+            assert!(lease_store.get().await.unwrap().is_none());
+
+            // Acquire a free lease
+            let lease = try_acquire(&lease_store, "a", 0).await;
+            assert_eq!(lease.holder, "a");
+            assert_eq!(lease.expires_at, LEASE_DURATION_MS);
+            assert_eq!(lease.epoch, 1);
+
+            // Renewing extends the lease in the same epoch
+            let lease = try_acquire(&lease_store, "a", 500).await;
+            assert_eq!(lease.holder, "a");
+            assert_eq!(lease.expires_at, 500 + LEASE_DURATION_MS);
+            assert_eq!(lease.epoch, 1);
+
+            // Other nodes can't take it before it expires
+            let lease = try_acquire(&lease_store, "b", 1000).await;
+            assert_eq!(lease.holder, "a");
+            assert_eq!(lease.epoch, 1);
+            assert!(!lease_expired(Some(&lease), Some(1000)));
+
+            // Once expired, it's taken over in a new epoch
+            assert!(lease_expired(Some(&lease), Some(1500)));
+            let lease = try_acquire(&lease_store, "b", 1500).await;
+            assert_eq!(lease.holder, "b");
+            assert_eq!(lease.sequencer_address, Address::from_low_u64_be(2));
+            assert_eq!(lease.epoch, 2);
+            assert_eq!(lease_store.get().await.unwrap(), Some(lease));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_role_transitions() {
+        let dir = tempfile::tempdir().unwrap();
+        for lease_store in lease_stores(&dir) {
+            let lease = try_acquire(&lease_store, "a", 0).await;
+            assert_eq!(
+                role_transition("a", false, &lease),
+                RoleTransition::TakeOver
+            );
+            assert_eq!(role_transition("a", true, &lease), RoleTransition::Keep);
+            assert_eq!(role_transition("b", false, &lease), RoleTransition::Keep);
+
+            // The leader steps down once another node takes the lease over
+            let lease = try_acquire(&lease_store, "b", 2 * LEASE_DURATION_MS).await;
+            assert_eq!(role_transition("a", true, &lease), RoleTransition::StepDown);
+            assert_eq!(
+                role_transition("b", false, &lease),
+                RoleTransition::TakeOver
+            );
+        }
+        assert!(lease_expired(None, Some(0)));
+        assert!(lease_expired(
+            Some(&SequencerLease {
+                holder: "a".to_string(),
+                sequencer_address: Address::zero(),
+                expires_at: u64::MAX,
+                epoch: 1,
+            }),
+            None
+        ));
+    }
+}
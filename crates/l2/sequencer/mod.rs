@@ -11,11 +11,14 @@ use block_producer::BlockProducer;
 use ethrex_blockchain::Blockchain;
 use ethrex_common::types::Genesis;
 use ethrex_l2_common::prover::ProverType;
+use ethrex_l2_rpc::preconfirmations::Preconfirmations;
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
+use forced_transactions::ForcedTransactions;
 use l1_committer::L1Committer;
 use l1_proof_sender::L1ProofSender;
 use l1_watcher::L1Watcher;
+use leader_election::LeaderElector;
 #[cfg(feature = "metrics")]
 use metrics::MetricsGatherer;
 use proof_coordinator::ProofCoordinator;
@@ -28,10 +31,12 @@ use utils::get_needed_proof_types;
 
 mod admin_server;
 pub mod block_producer;
+pub mod forced_transactions;
 pub mod l1_committer;
 pub mod l1_proof_sender;
 pub mod l1_proof_verifier;
 pub mod l1_watcher;
+pub mod leader_election;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod proof_coordinator;
@@ -51,6 +56,7 @@ pub async fn start_l2(
     _l2_url: Url,
     genesis: Genesis,
     checkpoints_dir: PathBuf,
+    preconfirmations: Preconfirmations,
 ) -> Result<
     (
         Option<GenServerHandle<L1Committer>>,
@@ -61,6 +67,9 @@ pub async fn start_l2(
 > {
     let initial_status = if cfg.based.enabled {
         SequencerStatus::default()
+    } else if cfg.high_availability.enabled {
+        // The LeaderElector promotes the node once it holds the sequencer lease
+        SequencerStatus::Following
     } else {
         SequencerStatus::Sequencing
     };
@@ -78,6 +87,7 @@ pub async fn start_l2(
     info!("Starting Sequencer in {initial_status} mode");
 
     let shared_state = SequencerState::from(initial_status);
+    let forced_transactions = ForcedTransactions::new();
 
     let Ok(needed_proof_types) = get_needed_proof_types(
         cfg.eth.rpc_url.clone(),
@@ -105,11 +115,27 @@ pub async fn start_l2(
         ));
     }
 
+    if cfg.proof_coordinator.aggregation_size.is_some()
+        && (cfg.aligned.aligned_mode
+            || needed_proof_types.is_empty()
+            || needed_proof_types.contains(&ProverType::TDX))
+    {
+        error!(
+            "Proof aggregation is only supported when SP1 and/or RISC0 proofs are verified by the OnChainProposer contract. Please unset the flag `--proof-coordinator.aggregation-size` or the `ETHREX_PROOF_COORDINATOR_AGGREGATION_SIZE` environment variable"
+        );
+        return Ok((
+            None,
+            None,
+            Box::pin(async { Ok::<(), errors::SequencerError>(()) }),
+        ));
+    }
+
     let l1_watcher = L1Watcher::spawn(
         store.clone(),
         blockchain.clone(),
         cfg.clone(),
         shared_state.clone(),
+        forced_transactions.clone(),
     )
     .await
     .inspect_err(|err| {
@@ -123,6 +149,7 @@ pub async fn start_l2(
         shared_state.clone(),
         genesis,
         checkpoints_dir.clone(),
+        forced_transactions.clone(),
     )
     .await
     .inspect_err(|err| {
@@ -154,6 +181,8 @@ pub async fn start_l2(
         blockchain.clone(),
         cfg.clone(),
         shared_state.clone(),
+        preconfirmations,
+        forced_transactions,
     )
     .await
     .inspect_err(|err| {
@@ -175,6 +204,23 @@ pub async fn start_l2(
             needed_proof_types.clone(),
         )));
     }
+    let leader_elector = if cfg.high_availability.enabled {
+        LeaderElector::spawn(
+            &cfg,
+            shared_state.clone(),
+            blockchain.clone(),
+            store.clone(),
+            rollup_store.clone(),
+            l1_committer.as_ref().ok().cloned(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!("Error starting Leader Elector: {err}");
+        })
+        .ok()
+    } else {
+        None
+    };
     if cfg.based.enabled {
         let _ = StateUpdater::spawn(
             cfg.clone(),
@@ -223,6 +269,7 @@ pub async fn start_l2(
         l1_watcher.ok(),
         l1_proof_sender.ok(),
         block_producer_handle.clone(),
+        leader_elector,
         #[cfg(feature = "metrics")]
         metrics_gatherer.ok(),
     )
@@ -74,6 +74,38 @@ pub enum ProofData {
     /// 7.
     /// The Server acknowledges the receipt of the proof and updates its state,
     ProofSubmitACK { batch_number: u64 },
+
+    /// 8.
+    /// The Client asks for batch proofs to aggregate, generated by the given prover type.
+    /// The commit hash is used to ensure the client and server are compatible.
+    AggregationRequest {
+        commit_hash: String,
+        prover_type: ProverType,
+    },
+
+    /// 9.
+    /// The Server responds with the proofs of a range of consecutive batches.
+    /// If the AggregationResponse is ProofData::AggregationResponse{None, None, None, None},
+    /// there is no range ready to be aggregated.
+    AggregationResponse {
+        first_batch: Option<u64>,
+        last_batch: Option<u64>,
+        batch_proofs: Option<Vec<BatchProof>>,
+        format: Option<ProofFormat>,
+    },
+
+    /// 10.
+    /// The Client submits the recursive proof aggregating the specified range of batches.
+    AggregatedProofSubmit {
+        first_batch: u64,
+        last_batch: u64,
+        batch_proof: BatchProof,
+    },
+
+    /// 11.
+    /// The Server acknowledges the receipt of the aggregated proof and stores it until
+    /// the whole range is verified on L1.
+    AggregatedProofSubmitACK { first_batch: u64, last_batch: u64 },
 }
 
 impl ProofData {
@@ -129,6 +161,59 @@ impl ProofData {
     pub fn proof_submit_ack(batch_number: u64) -> Self {
         ProofData::ProofSubmitACK { batch_number }
     }
+
+    /// Builder function for creating an AggregationRequest
+    pub fn aggregation_request(commit_hash: String, prover_type: ProverType) -> Self {
+        ProofData::AggregationRequest {
+            commit_hash,
+            prover_type,
+        }
+    }
+
+    /// Builder function for creating an AggregationResponse
+    pub fn aggregation_response(
+        first_batch: u64,
+        last_batch: u64,
+        batch_proofs: Vec<BatchProof>,
+        format: ProofFormat,
+    ) -> Self {
+        ProofData::AggregationResponse {
+            first_batch: Some(first_batch),
+            last_batch: Some(last_batch),
+            batch_proofs: Some(batch_proofs),
+            format: Some(format),
+        }
+    }
+
+    pub fn empty_aggregation_response() -> Self {
+        ProofData::AggregationResponse {
+            first_batch: None,
+            last_batch: None,
+            batch_proofs: None,
+            format: None,
+        }
+    }
+
+    /// Builder function for creating an AggregatedProofSubmit
+    pub fn aggregated_proof_submit(
+        first_batch: u64,
+        last_batch: u64,
+        batch_proof: BatchProof,
+    ) -> Self {
+        ProofData::AggregatedProofSubmit {
+            first_batch,
+            last_batch,
+            batch_proof,
+        }
+    }
+
+    /// Builder function for creating an AggregatedProofSubmitACK
+    pub fn aggregated_proof_submit_ack(first_batch: u64, last_batch: u64) -> Self {
+        ProofData::AggregatedProofSubmitACK {
+            first_batch,
+            last_batch,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -152,6 +237,7 @@ pub struct ProofCoordinator {
     tdx_private_key: Option<SecretKey>,
     needed_proof_types: Vec<ProverType>,
     aligned: bool,
+    aggregation_size: Option<u64>,
     git_commit_hash: String,
     #[cfg(feature = "metrics")]
     request_timestamp: Arc<Mutex<HashMap<u64, SystemTime>>>,
@@ -195,6 +281,7 @@ impl ProofCoordinator {
             needed_proof_types,
             git_commit_hash: get_git_commit_hash(),
             aligned: config.aligned.aligned_mode,
+            aggregation_size: config.proof_coordinator.aggregation_size,
             #[cfg(feature = "metrics")]
             request_timestamp: Arc::new(Mutex::new(HashMap::new())),
             qpl_tool_path: config.proof_coordinator.qpl_tool_path.clone(),
@@ -248,7 +335,10 @@ impl ProofCoordinator {
         commit_hash: String,
     ) -> Result<(), ProofCoordinatorError> {
         info!("BatchRequest received");
-        let batch_to_prove = 1 + self.rollup_store.get_latest_sent_batch_proof().await?;
+        let batch_to_prove = match self.aggregation_size {
+            Some(aggregation_size) => self.next_batch_to_aggregate(aggregation_size).await?,
+            None => 1 + self.rollup_store.get_latest_sent_batch_proof().await?,
+        };
 
         if commit_hash != self.git_commit_hash {
             error!(
@@ -262,18 +352,7 @@ impl ProofCoordinator {
             return Ok(());
         }
 
-        let mut all_proofs_exist = true;
-        for proof_type in &self.needed_proof_types {
-            if self
-                .rollup_store
-                .get_proof_by_batch_and_type(batch_to_prove, *proof_type)
-                .await?
-                .is_none()
-            {
-                all_proofs_exist = false;
-                break;
-            }
-        }
+        let all_proofs_exist = self.has_batch_proofs(batch_to_prove).await?;
 
         let response =
             if all_proofs_exist || !self.rollup_store.contains_batch(&batch_to_prove).await? {
@@ -291,7 +370,9 @@ impl ProofCoordinator {
                     ));
                 };
                 debug!("Sending BatchResponse for block_number: {batch_to_prove}");
-                let format = if self.aligned {
+                // Proofs to be aggregated must be compressed, as they are verified
+                // inside the aggregation program.
+                let format = if self.aligned || self.aggregation_size.is_some() {
                     ProofFormat::Compressed
                 } else {
                     ProofFormat::Groth16
@@ -364,6 +445,187 @@ impl ProofCoordinator {
         Ok(())
     }
 
+    /// Returns whether every needed proof type was received for the given batch.
+    async fn has_batch_proofs(&self, batch_number: u64) -> Result<bool, ProofCoordinatorError> {
+        for proof_type in &self.needed_proof_types {
+            if self
+                .rollup_store
+                .get_proof_by_batch_and_type(batch_number, *proof_type)
+                .await?
+                .is_none()
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns whether every needed proof type was aggregated for the given range.
+    async fn has_aggregated_proofs(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+    ) -> Result<bool, ProofCoordinatorError> {
+        for proof_type in &self.needed_proof_types {
+            if self
+                .rollup_store
+                .get_aggregated_proof(first_batch, last_batch, *proof_type)
+                .await?
+                .is_none()
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the next range of batches to aggregate. Ranges that have already been
+    /// aggregated but not yet sent to L1 are skipped, so that provers can move on to
+    /// the following batches while the proof sender catches up.
+    async fn next_range_to_aggregate(
+        &self,
+        aggregation_size: u64,
+    ) -> Result<(u64, u64), ProofCoordinatorError> {
+        let mut first_batch = 1 + self.rollup_store.get_latest_sent_batch_proof().await?;
+        // Ranges are ordered by their first batch
+        for (range_first_batch, range_last_batch) in
+            self.rollup_store.get_aggregated_ranges().await?
+        {
+            if range_first_batch == first_batch
+                && self
+                    .has_aggregated_proofs(range_first_batch, range_last_batch)
+                    .await?
+            {
+                first_batch = range_last_batch + 1;
+            }
+        }
+        Ok((first_batch, first_batch + aggregation_size - 1))
+    }
+
+    /// Returns the first batch of the next range to aggregate that is missing a proof,
+    /// or its last batch if every batch in the range was already proven.
+    async fn next_batch_to_aggregate(
+        &self,
+        aggregation_size: u64,
+    ) -> Result<u64, ProofCoordinatorError> {
+        let (first_batch, last_batch) = self.next_range_to_aggregate(aggregation_size).await?;
+        for batch_number in first_batch..last_batch {
+            if !self.has_batch_proofs(batch_number).await? {
+                return Ok(batch_number);
+            }
+        }
+        Ok(last_batch)
+    }
+
+    async fn handle_aggregation_request(
+        &mut self,
+        stream: &mut TcpStream,
+        commit_hash: String,
+        prover_type: ProverType,
+    ) -> Result<(), ProofCoordinatorError> {
+        info!("AggregationRequest received for {prover_type}");
+
+        if commit_hash != self.git_commit_hash {
+            error!(
+                "Code version mismatch: expected {}, got {}",
+                self.git_commit_hash, commit_hash
+            );
+
+            let response = ProofData::invalid_code_version(self.git_commit_hash.clone());
+            send_response(stream, &response).await?;
+            info!("InvalidCodeVersion sent");
+            return Ok(());
+        }
+
+        let response = self.aggregation_response(prover_type).await?;
+        send_response(stream, &response).await?;
+        info!("AggregationResponse sent");
+        Ok(())
+    }
+
+    async fn aggregation_response(
+        &self,
+        prover_type: ProverType,
+    ) -> Result<ProofData, ProofCoordinatorError> {
+        let Some(aggregation_size) = self.aggregation_size else {
+            debug!("Proof aggregation is disabled, sending empty AggregationResponse");
+            return Ok(ProofData::empty_aggregation_response());
+        };
+        if !self.needed_proof_types.contains(&prover_type) {
+            debug!("{prover_type} proofs are not needed, sending empty AggregationResponse");
+            return Ok(ProofData::empty_aggregation_response());
+        }
+
+        let (first_batch, last_batch) = self.next_range_to_aggregate(aggregation_size).await?;
+        if self
+            .rollup_store
+            .get_aggregated_proof(first_batch, last_batch, prover_type)
+            .await?
+            .is_some()
+        {
+            debug!(
+                "Batches {first_batch} to {last_batch} were already aggregated for {prover_type}"
+            );
+            return Ok(ProofData::empty_aggregation_response());
+        }
+
+        let mut batch_proofs = Vec::new();
+        for batch_number in first_batch..=last_batch {
+            let Some(batch_proof) = self
+                .rollup_store
+                .get_proof_by_batch_and_type(batch_number, prover_type)
+                .await?
+            else {
+                debug!(
+                    "Missing {prover_type} proof for batch {batch_number}, sending empty AggregationResponse"
+                );
+                return Ok(ProofData::empty_aggregation_response());
+            };
+            batch_proofs.push(batch_proof);
+        }
+
+        debug!("Sending AggregationResponse for batches {first_batch} to {last_batch}");
+        Ok(ProofData::aggregation_response(
+            first_batch,
+            last_batch,
+            batch_proofs,
+            ProofFormat::Groth16,
+        ))
+    }
+
+    async fn handle_aggregated_submit(
+        &mut self,
+        stream: &mut TcpStream,
+        first_batch: u64,
+        last_batch: u64,
+        batch_proof: BatchProof,
+    ) -> Result<(), ProofCoordinatorError> {
+        info!("AggregatedProofSubmit received for batches {first_batch} to {last_batch}");
+
+        let prover_type = batch_proof.prover_type();
+        if self
+            .rollup_store
+            .get_aggregated_proof(first_batch, last_batch, prover_type)
+            .await?
+            .is_some()
+        {
+            info!(
+                ?first_batch,
+                ?last_batch,
+                ?prover_type,
+                "An aggregated proof was received for a range and type that is already stored"
+            );
+        } else {
+            self.rollup_store
+                .store_aggregated_proof(first_batch, last_batch, prover_type, batch_proof)
+                .await?;
+        }
+        let response = ProofData::aggregated_proof_submit_ack(first_batch, last_batch);
+        send_response(stream, &response).await?;
+        info!("AggregatedProofSubmit ACK sent");
+        Ok(())
+    }
+
     async fn handle_setup(
         &mut self,
         stream: &mut TcpStream,
@@ -492,6 +754,31 @@ impl ConnectionHandler {
                         error!("Failed to handle ProofSubmit: {e}");
                     }
                 }
+                Ok(ProofData::AggregationRequest {
+                    commit_hash,
+                    prover_type,
+                }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_aggregation_request(&mut stream, commit_hash, prover_type)
+                        .await
+                    {
+                        error!("Failed to handle AggregationRequest: {e}");
+                    }
+                }
+                Ok(ProofData::AggregatedProofSubmit {
+                    first_batch,
+                    last_batch,
+                    batch_proof,
+                }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_aggregated_submit(&mut stream, first_batch, last_batch, batch_proof)
+                        .await
+                    {
+                        error!("Failed to handle AggregatedProofSubmit: {e}");
+                    }
+                }
                 Ok(ProofData::ProverSetup {
                     prover_type,
                     payload,
@@ -567,3 +854,140 @@ async fn send_response(
         .map_err(ProofCoordinatorError::ConnectionError)?;
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ethrex_l2_common::prover::ProofCalldata;
+    use ethrex_storage_rollup::EngineTypeRollup;
+    use reqwest::Url;
+    use std::path::Path;
+
+    const AGGREGATION_SIZE: u64 = 4;
+    const NEEDED_PROOF_TYPES: [ProverType; 2] = [ProverType::SP1, ProverType::RISC0];
+
+    fn proof_coordinator() -> ProofCoordinator {
+        ProofCoordinator {
+            listen_ip: IpAddr::from([127, 0, 0, 1]),
+            port: 3900,
+            eth_client: EthClient::new(Url::parse("http://localhost:8545").unwrap()).unwrap(),
+            on_chain_proposer_address: Address::zero(),
+            rollup_store: StoreRollup::new(Path::new(""), EngineTypeRollup::InMemory).unwrap(),
+            rpc_url: "http://localhost:8545".to_string(),
+            tdx_private_key: None,
+            needed_proof_types: NEEDED_PROOF_TYPES.to_vec(),
+            aligned: false,
+            aggregation_size: Some(AGGREGATION_SIZE),
+            git_commit_hash: get_git_commit_hash(),
+            #[cfg(feature = "metrics")]
+            request_timestamp: Arc::new(Mutex::new(HashMap::new())),
+            qpl_tool_path: None,
+        }
+    }
+
+    fn proof(prover_type: ProverType) -> BatchProof {
+        BatchProof::ProofCalldata(ProofCalldata {
+            prover_type,
+            calldata: vec![],
+        })
+    }
+
+    async fn store_aggregated_proofs(
+        proof_coordinator: &ProofCoordinator,
+        first_batch: u64,
+        last_batch: u64,
+        proof_types: &[ProverType],
+    ) {
+        for proof_type in proof_types {
+            proof_coordinator
+                .rollup_store
+                .store_aggregated_proof(first_batch, last_batch, *proof_type, proof(*proof_type))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn next_range_starts_after_the_latest_sent_batch_proof() {
+        let proof_coordinator = proof_coordinator();
+        assert_eq!(
+            proof_coordinator
+                .next_range_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            (1, 4)
+        );
+
+        proof_coordinator
+            .rollup_store
+            .set_latest_sent_batch_proof(6)
+            .await
+            .unwrap();
+        assert_eq!(
+            proof_coordinator
+                .next_range_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            (7, 10)
+        );
+    }
+
+    #[tokio::test]
+    async fn next_range_skips_pending_aggregated_ranges() {
+        let proof_coordinator = proof_coordinator();
+        store_aggregated_proofs(&proof_coordinator, 1, 4, &NEEDED_PROOF_TYPES).await;
+        store_aggregated_proofs(&proof_coordinator, 5, 8, &NEEDED_PROOF_TYPES).await;
+        assert_eq!(
+            proof_coordinator
+                .next_range_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            (9, 12)
+        );
+
+        // Ranges missing a proof type still have to be aggregated
+        store_aggregated_proofs(&proof_coordinator, 9, 12, &[ProverType::SP1]).await;
+        assert_eq!(
+            proof_coordinator
+                .next_range_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            (9, 12)
+        );
+    }
+
+    #[tokio::test]
+    async fn next_range_ignores_ranges_not_following_the_latest_sent_batch_proof() {
+        let proof_coordinator = proof_coordinator();
+        store_aggregated_proofs(&proof_coordinator, 5, 8, &NEEDED_PROOF_TYPES).await;
+        assert_eq!(
+            proof_coordinator
+                .next_range_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            (1, 4)
+        );
+    }
+
+    #[tokio::test]
+    async fn next_batch_to_aggregate_is_the_first_one_missing_a_proof() {
+        let proof_coordinator = proof_coordinator();
+        for batch_number in 1..=2 {
+            for proof_type in NEEDED_PROOF_TYPES {
+                proof_coordinator
+                    .rollup_store
+                    .store_proof_by_batch_and_type(batch_number, proof_type, proof(proof_type))
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(
+            proof_coordinator
+                .next_batch_to_aggregate(AGGREGATION_SIZE)
+                .await
+                .unwrap(),
+            3
+        );
+    }
+}
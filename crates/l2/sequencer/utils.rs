@@ -1,7 +1,7 @@
 use aligned_sdk::common::types::Network;
-use ethrex_common::types::Block;
 use ethrex_common::types::batch::Batch;
 use ethrex_common::types::fee_config::FeeConfig;
+use ethrex_common::types::{Block, BlockNumber};
 use ethrex_common::utils::keccak;
 use ethrex_common::{Address, H256, types::TxType};
 use ethrex_l2_common::prover::ProverType;
@@ -143,6 +143,35 @@ where
     Ok(is_up_to_date)
 }
 
+/// Records `lead_sequencer` as in charge of the blocks after the last batch committed on L1, as
+/// the lead sequencer only changes when a batch is committed. Nothing is recorded until the node
+/// has that batch, since the blocks before it are still validated against the previous lead
+/// sequencer. Returns the first block of the lead sequencer if it was recorded.
+pub async fn track_lead_sequencer(
+    rollup_store: &StoreRollup,
+    last_committed_batch: u64,
+    lead_sequencer: Address,
+) -> Result<Option<BlockNumber>, RollupStoreError> {
+    let Some(first_block) = rollup_store
+        .get_block_numbers_by_batch(last_committed_batch)
+        .await?
+        .and_then(|blocks| blocks.last().map(|last_block| last_block + 1))
+    else {
+        return Ok(None);
+    };
+    if rollup_store
+        .get_lead_sequencer_by_block(first_block)
+        .await?
+        == Some(lead_sequencer)
+    {
+        return Ok(None);
+    }
+    rollup_store
+        .store_lead_sequencer_from_block(first_block, lead_sequencer)
+        .await?;
+    Ok(Some(first_block))
+}
+
 pub async fn fetch_blocks_with_respective_fee_configs<E>(
     batch: &Batch,
     store: &Store,
@@ -192,3 +221,77 @@ pub fn get_git_commit_hash() -> String {
 pub fn batch_checkpoint_name(batch_number: u64) -> String {
     format!("checkpoint_batch_{batch_number}")
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ethrex_storage_rollup::EngineTypeRollup;
+    use std::path::Path;
+
+    async fn seal_batch(
+        rollup_store: &StoreRollup,
+        number: u64,
+        first_block: u64,
+        last_block: u64,
+    ) {
+        rollup_store
+            .seal_batch(Batch {
+                number,
+                first_block,
+                last_block,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_track_lead_sequencer_across_rotation() {
+        let rollup_store = StoreRollup::new(Path::new(""), EngineTypeRollup::InMemory).unwrap();
+        rollup_store.init().await.unwrap();
+        let first = Address::from_low_u64_be(1);
+        let second = Address::from_low_u64_be(2);
+
+        // Nothing committed yet, the lead sequencer starts after the genesis batch
+        assert_eq!(
+            track_lead_sequencer(&rollup_store, 0, first).await.unwrap(),
+            Some(1)
+        );
+        seal_batch(&rollup_store, 1, 1, 10).await;
+        assert_eq!(
+            track_lead_sequencer(&rollup_store, 1, first).await.unwrap(),
+            None
+        );
+
+        // The lead sequencer rotates once batch 2 is committed, but the node doesn't have it
+        // yet, so the blocks it's still missing are validated against the previous one
+        assert_eq!(
+            track_lead_sequencer(&rollup_store, 2, second)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            rollup_store.get_lead_sequencer_by_block(15).await.unwrap(),
+            Some(first)
+        );
+
+        seal_batch(&rollup_store, 2, 11, 20).await;
+        assert_eq!(
+            track_lead_sequencer(&rollup_store, 2, second)
+                .await
+                .unwrap(),
+            Some(21)
+        );
+        for (block_number, lead_sequencer) in [(1, first), (15, first), (20, first), (21, second)] {
+            assert_eq!(
+                rollup_store
+                    .get_lead_sequencer_by_block(block_number)
+                    .await
+                    .unwrap(),
+                Some(lead_sequencer)
+            );
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use std::fmt::Debug;
 
 use ethrex_common::{
-    H256,
+    Address, H256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch, fee_config::FeeConfig},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
@@ -152,6 +152,34 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         proof_type: ProverType,
     ) -> Result<(), RollupStoreError>;
 
+    /// Stores a proof aggregating the batches in the range `first_batch..=last_batch`.
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Retrieves the proof aggregating the batches in the range `first_batch..=last_batch`.
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<Option<BatchProof>, RollupStoreError>;
+
+    /// Returns the `(first_batch, last_batch)` ranges with at least one aggregated proof
+    /// stored, ordered by first batch.
+    async fn get_aggregated_ranges(&self) -> Result<Vec<(u64, u64)>, RollupStoreError>;
+
+    /// Deletes every aggregated proof of the range `first_batch..=last_batch`.
+    async fn delete_aggregated_proofs(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+    ) -> Result<(), RollupStoreError>;
+
     async fn revert_to_batch(&self, batch_number: u64) -> Result<(), RollupStoreError>;
 
     async fn store_prover_input_by_batch_and_version(
@@ -177,4 +205,53 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         &self,
         block_number: BlockNumber,
     ) -> Result<Option<FeeConfig>, RollupStoreError>;
+
+    /// Stores the lead sequencer in charge of the blocks starting from the given block number,
+    /// replacing the ones stored from that block onwards.
+    async fn store_lead_sequencer_from_block(
+        &self,
+        block_number: BlockNumber,
+        lead_sequencer: Address,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Retrieves the lead sequencer in charge of the given block number, i.e. the one stored
+    /// for the greatest block number lower or equal to it.
+    async fn get_lead_sequencer_by_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<Address>, RollupStoreError>;
+
+    /// Takes or renews the sequencer lease for `holder` until `expires_at`. It only succeeds if
+    /// the lease is free, already held by `holder` or expired at `now`, and its epoch is
+    /// increased whenever it changes holder. Timestamps are unix milliseconds. Returns the
+    /// lease as it is after the attempt.
+    /// This write is never fenced, see [`StoreEngineRollup::set_sequencer_lease_fence`].
+    async fn try_acquire_sequencer_lease(
+        &self,
+        holder: &str,
+        sequencer_address: Address,
+        now: u64,
+        expires_at: u64,
+    ) -> Result<SequencerLease, RollupStoreError>;
+
+    /// Returns the current sequencer lease, if it was ever taken.
+    async fn get_sequencer_lease(&self) -> Result<Option<SequencerLease>, RollupStoreError>;
+
+    /// Makes every following write fail unless the sequencer lease is still at `epoch`, checked
+    /// atomically with the write, so a node that lost the lease (e.g. because of clock skew)
+    /// can't keep writing after another one took over. `None` disables the check.
+    async fn set_sequencer_lease_fence(&self, epoch: Option<u64>) -> Result<(), RollupStoreError>;
+}
+
+/// Lease deciding which node of an active/standby sequencer deployment is the leader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencerLease {
+    /// Identifier of the node holding the lease.
+    pub holder: String,
+    /// Address signing the blocks produced by the holder.
+    pub sequencer_address: Address,
+    /// Unix timestamp in milliseconds after which the lease can be taken by another node.
+    pub expires_at: u64,
+    /// Increased every time the lease changes holder, used as a fencing token for writes.
+    pub epoch: u64,
 }
@@ -11,8 +11,15 @@ pub enum RollupStoreError {
     #[cfg(feature = "sql")]
     #[error("SQL Query error: unexpected type found while querying DB")]
     SQLInvalidTypeError,
+    #[cfg(feature = "postgres")]
+    #[error("Postgres error: {0}")]
+    PostgresError(#[from] tokio_postgres::Error),
     #[error("{0}")]
     Custom(String),
     #[error("Bincode (de)serialization error: {0}")]
     BincodeError(#[from] bincode::Error),
+    #[error(
+        "Write rejected: the sequencer lease is at epoch {epoch}, but writes are fenced to epoch {fence}"
+    )]
+    StaleSequencerLease { fence: u64, epoch: u64 },
 }
@@ -3,5 +3,6 @@ mod error;
 mod store;
 mod store_db;
 
+pub use api::SequencerLease;
 pub use error::RollupStoreError;
 pub use store::{EngineType as EngineTypeRollup, Store as StoreRollup};
@@ -1,17 +1,17 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     sync::{Arc, Mutex, MutexGuard},
 };
 
 use crate::error::RollupStoreError;
 use ethrex_common::{
-    H256,
+    Address, H256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch, fee_config::FeeConfig},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
 
-use crate::api::StoreEngineRollup;
+use crate::api::{SequencerLease, StoreEngineRollup};
 
 #[derive(Default, Clone)]
 pub struct Store(Arc<Mutex<StoreInner>>);
@@ -42,6 +42,8 @@ struct StoreInner {
     account_updates_by_block_number: HashMap<BlockNumber, Vec<AccountUpdate>>,
     /// Map of (ProverType, batch_number) to batch proof data
     batch_proofs: HashMap<(ProverType, u64), BatchProof>,
+    /// Map of (first_batch, last_batch) to the aggregated proofs of that range
+    aggregated_proofs: BTreeMap<(u64, u64), HashMap<ProverType, BatchProof>>,
     /// Map of batch number to commit transaction hash
     commit_txs: HashMap<u64, H256>,
     /// Map of batch number to verify transaction hash
@@ -50,6 +52,10 @@ struct StoreInner {
     batch_prover_input: HashMap<(u64, String), Vec<u8>>,
     /// Map of block number to FeeConfig
     fee_config_by_block: HashMap<BlockNumber, FeeConfig>,
+    /// Map of first block number to the lead sequencer in charge from it
+    lead_sequencers_by_block: BTreeMap<BlockNumber, Address>,
+    /// Lease of the active sequencer
+    sequencer_lease: Option<SequencerLease>,
 }
 
 impl Store {
@@ -291,6 +297,9 @@ impl StoreEngineRollup for Store {
         store
             .batch_prover_input
             .retain(|(batch, _), _| *batch <= batch_number);
+        store
+            .aggregated_proofs
+            .retain(|(_, last_batch), _| *last_batch <= batch_number);
         Ok(())
     }
 
@@ -354,6 +363,50 @@ impl StoreEngineRollup for Store {
         Ok(())
     }
 
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .aggregated_proofs
+            .entry((first_batch, last_batch))
+            .or_default()
+            .insert(proof_type, proof);
+        Ok(())
+    }
+
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<Option<BatchProof>, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .aggregated_proofs
+            .get(&(first_batch, last_batch))
+            .and_then(|proofs| proofs.get(&proof_type))
+            .cloned())
+    }
+
+    async fn get_aggregated_ranges(&self) -> Result<Vec<(u64, u64)>, RollupStoreError> {
+        Ok(self.inner()?.aggregated_proofs.keys().copied().collect())
+    }
+
+    async fn delete_aggregated_proofs(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .aggregated_proofs
+            .remove(&(first_batch, last_batch));
+        Ok(())
+    }
+
     async fn get_last_batch_number(&self) -> Result<Option<u64>, RollupStoreError> {
         Ok(self.inner()?.state_roots.keys().max().cloned())
     }
@@ -420,6 +473,65 @@ impl StoreEngineRollup for Store {
             .get(&block_number)
             .cloned())
     }
+
+    async fn store_lead_sequencer_from_block(
+        &self,
+        block_number: BlockNumber,
+        lead_sequencer: Address,
+    ) -> Result<(), RollupStoreError> {
+        let mut store = self.inner()?;
+        store.lead_sequencers_by_block.split_off(&block_number);
+        store
+            .lead_sequencers_by_block
+            .insert(block_number, lead_sequencer);
+        Ok(())
+    }
+
+    async fn get_lead_sequencer_by_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<Address>, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .lead_sequencers_by_block
+            .range(..=block_number)
+            .next_back()
+            .map(|(_, lead_sequencer)| *lead_sequencer))
+    }
+
+    async fn try_acquire_sequencer_lease(
+        &self,
+        holder: &str,
+        sequencer_address: Address,
+        now: u64,
+        expires_at: u64,
+    ) -> Result<SequencerLease, RollupStoreError> {
+        let mut inner = self.inner()?;
+        let lease = match inner.sequencer_lease.take() {
+            Some(lease) if lease.holder != holder && lease.expires_at > now => lease,
+            previous => SequencerLease {
+                holder: holder.to_owned(),
+                sequencer_address,
+                expires_at,
+                epoch: match previous {
+                    Some(previous) if previous.holder == holder => previous.epoch,
+                    Some(previous) => previous.epoch + 1,
+                    None => 1,
+                },
+            },
+        };
+        inner.sequencer_lease = Some(lease.clone());
+        Ok(lease)
+    }
+
+    async fn get_sequencer_lease(&self) -> Result<Option<SequencerLease>, RollupStoreError> {
+        Ok(self.inner()?.sequencer_lease.clone())
+    }
+
+    async fn set_sequencer_lease_fence(&self, _epoch: Option<u64>) -> Result<(), RollupStoreError> {
+        // The in-memory store can't be shared between nodes, so there are no writes to fence
+        Ok(())
+    }
 }
 
 impl Debug for Store {
@@ -0,0 +1,107 @@
+//! Versioned schema migrations shared by the SQL backends.
+//!
+//! Each backend keeps a `schema_migrations` table with the versions already applied and,
+//! on start, transactionally applies every migration with a greater version in order.
+//! Migrations must never be edited once released: schema changes (e.g. a new column for
+//! `ProofData` fields) go in a new migration appended at the end of [`MIGRATIONS`].
+use crate::RollupStoreError;
+
+pub(crate) struct Migration {
+    pub version: u64,
+    pub description: &'static str,
+    /// Statements for the embedded libsql backend.
+    #[cfg_attr(not(feature = "sql"), allow(dead_code))]
+    pub sqlite: &'static [&'static str],
+    /// Statements for the Postgres backend.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub postgres: &'static [&'static str],
+}
+
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    // Databases created before versioned migrations already have (some of) these tables,
+    // so every statement must be idempotent.
+    Migration {
+        version: 1,
+        description: "initial schema",
+        sqlite: &[
+            "CREATE TABLE IF NOT EXISTS blocks (block_number INT PRIMARY KEY, batch INT)",
+            "CREATE TABLE IF NOT EXISTS messages (batch INT, idx INT, message_hash BLOB, PRIMARY KEY (batch, idx))",
+            "CREATE TABLE IF NOT EXISTS privileged_transactions (batch INT PRIMARY KEY, transactions_hash BLOB)",
+            "CREATE TABLE IF NOT EXISTS state_roots (batch INT PRIMARY KEY, state_root BLOB)",
+            "CREATE TABLE IF NOT EXISTS blob_bundles (batch INT, idx INT, blob_bundle BLOB, PRIMARY KEY (batch, idx))",
+            "CREATE TABLE IF NOT EXISTS account_updates (block_number INT PRIMARY KEY, updates BLOB)",
+            "CREATE TABLE IF NOT EXISTS commit_txs (batch INT PRIMARY KEY, commit_tx BLOB)",
+            "CREATE TABLE IF NOT EXISTS verify_txs (batch INT PRIMARY KEY, verify_tx BLOB)",
+            "CREATE TABLE IF NOT EXISTS operation_count (_id INT PRIMARY KEY, transactions INT, privileged_transactions INT, messages INT)",
+            "INSERT OR IGNORE INTO operation_count VALUES (0, 0, 0, 0)",
+            "CREATE TABLE IF NOT EXISTS latest_sent (_id INT PRIMARY KEY, batch INT)",
+            "INSERT OR IGNORE INTO latest_sent VALUES (0, 0)",
+            "CREATE TABLE IF NOT EXISTS batch_proofs (batch INT, prover_type INT, proof BLOB, PRIMARY KEY (batch, prover_type))",
+            "CREATE TABLE IF NOT EXISTS block_signatures (block_hash BLOB PRIMARY KEY, signature BLOB)",
+            "CREATE TABLE IF NOT EXISTS batch_signatures (batch INT PRIMARY KEY, signature BLOB)",
+            "CREATE TABLE IF NOT EXISTS batch_prover_input (batch INT, prover_version TEXT, prover_input BLOB, PRIMARY KEY (batch, prover_version))",
+            "CREATE TABLE IF NOT EXISTS fee_config (block_number INT PRIMARY KEY, fee_config BLOB)",
+            "CREATE TABLE IF NOT EXISTS lead_sequencers (block_number INT PRIMARY KEY, lead_sequencer BLOB)",
+            "CREATE TABLE IF NOT EXISTS aggregated_proofs (first_batch INT, last_batch INT, prover_type INT, proof BLOB, PRIMARY KEY (first_batch, last_batch, prover_type))",
+        ],
+        postgres: &[
+            "CREATE TABLE IF NOT EXISTS blocks (block_number BIGINT PRIMARY KEY, batch BIGINT)",
+            "CREATE TABLE IF NOT EXISTS messages (batch BIGINT, idx BIGINT, message_hash BYTEA, PRIMARY KEY (batch, idx))",
+            "CREATE TABLE IF NOT EXISTS privileged_transactions (batch BIGINT PRIMARY KEY, transactions_hash BYTEA)",
+            "CREATE TABLE IF NOT EXISTS state_roots (batch BIGINT PRIMARY KEY, state_root BYTEA)",
+            "CREATE TABLE IF NOT EXISTS blob_bundles (batch BIGINT, idx BIGINT, blob_bundle BYTEA, PRIMARY KEY (batch, idx))",
+            "CREATE TABLE IF NOT EXISTS account_updates (block_number BIGINT PRIMARY KEY, updates BYTEA)",
+            "CREATE TABLE IF NOT EXISTS commit_txs (batch BIGINT PRIMARY KEY, commit_tx BYTEA)",
+            "CREATE TABLE IF NOT EXISTS verify_txs (batch BIGINT PRIMARY KEY, verify_tx BYTEA)",
+            "CREATE TABLE IF NOT EXISTS operation_count (_id BIGINT PRIMARY KEY, transactions BIGINT, privileged_transactions BIGINT, messages BIGINT)",
+            "INSERT INTO operation_count VALUES (0, 0, 0, 0) ON CONFLICT DO NOTHING",
+            "CREATE TABLE IF NOT EXISTS latest_sent (_id BIGINT PRIMARY KEY, batch BIGINT)",
+            "INSERT INTO latest_sent VALUES (0, 0) ON CONFLICT DO NOTHING",
+            "CREATE TABLE IF NOT EXISTS batch_proofs (batch BIGINT, prover_type BIGINT, proof BYTEA, PRIMARY KEY (batch, prover_type))",
+            "CREATE TABLE IF NOT EXISTS block_signatures (block_hash BYTEA PRIMARY KEY, signature BYTEA)",
+            "CREATE TABLE IF NOT EXISTS batch_signatures (batch BIGINT PRIMARY KEY, signature BYTEA)",
+            "CREATE TABLE IF NOT EXISTS batch_prover_input (batch BIGINT, prover_version TEXT, prover_input BYTEA, PRIMARY KEY (batch, prover_version))",
+            "CREATE TABLE IF NOT EXISTS fee_config (block_number BIGINT PRIMARY KEY, fee_config BYTEA)",
+            "CREATE TABLE IF NOT EXISTS lead_sequencers (block_number BIGINT PRIMARY KEY, lead_sequencer BYTEA)",
+            "CREATE TABLE IF NOT EXISTS aggregated_proofs (first_batch BIGINT, last_batch BIGINT, prover_type BIGINT, proof BYTEA, PRIMARY KEY (first_batch, last_batch, prover_type))",
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "index blocks by batch",
+        sqlite: &["CREATE INDEX IF NOT EXISTS blocks_batch_idx ON blocks (batch)"],
+        postgres: &["CREATE INDEX IF NOT EXISTS blocks_batch_idx ON blocks (batch)"],
+    },
+    Migration {
+        version: 3,
+        description: "sequencer lease",
+        sqlite: &[
+            "CREATE TABLE IF NOT EXISTS sequencer_lease (_id INT PRIMARY KEY, holder TEXT, sequencer_address BLOB, expires_at INT, epoch INT NOT NULL DEFAULT 0)",
+        ],
+        postgres: &[
+            "CREATE TABLE IF NOT EXISTS sequencer_lease (_id BIGINT PRIMARY KEY, holder TEXT, sequencer_address BYTEA, expires_at BIGINT, epoch BIGINT NOT NULL DEFAULT 0)",
+        ],
+    },
+];
+
+/// Returns the version the schema is at after applying every known migration.
+pub(crate) fn latest_version() -> u64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or_default()
+}
+
+/// Returns the migrations that still need to be applied to a database at `current_version`.
+/// Fails if the database was migrated by a newer version of ethrex, as we can't tell whether
+/// the schema is still compatible.
+pub(crate) fn pending_migrations(
+    current_version: u64,
+) -> Result<impl Iterator<Item = &'static Migration>, RollupStoreError> {
+    if current_version > latest_version() {
+        return Err(RollupStoreError::Custom(format!(
+            "rollup store schema is at version {current_version}, but this binary only knows up to version {}. Refusing to start with a newer schema",
+            latest_version()
+        )));
+    }
+    Ok(MIGRATIONS
+        .iter()
+        .filter(move |migration| migration.version > current_version))
+}
@@ -1,3 +1,7 @@
 pub mod in_memory;
+#[cfg(any(feature = "sql", feature = "postgres"))]
+mod migrations;
+#[cfg(feature = "postgres")]
+pub mod postgres;
 #[cfg(feature = "sql")]
 pub mod sql;
@@ -1,9 +1,13 @@
 use std::{fmt::Debug, path::Path, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
-use crate::{RollupStoreError, api::StoreEngineRollup};
+use crate::{
+    RollupStoreError,
+    api::{SequencerLease, StoreEngineRollup},
+    store_db::migrations::pending_migrations,
+};
 use ethrex_common::{
-    H256,
+    Address, H256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch, fee_config::FeeConfig},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
@@ -12,14 +16,17 @@ use libsql::{
     Builder, Connection, Row, Rows, Transaction, Value,
     params::{IntoParams, Params},
 };
+use tracing::info;
 
 /// ### SQLStore
 /// - `read_conn`: a connection to the database to be used for read only statements
 /// - `write_conn`: a connection to the database to be used for writing, protected by a Mutex to enforce a maximum of 1 writer.
 ///   If writes are done using the read only connection `SQLite failure: database is locked` problems will arise
+/// - `fence`: the sequencer lease epoch writes are fenced to, if any
 pub struct SQLStore {
     read_conn: Connection,
     write_conn: Arc<Mutex<Connection>>,
+    fence: Mutex<Option<u64>>,
 }
 
 impl Debug for SQLStore {
@@ -28,26 +35,6 @@ impl Debug for SQLStore {
     }
 }
 
-const DB_SCHEMA: [&str; 17] = [
-    "CREATE TABLE blocks (block_number INT PRIMARY KEY, batch INT)",
-    "CREATE TABLE messages (batch INT, idx INT, message_hash BLOB, PRIMARY KEY (batch, idx))",
-    "CREATE TABLE privileged_transactions (batch INT PRIMARY KEY, transactions_hash BLOB)",
-    "CREATE TABLE state_roots (batch INT PRIMARY KEY, state_root BLOB)",
-    "CREATE TABLE blob_bundles (batch INT, idx INT, blob_bundle BLOB, PRIMARY KEY (batch, idx))",
-    "CREATE TABLE account_updates (block_number INT PRIMARY KEY, updates BLOB)",
-    "CREATE TABLE commit_txs (batch INT PRIMARY KEY, commit_tx BLOB)",
-    "CREATE TABLE verify_txs (batch INT PRIMARY KEY, verify_tx BLOB)",
-    "CREATE TABLE operation_count (_id INT PRIMARY KEY, transactions INT, privileged_transactions INT, messages INT)",
-    "INSERT INTO operation_count VALUES (0, 0, 0, 0)",
-    "CREATE TABLE latest_sent (_id INT PRIMARY KEY, batch INT)",
-    "INSERT INTO latest_sent VALUES (0, 0)",
-    "CREATE TABLE batch_proofs (batch INT, prover_type INT, proof BLOB, PRIMARY KEY (batch, prover_type))",
-    "CREATE TABLE block_signatures (block_hash BLOB PRIMARY KEY, signature BLOB)",
-    "CREATE TABLE batch_signatures (batch INT PRIMARY KEY, signature BLOB)",
-    "CREATE TABLE batch_prover_input (batch INT, prover_version TEXT, prover_input BLOB, PRIMARY KEY (batch, prover_version))",
-    "CREATE TABLE fee_config (block_number INT PRIMARY KEY, fee_config BLOB)",
-];
-
 impl SQLStore {
     pub fn new(path: impl AsRef<Path>) -> Result<Self, RollupStoreError> {
         futures::executor::block_on(async {
@@ -60,6 +47,7 @@ impl SQLStore {
             let store = SQLStore {
                 read_conn: db.connect()?,
                 write_conn: Arc::new(Mutex::new(write_conn)),
+                fence: Mutex::new(None),
             };
             store.init_db().await?;
             Ok(store)
@@ -68,7 +56,28 @@ impl SQLStore {
 
     async fn execute<T: IntoParams>(&self, sql: &str, params: T) -> Result<(), RollupStoreError> {
         let conn = self.write_conn.lock().await;
-        conn.execute(sql, params).await?;
+        let tx = conn.transaction().await?;
+        self.check_fence(&tx).await?;
+        tx.execute(sql, params).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Fails if writes are fenced to a sequencer lease epoch other than the current one
+    async fn check_fence(&self, tx: &Transaction) -> Result<(), RollupStoreError> {
+        let Some(fence) = *self.fence.lock().await else {
+            return Ok(());
+        };
+        let mut rows = tx
+            .query("SELECT epoch FROM sequencer_lease WHERE _id = 0", ())
+            .await?;
+        let epoch = match rows.next().await? {
+            Some(row) => read_from_row_int(&row, 0)?,
+            None => 0,
+        };
+        if epoch != fence {
+            return Err(RollupStoreError::StaleSequencerLease { fence, epoch });
+        }
         Ok(())
     }
 
@@ -82,21 +91,47 @@ impl SQLStore {
         // https://sqlite.org/wal.html#concurrency
         // still a limit of only 1 writer is imposed by sqlite databases
         self.query("PRAGMA journal_mode=WAL;", ()).await?;
+        self.run_migrations().await
+    }
+
+    /// Applies every pending schema migration, each one in its own transaction.
+    async fn run_migrations(&self) -> Result<(), RollupStoreError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (version INT PRIMARY KEY, description TEXT)",
+            (),
+        )
+        .await?;
+        let current_version = self.get_schema_version().await?;
+        for migration in pending_migrations(current_version)? {
+            let mut queries = migration
+                .sqlite
+                .iter()
+                .map(|query| (*query, Params::None))
+                .collect::<Vec<_>>();
+            queries.push((
+                "INSERT INTO schema_migrations VALUES (?1, ?2)",
+                (migration.version, migration.description).into_params()?,
+            ));
+            self.execute_in_tx(queries, None).await?;
+            info!(
+                "Applied rollup store migration {} ({})",
+                migration.version, migration.description
+            );
+        }
+        Ok(())
+    }
+
+    async fn get_schema_version(&self) -> Result<u64, RollupStoreError> {
         let mut rows = self
             .query(
-                "SELECT name FROM sqlite_schema WHERE type='table' AND name='blocks'",
+                "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
                 (),
             )
             .await?;
-        if rows.next().await?.is_none() {
-            let empty_param = ().into_params()?;
-            let queries = DB_SCHEMA
-                .iter()
-                .map(|v| (*v, empty_param.clone()))
-                .collect();
-            self.execute_in_tx(queries, None).await?;
+        match rows.next().await? {
+            Some(row) => read_from_row_int(&row, 0),
+            None => Ok(0),
         }
-        Ok(())
     }
 
     /// Executes a set of queries in a SQL transaction
@@ -114,6 +149,7 @@ impl SQLStore {
         } else {
             let conn = self.write_conn.lock().await;
             let tx = conn.transaction().await?;
+            self.check_fence(&tx).await?;
             for (query, params) in queries {
                 tx.execute(query, params).await?;
             }
@@ -654,6 +690,10 @@ impl StoreEngineRollup for SQLStore {
                 "DELETE FROM batch_prover_input WHERE batch > ?1",
                 [batch_number].into_params()?,
             ),
+            (
+                "DELETE FROM aggregated_proofs WHERE last_batch > ?1",
+                [batch_number].into_params()?,
+            ),
         ];
         self.execute_in_tx(queries, None).await
     }
@@ -705,6 +745,7 @@ impl StoreEngineRollup for SQLStore {
     async fn seal_batch(&self, batch: Batch) -> Result<(), RollupStoreError> {
         let conn = self.write_conn.lock().await;
         let transaction = conn.transaction().await?;
+        self.check_fence(&transaction).await?;
 
         self.seal_batch_in_tx(batch, &transaction).await?;
 
@@ -719,6 +760,7 @@ impl StoreEngineRollup for SQLStore {
     ) -> Result<(), RollupStoreError> {
         let conn = self.write_conn.lock().await;
         let transaction = conn.transaction().await?;
+        self.check_fence(&transaction).await?;
 
         self.store_prover_input_by_batch_and_version_in_tx(
             batch.number,
@@ -833,6 +875,81 @@ impl StoreEngineRollup for SQLStore {
         .await
     }
 
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        prover_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError> {
+        let serialized_proof = bincode::serialize(&proof)?;
+        let prover_type: u32 = prover_type.into();
+        self.execute_in_tx(
+            vec![
+                (
+                    "DELETE FROM aggregated_proofs WHERE first_batch = ?1 AND last_batch = ?2 AND prover_type = ?3",
+                    (first_batch, last_batch, prover_type).into_params()?,
+                ),
+                (
+                    "INSERT INTO aggregated_proofs VALUES (?1, ?2, ?3, ?4)",
+                    (first_batch, last_batch, prover_type, serialized_proof).into_params()?,
+                ),
+            ],
+            None,
+        )
+        .await
+    }
+
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        prover_type: ProverType,
+    ) -> Result<Option<BatchProof>, RollupStoreError> {
+        let prover_type: u32 = prover_type.into();
+        let mut rows = self
+            .query(
+                "SELECT proof FROM aggregated_proofs WHERE first_batch = ?1 AND last_batch = ?2 AND prover_type = ?3",
+                (first_batch, last_batch, prover_type),
+            )
+            .await?;
+
+        if let Some(row) = rows.next().await? {
+            let vec = read_from_row_blob(&row, 0)?;
+            return Ok(Some(bincode::deserialize(&vec)?));
+        }
+        Ok(None)
+    }
+
+    async fn get_aggregated_ranges(&self) -> Result<Vec<(u64, u64)>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT DISTINCT first_batch, last_batch FROM aggregated_proofs ORDER BY first_batch, last_batch",
+                (),
+            )
+            .await?;
+        let mut ranges = Vec::new();
+        while let Some(row) = rows.next().await? {
+            ranges.push((read_from_row_int(&row, 0)?, read_from_row_int(&row, 1)?));
+        }
+        Ok(ranges)
+    }
+
+    async fn delete_aggregated_proofs(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.execute_in_tx(
+            vec![(
+                "DELETE FROM aggregated_proofs WHERE first_batch = ?1 AND last_batch = ?2",
+                (first_batch, last_batch).into_params()?,
+            )],
+            None,
+        )
+        .await
+    }
+
     async fn get_last_batch_number(&self) -> Result<Option<u64>, RollupStoreError> {
         let mut rows = self.query("SELECT MAX(batch) FROM state_roots", ()).await?;
         rows.next()
@@ -918,11 +1035,162 @@ impl StoreEngineRollup for SQLStore {
         }
         Ok(None)
     }
+
+    async fn store_lead_sequencer_from_block(
+        &self,
+        block_number: BlockNumber,
+        lead_sequencer: Address,
+    ) -> Result<(), RollupStoreError> {
+        let queries = vec![
+            (
+                "DELETE FROM lead_sequencers WHERE block_number >= ?1",
+                vec![block_number].into_params()?,
+            ),
+            (
+                "INSERT INTO lead_sequencers VALUES (?1, ?2)",
+                (block_number, Vec::from(lead_sequencer.to_fixed_bytes())).into_params()?,
+            ),
+        ];
+        self.execute_in_tx(queries, None).await
+    }
+
+    async fn get_lead_sequencer_by_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<Address>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT lead_sequencer FROM lead_sequencers WHERE block_number <= ?1 ORDER BY block_number DESC LIMIT 1",
+                vec![block_number],
+            )
+            .await?;
+        rows.next()
+            .await?
+            .map(|row| read_from_row_blob(&row, 0).map(|vec| Address::from_slice(&vec)))
+            .transpose()
+    }
+
+    async fn try_acquire_sequencer_lease(
+        &self,
+        holder: &str,
+        sequencer_address: Address,
+        now: u64,
+        expires_at: u64,
+    ) -> Result<SequencerLease, RollupStoreError> {
+        // A single upsert, so that concurrent nodes sharing the database can't both win.
+        // It isn't fenced, as taking over the lease is what moves the fence forward.
+        self.write_conn
+            .lock()
+            .await
+            .execute(
+                "INSERT INTO sequencer_lease (_id, holder, sequencer_address, expires_at, epoch) VALUES (0, ?1, ?2, ?3, 1) ON CONFLICT (_id) DO UPDATE SET holder = excluded.holder, sequencer_address = excluded.sequencer_address, expires_at = excluded.expires_at, epoch = CASE WHEN sequencer_lease.holder = excluded.holder THEN sequencer_lease.epoch ELSE sequencer_lease.epoch + 1 END WHERE sequencer_lease.holder = excluded.holder OR sequencer_lease.expires_at <= ?4",
+                (
+                    holder,
+                    Vec::from(sequencer_address.to_fixed_bytes()),
+                    expires_at,
+                    now,
+                ),
+            )
+            .await?;
+        self.get_sequencer_lease()
+            .await?
+            .ok_or(RollupStoreError::Custom(
+                "Sequencer lease missing after being acquired".to_owned(),
+            ))
+    }
+
+    async fn get_sequencer_lease(&self) -> Result<Option<SequencerLease>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT holder, sequencer_address, expires_at, epoch FROM sequencer_lease WHERE _id = 0",
+                (),
+            )
+            .await?;
+        let Some(row) = rows.next().await? else {
+            return Ok(None);
+        };
+        Ok(Some(SequencerLease {
+            holder: row.get_str(0)?.to_string(),
+            sequencer_address: Address::from_slice(&read_from_row_blob(&row, 1)?),
+            expires_at: read_from_row_int(&row, 2)?,
+            epoch: read_from_row_int(&row, 3)?,
+        }))
+    }
+
+    async fn set_sequencer_lease_fence(&self, epoch: Option<u64>) -> Result<(), RollupStoreError> {
+        *self.fence.lock().await = epoch;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store_db::migrations::{MIGRATIONS, latest_version};
+    use ethrex_l2_common::prover::ProofBytes;
+
+    #[tokio::test]
+    async fn test_migrations_are_recorded() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        assert_eq!(store.get_schema_version().await?, latest_version());
+
+        let mut rows = store
+            .query("SELECT version FROM schema_migrations ORDER BY version", ())
+            .await?;
+        let mut versions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            versions.push(read_from_row_int(&row, 0)?);
+        }
+        let expected: Vec<u64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(versions, expected);
+
+        // Running the migrations again is a no-op
+        store.run_migrations().await?;
+        assert_eq!(store.get_schema_version().await?, latest_version());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_is_migrated() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rollup_store");
+        {
+            // Databases created before versioned migrations only had some of the tables
+            let db = Builder::new_local(&path).build().await?;
+            let conn = db.connect()?;
+            conn.execute(
+                "CREATE TABLE operation_count (_id INT PRIMARY KEY, transactions INT, privileged_transactions INT, messages INT)",
+                (),
+            )
+            .await?;
+            conn.execute("INSERT INTO operation_count VALUES (0, 5, 6, 7)", ())
+                .await?;
+        }
+
+        let store = SQLStore::new(&path)?;
+        assert_eq!(store.get_schema_version().await?, latest_version());
+        // Existing data is kept and missing tables are created
+        assert_eq!(store.get_operations_count().await?, [5, 6, 7]);
+        assert!(store.get_aggregated_ranges().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_newer_schema_is_rejected() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rollup_store");
+        {
+            let store = SQLStore::new(&path)?;
+            store
+                .execute(
+                    "INSERT INTO schema_migrations VALUES (?1, ?2)",
+                    (latest_version() + 1, "from the future"),
+                )
+                .await?;
+        }
+        assert!(SQLStore::new(&path).is_err());
+        Ok(())
+    }
 
     #[tokio::test]
     async fn test_schema_tables() -> anyhow::Result<()> {
@@ -940,6 +1208,9 @@ mod tests {
             "block_signatures",
             "batch_signatures",
             "batch_prover_input",
+            "lead_sequencers",
+            "aggregated_proofs",
+            "sequencer_lease",
         ];
         let mut attributes = Vec::new();
         for table in tables {
@@ -987,6 +1258,17 @@ mod tests {
                 ("batch_prover_input", "batch") => "INT",
                 ("batch_prover_input", "prover_version") => "TEXT",
                 ("batch_prover_input", "prover_input") => "BLOB",
+                ("lead_sequencers", "block_number") => "INT",
+                ("lead_sequencers", "lead_sequencer") => "BLOB",
+                ("aggregated_proofs", "first_batch") => "INT",
+                ("aggregated_proofs", "last_batch") => "INT",
+                ("aggregated_proofs", "prover_type") => "INT",
+                ("aggregated_proofs", "proof") => "BLOB",
+                ("sequencer_lease", "_id") => "INT",
+                ("sequencer_lease", "holder") => "TEXT",
+                ("sequencer_lease", "sequencer_address") => "BLOB",
+                ("sequencer_lease", "expires_at") => "INT",
+                ("sequencer_lease", "epoch") => "INT",
                 _ => {
                     return Err(anyhow::Error::msg(
                         "unexpected attribute {name} in table {table}",
@@ -997,4 +1279,128 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_lead_sequencer_by_block() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let first = Address::from_low_u64_be(1);
+        let second = Address::from_low_u64_be(2);
+        store.store_lead_sequencer_from_block(10, first).await?;
+        store.store_lead_sequencer_from_block(20, second).await?;
+
+        assert_eq!(store.get_lead_sequencer_by_block(9).await?, None);
+        assert_eq!(store.get_lead_sequencer_by_block(10).await?, Some(first));
+        assert_eq!(store.get_lead_sequencer_by_block(19).await?, Some(first));
+        assert_eq!(store.get_lead_sequencer_by_block(20).await?, Some(second));
+        assert_eq!(store.get_lead_sequencer_by_block(100).await?, Some(second));
+
+        // A lead sequencer stored from an earlier block replaces the later ones
+        let third = Address::from_low_u64_be(3);
+        store.store_lead_sequencer_from_block(15, third).await?;
+        assert_eq!(store.get_lead_sequencer_by_block(14).await?, Some(first));
+        assert_eq!(store.get_lead_sequencer_by_block(15).await?, Some(third));
+        assert_eq!(store.get_lead_sequencer_by_block(100).await?, Some(third));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sequencer_lease() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let first = Address::from_low_u64_be(1);
+        let second = Address::from_low_u64_be(2);
+        assert_eq!(store.get_sequencer_lease().await?, None);
+
+        let lease = store
+            .try_acquire_sequencer_lease("a", first, 0, 100)
+            .await?;
+        assert_eq!(lease.holder, "a");
+        // The holder can renew it, others can't take it before it expires.
+        let lease = store
+            .try_acquire_sequencer_lease("a", first, 50, 150)
+            .await?;
+        assert_eq!(lease.expires_at, 150);
+        let lease = store
+            .try_acquire_sequencer_lease("b", second, 100, 200)
+            .await?;
+        assert_eq!(lease.holder, "a");
+        // Once expired, another node takes over.
+        let lease = store
+            .try_acquire_sequencer_lease("b", second, 150, 250)
+            .await?;
+        assert_eq!(
+            lease,
+            SequencerLease {
+                holder: "b".to_string(),
+                sequencer_address: second,
+                expires_at: 250,
+                epoch: 2,
+            }
+        );
+        assert_eq!(store.get_sequencer_lease().await?, Some(lease));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_fenced_to_the_lease_epoch() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let address = Address::from_low_u64_be(1);
+        let lease = store
+            .try_acquire_sequencer_lease("a", address, 0, 100)
+            .await?;
+        store.set_sequencer_lease_fence(Some(lease.epoch)).await?;
+        store.set_latest_sent_batch_proof(1).await?;
+
+        // Another node takes over once the lease expires, even if "a" thinks it still holds it
+        store
+            .try_acquire_sequencer_lease("b", address, 100, 200)
+            .await?;
+        assert!(matches!(
+            store.set_latest_sent_batch_proof(2).await,
+            Err(RollupStoreError::StaleSequencerLease { fence: 1, epoch: 2 })
+        ));
+        assert!(store.seal_batch(Batch::default()).await.is_err());
+        assert_eq!(store.get_latest_sent_batch_proof().await?, 1);
+
+        store.set_sequencer_lease_fence(None).await?;
+        store.set_latest_sent_batch_proof(2).await?;
+        assert_eq!(store.get_latest_sent_batch_proof().await?, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_proof_ranges() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let proof = BatchProof::ProofBytes(ProofBytes {
+            prover_type: ProverType::SP1,
+            proof: vec![1, 2, 3],
+            public_values: vec![4, 5, 6],
+        });
+        store
+            .store_aggregated_proof(5, 8, ProverType::SP1, proof.clone())
+            .await?;
+        store
+            .store_aggregated_proof(5, 8, ProverType::RISC0, proof.clone())
+            .await?;
+        store
+            .store_aggregated_proof(1, 4, ProverType::SP1, proof.clone())
+            .await?;
+
+        assert_eq!(store.get_aggregated_ranges().await?, vec![(1, 4), (5, 8)]);
+        assert_eq!(
+            store.get_aggregated_proof(5, 8, ProverType::SP1).await?,
+            Some(proof)
+        );
+        assert_eq!(
+            store.get_aggregated_proof(5, 9, ProverType::SP1).await?,
+            None
+        );
+
+        store.delete_aggregated_proofs(1, 4).await?;
+        assert_eq!(store.get_aggregated_ranges().await?, vec![(5, 8)]);
+
+        // Ranges that are not fully included in the remaining batches are reverted
+        store.revert_to_batch(6).await?;
+        assert!(store.get_aggregated_ranges().await?.is_empty());
+        Ok(())
+    }
 }
@@ -35,6 +35,8 @@ pub async fn get_batch(commit_hash: String) -> Result<(u64, ProgramInput), Strin
                     #[cfg(feature = "l2")]
                     blob_proof: input.blob_proof,
                     fee_configs: Some(input.fee_configs),
+                    #[cfg(feature = "l2")]
+                    forced_transactions: input.forced_transactions,
                 },
             )),
             _ => Err("No blocks to prove.".to_owned()),
@@ -19,16 +19,19 @@ use ethrex_l2_rpc::clients::{
     get_base_fee_vault_address, get_l1_blob_base_fee_per_gas, get_l1_fee_vault_address,
     get_operator_fee, get_operator_fee_vault_address,
 };
-use ethrex_l2_rpc::signer::{LocalSigner, Signer};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
 use ethrex_l2_sdk::{
     COMMON_BRIDGE_L2_ADDRESS, bridge_address, calldata::encode_calldata, claim_erc20withdraw,
-    claim_withdraw, compile_contract, create_deploy, deposit_erc20, get_address_alias,
-    get_erc1967_slot, git_clone, l1_to_l2_tx_data::L1ToL2TransactionData,
-    wait_for_transaction_receipt,
+    claim_erc721withdraw, claim_erc1155withdraw, claim_withdraw, compile_contract, create_deploy,
+    deposit_erc20, deposit_erc721, deposit_erc1155, get_address_alias, get_erc1967_slot, git_clone,
+    l1_to_l2_tx_data::L1ToL2TransactionData, wait_for_transaction_receipt, withdraw_erc721,
+    withdraw_erc1155,
 };
 use ethrex_l2_sdk::{
-    FEE_TOKEN_REGISTRY_ADDRESS, L2_WITHDRAW_SIGNATURE, REGISTER_FEE_TOKEN_SIGNATURE,
-    build_generic_tx, get_last_verified_batch, send_generic_transaction, wait_for_message_proof,
+    FEE_TOKEN_REGISTRY_ADDRESS, L2_WITHDRAW_ERC721_SIGNATURE, L2_WITHDRAW_ERC1155_SIGNATURE,
+    L2_WITHDRAW_SIGNATURE, REGISTER_FEE_TOKEN_SIGNATURE, build_generic_tx,
+    get_forced_transaction_fee, get_last_verified_batch, send_generic_transaction,
+    wait_for_message_proof,
 };
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_rpc::{
@@ -218,6 +221,24 @@ async fn l2_integration_test() -> Result<(), Box<dyn std::error::Error>> {
         private_keys.pop().unwrap(),
     ));
 
+    set.spawn(test_forced_transaction(
+        l1_client.clone(),
+        l2_client.clone(),
+        private_keys.pop().unwrap(),
+    ));
+
+    set.spawn(test_erc721_roundtrip(
+        l1_client.clone(),
+        l2_client.clone(),
+        private_keys.pop().unwrap(),
+    ));
+
+    set.spawn(test_erc1155_roundtrip(
+        l1_client.clone(),
+        l2_client.clone(),
+        private_keys.pop().unwrap(),
+    ));
+
     while let Some(res) = set.join_next().await {
         let fees_details = res??;
         acc_priority_fees += fees_details.priority_fees;
@@ -709,6 +730,7 @@ async fn test_erc20_roundtrip(
             token_l1,
             token_l2,
             value: token_amount,
+            token_id: U256::zero(),
             l2_tx_hash: withdrawal_tx_hash
         }
     );
@@ -749,6 +771,7 @@ async fn test_erc20_roundtrip(
             token_l1,
             token_l2,
             value: token_amount,
+            token_id: U256::zero(),
             l2_tx_hash: withdrawal_tx_hash
         }
     );
@@ -760,6 +783,420 @@ async fn test_erc20_roundtrip(
     Ok(deploy_fees + approve_fees + withdraw_fees)
 }
 
+/// Tests the full roundtrip of an ERC721 token from L1 to L2 and back
+/// 1. Deploys an ERC721 token on L1
+/// 2. Deploys an ERC721 token on L2 that points to the L1 token
+/// 3. Mints a token on L1 and deposits it to L2 through the bridge
+/// 4. Checks the privileged transaction sent by the bridge and the token owners
+/// 5. Withdraws the token back to L1 and claims it
+/// 6. Checks that the withdrawal is correctly recorded in the L2ToL1MessagesTable widget
+async fn test_erc721_roundtrip(
+    l1_client: EthClient,
+    l2_client: EthClient,
+    rich_wallet_private_key: SecretKey,
+) -> Result<FeesDetails> {
+    let token_id: U256 = U256::from(42);
+
+    let rich_wallet_signer: Signer = LocalSigner::new(rich_wallet_private_key).into();
+    let rich_address = rich_wallet_signer.address();
+
+    let init_code_l1 = compile_example_contract("L1ERC721.sol", "TestNFT")?;
+
+    println!("test_erc721_roundtrip: Deploying ERC721 token on L1");
+    let token_l1 = test_deploy_l1(&l1_client, &init_code_l1, &rich_wallet_private_key).await?;
+
+    let init_code_l2 = [
+        compile_example_contract("L2ERC721.sol", "TestNFTL2")?,
+        vec![0u8; 12],
+        token_l1.to_fixed_bytes().to_vec(),
+    ]
+    .concat();
+
+    let (token_l2, deploy_fees) = test_deploy(
+        &l2_client,
+        &init_code_l2,
+        &rich_wallet_private_key,
+        "test_erc721_roundtrip",
+    )
+    .await?;
+
+    println!("test_erc721_roundtrip: token l1={token_l1:x}, l2={token_l2:x}");
+    test_send(
+        &l1_client,
+        &rich_wallet_private_key,
+        token_l1,
+        "freeMint(uint256)",
+        &[Value::Uint(token_id)],
+        "test_erc721_roundtrip",
+    )
+    .await?;
+    test_send(
+        &l1_client,
+        &rich_wallet_private_key,
+        token_l1,
+        "approve(address,uint256)",
+        &[Value::Address(bridge_address()?), Value::Uint(token_id)],
+        "test_erc721_roundtrip",
+    )
+    .await?;
+
+    println!("test_erc721_roundtrip: Depositing ERC721 token from L1 to L2");
+    let deposit_tx = deposit_erc721(
+        token_l1,
+        token_l2,
+        token_id,
+        rich_address,
+        &rich_wallet_signer,
+        &l1_client,
+    )
+    .await?;
+
+    println!("test_erc721_roundtrip: Waiting for deposit transaction receipt on L1");
+    let res = wait_for_transaction_receipt(deposit_tx, &l1_client, 10).await?;
+    assert!(res.receipt.status);
+
+    assert_privileged_tx_data(
+        &res,
+        &L1ToL2TransactionData::new_erc721_deposit(token_l1, token_l2, rich_address, token_id)?,
+    );
+
+    println!("test_erc721_roundtrip: Waiting for deposit transaction receipt on L2");
+    let l2_receipt = wait_for_l2_deposit_receipt(&res, &l1_client, &l2_client).await?;
+    assert!(l2_receipt.receipt.status);
+
+    assert_eq!(
+        test_owner_of(&l1_client, token_l1, token_id).await,
+        bridge_address()?
+    );
+    assert_eq!(
+        test_owner_of(&l2_client, token_l2, token_id).await,
+        rich_address
+    );
+
+    println!("test_erc721_roundtrip: Withdrawing ERC721 token from L2 to L1");
+    let withdraw_tx = withdraw_erc721(
+        token_l1,
+        token_l2,
+        token_id,
+        rich_address,
+        &rich_wallet_signer,
+        &l2_client,
+    )
+    .await?;
+    let withdraw_receipt = wait_for_transaction_receipt(withdraw_tx, &l2_client, 10).await?;
+    assert!(withdraw_receipt.receipt.status);
+
+    // Calculate transaction size
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        data: Bytes::from(encode_calldata(
+            L2_WITHDRAW_ERC721_SIGNATURE,
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(rich_address),
+                Value::Uint(token_id),
+            ],
+        )?),
+        ..Default::default()
+    });
+    let transaction_size: u64 = tx.encode_to_vec().len().try_into().unwrap();
+
+    let withdraw_fees =
+        get_fees_details_l2(&withdraw_receipt, &l2_client, transaction_size).await?;
+
+    let withdrawal_tx_hash = withdraw_receipt.tx_info.transaction_hash;
+    assert_eq!(
+        find_withdrawal_with_widget(
+            bridge_address()?,
+            withdrawal_tx_hash,
+            &l2_client,
+            &l1_client,
+        )
+        .await
+        .unwrap(),
+        L2ToL1MessageRow {
+            status: L2ToL1MessageStatus::WithdrawalInitiated,
+            kind: L2ToL1MessageKind::ERC721Withdraw,
+            receiver: rich_address,
+            token_l1,
+            token_l2,
+            value: U256::one(),
+            token_id,
+            l2_tx_hash: withdrawal_tx_hash
+        }
+    );
+
+    let proof = wait_for_verified_proof(&l1_client, &l2_client, withdrawal_tx_hash).await;
+
+    println!("test_erc721_roundtrip: Claiming withdrawal on L1");
+    let withdraw_claim_tx = claim_erc721withdraw(
+        token_l1,
+        token_l2,
+        token_id,
+        &rich_wallet_signer,
+        &l1_client,
+        &proof,
+    )
+    .await
+    .expect("error while claiming");
+    wait_for_transaction_receipt(withdraw_claim_tx, &l1_client, 5).await?;
+
+    assert_eq!(
+        find_withdrawal_with_widget(
+            bridge_address()?,
+            withdrawal_tx_hash,
+            &l2_client,
+            &l1_client,
+        )
+        .await
+        .unwrap()
+        .status,
+        L2ToL1MessageStatus::WithdrawalClaimed
+    );
+    assert_eq!(
+        test_owner_of(&l1_client, token_l1, token_id).await,
+        rich_address
+    );
+    assert!(
+        test_balance_of(&l2_client, token_l2, rich_address)
+            .await
+            .is_zero()
+    );
+    Ok(deploy_fees + withdraw_fees)
+}
+
+/// Tests the full roundtrip of an ERC1155 token from L1 to L2 and back
+/// 1. Deploys an ERC1155 token on L1
+/// 2. Deploys an ERC1155 token on L2 that points to the L1 token
+/// 3. Mints some tokens on L1 and deposits part of them to L2 through the bridge
+/// 4. Checks the privileged transaction sent by the bridge and the balances
+/// 5. Withdraws the tokens back to L1 and claims them
+/// 6. Checks that the withdrawal is correctly recorded in the L2ToL1MessagesTable widget
+async fn test_erc1155_roundtrip(
+    l1_client: EthClient,
+    l2_client: EthClient,
+    rich_wallet_private_key: SecretKey,
+) -> Result<FeesDetails> {
+    let token_id: U256 = U256::from(7);
+    let minted_amount: U256 = U256::from(1000);
+    let token_amount: U256 = U256::from(100);
+
+    let rich_wallet_signer: Signer = LocalSigner::new(rich_wallet_private_key).into();
+    let rich_address = rich_wallet_signer.address();
+
+    let init_code_l1 = compile_example_contract("L1ERC1155.sol", "TestMultiToken")?;
+
+    println!("test_erc1155_roundtrip: Deploying ERC1155 token on L1");
+    let token_l1 = test_deploy_l1(&l1_client, &init_code_l1, &rich_wallet_private_key).await?;
+
+    let init_code_l2 = [
+        compile_example_contract("L2ERC1155.sol", "TestMultiTokenL2")?,
+        vec![0u8; 12],
+        token_l1.to_fixed_bytes().to_vec(),
+    ]
+    .concat();
+
+    let (token_l2, deploy_fees) = test_deploy(
+        &l2_client,
+        &init_code_l2,
+        &rich_wallet_private_key,
+        "test_erc1155_roundtrip",
+    )
+    .await?;
+
+    println!("test_erc1155_roundtrip: token l1={token_l1:x}, l2={token_l2:x}");
+    test_send(
+        &l1_client,
+        &rich_wallet_private_key,
+        token_l1,
+        "freeMint(uint256,uint256)",
+        &[Value::Uint(token_id), Value::Uint(minted_amount)],
+        "test_erc1155_roundtrip",
+    )
+    .await?;
+    test_send(
+        &l1_client,
+        &rich_wallet_private_key,
+        token_l1,
+        "setApprovalForAll(address,bool)",
+        &[Value::Address(bridge_address()?), Value::Bool(true)],
+        "test_erc1155_roundtrip",
+    )
+    .await?;
+
+    println!("test_erc1155_roundtrip: Depositing ERC1155 tokens from L1 to L2");
+    let deposit_tx = deposit_erc1155(
+        token_l1,
+        token_l2,
+        token_id,
+        token_amount,
+        rich_address,
+        &rich_wallet_signer,
+        &l1_client,
+    )
+    .await?;
+
+    println!("test_erc1155_roundtrip: Waiting for deposit transaction receipt on L1");
+    let res = wait_for_transaction_receipt(deposit_tx, &l1_client, 10).await?;
+    assert!(res.receipt.status);
+
+    assert_privileged_tx_data(
+        &res,
+        &L1ToL2TransactionData::new_erc1155_deposit(
+            token_l1,
+            token_l2,
+            rich_address,
+            token_id,
+            token_amount,
+        )?,
+    );
+
+    println!("test_erc1155_roundtrip: Waiting for deposit transaction receipt on L2");
+    let l2_receipt = wait_for_l2_deposit_receipt(&res, &l1_client, &l2_client).await?;
+    assert!(l2_receipt.receipt.status);
+
+    assert_eq!(
+        test_erc1155_balance_of(&l1_client, token_l1, rich_address, token_id).await,
+        minted_amount - token_amount
+    );
+    assert_eq!(
+        test_erc1155_balance_of(&l2_client, token_l2, rich_address, token_id).await,
+        token_amount
+    );
+
+    println!("test_erc1155_roundtrip: Withdrawing ERC1155 tokens from L2 to L1");
+    let withdraw_tx = withdraw_erc1155(
+        token_l1,
+        token_l2,
+        token_id,
+        token_amount,
+        rich_address,
+        &rich_wallet_signer,
+        &l2_client,
+    )
+    .await?;
+    let withdraw_receipt = wait_for_transaction_receipt(withdraw_tx, &l2_client, 10).await?;
+    assert!(withdraw_receipt.receipt.status);
+
+    // Calculate transaction size
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        data: Bytes::from(encode_calldata(
+            L2_WITHDRAW_ERC1155_SIGNATURE,
+            &[
+                Value::Address(token_l1),
+                Value::Address(token_l2),
+                Value::Address(rich_address),
+                Value::Uint(token_id),
+                Value::Uint(token_amount),
+            ],
+        )?),
+        ..Default::default()
+    });
+    let transaction_size: u64 = tx.encode_to_vec().len().try_into().unwrap();
+
+    let withdraw_fees =
+        get_fees_details_l2(&withdraw_receipt, &l2_client, transaction_size).await?;
+
+    let withdrawal_tx_hash = withdraw_receipt.tx_info.transaction_hash;
+    assert_eq!(
+        find_withdrawal_with_widget(
+            bridge_address()?,
+            withdrawal_tx_hash,
+            &l2_client,
+            &l1_client,
+        )
+        .await
+        .unwrap(),
+        L2ToL1MessageRow {
+            status: L2ToL1MessageStatus::WithdrawalInitiated,
+            kind: L2ToL1MessageKind::ERC1155Withdraw,
+            receiver: rich_address,
+            token_l1,
+            token_l2,
+            value: token_amount,
+            token_id,
+            l2_tx_hash: withdrawal_tx_hash
+        }
+    );
+
+    let proof = wait_for_verified_proof(&l1_client, &l2_client, withdrawal_tx_hash).await;
+
+    println!("test_erc1155_roundtrip: Claiming withdrawal on L1");
+    let withdraw_claim_tx = claim_erc1155withdraw(
+        token_l1,
+        token_l2,
+        token_id,
+        token_amount,
+        &rich_wallet_signer,
+        &l1_client,
+        &proof,
+    )
+    .await
+    .expect("error while claiming");
+    wait_for_transaction_receipt(withdraw_claim_tx, &l1_client, 5).await?;
+
+    assert_eq!(
+        find_withdrawal_with_widget(
+            bridge_address()?,
+            withdrawal_tx_hash,
+            &l2_client,
+            &l1_client,
+        )
+        .await
+        .unwrap()
+        .status,
+        L2ToL1MessageStatus::WithdrawalClaimed
+    );
+    assert_eq!(
+        test_erc1155_balance_of(&l1_client, token_l1, rich_address, token_id).await,
+        minted_amount
+    );
+    assert!(
+        test_erc1155_balance_of(&l2_client, token_l2, rich_address, token_id)
+            .await
+            .is_zero()
+    );
+    Ok(deploy_fees + withdraw_fees)
+}
+
+/// Compiles one of the example contracts and returns its init code
+fn compile_example_contract(file_name: &str, contract_name: &str) -> Result<Vec<u8>> {
+    let contracts_path = Path::new("contracts");
+
+    get_contract_dependencies(contracts_path);
+    let remappings = [(
+        "@openzeppelin/contracts",
+        contracts_path
+            .join("lib/openzeppelin-contracts-upgradeable/lib/openzeppelin-contracts/contracts"),
+    )];
+    compile_contract(
+        contracts_path,
+        &contracts_path.join("src/example").join(file_name),
+        false,
+        false,
+        Some(&remappings),
+        &[contracts_path],
+    )?;
+    Ok(hex::decode(String::from_utf8(std::fs::read(format!(
+        "contracts/solc_out/{contract_name}.bin"
+    ))?)?)?)
+}
+
+/// Checks that the privileged transaction sent by the bridge in an L1 receipt is the expected one
+fn assert_privileged_tx_data(receipt: &RpcReceipt, expected: &L1ToL2TransactionData) {
+    let data = receipt
+        .logs
+        .iter()
+        .find_map(|log| PrivilegedTransactionData::from_log(log.log.clone()).ok())
+        .expect("receipt contains no privileged transaction");
+
+    assert_eq!(data.from, COMMON_BRIDGE_L2_ADDRESS);
+    assert_eq!(data.to_address, expected.to);
+    assert_eq!(data.gas_limit, U256::from(expected.gas_limit));
+    assert_eq!(data.value, expected.value);
+    assert_eq!(data.calldata, expected.calldata.to_vec());
+}
+
 /// Tests that the aliasing is done correctly when calling from L1 to L2
 /// 1. Deploys a contract on L1 that will call the CommonBridge contract sendToL2 function
 /// 2. Calls the contract to send a message to L2
@@ -969,6 +1406,7 @@ async fn test_forced_withdrawal(
             token_l1: Default::default(),
             token_l2: Default::default(),
             value: transfer_value,
+            token_id: U256::zero(),
             l2_tx_hash: withdrawal_tx_hash
         }
     );
@@ -1009,6 +1447,7 @@ async fn test_forced_withdrawal(
             token_l1: Default::default(),
             token_l2: Default::default(),
             value: transfer_value,
+            token_id: U256::zero(),
             l2_tx_hash: withdrawal_tx_hash
         }
     );
@@ -1024,6 +1463,78 @@ async fn test_forced_withdrawal(
     Ok(FeesDetails::default())
 }
 
+/// Test forcing the inclusion of an L2 transfer by sending it to the CommonBridge
+/// 1. Sign a transfer on L2 without sending it to the sequencer.
+/// 2. Queue it in the CommonBridge on L1.
+/// 3. Wait for the sequencer to include it and check the receiver balance on L2.
+async fn test_forced_transaction(
+    l1_client: EthClient,
+    l2_client: EthClient,
+    rich_wallet_private_key: SecretKey,
+) -> Result<FeesDetails> {
+    println!("forced_transaction: Signing transfer on L2");
+    let signer: Signer = LocalSigner::new(rich_wallet_private_key).into();
+    let receiver = Address::random();
+    let transfer_value = U256::from(100);
+
+    let tx = build_generic_tx(
+        &l2_client,
+        TxType::EIP1559,
+        receiver,
+        signer.address(),
+        Bytes::new(),
+        Overrides {
+            value: Some(transfer_value),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let tx: EIP1559Transaction = tx.try_into()?;
+    let tx = Transaction::EIP1559Transaction(tx.sign(&signer).await?);
+    let tx_hash = tx.hash();
+
+    println!("forced_transaction: Sending transaction {tx_hash:#x} to the CommonBridge");
+    let l1_signer: Signer = LocalSigner::new(rich_wallet_private_key).into();
+    let calldata = encode_calldata(
+        "forceTransaction(bytes)",
+        &[Value::Bytes(tx.encode_canonical_to_vec().into())],
+    )?;
+    let force_tx = build_generic_tx(
+        &l1_client,
+        TxType::EIP1559,
+        bridge_address()?,
+        l1_signer.address(),
+        calldata.into(),
+        Overrides {
+            value: Some(get_forced_transaction_fee(&l1_client, bridge_address()?).await?),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let force_tx_hash = send_generic_transaction(&l1_client, force_tx, &l1_signer).await?;
+    let receipt = wait_for_transaction_receipt(force_tx_hash, &l1_client, 10).await?;
+    assert!(receipt.receipt.status, "forceTransaction reverted");
+
+    println!("forced_transaction: Waiting for the transaction to be included on L2");
+    let receipt = wait_for_transaction_receipt(tx_hash, &l2_client, 10000).await?;
+    assert!(receipt.receipt.status, "Forced transaction failed");
+
+    let receiver_balance = l2_client
+        .get_balance(receiver, BlockIdentifier::Tag(BlockTag::Latest))
+        .await?;
+    assert_eq!(
+        receiver_balance, transfer_value,
+        "forced_transaction: receiver balance didn't increase as expected"
+    );
+
+    get_fees_details_l2(
+        &receipt,
+        &l2_client,
+        u64::try_from(EIP1559_DEFAULT_SERIALIZED_LENGTH).unwrap(),
+    )
+    .await
+}
+
 async fn test_balance_of(client: &EthClient, token: Address, user: Address) -> U256 {
     let res = client
         .call(
@@ -1038,6 +1549,43 @@ async fn test_balance_of(client: &EthClient, token: Address, user: Address) -> U
     U256::from_str_radix(res.trim_start_matches("0x"), 16).unwrap()
 }
 
+async fn test_owner_of(client: &EthClient, token: Address, token_id: U256) -> Address {
+    let res = client
+        .call(
+            token,
+            encode_calldata("ownerOf(uint256)", &[Value::Uint(token_id)])
+                .unwrap()
+                .into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let owner = hex::decode(res.trim_start_matches("0x")).unwrap();
+    Address::from_slice(&owner[12..32])
+}
+
+async fn test_erc1155_balance_of(
+    client: &EthClient,
+    token: Address,
+    user: Address,
+    token_id: U256,
+) -> U256 {
+    let res = client
+        .call(
+            token,
+            encode_calldata(
+                "balanceOf(address,uint256)",
+                &[Value::Address(user), Value::Uint(token_id)],
+            )
+            .unwrap()
+            .into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    U256::from_str_radix(res.trim_start_matches("0x"), 16).unwrap()
+}
+
 async fn test_balance_of_optional(
     client: &EthClient,
     token: Address,
@@ -34,3 +34,31 @@ pub enum UtilsError {
     #[error("Failed to compute deposit logs hash: {0}")]
     PrivilegedTransactionError(#[from] PrivilegedTransactionError),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Snapshot IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize snapshot metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+    #[error("Failed to decode snapshot data: {0}")]
+    Decode(#[from] ethrex_rlp::error::RLPDecodeError),
+    #[error("Failed due to a Store error: {0}")]
+    StoreError(#[from] StoreError),
+    #[error("Failed due to a rollup Store error: {0}")]
+    RollupStoreError(#[from] ethrex_storage_rollup::RollupStoreError),
+    #[error("Failed due to a Trie error: {0}")]
+    TrieError(#[from] ethrex_trie::TrieError),
+    #[error("Failed to query L1: {0}")]
+    EthClientError(#[from] ethrex_rpc::clients::EthClientError),
+    #[error("Batch {0} is not sealed")]
+    BatchNotSealed(u64),
+    #[error("State root mismatch: expected {expected:#x}, got {actual:#x}")]
+    StateRootMismatch { expected: H256, actual: H256 },
+    #[error(
+        "The state root of batch {0} is not known, pass the L1 RPC to check the snapshot against or --trust-snapshot to trust it"
+    )]
+    UntrustedSnapshot(u64),
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+}
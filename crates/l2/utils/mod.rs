@@ -1,4 +1,5 @@
 pub mod error;
 pub mod parse;
+pub mod snapshot;
 pub mod state_reconstruct;
 pub mod test_data_io;
@@ -0,0 +1,668 @@
+//! L2 state snapshots taken at a sealed batch.
+//!
+//! A snapshot is a directory with the following files:
+//! - `metadata.json`: chain identification and the sealed [`Batch`] the snapshot was taken at.
+//! - `accounts.rlp`: a stream of RLP-encoded [`SnapshotAccount`]s, ordered by hashed address.
+//! - `codes.rlp`: a stream of RLP-encoded contract bytecodes referenced by the accounts.
+//! - `blocks.rlp`: a stream of RLP-encoded blocks ending at the batch's last block, so that
+//!   the importing node has the ancestors it needs to keep producing blocks (e.g. `BLOCKHASH`).
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use ethrex_common::{
+    constants::{EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH},
+    types::{AccountState, Block, BlockNumber, Code, batch::Batch},
+    utils::keccak,
+};
+use ethrex_l2_sdk::get_batch_committed_state_root;
+use ethrex_rlp::{
+    decode::RLPDecode,
+    encode::RLPEncode,
+    error::RLPDecodeError,
+    structs::{Decoder, Encoder},
+};
+use ethrex_rpc::clients::EthClient;
+use ethrex_storage::Store;
+use ethrex_storage_rollup::StoreRollup;
+use ethrex_trie::Trie;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::utils::error::SnapshotError;
+
+pub const SNAPSHOT_VERSION: u64 = 1;
+/// Amount of blocks (including the batch's last block) exported along with the state.
+pub const SNAPSHOT_BLOCKS: u64 = 256;
+
+const METADATA_FILE: &str = "metadata.json";
+const ACCOUNTS_FILE: &str = "accounts.rlp";
+const CODES_FILE: &str = "codes.rlp";
+const BLOCKS_FILE: &str = "blocks.rlp";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    pub version: u64,
+    pub chain_id: u64,
+    pub genesis_hash: H256,
+    pub batch: Batch,
+    pub account_count: u64,
+    pub code_count: u64,
+    pub block_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotAccount {
+    pub hashed_address: H256,
+    pub state: AccountState,
+    /// Non-zero storage slots as (hashed key, value) pairs.
+    pub storage: Vec<(H256, U256)>,
+}
+
+impl RLPEncode for SnapshotAccount {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.hashed_address)
+            .encode_field(&self.state)
+            .encode_field(&self.storage)
+            .finish();
+    }
+}
+
+impl RLPDecode for SnapshotAccount {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (hashed_address, decoder) = decoder.decode_field("hashed_address")?;
+        let (state, decoder) = decoder.decode_field("state")?;
+        let (storage, decoder) = decoder.decode_field("storage")?;
+        let account = SnapshotAccount {
+            hashed_address,
+            state,
+            storage,
+        };
+        Ok((account, decoder.finish()?))
+    }
+}
+
+/// Writes a snapshot of the state at the given sealed batch (or the latest sealed one) into `output_dir`.
+/// Every storage root and the resulting state root are recomputed and checked against the store
+/// while exporting, so a successfully written snapshot is known to match the batch's state root.
+pub async fn export_snapshot(
+    store: &Store,
+    rollup_store: &StoreRollup,
+    batch_number: Option<u64>,
+    output_dir: &Path,
+) -> Result<SnapshotMetadata, SnapshotError> {
+    let batch_number = match batch_number {
+        Some(batch_number) => batch_number,
+        None => rollup_store
+            .get_batch_number()
+            .await?
+            .ok_or(SnapshotError::InvalidSnapshot(
+                "the rollup store has no sealed batches".to_owned(),
+            ))?,
+    };
+
+    let last_block = rollup_store
+        .get_block_numbers_by_batch(batch_number)
+        .await?
+        .and_then(|blocks| blocks.last().copied())
+        .ok_or(SnapshotError::BatchNotSealed(batch_number))?;
+    let last_header = store
+        .get_block_header(last_block)?
+        .ok_or(SnapshotError::InvalidSnapshot(format!(
+            "block {last_block} is not in the store"
+        )))?;
+    let fork = store.get_chain_config().get_fork(last_header.timestamp);
+    let batch = rollup_store
+        .get_batch(batch_number, fork)
+        .await?
+        .ok_or(SnapshotError::BatchNotSealed(batch_number))?;
+
+    if last_header.state_root != batch.state_root {
+        return Err(SnapshotError::StateRootMismatch {
+            expected: batch.state_root,
+            actual: last_header.state_root,
+        });
+    }
+    if !store.has_state_root(batch.state_root)? {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "state root {:#x} of batch {batch_number} is not available in the store",
+            batch.state_root
+        )));
+    }
+
+    std::fs::create_dir_all(output_dir)?;
+
+    info!(
+        "Exporting state of batch {batch_number} (state root {:#x})",
+        batch.state_root
+    );
+
+    let mut accounts_writer = BufWriter::new(File::create(output_dir.join(ACCOUNTS_FILE))?);
+    let mut state_trie = Trie::new_temp();
+    let mut code_hashes = BTreeSet::new();
+    let mut account_count = 0;
+
+    for (hashed_address, state) in store.iter_accounts(batch.state_root)? {
+        let storage: Vec<(H256, U256)> = store
+            .iter_storage(batch.state_root, hashed_address)?
+            .map(|iter| iter.collect())
+            .unwrap_or_default();
+
+        let mut storage_trie = Trie::new_temp();
+        for (hashed_key, value) in &storage {
+            storage_trie.insert(hashed_key.0.to_vec(), value.encode_to_vec())?;
+        }
+        let storage_root = storage_trie.hash_no_commit();
+        if storage_root != state.storage_root {
+            return Err(SnapshotError::StateRootMismatch {
+                expected: state.storage_root,
+                actual: storage_root,
+            });
+        }
+
+        state_trie.insert(hashed_address.0.to_vec(), state.encode_to_vec())?;
+        if state.code_hash != *EMPTY_KECCACK_HASH {
+            code_hashes.insert(state.code_hash);
+        }
+
+        let account = SnapshotAccount {
+            hashed_address,
+            state,
+            storage,
+        };
+        accounts_writer.write_all(&account.encode_to_vec())?;
+        account_count += 1;
+    }
+    accounts_writer.flush()?;
+
+    let state_root = state_trie.hash_no_commit();
+    if state_root != batch.state_root {
+        return Err(SnapshotError::StateRootMismatch {
+            expected: batch.state_root,
+            actual: state_root,
+        });
+    }
+
+    let mut codes_writer = BufWriter::new(File::create(output_dir.join(CODES_FILE))?);
+    for code_hash in &code_hashes {
+        let code = store
+            .get_account_code(*code_hash)?
+            .ok_or(SnapshotError::InvalidSnapshot(format!(
+                "missing code for hash {code_hash:#x}"
+            )))?;
+        if keccak(&code.bytecode) != *code_hash {
+            return Err(SnapshotError::InvalidSnapshot(format!(
+                "stored code does not match its hash {code_hash:#x}"
+            )));
+        }
+        codes_writer.write_all(&code.bytecode.encode_to_vec())?;
+    }
+    codes_writer.flush()?;
+
+    let first_block = last_block.saturating_sub(SNAPSHOT_BLOCKS - 1).max(1);
+    let mut blocks_writer = BufWriter::new(File::create(output_dir.join(BLOCKS_FILE))?);
+    for block_number in first_block..=last_block {
+        let block = store.get_block_by_number(block_number).await?.ok_or(
+            SnapshotError::InvalidSnapshot(format!("block {block_number} is not in the store")),
+        )?;
+        blocks_writer.write_all(&block.encode_to_vec())?;
+    }
+    blocks_writer.flush()?;
+
+    let genesis_hash = store
+        .get_block_header(0)?
+        .ok_or(SnapshotError::InvalidSnapshot(
+            "genesis block is not in the store".to_owned(),
+        ))?
+        .hash();
+
+    let metadata = SnapshotMetadata {
+        version: SNAPSHOT_VERSION,
+        chain_id: store.get_chain_config().chain_id,
+        genesis_hash,
+        batch,
+        account_count,
+        code_count: code_hashes.len().try_into().unwrap_or(u64::MAX),
+        block_count: last_block - first_block + 1,
+    };
+    std::fs::write(
+        output_dir.join(METADATA_FILE),
+        serde_json::to_string_pretty(&metadata)?,
+    )?;
+
+    info!(
+        "Exported {} accounts, {} codes and {} blocks of batch {batch_number}",
+        metadata.account_count, metadata.code_count, metadata.block_count
+    );
+
+    Ok(metadata)
+}
+
+pub fn read_snapshot_metadata(snapshot_dir: &Path) -> Result<SnapshotMetadata, SnapshotError> {
+    let metadata: SnapshotMetadata =
+        serde_json::from_slice(&std::fs::read(snapshot_dir.join(METADATA_FILE))?)?;
+    if metadata.version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+            metadata.version
+        )));
+    }
+    Ok(metadata)
+}
+
+/// Returns the state root the snapshot's batch must have.
+/// When an L1 client is given this is the `newStateRoot` committed in the OnChainProposer,
+/// otherwise the one sealed in the local rollup store. The snapshot's own batch is only used
+/// when `trust_snapshot` is set, as checking the snapshot against itself proves nothing.
+pub async fn expected_state_root(
+    metadata: &SnapshotMetadata,
+    rollup_store: &StoreRollup,
+    l1: Option<(&EthClient, Address)>,
+    trust_snapshot: bool,
+) -> Result<H256, SnapshotError> {
+    let batch_number = metadata.batch.number;
+    if let Some((client, on_chain_proposer_address)) = l1 {
+        let state_root =
+            get_batch_committed_state_root(client, on_chain_proposer_address, batch_number).await?;
+        if state_root.is_zero() {
+            return Err(SnapshotError::InvalidSnapshot(format!(
+                "batch {batch_number} is not committed on L1"
+            )));
+        }
+        return Ok(state_root);
+    }
+    if let Some(state_root) = rollup_store.get_state_root_by_batch(batch_number).await? {
+        return Ok(state_root);
+    }
+    if !trust_snapshot {
+        return Err(SnapshotError::UntrustedSnapshot(batch_number));
+    }
+    Ok(metadata.batch.state_root)
+}
+
+/// Bootstraps an empty store from the snapshot in `snapshot_dir`.
+/// The rebuilt state root must match `expected_state_root`, after which the snapshot's blocks
+/// are made canonical and its batch is sealed in the rollup store.
+pub async fn import_snapshot(
+    store: &Store,
+    rollup_store: &StoreRollup,
+    snapshot_dir: &Path,
+    expected_state_root: H256,
+) -> Result<SnapshotMetadata, SnapshotError> {
+    let metadata = read_snapshot_metadata(snapshot_dir)?;
+    let batch = &metadata.batch;
+
+    let chain_id = store.get_chain_config().chain_id;
+    if metadata.chain_id != chain_id {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "snapshot is for chain id {}, but the node is configured for chain id {chain_id}",
+            metadata.chain_id
+        )));
+    }
+    let genesis_hash = store
+        .get_block_header(0)?
+        .ok_or(SnapshotError::InvalidSnapshot(
+            "genesis block is not in the store".to_owned(),
+        ))?
+        .hash();
+    if metadata.genesis_hash != genesis_hash {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "snapshot genesis {:#x} does not match the node's genesis {genesis_hash:#x}",
+            metadata.genesis_hash
+        )));
+    }
+    if store.get_latest_block_number().await? != 0 {
+        return Err(SnapshotError::InvalidSnapshot(
+            "the target store already has blocks past genesis".to_owned(),
+        ));
+    }
+    if batch.state_root != expected_state_root {
+        return Err(SnapshotError::StateRootMismatch {
+            expected: expected_state_root,
+            actual: batch.state_root,
+        });
+    }
+
+    info!(
+        "Importing state of batch {} (state root {expected_state_root:#x})",
+        batch.number
+    );
+
+    let mut code_hashes = BTreeSet::new();
+    for bytecode in RlpStreamReader::<Bytes>::open(&snapshot_dir.join(CODES_FILE))? {
+        let code = Code::from_bytecode(bytecode?);
+        code_hashes.insert(code.hash);
+        store.add_account_code(code).await?;
+    }
+
+    let mut state_trie = store.open_direct_state_trie(*EMPTY_TRIE_HASH)?;
+    let mut account_count: u64 = 0;
+    for account in RlpStreamReader::<SnapshotAccount>::open(&snapshot_dir.join(ACCOUNTS_FILE))? {
+        let account = account?;
+        if account.state.code_hash != *EMPTY_KECCACK_HASH
+            && !code_hashes.contains(&account.state.code_hash)
+        {
+            return Err(SnapshotError::InvalidSnapshot(format!(
+                "missing code {:#x} for account {:#x}",
+                account.state.code_hash, account.hashed_address
+            )));
+        }
+
+        // TODO(#5195): committing each storage trie individually is inefficient.
+        let mut storage_trie =
+            store.open_direct_storage_trie(account.hashed_address, *EMPTY_TRIE_HASH)?;
+        for (hashed_key, value) in &account.storage {
+            storage_trie.insert(hashed_key.0.to_vec(), value.encode_to_vec())?;
+        }
+        let storage_root = storage_trie.hash()?;
+        if storage_root != account.state.storage_root {
+            return Err(SnapshotError::StateRootMismatch {
+                expected: account.state.storage_root,
+                actual: storage_root,
+            });
+        }
+
+        state_trie.insert(
+            account.hashed_address.0.to_vec(),
+            account.state.encode_to_vec(),
+        )?;
+        account_count += 1;
+    }
+    if account_count != metadata.account_count {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "snapshot has {account_count} accounts, but its metadata lists {}",
+            metadata.account_count
+        )));
+    }
+    let state_root = state_trie.hash()?;
+    if state_root != expected_state_root {
+        return Err(SnapshotError::StateRootMismatch {
+            expected: expected_state_root,
+            actual: state_root,
+        });
+    }
+
+    // The amount of blocks is bounded by `SNAPSHOT_BLOCKS`, so they can be kept in memory
+    let blocks = RlpStreamReader::<Block>::open(&snapshot_dir.join(BLOCKS_FILE))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let head = blocks.last().ok_or(SnapshotError::InvalidSnapshot(
+        "snapshot has no blocks".to_owned(),
+    ))?;
+    let head_number = head.header.number;
+    let head_hash = head.hash();
+    if head_number != batch.last_block || head.header.state_root != expected_state_root {
+        return Err(SnapshotError::InvalidSnapshot(format!(
+            "last snapshot block {head_number} does not close batch {}",
+            batch.number
+        )));
+    }
+    for (parent, child) in blocks.iter().zip(blocks.iter().skip(1)) {
+        if child.header.parent_hash != parent.hash() {
+            return Err(SnapshotError::InvalidSnapshot(format!(
+                "block {} is not a child of block {}",
+                child.header.number, parent.header.number
+            )));
+        }
+    }
+    let canonical: Vec<(BlockNumber, H256)> = blocks
+        .iter()
+        .map(|block| (block.header.number, block.hash()))
+        .collect();
+
+    store.add_blocks(blocks).await?;
+    store
+        .forkchoice_update(
+            Some(canonical),
+            head_number,
+            head_hash,
+            Some(head_number),
+            Some(head_number),
+        )
+        .await?;
+
+    if !rollup_store.contains_batch(&batch.number).await? {
+        rollup_store.seal_batch(batch.clone()).await?;
+    }
+
+    info!(
+        "Imported {account_count} accounts and {} blocks up to block {head_number} (batch {})",
+        metadata.block_count, batch.number
+    );
+
+    Ok(metadata)
+}
+
+/// Reads the RLP items of a snapshot file one at a time, so that importing a snapshot doesn't
+/// need to hold whole files in memory
+struct RlpStreamReader<T> {
+    reader: BufReader<File>,
+    item: PhantomData<T>,
+}
+
+impl<T: RLPDecode> RlpStreamReader<T> {
+    fn open(path: &Path) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            reader: BufReader::new(File::open(path)?),
+            item: PhantomData,
+        })
+    }
+
+    /// Reads the next RLP item from the file, including its prefix, or `None` at the end of it
+    fn read_item(&mut self) -> Result<Option<Vec<u8>>, SnapshotError> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let mut prefix = [0; 1];
+        self.reader.read_exact(&mut prefix)?;
+        let [prefix] = prefix;
+        let mut item = vec![prefix];
+        let payload_len: u64 = match prefix {
+            0x00..=0x7f => 0,
+            0x80..=0xb7 => (prefix - 0x80).into(),
+            0xc0..=0xf7 => (prefix - 0xc0).into(),
+            // Long strings and lists are prefixed with the length of their length
+            0xb8..=0xbf | 0xf8..=0xff => {
+                let len_of_len = if prefix < 0xc0 {
+                    prefix - 0xb7
+                } else {
+                    prefix - 0xf7
+                };
+                let mut len = [0; 8];
+                let len_bytes = len
+                    .get_mut(8 - usize::from(len_of_len)..)
+                    .ok_or(RLPDecodeError::InvalidLength)?;
+                self.reader.read_exact(len_bytes)?;
+                item.extend_from_slice(len_bytes);
+                u64::from_be_bytes(len)
+            }
+        };
+        let read = (&mut self.reader)
+            .take(payload_len)
+            .read_to_end(&mut item)?;
+        if u64::try_from(read).ok() != Some(payload_len) {
+            return Err(RLPDecodeError::InvalidLength.into());
+        }
+        Ok(Some(item))
+    }
+}
+
+impl<T: RLPDecode> Iterator for RlpStreamReader<T> {
+    type Item = Result<T, SnapshotError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_item()
+            .transpose()
+            .map(|item| Ok(T::decode(&item?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_common::types::{BlockBody, BlockHeader, Genesis};
+    use ethrex_storage::EngineType;
+    use ethrex_storage_rollup::EngineTypeRollup;
+
+    use super::*;
+
+    async fn genesis_store() -> Store {
+        let file = File::open("../../fixtures/genesis/l2.json").unwrap();
+        let genesis: Genesis = serde_json::from_reader(BufReader::new(file)).unwrap();
+        let mut store = Store::new("", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        store
+    }
+
+    async fn empty_rollup_store() -> StoreRollup {
+        let rollup_store = StoreRollup::new(Path::new(""), EngineTypeRollup::InMemory).unwrap();
+        rollup_store.init().await.unwrap();
+        rollup_store
+    }
+
+    /// Stores with a block on top of genesis, sealed as batch 1
+    async fn chain_with_sealed_batch() -> (Store, StoreRollup, H256) {
+        let store = genesis_store().await;
+        let genesis = store.get_block_header(0).unwrap().unwrap();
+        let header = BlockHeader {
+            hash: Default::default(),
+            parent_hash: genesis.hash(),
+            number: 1,
+            timestamp: genesis.timestamp + 1,
+            ..genesis
+        };
+        let block = Block::new(header, BlockBody::default());
+        let (block_hash, state_root) = (block.hash(), block.header.state_root);
+        store.add_block(block).await.unwrap();
+        store
+            .forkchoice_update(Some(vec![(1, block_hash)]), 1, block_hash, None, None)
+            .await
+            .unwrap();
+
+        let rollup_store = empty_rollup_store().await;
+        rollup_store
+            .seal_batch(Batch {
+                number: 1,
+                first_block: 1,
+                last_block: 1,
+                state_root,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        (store, rollup_store, state_root)
+    }
+
+    fn accounts(store: &Store, state_root: H256) -> Vec<(H256, AccountState)> {
+        store.iter_accounts(state_root).unwrap().collect()
+    }
+
+    #[tokio::test]
+    async fn exported_snapshot_can_be_imported() {
+        let (store, rollup_store, state_root) = chain_with_sealed_batch().await;
+        let snapshot_dir = tempfile::tempdir().unwrap();
+        let exported = export_snapshot(&store, &rollup_store, None, snapshot_dir.path())
+            .await
+            .unwrap();
+        assert_eq!(exported.batch.number, 1);
+        assert!(exported.code_count > 0);
+
+        let target = genesis_store().await;
+        let target_rollup_store = empty_rollup_store().await;
+        let expected = expected_state_root(&exported, &rollup_store, None, false)
+            .await
+            .unwrap();
+        let imported =
+            import_snapshot(&target, &target_rollup_store, snapshot_dir.path(), expected)
+                .await
+                .unwrap();
+
+        assert_eq!(imported.account_count, exported.account_count);
+        assert_eq!(target.get_latest_block_number().await.unwrap(), 1);
+        assert_eq!(
+            target.get_block_header(1).unwrap().unwrap().hash(),
+            store.get_block_header(1).unwrap().unwrap().hash()
+        );
+        assert_eq!(accounts(&target, state_root), accounts(&store, state_root));
+        for (hashed_address, _) in accounts(&store, state_root) {
+            let storage = |store: &Store| -> Vec<(H256, U256)> {
+                store
+                    .iter_storage(state_root, hashed_address)
+                    .unwrap()
+                    .map(|iter| iter.collect())
+                    .unwrap_or_default()
+            };
+            assert_eq!(storage(&target), storage(&store));
+        }
+        assert_eq!(
+            target_rollup_store
+                .get_state_root_by_batch(1)
+                .await
+                .unwrap(),
+            Some(state_root)
+        );
+    }
+
+    #[tokio::test]
+    async fn tampered_snapshot_is_rejected() {
+        let (store, rollup_store, state_root) = chain_with_sealed_batch().await;
+        let snapshot_dir = tempfile::tempdir().unwrap();
+        export_snapshot(&store, &rollup_store, Some(1), snapshot_dir.path())
+            .await
+            .unwrap();
+
+        // Give some balance to the first exported account
+        let accounts_file = snapshot_dir.path().join(ACCOUNTS_FILE);
+        let mut tampered = Vec::new();
+        for (i, account) in RlpStreamReader::<SnapshotAccount>::open(&accounts_file)
+            .unwrap()
+            .enumerate()
+        {
+            let mut account = account.unwrap();
+            if i == 0 {
+                account.state.balance += U256::one();
+            }
+            tampered.extend(account.encode_to_vec());
+        }
+        std::fs::write(&accounts_file, tampered).unwrap();
+
+        let result = import_snapshot(
+            &genesis_store().await,
+            &empty_rollup_store().await,
+            snapshot_dir.path(),
+            state_root,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(SnapshotError::StateRootMismatch { expected, .. }) if expected == state_root
+        ));
+    }
+
+    #[tokio::test]
+    async fn snapshot_is_only_checked_against_itself_when_trusted() {
+        let (store, rollup_store, state_root) = chain_with_sealed_batch().await;
+        let snapshot_dir = tempfile::tempdir().unwrap();
+        let metadata = export_snapshot(&store, &rollup_store, None, snapshot_dir.path())
+            .await
+            .unwrap();
+        let unrelated_rollup_store = empty_rollup_store().await;
+
+        assert!(matches!(
+            expected_state_root(&metadata, &unrelated_rollup_store, None, false).await,
+            Err(SnapshotError::UntrustedSnapshot(1))
+        ));
+        assert_eq!(
+            expected_state_root(&metadata, &unrelated_rollup_store, None, true)
+                .await
+                .unwrap(),
+            state_root
+        );
+    }
+}
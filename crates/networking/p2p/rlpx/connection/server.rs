@@ -977,8 +977,15 @@ async fn handle_incoming_message(
         }
         Message::GetReceipts(GetReceipts { id, block_hashes }) if peer_supports_eth => {
             if let Some(eth) = &state.negotiated_eth_capability {
+                let earliest_block = state.storage.get_earliest_block_number().await?;
                 let mut receipts = Vec::new();
                 for hash in block_hashes.iter() {
+                    // Stop at the first block we can't serve, either unknown or with its
+                    // receipts expired, as an empty list would mean the block has no receipts
+                    match state.storage.get_block_number(*hash).await? {
+                        Some(number) if number >= earliest_block => {}
+                        _ => break,
+                    }
                     receipts.push(state.storage.get_receipts_for_block(hash).await?);
                 }
                 let response = match eth.version {
@@ -266,8 +266,10 @@ impl GetBlockBodies {
                         break;
                     }
                 }
+                // Bodies must be returned in the requested order, so stop at the first one
+                // we can't serve, either unknown or expired (EIP-4444)
                 Ok(None) => {
-                    continue;
+                    break;
                 }
                 Err(err) => {
                     error!(
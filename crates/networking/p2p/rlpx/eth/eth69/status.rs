@@ -94,6 +94,8 @@ impl StatusMessage69 {
 
         let genesis = genesis_header.hash();
         let lastest_block_hash = block_header.hash();
+        // Blocks below this one had their bodies and receipts expired (EIP-4444)
+        let earliest_block = storage.get_earliest_block_number().await?;
         let fork_id = ForkId::new(
             chain_config,
             genesis_header,
@@ -106,7 +108,7 @@ impl StatusMessage69 {
             network_id,
             genesis,
             fork_id,
-            earliest_block: 0,
+            earliest_block,
             lastest_block,
             lastest_block_hash,
         })
@@ -28,9 +28,11 @@ impl BlockRangeUpdate {
                     "Block {latest_block}"
                 )))?;
         let latest_block_hash = block_header.hash();
+        // Blocks below this one had their bodies and receipts expired (EIP-4444)
+        let earliest_block = storage.get_earliest_block_number().await?;
 
         Ok(Self {
-            earliest_block: 0,
+            earliest_block,
             latest_block,
             latest_block_hash,
         })
@@ -1,6 +1,8 @@
+use crate::discv4::peer_table::PeerTable;
 use crate::rlpx::connection::server::send;
 use crate::rlpx::l2::messages::{BatchSealed, L2Message, NewBlock};
 use crate::rlpx::{connection::server::Established, error::PeerConnectionError, message::Message};
+use crate::types::Node;
 use ethereum_types::Address;
 use ethereum_types::Signature;
 use ethrex_blockchain::error::ChainError;
@@ -109,9 +111,51 @@ impl L2ConnState {
     }
 }
 
-fn validate_signature(_recovered_lead_sequencer: Address) -> bool {
-    // Until the RPC module can be included in the P2P crate, we skip the validation
-    true
+/// Checks that a message about the given block was signed by the lead sequencer in charge of it,
+/// as tracked by the based `StateUpdater` or the `LeaderElector`, which keep the lead sequencer of
+/// every range of blocks.
+/// Messages signed by anyone else are dropped without penalizing the peer, as honest peers relay
+/// them too, e.g. when our view of the lead sequencer lags behind theirs after a failover.
+async fn is_signed_by_lead_sequencer(
+    store_rollup: &StoreRollup,
+    node: &Node,
+    block_number: u64,
+    recovered_lead_sequencer: Address,
+) -> Result<bool, PeerConnectionError> {
+    let Some(lead_sequencer) = store_rollup
+        .get_lead_sequencer_by_block(block_number)
+        .await?
+    else {
+        // The lead sequencer wasn't fetched from L1 yet, the message will be received again later
+        debug!("Lead sequencer for block {block_number} unknown, ignoring message");
+        return Ok(false);
+    };
+    if recovered_lead_sequencer == lead_sequencer {
+        return Ok(true);
+    }
+    debug!(
+        peer=%node,
+        signer=?recovered_lead_sequencer,
+        lead_sequencer=?lead_sequencer,
+        "Received message for block {block_number} not signed by the lead sequencer, ignoring it",
+    );
+    Ok(false)
+}
+
+/// Penalizes a peer that sent a message whose signature can't be recovered, which no honest
+/// peer would relay
+async fn record_invalid_signature(
+    peer_table: &mut PeerTable,
+    node: &Node,
+    error: impl std::fmt::Display,
+) -> Result<(), PeerConnectionError> {
+    warn!(
+        peer=%node,
+        %error,
+        "Received message with an invalid signature",
+    );
+    peer_table.record_critical_failure(&node.node_id()).await?;
+    Ok(())
 }
 
 pub(crate) async fn handle_based_capability_message(
@@ -302,21 +346,27 @@ async fn should_process_new_block(
 
     let msg_signature = msg.signature;
     let recovered_lead_sequencer =
-        tokio::task::spawn_blocking(move || recover_address(msg_signature, block_hash))
+        match tokio::task::spawn_blocking(move || recover_address(msg_signature, block_hash))
             .await
             .map_err(|_| {
                 PeerConnectionError::InternalError("Recover Address task failed".to_string())
-            })?
-            .map_err(|e| {
-                error!(
-                    peer=%established.node,
-                    error=%e,
-                    "Failed to recover lead sequencer",
-                );
-                PeerConnectionError::CryptographyError(e.to_string())
-            })?;
+            })? {
+            Ok(recovered_lead_sequencer) => recovered_lead_sequencer,
+            Err(error) => {
+                record_invalid_signature(&mut established.peer_table, &established.node, error)
+                    .await?;
+                return Ok(false);
+            }
+        };
 
-    if !validate_signature(recovered_lead_sequencer) {
+    if !is_signed_by_lead_sequencer(
+        &l2_state.store_rollup,
+        &established.node,
+        msg.block.header.number,
+        recovered_lead_sequencer,
+    )
+    .await?
+    {
         return Ok(false);
     }
     l2_state
@@ -357,16 +407,22 @@ async fn should_process_batch_sealed(
 
     let hash = batch_hash(&msg.batch);
 
-    let recovered_lead_sequencer = recover_address(msg.signature, hash).map_err(|e| {
-        error!(
-            peer=%established.node,
-            error=%e,
-            "Failed to recover lead sequencer",
-        );
-        PeerConnectionError::CryptographyError(e.to_string())
-    })?;
+    let recovered_lead_sequencer = match recover_address(msg.signature, hash) {
+        Ok(recovered_lead_sequencer) => recovered_lead_sequencer,
+        Err(error) => {
+            record_invalid_signature(&mut established.peer_table, &established.node, error).await?;
+            return Ok(false);
+        }
+    };
 
-    if !validate_signature(recovered_lead_sequencer) {
+    if !is_signed_by_lead_sequencer(
+        &l2_state.store_rollup,
+        &established.node,
+        msg.batch.last_block,
+        recovered_lead_sequencer,
+    )
+    .await?
+    {
         return Ok(false);
     }
     l2_state
@@ -495,9 +551,48 @@ async fn process_batch_sealed(
     Ok(())
 }
 
-// These tests are disabled because they previously assumed
+// The connection tests are disabled because they previously assumed
 // the connection used the old struct RLPxConnection, but
 // the new GenServer approach changes a lot of things,
 // this will be eventually addressed (#3563)
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::path::Path;
+
+    use ethrex_storage_rollup::EngineTypeRollup;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn only_messages_signed_by_the_lead_sequencer_are_accepted() {
+        let store_rollup = StoreRollup::new(Path::new(""), EngineTypeRollup::InMemory).unwrap();
+        let node = Node::from_enode_url(
+            "enode://d860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666@18.138.108.67:30303",
+        )
+        .unwrap();
+        let lead_sequencer = Address::repeat_byte(1);
+        let other_sequencer = Address::repeat_byte(2);
+
+        // Unknown lead sequencer
+        assert!(
+            !is_signed_by_lead_sequencer(&store_rollup, &node, 10, lead_sequencer)
+                .await
+                .unwrap()
+        );
+
+        store_rollup
+            .store_lead_sequencer_from_block(5, lead_sequencer)
+            .await
+            .unwrap();
+        assert!(
+            is_signed_by_lead_sequencer(&store_rollup, &node, 10, lead_sequencer)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !is_signed_by_lead_sequencer(&store_rollup, &node, 10, other_sequencer)
+                .await
+                .unwrap()
+        );
+    }
+}
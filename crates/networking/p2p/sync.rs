@@ -43,7 +43,7 @@ use std::{
 };
 use tokio::{sync::mpsc::error::SendError, time::Instant};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, warn};
+use tracing::{Span, debug, error, info, instrument, warn};
 
 /// The minimum amount of blocks from the head that we want to full sync during a snap sync
 const MIN_FULL_BLOCKS: u64 = 10_000;
@@ -153,14 +153,22 @@ impl Syncer {
     }
 
     /// Performs the sync cycle described in `start_sync`, returns an error if the sync fails at any given step and aborts all active processes
+    #[instrument(
+        level = "info",
+        name = "Sync cycle",
+        skip_all,
+        fields(?sync_head, sync.mode = tracing::field::Empty)
+    )]
     async fn sync_cycle(&mut self, sync_head: H256, store: Store) -> Result<(), SyncError> {
         // Take picture of the current sync mode, we will update the original value when we need to
         if self.snap_enabled.load(Ordering::Relaxed) {
+            Span::current().record("sync.mode", "snap");
             METRICS.enable().await;
             let sync_cycle_result = self.sync_cycle_snap(sync_head, store).await;
             METRICS.disable().await;
             sync_cycle_result
         } else {
+            Span::current().record("sync.mode", "full");
             self.sync_cycle_full(sync_head, store).await
         }
     }
@@ -993,6 +1001,11 @@ impl Syncer {
                 None,
             )
             .await?;
+        // Bodies and receipts before the pivot are never downloaded, so history is expired up
+        // to it. This also keeps peers from asking us for history we don't have.
+        store
+            .update_earliest_block_number(pivot_header.number)
+            .await?;
         Ok(())
     }
 }
@@ -71,6 +71,12 @@ pub enum EthClientError {
     GetBlobBaseFeeError(#[from] GetBlobBaseFeeRequestError),
     #[error("All RPC calls failed")]
     FailedAllRPC,
+    #[error("RPC server {0} rate limited the request")]
+    RateLimited(String),
+    #[error("Invalid batch response: {0}")]
+    InvalidBatchResponse(String),
+    #[error("Subscription error: {0}")]
+    SubscriptionError(String),
     #[error("Generic transaction error: {0}")]
     GenericTransactionError(#[from] GenericTransactionError),
     #[error("Failed to parse hex string: {0}")]
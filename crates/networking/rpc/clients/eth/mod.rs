@@ -1,4 +1,8 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
     clients::eth::errors::{
@@ -14,7 +18,7 @@ use crate::{
         receipt::{RpcLog, RpcReceipt},
         transaction::RpcTransaction,
     },
-    utils::{RpcErrorResponse, RpcRequest, RpcSuccessResponse},
+    utils::{RpcErrorResponse, RpcRequest, RpcRequestId, RpcSuccessResponse},
 };
 use bytes::Bytes;
 use errors::{
@@ -29,12 +33,18 @@ use ethrex_common::{
     utils::decode_hex,
 };
 use ethrex_rlp::decode::RLPDecode;
-use reqwest::{Client, Url};
-use serde::Deserialize;
+use reqwest::{Client, StatusCode, Url, header::RETRY_AFTER};
+use serde::{Deserialize, de::DeserializeOwned};
 use serde_json::{Value, json};
 use tracing::{debug, trace, warn};
+use transport::{
+    ProviderSet, RequestOutcome, RetryConfig, RpcMiddleware, order_batch_responses,
+    validate_batch_response,
+};
 
 pub mod errors;
+pub mod subscription;
+pub mod transport;
 
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
@@ -53,6 +63,9 @@ pub struct EthClient {
     pub max_retry_delay: u64,
     pub maximum_allowed_max_fee_per_gas: Option<u64>,
     pub maximum_allowed_max_fee_per_blob_gas: Option<u64>,
+    providers: ProviderSet,
+    retry_config: RetryConfig,
+    middleware: Option<Arc<dyn RpcMiddleware>>,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -106,6 +119,7 @@ impl EthClient {
     ) -> Result<Self, EthClientError> {
         Ok(Self {
             client: Client::new(),
+            providers: ProviderSet::new(urls.clone()),
             urls,
             max_number_of_retries,
             backoff_factor,
@@ -113,9 +127,34 @@ impl EthClient {
             max_retry_delay,
             maximum_allowed_max_fee_per_gas,
             maximum_allowed_max_fee_per_blob_gas,
+            retry_config: RetryConfig::default(),
+            middleware: None,
         })
     }
 
+    /// Sets the retry policy used for transport-level failures
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Sets a hook called around every HTTP request, e.g. to collect metrics
+    pub fn with_middleware(mut self, middleware: Arc<dyn RpcMiddleware>) -> Self {
+        self.middleware = Some(middleware);
+        self
+    }
+
+    /// Retry policy used for transport-level failures
+    pub fn retry_config(&self) -> RetryConfig {
+        self.retry_config
+    }
+
+    /// Returns the health score of the given RPC URL, from 0 to
+    /// [`transport::MAX_PROVIDER_SCORE`], or None if the URL isn't used by this client.
+    pub fn provider_score(&self, url: &Url) -> Option<u32> {
+        self.providers.score(url)
+    }
+
     pub fn new_with_multiple_urls(urls: Vec<Url>) -> Result<EthClient, EthClientError> {
         Self::new_with_config(
             urls,
@@ -128,29 +167,45 @@ impl EthClient {
         )
     }
 
-    /// Send a request to the RPC. Tries each URL until one succeeds.
+    /// Send a request to the RPC. Tries each URL, healthiest first, until one succeeds.
     pub async fn send_request(&self, request: RpcRequest) -> Result<RpcResponse, EthClientError> {
-        let mut response = Err(EthClientError::FailedAllRPC);
+        self.send_request_to_urls(self.providers.ordered(), &request)
+            .await
+    }
 
-        for url in self.urls.iter() {
-            response = self.send_request_to_url(url, &request).await;
-            // Some RPC servers don't implement all the endpoints or don't implement them completely/correctly
-            // so if the server returns Ok(RpcResponse::Error) we retry with the others
-            match &response {
-                Ok(RpcResponse::Success(_)) => {
-                    debug!(endpoint = %url, "RPC request successful");
-                    return response;
-                }
-                Ok(RpcResponse::Error(err)) => {
-                    debug!(endpoint = %url, error = ?err.error, "RPC server returned an error");
-                }
-                Err(error) => {
-                    warn!(endpoint = %url, %error, "Could not request RPC server");
-                }
-            }
+    /// Send several requests to the RPC in a single JSON-RPC batch.
+    ///
+    /// The request ids are overwritten with their position in the batch, and the
+    /// responses are returned in the same order as the requests. Tries each URL,
+    /// healthiest first, until one answers the whole batch.
+    pub async fn send_batch_request(
+        &self,
+        mut requests: Vec<RpcRequest>,
+    ) -> Result<Vec<RpcResponse>, EthClientError> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
         }
-
-        response
+        for (index, request) in (0u64..).zip(requests.iter_mut()) {
+            request.id = RpcRequestId::Number(index);
+        }
+        let body = serde_json::ser::to_string(&requests).map_err(|error| {
+            EthClientError::FailedToSerializeRequestBody(format!("{error}: {requests:?}"))
+        })?;
+        let batch_len = requests.len();
+
+        let responses = self
+            .post_with_failover(
+                self.providers.ordered(),
+                "batch",
+                &body,
+                |responses: &Vec<RpcResponse>| match validate_batch_response(responses, batch_len) {
+                    Ok(()) => RequestOutcome::Success,
+                    Err(_) => RequestOutcome::InvalidBatchResponse,
+                },
+            )
+            .await?;
+        debug!(batch_size = batch_len, "RPC batch request successful");
+        order_batch_responses(responses, batch_len)
     }
 
     /// Send a request to **all** RPC URLs.
@@ -163,49 +218,182 @@ impl EthClient {
         let mut response = Err(EthClientError::FailedAllRPC);
 
         for url in self.urls.iter() {
-            let maybe_response = self.send_request_to_url(url, &request).await;
-
-            match &maybe_response {
-                Ok(RpcResponse::Success(_)) => {
-                    debug!(endpoint = %url, "RPC request successful");
-                }
-                Ok(RpcResponse::Error(err)) => {
-                    debug!(endpoint = %url, error = ?err.error, "RPC server returned an error");
-                }
-                Err(error) => {
-                    warn!(endpoint = %url, %error, "Could not request RPC server");
-                }
-            };
-
+            let maybe_response = self.send_request_to_urls(vec![url.clone()], &request).await;
             response = response.or(maybe_response);
         }
 
         response
     }
 
-    /// Send a request to a specific URL.
-    async fn send_request_to_url(
+    /// Send a request to the given URLs in order until one succeeds, see
+    /// [`Self::post_with_failover`].
+    async fn send_request_to_urls(
         &self,
-        rpc_url: &Url,
+        urls: Vec<Url>,
         request: &RpcRequest,
     ) -> Result<RpcResponse, EthClientError> {
+        let body = serde_json::ser::to_string(&request).map_err(|error| {
+            EthClientError::FailedToSerializeRequestBody(format!("{error}: {request:?}"))
+        })?;
+        self.post_with_failover(urls, &request.method, &body, |response| match response {
+            RpcResponse::Success(_) => RequestOutcome::Success,
+            RpcResponse::Error(_) => RequestOutcome::RpcError,
+        })
+        .await
+    }
+
+    /// Posts a JSON body to the given URLs in order until one answers with a response
+    /// `outcome_of` considers a success.
+    ///
+    /// Some RPC servers don't implement all the endpoints or don't implement them
+    /// completely/correctly, so any other answer moves on to the next URL. Timeouts,
+    /// connection errors, 5xx and 429 responses move on to the next URL as well, and
+    /// the URLs that failed that way are retried with backoff once all of them were
+    /// tried. Returns the last answer or error if none succeeds.
+    async fn post_with_failover<T: DeserializeOwned + std::fmt::Debug>(
+        &self,
+        urls: Vec<Url>,
+        method: &str,
+        body: &str,
+        outcome_of: impl Fn(&T) -> RequestOutcome,
+    ) -> Result<T, EthClientError> {
+        let max_attempts = self.retry_config.max_attempts_per_provider.max(1);
+        let mut response = Err(EthClientError::FailedAllRPC);
+        let mut pending = urls;
+        let mut attempt = 1;
+        loop {
+            let mut retryable = Vec::new();
+            let mut retry_delay = Duration::ZERO;
+
+            for url in pending {
+                match self
+                    .post_once(&url, method, body, attempt, &outcome_of)
+                    .await
+                {
+                    Attempt::Succeeded(answer) => {
+                        debug!(endpoint = %url, "RPC request successful");
+                        return Ok(answer);
+                    }
+                    Attempt::Answered(answer) => {
+                        debug!(endpoint = %url, ?answer, "RPC server answered without success");
+                        response = Ok(answer);
+                    }
+                    Attempt::Failed { error, retry_after } => {
+                        warn!(endpoint = %url, %error, "Could not request RPC server");
+                        if let Some(delay) = retry_after {
+                            retry_delay = retry_delay.max(delay);
+                            retryable.push(url);
+                        }
+                        response = Err(error);
+                    }
+                }
+            }
+
+            if retryable.is_empty() || attempt >= max_attempts {
+                return response;
+            }
+            debug!(%method, ?retry_delay, retries = retryable.len(), "Retrying RPC request");
+            tokio::time::sleep(retry_delay).await;
+            attempt += 1;
+            // Retry the failed URLs, healthiest first
+            pending = self
+                .providers
+                .ordered()
+                .into_iter()
+                .filter(|url| retryable.contains(url))
+                .collect();
+        }
+    }
+
+    /// Makes a single HTTP round-trip, updating the provider health score and
+    /// reporting it to the middleware.
+    async fn post_once<T: DeserializeOwned + std::fmt::Debug>(
+        &self,
+        rpc_url: &Url,
+        method: &str,
+        body: &str,
+        attempt: u32,
+        outcome_of: &impl Fn(&T) -> RequestOutcome,
+    ) -> Attempt<T> {
         let id = uuid::Uuid::new_v4();
-        trace!(endpoint = %rpc_url, ?request, %id, "Sending RPC request");
+        trace!(endpoint = %rpc_url, %method, %id, attempt, "Sending RPC request");
+        if let Some(middleware) = &self.middleware {
+            middleware.on_request(rpc_url, method);
+        }
+        let start = Instant::now();
+        let result = self.post(rpc_url, body).await;
+        let elapsed = start.elapsed();
+
+        let (outcome, attempt_result) = match result {
+            Ok(answer) => {
+                trace!(endpoint = %rpc_url, %id, ?answer, "Response deserialized successfully");
+                let outcome = outcome_of(&answer);
+                if outcome == RequestOutcome::Success {
+                    self.providers.record_success(rpc_url);
+                    (outcome, Attempt::Succeeded(answer))
+                } else {
+                    (outcome, Attempt::Answered(answer))
+                }
+            }
+            Err(PostError::RateLimited(retry_after)) => {
+                let delay = retry_after
+                    .unwrap_or_else(|| self.retry_config.backoff(attempt))
+                    .min(self.retry_config.max_backoff);
+                self.providers.record_rate_limited(rpc_url, delay);
+                (
+                    RequestOutcome::RateLimited,
+                    Attempt::Failed {
+                        error: EthClientError::RateLimited(rpc_url.to_string()),
+                        retry_after: Some(delay),
+                    },
+                )
+            }
+            Err(PostError::Reqwest(error)) => {
+                let outcome = if error.is_timeout() {
+                    RequestOutcome::Timeout
+                } else {
+                    RequestOutcome::TransportError(error.to_string())
+                };
+                let retryable = error.is_timeout()
+                    || error.is_connect()
+                    || error
+                        .status()
+                        .is_some_and(|status| status.is_server_error());
+                self.providers.record_failure(rpc_url);
+                (
+                    outcome,
+                    Attempt::Failed {
+                        retry_after: retryable.then(|| self.retry_config.backoff(attempt)),
+                        error: error.into(),
+                    },
+                )
+            }
+        };
+        if let Some(middleware) = &self.middleware {
+            middleware.on_response(rpc_url, method, &outcome, elapsed);
+        }
+        attempt_result
+    }
 
-        self.client
+    async fn post<T: DeserializeOwned>(&self, rpc_url: &Url, body: &str) -> Result<T, PostError> {
+        let response = self
+            .client
             .post(rpc_url.as_str())
             .header("content-type", "application/json")
-            .body(serde_json::ser::to_string(&request).map_err(|error| {
-                EthClientError::FailedToSerializeRequestBody(format!("{error}: {request:?}"))
-            })?)
+            .timeout(self.retry_config.request_timeout)
+            .body(body.to_owned())
             .send()
-            .await
-            .inspect(|_| trace!(endpoint = %rpc_url, %id, "Request finished successfully"))?
-            .json::<RpcResponse>()
-            .await
-            .inspect(|body| trace!(endpoint = %rpc_url, %id, ?body, "Response deserialized successfully"))
-            .inspect_err(|err| trace!(endpoint = %rpc_url, %id, %err, "Failed to deserialize response"))
-            .map_err(EthClientError::from)
+            .await?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_secs);
+            return Err(PostError::RateLimited(retry_after));
+        }
+        Ok(response.error_for_status()?.json::<T>().await?)
     }
 
     pub async fn send_raw_transaction(&self, data: &[u8]) -> Result<H256, EthClientError> {
@@ -698,7 +886,7 @@ impl EthClient {
         let mut map = BTreeMap::new();
         for url in self.urls.iter() {
             let response = match self
-                .send_request_to_url(url, &RpcRequest::new("eth_blockNumber", None))
+                .send_request_to_urls(vec![url.clone()], &RpcRequest::new("eth_blockNumber", None))
                 .await
             {
                 Ok(RpcResponse::Success(ok)) => serde_json::to_value(ok).unwrap_or_else(|e| {
@@ -714,3 +902,317 @@ impl EthClient {
         map
     }
 }
+
+/// Failure of a single HTTP round-trip
+#[derive(Debug, thiserror::Error)]
+enum PostError {
+    #[error("rate limited")]
+    RateLimited(Option<Duration>),
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+}
+
+/// Result of a single HTTP round-trip, see [`EthClient::post_with_failover`]
+enum Attempt<T> {
+    Succeeded(T),
+    /// The provider answered, but not successfully. It isn't retried.
+    Answered(T),
+    /// The request failed, `retry_after` is set when it can be retried
+    Failed {
+        error: EthClientError,
+        retry_after: Option<Duration>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{
+        Router,
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::post,
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const BLOCK_NUMBER: &str = r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#;
+    const METHOD_NOT_FOUND: &str =
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"method not found"}}"#;
+
+    fn json_response(status: StatusCode, body: &'static str) -> Response {
+        (status, [("content-type", "application/json")], body).into_response()
+    }
+
+    /// Starts an HTTP server answering the n-th request it receives with `respond(n)`,
+    /// returning its URL and the number of requests it received
+    async fn start_provider(
+        respond: impl Fn(usize) -> Response + Send + Sync + 'static,
+    ) -> (Url, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let respond = Arc::new(respond);
+        let router = Router::new().route(
+            "/",
+            post({
+                let hits = hits.clone();
+                move || {
+                    let response = respond(hits.fetch_add(1, Ordering::SeqCst));
+                    async move { response }
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind provider");
+        let url = Url::parse(&format!(
+            "http://{}",
+            listener.local_addr().expect("provider address")
+        ))
+        .expect("valid url");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (url, hits)
+    }
+
+    /// Records the outcomes reported to the middleware
+    #[derive(Debug, Default)]
+    struct RecordingMiddleware {
+        requests: AtomicUsize,
+        outcomes: Mutex<Vec<(String, RequestOutcome)>>,
+    }
+
+    impl RpcMiddleware for RecordingMiddleware {
+        fn on_request(&self, _url: &Url, _method: &str) {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_response(&self, _url: &Url, method: &str, outcome: &RequestOutcome, _: Duration) {
+            self.outcomes
+                .lock()
+                .unwrap()
+                .push((method.to_string(), outcome.clone()));
+        }
+    }
+
+    // Retries without waiting between them
+    fn client(urls: Vec<Url>, max_attempts_per_provider: u32) -> EthClient {
+        EthClient::new_with_multiple_urls(urls)
+            .expect("create client")
+            .with_retry_config(RetryConfig {
+                max_attempts_per_provider,
+                initial_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+                ..Default::default()
+            })
+    }
+
+    #[test]
+    fn transport_retries_dont_use_the_gas_bump_delays() {
+        let url = Url::parse("http://localhost:8545").expect("valid url");
+        let config = EthClient::new(url).expect("create client").retry_config();
+        assert_eq!(config, RetryConfig::default());
+        assert!(config.backoff(1) < Duration::from_secs(1));
+        assert!(config.backoff(u32::MAX) < Duration::from_secs(MIN_RETRY_DELAY));
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let (url, hits) = start_provider(|hit| match hit {
+            0 => json_response(StatusCode::INTERNAL_SERVER_ERROR, ""),
+            1 => json_response(StatusCode::BAD_GATEWAY, ""),
+            _ => json_response(StatusCode::OK, BLOCK_NUMBER),
+        })
+        .await;
+        let client = client(vec![url], 3);
+
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_are_retried() {
+        let (url, hits) = start_provider(|hit| match hit {
+            0 => (StatusCode::TOO_MANY_REQUESTS, [("retry-after", "0")]).into_response(),
+            _ => json_response(StatusCode::OK, BLOCK_NUMBER),
+        })
+        .await;
+        let client = client(vec![url.clone()], 2);
+
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert!(client.provider_score(&url).unwrap() < transport::MAX_PROVIDER_SCORE);
+    }
+
+    #[tokio::test]
+    async fn failing_provider_fails_over_before_retrying() {
+        let (failing_url, failing_hits) =
+            start_provider(|_| json_response(StatusCode::SERVICE_UNAVAILABLE, "")).await;
+        let (healthy_url, healthy_hits) =
+            start_provider(|_| json_response(StatusCode::OK, BLOCK_NUMBER)).await;
+        let client = client(vec![failing_url, healthy_url], 3);
+
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(failing_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(healthy_hits.load(Ordering::SeqCst), 1);
+
+        // The healthy provider is now tried first
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(failing_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(healthy_hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failing_providers_are_retried_once_all_were_tried() {
+        let (first_url, first_hits) = start_provider(|hit| match hit {
+            0 => json_response(StatusCode::SERVICE_UNAVAILABLE, ""),
+            _ => json_response(StatusCode::OK, BLOCK_NUMBER),
+        })
+        .await;
+        let (second_url, second_hits) =
+            start_provider(|_| json_response(StatusCode::SERVICE_UNAVAILABLE, "")).await;
+        let client = client(vec![first_url, second_url], 2);
+
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(first_hits.load(Ordering::SeqCst), 2);
+        assert_eq!(second_hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rpc_and_client_errors_are_not_retried() {
+        let (rpc_error_url, rpc_error_hits) =
+            start_provider(|_| json_response(StatusCode::OK, METHOD_NOT_FOUND)).await;
+        let (bad_request_url, bad_request_hits) =
+            start_provider(|_| json_response(StatusCode::BAD_REQUEST, "")).await;
+        let (healthy_url, healthy_hits) =
+            start_provider(|_| json_response(StatusCode::OK, BLOCK_NUMBER)).await;
+        let client = client(vec![rpc_error_url, bad_request_url, healthy_url], 3);
+
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+        assert_eq!(rpc_error_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(bad_request_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(healthy_hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn all_providers_failing_returns_the_last_error() {
+        let (url, hits) = start_provider(|_| {
+            (StatusCode::TOO_MANY_REQUESTS, [("retry-after", "0")]).into_response()
+        })
+        .await;
+        let client = client(vec![url], 3);
+
+        assert!(matches!(
+            client.get_block_number().await,
+            Err(EthClientError::RateLimited(_))
+        ));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn batch_responses_are_returned_in_request_order() {
+        let (url, hits) = start_provider(|_| {
+            json_response(
+                StatusCode::OK,
+                r#"[{"jsonrpc":"2.0","id":1,"result":"0x1"},{"jsonrpc":"2.0","id":0,"result":"0x0"}]"#,
+            )
+        })
+        .await;
+        let client = client(vec![url], 1);
+
+        let responses = client
+            .send_batch_request(vec![
+                RpcRequest::new("eth_chainId", None),
+                RpcRequest::new("eth_blockNumber", None),
+            ])
+            .await
+            .unwrap();
+        let results: Vec<_> = responses
+            .iter()
+            .map(|response| match response {
+                RpcResponse::Success(success) => success.result.clone(),
+                RpcResponse::Error(_) => Value::Null,
+            })
+            .collect();
+        assert_eq!(results, vec![json!("0x0"), json!("0x1")]);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert!(
+            client
+                .send_batch_request(Vec::new())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_batch_response_moves_to_the_next_provider() {
+        let (invalid_url, invalid_hits) = start_provider(|_| {
+            json_response(
+                StatusCode::OK,
+                r#"[{"jsonrpc":"2.0","id":0,"result":"0x0"}]"#,
+            )
+        })
+        .await;
+        let client = client(vec![invalid_url.clone()], 3);
+        let batch = || {
+            vec![
+                RpcRequest::new("eth_chainId", None),
+                RpcRequest::new("eth_blockNumber", None),
+            ]
+        };
+
+        assert!(matches!(
+            client.send_batch_request(batch()).await,
+            Err(EthClientError::InvalidBatchResponse(_))
+        ));
+        assert_eq!(invalid_hits.load(Ordering::SeqCst), 1);
+
+        let (valid_url, valid_hits) = start_provider(|_| {
+            json_response(
+                StatusCode::OK,
+                r#"[{"jsonrpc":"2.0","id":0,"result":"0x0"},{"jsonrpc":"2.0","id":1,"result":"0x1"}]"#,
+            )
+        })
+        .await;
+        let client = self::client(vec![invalid_url, valid_url], 3);
+        assert_eq!(client.send_batch_request(batch()).await.unwrap().len(), 2);
+        assert_eq!(invalid_hits.load(Ordering::SeqCst), 2);
+        assert_eq!(valid_hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn middleware_sees_every_round_trip() {
+        let (url, _) = start_provider(|hit| match hit {
+            0 => json_response(StatusCode::SERVICE_UNAVAILABLE, ""),
+            1 => json_response(StatusCode::OK, METHOD_NOT_FOUND),
+            _ => json_response(StatusCode::OK, BLOCK_NUMBER),
+        })
+        .await;
+        let middleware = Arc::new(RecordingMiddleware::default());
+        let client = client(vec![url], 2).with_middleware(middleware.clone());
+
+        assert!(client.get_block_number().await.is_err());
+        assert_eq!(client.get_block_number().await.unwrap(), U256::from(16));
+
+        assert_eq!(middleware.requests.load(Ordering::SeqCst), 3);
+        let outcomes = middleware.outcomes.lock().unwrap().clone();
+        assert_eq!(outcomes.len(), 3);
+        assert!(matches!(
+            outcomes[0],
+            (_, RequestOutcome::TransportError(_))
+        ));
+        assert_eq!(
+            outcomes[1],
+            ("eth_blockNumber".to_string(), RequestOutcome::RpcError)
+        );
+        assert_eq!(
+            outcomes[2],
+            ("eth_blockNumber".to_string(), RequestOutcome::Success)
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! WebSocket subscriptions (`eth_subscribe`) for [`EthClient`].
+//!
+//! Subscriptions reconnect automatically, and the blocks missed while
+//! disconnected are backfilled through the client's HTTP providers, so the
+//! consumer sees a gapless stream.
+
+use std::time::Duration;
+
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockHeader, BlockNumber},
+};
+use futures::{SinkExt, StreamExt};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{net::TcpStream, sync::mpsc, task::JoinHandle};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+use tracing::{debug, warn};
+
+use super::{EthClient, errors::EthClientError};
+use crate::types::{block_identifier::BlockIdentifier, receipt::RpcLog};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Amount of events buffered before the subscription waits for the consumer
+const SUBSCRIPTION_CHANNEL_CAPACITY: usize = 1024;
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Amount of blocks requested per `eth_getLogs` call when backfilling logs
+const LOGS_BACKFILL_BLOCK_STEP: u64 = 1000;
+/// Maximum amount of headers fetched when backfilling `newHeads`, older ones are skipped
+const MAX_HEADS_BACKFILL: u64 = 256;
+
+/// Filter of a `logs` subscription
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    pub address: Address,
+    pub topics: Vec<H256>,
+}
+
+/// Stream of events of an `eth_subscribe` subscription.
+/// The subscription is closed when this value is dropped.
+#[derive(Debug)]
+pub struct Subscription<T> {
+    receiver: mpsc::Receiver<T>,
+    task: JoinHandle<()>,
+}
+
+impl<T> Subscription<T> {
+    /// Waits for the next event, returns None if the subscription task stopped
+    pub async fn next(&mut self) -> Option<T> {
+        self.receiver.recv().await
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Deserialize)]
+struct SubscriptionNotification {
+    params: SubscriptionNotificationParams,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionNotificationParams {
+    result: Value,
+}
+
+impl EthClient {
+    /// Subscribes to the logs matching `filter` through the WebSocket endpoint `ws_url`.
+    ///
+    /// Logs are delivered starting from `from_block`, ordered by block and log index.
+    /// Logs removed by a reorg are delivered with `removed` set.
+    pub fn subscribe_logs(
+        &self,
+        ws_url: Url,
+        from_block: BlockNumber,
+        filter: LogFilter,
+    ) -> Subscription<RpcLog> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CHANNEL_CAPACITY);
+        let task = tokio::spawn(run_logs_subscription(
+            self.clone(),
+            ws_url,
+            from_block,
+            filter,
+            sender,
+        ));
+        Subscription { receiver, task }
+    }
+
+    /// Subscribes to the new chain heads through the WebSocket endpoint `ws_url`.
+    ///
+    /// After a reconnection the headers of up to the last [`MAX_HEADS_BACKFILL`]
+    /// missed blocks are delivered before the new ones.
+    pub fn subscribe_new_heads(&self, ws_url: Url) -> Subscription<BlockHeader> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CHANNEL_CAPACITY);
+        let task = tokio::spawn(run_new_heads_subscription(self.clone(), ws_url, sender));
+        Subscription { receiver, task }
+    }
+}
+
+/// Tracks the logs already delivered, so the ones seen both in the backfill and
+/// in the subscription are delivered once
+#[derive(Debug)]
+struct LogsCursor {
+    /// First block whose logs may not have been delivered yet
+    next_block: BlockNumber,
+    last_delivered: Option<(BlockNumber, u64)>,
+}
+
+impl LogsCursor {
+    fn new(from_block: BlockNumber) -> Self {
+        Self {
+            next_block: from_block,
+            last_delivered: None,
+        }
+    }
+
+    /// Returns whether the log must be delivered, updating the cursor if so.
+    /// A removed log rewinds the cursor to just before it, so the logs replacing it
+    /// after the reorg are delivered even if they take the same position
+    fn accept(&mut self, log: &RpcLog) -> bool {
+        let position = (log.block_number, log.log_index);
+        if log.removed {
+            if self.last_delivered.is_some_and(|last| position <= last) {
+                self.next_block = log.block_number;
+                self.last_delivered = log
+                    .log_index
+                    .checked_sub(1)
+                    .map(|log_index| (log.block_number, log_index));
+            }
+            return true;
+        }
+        if log.block_number < self.next_block
+            || self.last_delivered.is_some_and(|last| position <= last)
+        {
+            return false;
+        }
+        self.next_block = log.block_number;
+        self.last_delivered = Some(position);
+        true
+    }
+}
+
+async fn run_logs_subscription(
+    client: EthClient,
+    ws_url: Url,
+    from_block: BlockNumber,
+    filter: LogFilter,
+    sender: mpsc::Sender<RpcLog>,
+) {
+    let params = json!([
+        "logs",
+        {
+            "address": format!("{:#x}", filter.address),
+            "topics": filter.topics.iter().map(|topic| format!("{topic:#x}")).collect::<Vec<_>>(),
+        }
+    ]);
+    let mut cursor = LogsCursor::new(from_block);
+    let mut reconnect_delay = INITIAL_RECONNECT_DELAY;
+    loop {
+        let result: Result<(), EthClientError> = async {
+            // Subscribe before backfilling so no log falls between both
+            let mut ws = subscribe(&ws_url, params.clone()).await?;
+            for log in backfill_logs(&client, &filter, cursor.next_block).await? {
+                if cursor.accept(&log) && sender.send(log).await.is_err() {
+                    return Ok(());
+                }
+            }
+            reconnect_delay = INITIAL_RECONNECT_DELAY;
+            loop {
+                let log: RpcLog = serde_json::from_value(next_notification(&mut ws).await?)
+                    .map_err(|error| EthClientError::SubscriptionError(error.to_string()))?;
+                if cursor.accept(&log) && sender.send(log).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        .await;
+        if let Err(error) = result {
+            warn!(endpoint = %ws_url, %error, "Logs subscription interrupted, reconnecting");
+        } else {
+            debug!(endpoint = %ws_url, "Logs subscription dropped");
+            return;
+        }
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+async fn backfill_logs(
+    client: &EthClient,
+    filter: &LogFilter,
+    from_block: BlockNumber,
+) -> Result<Vec<RpcLog>, EthClientError> {
+    let head = client.get_block_number().await?.as_u64();
+    let mut logs = Vec::new();
+    let mut start = from_block;
+    while start <= head {
+        let end = head.min(start + LOGS_BACKFILL_BLOCK_STEP - 1);
+        logs.extend(
+            client
+                .get_logs(
+                    U256::from(start),
+                    U256::from(end),
+                    filter.address,
+                    filter.topics.clone(),
+                )
+                .await?,
+        );
+        start = end + 1;
+    }
+    Ok(logs)
+}
+
+/// Tracks the last header delivered. Headers are delivered unless already seen
+/// or older than the last one, a different header at the same height means there was a reorg
+#[derive(Debug, Default)]
+struct HeadsCursor {
+    last_delivered: Option<(BlockNumber, H256)>,
+}
+
+impl HeadsCursor {
+    /// Returns whether the header must be delivered, updating the cursor if so
+    fn accept(&mut self, header: &BlockHeader) -> bool {
+        let hash = header.hash();
+        if self
+            .last_delivered
+            .is_some_and(|(number, last_hash)| header.number < number || hash == last_hash)
+        {
+            return false;
+        }
+        self.last_delivered = Some((header.number, hash));
+        true
+    }
+}
+
+async fn run_new_heads_subscription(
+    client: EthClient,
+    ws_url: Url,
+    sender: mpsc::Sender<BlockHeader>,
+) {
+    let mut cursor = HeadsCursor::default();
+    let mut reconnect_delay = INITIAL_RECONNECT_DELAY;
+    loop {
+        let result: Result<(), EthClientError> = async {
+            let mut ws = subscribe(&ws_url, json!(["newHeads"])).await?;
+            if let Some((last_number, _)) = cursor.last_delivered {
+                for header in backfill_headers(&client, last_number + 1).await? {
+                    if cursor.accept(&header) && sender.send(header).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            reconnect_delay = INITIAL_RECONNECT_DELAY;
+            loop {
+                let header: BlockHeader = serde_json::from_value(next_notification(&mut ws).await?)
+                    .map_err(|error| EthClientError::SubscriptionError(error.to_string()))?;
+                if cursor.accept(&header) && sender.send(header).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        .await;
+        if let Err(error) = result {
+            warn!(endpoint = %ws_url, %error, "New heads subscription interrupted, reconnecting");
+        } else {
+            debug!(endpoint = %ws_url, "New heads subscription dropped");
+            return;
+        }
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+async fn backfill_headers(
+    client: &EthClient,
+    from_block: BlockNumber,
+) -> Result<Vec<BlockHeader>, EthClientError> {
+    let head = client.get_block_number().await?.as_u64();
+    let from_block = from_block.max(head.saturating_sub(MAX_HEADS_BACKFILL - 1));
+    let mut headers = Vec::new();
+    for number in from_block..=head {
+        let block = client
+            .get_block_by_number(BlockIdentifier::Number(number), false)
+            .await?;
+        headers.push(block.header);
+    }
+    Ok(headers)
+}
+
+/// Connects to `ws_url` and sends an `eth_subscribe` request with the given params
+async fn subscribe(ws_url: &Url, params: Value) -> Result<WsStream, EthClientError> {
+    let (mut ws, _) = connect_async(ws_url.as_str())
+        .await
+        .map_err(|error| EthClientError::SubscriptionError(error.to_string()))?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_subscribe",
+        "params": params,
+    });
+    ws.send(Message::text(request.to_string()))
+        .await
+        .map_err(|error| EthClientError::SubscriptionError(error.to_string()))?;
+
+    loop {
+        let response = next_message(&mut ws).await?;
+        if response.get("id") != Some(&json!(1)) {
+            continue;
+        }
+        if let Some(error) = response.get("error") {
+            return Err(EthClientError::SubscriptionError(format!(
+                "eth_subscribe failed: {error}"
+            )));
+        }
+        debug!(endpoint = %ws_url, subscription = ?response.get("result"), "Subscribed");
+        return Ok(ws);
+    }
+}
+
+/// Waits for the next `eth_subscription` notification and returns its result
+async fn next_notification(ws: &mut WsStream) -> Result<Value, EthClientError> {
+    loop {
+        let message = next_message(ws).await?;
+        if let Ok(notification) = serde_json::from_value::<SubscriptionNotification>(message) {
+            return Ok(notification.params.result);
+        }
+    }
+}
+
+/// Waits for the next JSON message, answering pings along the way
+async fn next_message(ws: &mut WsStream) -> Result<Value, EthClientError> {
+    loop {
+        let message = ws
+            .next()
+            .await
+            .ok_or_else(|| EthClientError::SubscriptionError("connection closed".to_owned()))?
+            .map_err(|error| EthClientError::SubscriptionError(error.to_string()))?;
+        let text = match message {
+            Message::Text(text) => text.to_string(),
+            Message::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Message::Close(_) => {
+                return Err(EthClientError::SubscriptionError(
+                    "connection closed".to_owned(),
+                ));
+            }
+            Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+        };
+        return serde_json::from_str(&text)
+            .map_err(|error| EthClientError::SubscriptionError(error.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::types::receipt::RpcLogInfo;
+
+    fn log(block_number: u64, log_index: u64, removed: bool) -> RpcLog {
+        RpcLog {
+            log: RpcLogInfo {
+                address: Address::zero(),
+                topics: vec![],
+                data: Bytes::new(),
+            },
+            log_index,
+            removed,
+            transaction_hash: H256::zero(),
+            transaction_index: 0,
+            block_hash: H256::zero(),
+            block_number,
+        }
+    }
+
+    #[test]
+    fn logs_cursor_skips_already_delivered_logs() {
+        let mut cursor = LogsCursor::new(10);
+        // Before the requested start
+        assert!(!cursor.accept(&log(9, 0, false)));
+        assert!(cursor.accept(&log(10, 0, false)));
+        assert!(cursor.accept(&log(10, 1, false)));
+        // Delivered again by the subscription after a backfill
+        assert!(!cursor.accept(&log(10, 1, false)));
+        assert!(cursor.accept(&log(12, 0, false)));
+        assert_eq!(cursor.next_block, 12);
+        // Reorged logs are always delivered
+        assert!(cursor.accept(&log(12, 0, true)));
+    }
+
+    #[test]
+    fn logs_cursor_delivers_logs_replaced_by_a_reorg() {
+        let mut cursor = LogsCursor::new(10);
+        assert!(cursor.accept(&log(10, 0, false)));
+        assert!(cursor.accept(&log(11, 0, false)));
+        assert!(cursor.accept(&log(11, 1, false)));
+
+        // The log is removed and included again at the same position
+        assert!(cursor.accept(&log(11, 1, true)));
+        assert!(cursor.accept(&log(11, 1, false)));
+        assert!(!cursor.accept(&log(11, 1, false)));
+
+        // Both logs of a block are removed and the block is replaced
+        assert!(cursor.accept(&log(11, 1, true)));
+        assert!(cursor.accept(&log(11, 0, true)));
+        assert!(cursor.accept(&log(11, 0, false)));
+        assert!(cursor.accept(&log(11, 1, false)));
+
+        // Logs delivered before the removed one are still skipped
+        assert!(cursor.accept(&log(11, 1, true)));
+        assert!(!cursor.accept(&log(11, 0, false)));
+        assert!(!cursor.accept(&log(10, 0, false)));
+
+        // Removing a log never delivered doesn't rewind the cursor
+        assert!(cursor.accept(&log(11, 1, false)));
+        assert!(cursor.accept(&log(13, 0, true)));
+        assert!(!cursor.accept(&log(11, 1, false)));
+    }
+
+    #[test]
+    fn heads_cursor_skips_seen_and_older_headers() {
+        let header = |number: u64, gas_used: u64| BlockHeader {
+            number,
+            gas_used,
+            ..Default::default()
+        };
+        let mut cursor = HeadsCursor::default();
+        assert!(cursor.accept(&header(5, 0)));
+        assert!(!cursor.accept(&header(5, 0)));
+        assert!(!cursor.accept(&header(4, 0)));
+        // Reorg at the same height
+        assert!(cursor.accept(&header(5, 1)));
+        assert!(cursor.accept(&header(6, 0)));
+    }
+}
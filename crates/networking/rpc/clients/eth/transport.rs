@@ -0,0 +1,347 @@
+//! Provider selection, retries and request hooks used by [`super::EthClient`].
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::Url;
+
+use super::{RpcResponse, errors::EthClientError};
+use crate::utils::RpcRequestId;
+
+/// Score every provider starts with, and the maximum it can reach
+pub const MAX_PROVIDER_SCORE: u32 = 100;
+/// Score gained by a provider after a successful request
+const SUCCESS_SCORE_REWARD: u32 = 10;
+/// Score lost by a provider after a failed request
+const FAILURE_SCORE_PENALTY: u32 = 25;
+
+pub const DEFAULT_MAX_ATTEMPTS_PER_PROVIDER: u32 = 3;
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Retry policy for transport-level failures (timeouts, connection errors,
+/// 5xx and 429 responses). It is independent from the client's retry settings,
+/// which are meant for resending transactions with bumped gas and wait minutes
+/// between attempts.
+///
+/// A failing provider is only retried once every other provider has been tried,
+/// so requests fail over to the next healthy provider before waiting. JSON-RPC
+/// error responses are never retried on the same provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Attempts made against a single provider before giving up on it
+    pub max_attempts_per_provider: u32,
+    /// Delay before the first retry, doubled on every following one
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between retries
+    pub max_backoff: Duration,
+    /// Timeout of a single HTTP request
+    pub request_timeout: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts_per_provider: DEFAULT_MAX_ATTEMPTS_PER_PROVIDER,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay to wait before the given retry, starting at 1
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Result of a single HTTP round-trip, as reported to the [`RpcMiddleware`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// The provider answered with a JSON-RPC success response
+    Success,
+    /// The provider answered with a JSON-RPC error response
+    RpcError,
+    /// The provider answered with a batch response not matching the batch sent
+    InvalidBatchResponse,
+    /// The provider answered with HTTP 429
+    RateLimited,
+    /// The request timed out
+    Timeout,
+    /// Any other transport failure
+    TransportError(String),
+}
+
+/// Hook called around every HTTP round-trip made by the client, meant for
+/// metrics and logging. Batched requests are reported once per batch with
+/// the method set to `"batch"`.
+pub trait RpcMiddleware: Debug + Send + Sync {
+    fn on_request(&self, _url: &Url, _method: &str) {}
+
+    fn on_response(&self, url: &Url, method: &str, outcome: &RequestOutcome, elapsed: Duration);
+}
+
+#[derive(Debug, Clone)]
+struct ProviderHealth {
+    score: u32,
+    /// Set when the provider rate-limited us, it is only used as a last resort until then
+    cooldown_until: Option<Instant>,
+}
+
+impl Default for ProviderHealth {
+    fn default() -> Self {
+        Self {
+            score: MAX_PROVIDER_SCORE,
+            cooldown_until: None,
+        }
+    }
+}
+
+/// Health scores of the RPC providers, shared among the clones of a client
+#[derive(Debug, Clone)]
+pub struct ProviderSet {
+    urls: Vec<Url>,
+    health: Arc<Mutex<Vec<ProviderHealth>>>,
+}
+
+impl ProviderSet {
+    pub fn new(urls: Vec<Url>) -> Self {
+        let health = vec![ProviderHealth::default(); urls.len()];
+        Self {
+            urls,
+            health: Arc::new(Mutex::new(health)),
+        }
+    }
+
+    pub fn urls(&self) -> &[Url] {
+        &self.urls
+    }
+
+    /// Returns the providers ordered by preference: the ones not cooling down
+    /// first, then by highest score. Ties keep the configured order.
+    pub fn ordered(&self) -> Vec<Url> {
+        let now = Instant::now();
+        let Ok(health) = self.health.lock() else {
+            return self.urls.clone();
+        };
+        let mut indexes: Vec<usize> = (0..self.urls.len()).collect();
+        indexes.sort_by_key(|&index| {
+            let provider = &health[index];
+            let cooling_down = provider.cooldown_until.is_some_and(|until| until > now);
+            (cooling_down, std::cmp::Reverse(provider.score))
+        });
+        indexes
+            .into_iter()
+            .map(|index| self.urls[index].clone())
+            .collect()
+    }
+
+    pub fn score(&self, url: &Url) -> Option<u32> {
+        let index = self.index_of(url)?;
+        self.health.lock().ok().map(|health| health[index].score)
+    }
+
+    pub fn record_success(&self, url: &Url) {
+        self.update(url, |provider| {
+            provider.score = provider
+                .score
+                .saturating_add(SUCCESS_SCORE_REWARD)
+                .min(MAX_PROVIDER_SCORE);
+            provider.cooldown_until = None;
+        });
+    }
+
+    pub fn record_failure(&self, url: &Url) {
+        self.update(url, |provider| {
+            provider.score = provider.score.saturating_sub(FAILURE_SCORE_PENALTY);
+        });
+    }
+
+    pub fn record_rate_limited(&self, url: &Url, cooldown: Duration) {
+        self.update(url, |provider| {
+            provider.score = provider.score.saturating_sub(FAILURE_SCORE_PENALTY);
+            provider.cooldown_until = Some(Instant::now() + cooldown);
+        });
+    }
+
+    fn index_of(&self, url: &Url) -> Option<usize> {
+        self.urls.iter().position(|provider| provider == url)
+    }
+
+    fn update(&self, url: &Url, f: impl FnOnce(&mut ProviderHealth)) {
+        let Some(index) = self.index_of(url) else {
+            return;
+        };
+        if let Ok(mut health) = self.health.lock() {
+            f(&mut health[index]);
+        }
+    }
+}
+
+/// Checks that a batch response has exactly one response for every id assigned by
+/// [`super::EthClient::send_batch_request`] (the position of each request in the batch).
+pub fn validate_batch_response(
+    responses: &[RpcResponse],
+    batch_len: usize,
+) -> Result<(), EthClientError> {
+    if responses.len() != batch_len {
+        return Err(EthClientError::InvalidBatchResponse(format!(
+            "expected {batch_len} responses, got {}",
+            responses.len()
+        )));
+    }
+    let mut seen = vec![false; batch_len];
+    for response in responses {
+        let id = response_id(response);
+        let Some(slot) = batch_index(id).and_then(|index| seen.get_mut(index)) else {
+            return Err(EthClientError::InvalidBatchResponse(format!(
+                "unexpected response id {id:?}"
+            )));
+        };
+        if *slot {
+            return Err(EthClientError::InvalidBatchResponse(format!(
+                "duplicated response id {id:?}"
+            )));
+        }
+        *slot = true;
+    }
+    Ok(())
+}
+
+/// Puts the responses of a batch back in request order, see [`validate_batch_response`].
+pub fn order_batch_responses(
+    mut responses: Vec<RpcResponse>,
+    batch_len: usize,
+) -> Result<Vec<RpcResponse>, EthClientError> {
+    validate_batch_response(&responses, batch_len)?;
+    // Every id is a distinct position in the batch, so this puts each response in its slot
+    responses.sort_by_key(|response| batch_index(response_id(response)));
+    Ok(responses)
+}
+
+fn response_id(response: &RpcResponse) -> &RpcRequestId {
+    match response {
+        RpcResponse::Success(success) => &success.id,
+        RpcResponse::Error(error) => &error.id,
+    }
+}
+
+fn batch_index(id: &RpcRequestId) -> Option<usize> {
+    match id {
+        RpcRequestId::Number(id) => usize::try_from(*id).ok(),
+        RpcRequestId::String(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::utils::RpcSuccessResponse;
+
+    fn success(id: u64) -> RpcResponse {
+        RpcResponse::Success(RpcSuccessResponse {
+            id: RpcRequestId::Number(id),
+            jsonrpc: "2.0".to_string(),
+            result: json!(id),
+        })
+    }
+
+    fn result_of(response: &RpcResponse) -> Option<u64> {
+        match response {
+            RpcResponse::Success(success) => success.result.as_u64(),
+            RpcResponse::Error(_) => None,
+        }
+    }
+
+    fn urls() -> Vec<Url> {
+        ["http://a.test", "http://b.test", "http://c.test"]
+            .into_iter()
+            .map(|url| Url::parse(url).expect("valid url"))
+            .collect()
+    }
+
+    #[test]
+    fn healthy_providers_keep_configured_order() {
+        let providers = ProviderSet::new(urls());
+        assert_eq!(providers.ordered(), urls());
+    }
+
+    #[test]
+    fn failing_provider_is_moved_back() {
+        let urls = urls();
+        let providers = ProviderSet::new(urls.clone());
+        providers.record_failure(&urls[0]);
+        assert_eq!(
+            providers.ordered(),
+            vec![urls[1].clone(), urls[2].clone(), urls[0].clone()]
+        );
+        // Recovering restores its position
+        providers.record_success(&urls[0]);
+        providers.record_success(&urls[0]);
+        providers.record_success(&urls[0]);
+        assert_eq!(providers.ordered(), urls);
+    }
+
+    #[test]
+    fn rate_limited_provider_is_used_last() {
+        let urls = urls();
+        let providers = ProviderSet::new(urls.clone());
+        providers.record_failure(&urls[2]);
+        providers.record_failure(&urls[2]);
+        providers.record_rate_limited(&urls[0], Duration::from_secs(60));
+        assert_eq!(
+            providers.ordered(),
+            vec![urls[1].clone(), urls[2].clone(), urls[0].clone()]
+        );
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_bounded() {
+        let config = RetryConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            ..Default::default()
+        };
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(400));
+        assert_eq!(config.backoff(4), Duration::from_millis(500));
+        assert_eq!(config.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn default_backoff_stays_short() {
+        let config = RetryConfig::default();
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert!(config.backoff(u32::MAX) <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn batch_responses_are_put_in_request_order() {
+        let ordered = order_batch_responses(vec![success(2), success(0), success(1)], 3)
+            .expect("valid batch");
+        let results: Vec<_> = ordered.iter().map(result_of).collect();
+        assert_eq!(results, vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn invalid_batch_responses_are_rejected() {
+        // Missing response
+        assert!(order_batch_responses(vec![success(0)], 2).is_err());
+        // Unknown id
+        assert!(order_batch_responses(vec![success(0), success(5)], 2).is_err());
+        // Duplicated id
+        assert!(order_batch_responses(vec![success(1), success(1)], 2).is_err());
+    }
+}
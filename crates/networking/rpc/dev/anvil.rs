@@ -0,0 +1,222 @@
+use ethrex_common::{
+    Address, BigEndianHash, H256, U256,
+    types::{
+        Code, EIP1559Transaction, GenericTransaction, MempoolTransaction, Transaction, TxType,
+    },
+};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    dev::{DevState, get_param, mine_if_automine, parse_quantity},
+    eth::transaction::EstimateGasRequest,
+    rpc::{RpcApiContext, RpcHandler},
+    utils::{RpcErr, RpcRequest, parse_json_hex},
+};
+
+/// Handling of rpc endpoint `anvil_setBalance`
+pub async fn set_balance(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    let balance: U256 = serde_json::from_value(get_param(req, 1, "balance")?.clone())?;
+    dev.update_account(address, |info, _| info.balance = balance)
+        .await?;
+    Ok(Value::Null)
+}
+
+/// Handling of rpc endpoint `anvil_setCode`
+pub async fn set_code(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    let code = get_param(req, 1, "code")?
+        .as_str()
+        .ok_or(RpcErr::WrongParam("Expected hex string".to_string()))?;
+    let code = hex::decode(code.trim_start_matches("0x"))
+        .map_err(|_| RpcErr::BadParams(format!("Invalid bytecode {code}")))?;
+    let code = Code::from_bytecode(code.into());
+    dev.update_account(address, |info, account_update| {
+        info.code_hash = code.hash;
+        account_update.code = Some(code);
+    })
+    .await?;
+    Ok(Value::Null)
+}
+
+/// Handling of rpc endpoint `anvil_setStorageAt`
+pub async fn set_storage_at(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    let slot: U256 = serde_json::from_value(get_param(req, 1, "slot")?.clone())?;
+    let value: U256 = serde_json::from_value(get_param(req, 2, "value")?.clone())?;
+    dev.update_account(address, |_, account_update| {
+        account_update
+            .added_storage
+            .insert(H256::from_uint(&slot), value);
+    })
+    .await?;
+    Ok(Value::Bool(true))
+}
+
+/// Handling of rpc endpoint `anvil_setNonce`
+pub async fn set_nonce(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    let nonce = parse_quantity(get_param(req, 1, "nonce")?)?;
+    dev.update_account(address, |info, _| info.nonce = nonce)
+        .await?;
+    Ok(Value::Null)
+}
+
+/// Handling of rpc endpoint `anvil_impersonateAccount`
+pub fn impersonate_account(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    dev.set_impersonated(address, true)?;
+    Ok(Value::Null)
+}
+
+/// Handling of rpc endpoint `anvil_stopImpersonatingAccount`
+pub fn stop_impersonating_account(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let address: Address = serde_json::from_value(get_param(req, 0, "address")?.clone())?;
+    dev.set_impersonated(address, false)?;
+    Ok(Value::Null)
+}
+
+/// Handling of rpc endpoint `eth_sendTransaction`, only available in dev mode for the accounts
+/// impersonated through `anvil_impersonateAccount`.
+/// The transaction is added to the mempool with the impersonated account as sender and a
+/// placeholder signature, see [`impersonate`]. It is only executed by [`DevState::mine`], which
+/// takes the senders from the mempool instead of recovering them.
+pub async fn send_transaction(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    let Some(dev) = context.dev.clone() else {
+        return Err(RpcErr::MethodNotFound(req.method.clone()));
+    };
+    let transaction: GenericTransaction =
+        serde_json::from_value(get_param(req, 0, "transaction")?.clone())?;
+    let sender = transaction.from;
+    if !dev.is_impersonated(&sender)? {
+        return Err(RpcErr::BadParams(format!(
+            "Account {sender:#x} is not impersonated"
+        )));
+    }
+    if !matches!(transaction.r#type, TxType::Legacy | TxType::EIP1559) {
+        return Err(RpcErr::BadParams(format!(
+            "Unsupported transaction type {:?}",
+            transaction.r#type
+        )));
+    }
+
+    let mut transaction = build_eip1559_transaction(transaction, &context).await?;
+    impersonate(&mut transaction, sender);
+    let transaction = Transaction::EIP1559Transaction(transaction);
+    let hash = transaction.hash();
+    debug!("Received transaction {hash:#x} from impersonated account {sender:#x}");
+
+    if let Some(tx_to_replace) = context
+        .blockchain
+        .validate_transaction(&transaction, sender)
+        .await?
+    {
+        context
+            .blockchain
+            .mempool
+            .replace_transaction(&tx_to_replace, hash)?;
+    }
+    context
+        .blockchain
+        .mempool
+        .add_transaction(hash, MempoolTransaction::new(transaction, sender))?;
+
+    mine_if_automine(&context).await?;
+    Ok(Value::String(format!("{hash:#x}")))
+}
+
+/// Replaces the signature of a transaction sent from an impersonated account with a placeholder
+/// carrying `sender`, so the RPC can show it, see [`impersonated_sender`].
+/// The placeholder's `r` is zero, which no valid signature has, and its `s` is the sender.
+fn impersonate(transaction: &mut EIP1559Transaction, sender: Address) {
+    transaction.signature_y_parity = false;
+    transaction.signature_r = U256::zero();
+    transaction.signature_s = U256::from_big_endian(sender.as_bytes());
+    transaction.inner_hash = Default::default();
+}
+
+/// Returns the sender carried by the placeholder signature of a transaction sent from an
+/// impersonated account, see [`impersonate`].
+///
+/// This is only used to show the sender through the RPC. Recovering the sender of such a
+/// transaction fails, so it can't enter the mempool or a block other than through
+/// [`send_transaction`] and [`DevState::mine`].
+pub(crate) fn impersonated_sender(transaction: &Transaction) -> Option<Address> {
+    match transaction {
+        Transaction::EIP1559Transaction(transaction) if transaction.signature_r.is_zero() => {
+            Some(Address::from(H256::from_uint(&transaction.signature_s)))
+        }
+        _ => None,
+    }
+}
+
+/// Fills in the fields left out of an `eth_sendTransaction` request, leaving the signature empty
+async fn build_eip1559_transaction(
+    transaction: GenericTransaction,
+    context: &RpcApiContext,
+) -> Result<EIP1559Transaction, RpcErr> {
+    let latest_block_number = context.storage.get_latest_block_number().await?;
+    let latest_header = context
+        .storage
+        .get_block_header(latest_block_number)?
+        .ok_or(RpcErr::Internal(format!(
+            "Missing latest block with number {latest_block_number}"
+        )))?;
+
+    let nonce = match transaction.nonce {
+        Some(nonce) => nonce,
+        None => {
+            let account_nonce = context
+                .storage
+                .get_nonce_by_account_address(latest_block_number, transaction.from)
+                .await?
+                .unwrap_or_default();
+            let mempool_nonce = context
+                .blockchain
+                .mempool
+                .get_nonce(&transaction.from)?
+                .unwrap_or_default();
+            account_nonce.max(mempool_nonce)
+        }
+    };
+    let gas_limit = match transaction.gas {
+        Some(gas) => gas,
+        None => {
+            let estimate = EstimateGasRequest {
+                transaction: transaction.clone(),
+                block: None,
+            }
+            .handle(context.clone())
+            .await?;
+            parse_json_hex(&estimate).map_err(RpcErr::Internal)?
+        }
+    };
+    let max_priority_fee_per_gas = transaction.max_priority_fee_per_gas.unwrap_or_default();
+    let max_fee_per_gas = match transaction.max_fee_per_gas {
+        Some(max_fee_per_gas) => max_fee_per_gas,
+        None if transaction.gas_price != 0 => transaction.gas_price,
+        None => latest_header
+            .base_fee_per_gas
+            .unwrap_or_default()
+            .saturating_mul(2)
+            .saturating_add(max_priority_fee_per_gas),
+    };
+
+    Ok(EIP1559Transaction {
+        chain_id: context.storage.get_chain_config().chain_id,
+        nonce,
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+        gas_limit,
+        to: transaction.to,
+        value: transaction.value,
+        data: transaction.input,
+        access_list: transaction
+            .access_list
+            .into_iter()
+            .map(|entry| (entry.address, entry.storage_keys))
+            .collect(),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,594 @@
+//! Anvil/Hardhat compatible `evm_*` and `anvil_*` namespaces, only served when the node runs
+//! with `--dev`.
+//!
+//! Blocks are built and stored directly through the [`Blockchain`] and the [`Store`] instead of
+//! going through the Engine API, so the chain can be mined on demand, rewound to a snapshot or
+//! have its state overridden.
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use ethrex_blockchain::{
+    Blockchain,
+    fork_choice::apply_fork_choice,
+    payload::{BuildPayloadArgs, create_payload},
+};
+use ethrex_common::{
+    Address, H256,
+    types::{
+        AccountInfo, AccountUpdate, Block, BlockHash, BlockHeader, BlockNumber,
+        ELASTICITY_MULTIPLIER, Fork, Receipt,
+    },
+};
+use ethrex_storage::{AccountUpdatesList, Store};
+use ethrex_vm::BlockExecutionResult;
+use serde_json::Value;
+use tokio::sync::{Mutex as TokioMutex, watch};
+use tracing::{info, warn};
+
+use crate::{
+    rpc::RpcApiContext,
+    utils::{RpcErr, RpcRequest, parse_json_hex},
+};
+
+mod anvil;
+pub(crate) use anvil::impersonated_sender;
+pub use anvil::{
+    impersonate_account, send_transaction, set_balance, set_code, set_nonce, set_storage_at,
+    stop_impersonating_account,
+};
+
+/// Blocks are mined every second by default, as the Engine API producer used to do.
+pub const DEFAULT_MINING_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug)]
+pub struct DevState {
+    storage: Store,
+    blockchain: Arc<Blockchain>,
+    coinbase: Address,
+    gas_ceil: u64,
+    extra_data: Bytes,
+    /// Whether a block is mined right after every transaction is received
+    automine: AtomicBool,
+    /// Time between blocks mined by [`DevState::interval_mining`], `None` if disabled
+    mining_interval: watch::Sender<Option<Duration>>,
+    /// Serializes every change to the head of the chain
+    chain: TokioMutex<DevChainState>,
+    impersonated_accounts: Mutex<HashSet<Address>>,
+}
+
+#[derive(Debug, Default)]
+struct DevChainState {
+    /// Seconds added to the system time for the timestamp of new blocks
+    time_offset: u64,
+    next_snapshot_id: u64,
+    snapshots: BTreeMap<u64, Snapshot>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    head_number: BlockNumber,
+    head_hash: BlockHash,
+    time_offset: u64,
+}
+
+impl DevState {
+    pub fn new(
+        storage: Store,
+        blockchain: Arc<Blockchain>,
+        gas_ceil: u64,
+        extra_data: Bytes,
+    ) -> Self {
+        let (mining_interval, _) = watch::channel(Some(DEFAULT_MINING_INTERVAL));
+        Self {
+            storage,
+            blockchain,
+            coinbase: Address::default(),
+            gas_ceil,
+            extra_data,
+            automine: AtomicBool::new(false),
+            mining_interval,
+            chain: TokioMutex::new(DevChainState::default()),
+            impersonated_accounts: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn automine(&self) -> bool {
+        self.automine.load(Ordering::Relaxed)
+    }
+
+    /// Mines a block every time the interval set through `evm_setIntervalMining` elapses.
+    pub async fn interval_mining(self: Arc<Self>) {
+        let mut interval_receiver = self.mining_interval.subscribe();
+        loop {
+            let interval = *interval_receiver.borrow_and_update();
+            let Some(interval) = interval else {
+                // Wait until interval mining is enabled again
+                if interval_receiver.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {
+                    if let Err(error) = self.mine(None).await {
+                        warn!("Failed to mine block: {error}");
+                    }
+                }
+                changed = interval_receiver.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds a block with the transactions in the mempool on top of the current head and makes
+    /// it the new head. If `timestamp` is given, the clock is moved so that later blocks follow it.
+    ///
+    /// The transactions are executed with the senders stored along them in the mempool and the
+    /// block is stored without being executed again, so the senders are never recovered from
+    /// the signatures. This is what lets the transactions sent from impersonated accounts in,
+    /// see [`send_transaction`].
+    pub async fn mine(&self, timestamp: Option<u64>) -> Result<BlockHash, RpcErr> {
+        let mut chain = self.chain.lock().await;
+        let head = self.head_header().await?;
+        let timestamp = match timestamp {
+            Some(timestamp) if timestamp <= head.timestamp => {
+                return Err(RpcErr::BadParams(format!(
+                    "Timestamp {timestamp} is not greater than the head block timestamp {}",
+                    head.timestamp
+                )));
+            }
+            Some(timestamp) => {
+                chain.time_offset = timestamp.saturating_sub(unix_time()?);
+                timestamp
+            }
+            None => next_timestamp(&chain, &head)?,
+        };
+
+        let payload = self.create_payload(&head, timestamp)?;
+        let payload_build_result = self
+            .blockchain
+            .build_payload(payload)
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        let block = payload_build_result.payload;
+        let account_updates_list = self
+            .storage
+            .apply_account_updates_batch(head.hash(), &payload_build_result.account_updates)?
+            .ok_or(RpcErr::Internal("Head block state not found".to_string()))?;
+
+        self.store_new_head(block, account_updates_list, payload_build_result.receipts)
+            .await
+    }
+
+    /// Applies `modify` to the state of `address` at the head of the chain. As state can only
+    /// be read at a block, the change is stored in a new empty block.
+    pub(crate) async fn update_account<F>(&self, address: Address, modify: F) -> Result<(), RpcErr>
+    where
+        F: FnOnce(&mut AccountInfo, &mut AccountUpdate),
+    {
+        let chain = self.chain.lock().await;
+        let head = self.head_header().await?;
+
+        let mut info = self
+            .storage
+            .get_account_info_by_hash(head.hash(), address)?
+            .unwrap_or_default();
+        let mut account_update = AccountUpdate::new(address);
+        modify(&mut info, &mut account_update);
+        account_update.info = Some(info);
+
+        let mut block = self.create_payload(&head, next_timestamp(&chain, &head)?)?;
+        let account_updates_list = self
+            .storage
+            .apply_account_updates_batch(head.hash(), &[account_update])?
+            .ok_or(RpcErr::Internal("Head block state not found".to_string()))?;
+        block.header.state_root = account_updates_list.state_trie_hash;
+
+        self.store_new_head(block, account_updates_list, Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn snapshot(&self) -> Result<u64, RpcErr> {
+        let mut chain = self.chain.lock().await;
+        let head = self.head_header().await?;
+        chain.next_snapshot_id += 1;
+        let id = chain.next_snapshot_id;
+        let snapshot = Snapshot {
+            head_number: head.number,
+            head_hash: head.hash(),
+            time_offset: chain.time_offset,
+        };
+        chain.snapshots.insert(id, snapshot);
+        Ok(id)
+    }
+
+    /// Moves the head back to the block of the snapshot `id`. The snapshot, and every snapshot
+    /// taken after it, can't be reverted to anymore. Returns false if the snapshot doesn't exist.
+    pub async fn revert(&self, id: u64) -> Result<bool, RpcErr> {
+        let mut chain = self.chain.lock().await;
+        let Some(snapshot) = chain.snapshots.remove(&id) else {
+            return Ok(false);
+        };
+        chain.snapshots.retain(|snapshot_id, _| *snapshot_id < id);
+
+        self.storage
+            .forkchoice_update(
+                None,
+                snapshot.head_number,
+                snapshot.head_hash,
+                Some(snapshot.head_number),
+                Some(snapshot.head_number),
+            )
+            .await?;
+        chain.time_offset = snapshot.time_offset;
+        info!(
+            "Reverted to block {} ({:#x})",
+            snapshot.head_number, snapshot.head_hash
+        );
+        Ok(true)
+    }
+
+    /// Moves the clock forward by `seconds` and returns the total time added.
+    pub async fn increase_time(&self, seconds: u64) -> u64 {
+        let mut chain = self.chain.lock().await;
+        chain.time_offset = chain.time_offset.saturating_add(seconds);
+        chain.time_offset
+    }
+
+    pub(crate) fn is_impersonated(&self, address: &Address) -> Result<bool, RpcErr> {
+        Ok(self
+            .impersonated_accounts
+            .lock()
+            .map_err(|_| RpcErr::Internal("Impersonated accounts lock was poisoned".to_string()))?
+            .contains(address))
+    }
+
+    pub(crate) fn set_impersonated(
+        &self,
+        address: Address,
+        impersonate: bool,
+    ) -> Result<(), RpcErr> {
+        let mut impersonated_accounts = self
+            .impersonated_accounts
+            .lock()
+            .map_err(|_| RpcErr::Internal("Impersonated accounts lock was poisoned".to_string()))?;
+        if impersonate {
+            impersonated_accounts.insert(address);
+        } else {
+            impersonated_accounts.remove(&address);
+        }
+        Ok(())
+    }
+
+    async fn head_header(&self) -> Result<BlockHeader, RpcErr> {
+        let head_number = self.storage.get_latest_block_number().await?;
+        self.storage
+            .get_block_header(head_number)?
+            .ok_or(RpcErr::Internal(format!(
+                "Missing head block with number {head_number}"
+            )))
+    }
+
+    fn create_payload(&self, head: &BlockHeader, timestamp: u64) -> Result<Block, RpcErr> {
+        let chain_config = self.storage.get_chain_config();
+        let args = BuildPayloadArgs {
+            parent: head.hash(),
+            timestamp,
+            fee_recipient: self.coinbase,
+            random: H256::zero(),
+            withdrawals: chain_config
+                .is_fork_activated(Fork::Shanghai, timestamp)
+                .then(Vec::new),
+            beacon_root: chain_config
+                .is_fork_activated(Fork::Cancun, timestamp)
+                .then_some(H256::zero()),
+            version: 3,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: self.gas_ceil,
+        };
+        create_payload(&args, &self.storage, self.extra_data.clone())
+            .map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+
+    async fn store_new_head(
+        &self,
+        block: Block,
+        account_updates_list: AccountUpdatesList,
+        receipts: Vec<Receipt>,
+    ) -> Result<BlockHash, RpcErr> {
+        let block_number = block.header.number;
+        let block_hash = block.hash();
+        let transactions_count = block.body.transactions.len();
+
+        self.blockchain
+            .remove_block_transactions_from_pool(&block)?;
+        let execution_result = BlockExecutionResult {
+            receipts,
+            requests: Vec::new(),
+        };
+        self.blockchain
+            .store_block(block, account_updates_list, execution_result)
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        apply_fork_choice(&self.storage, block_hash, block_hash, block_hash)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+
+        info!(
+            "Mined block {block_number} ({block_hash:#x}), transaction_count {transactions_count}"
+        );
+        Ok(block_hash)
+    }
+}
+
+/// Mines a block if automine is enabled, to be called after a transaction is received.
+pub async fn mine_if_automine(context: &RpcApiContext) -> Result<(), RpcErr> {
+    if let Some(dev) = &context.dev
+        && dev.automine()
+    {
+        dev.mine(None).await?;
+    }
+    Ok(())
+}
+
+/// Handling of rpc endpoint `evm_mine`, with an optional timestamp for the new block
+pub async fn mine(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let timestamp = match req.params.as_ref().and_then(|params| params.first()) {
+        Some(timestamp) => Some(parse_quantity(timestamp)?),
+        None => None,
+    };
+    dev.mine(timestamp).await?;
+    Ok(Value::String("0x0".to_string()))
+}
+
+/// Handling of rpc endpoint `evm_setAutomine`
+pub fn set_automine(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let automine = get_param(req, 0, "automine")?
+        .as_bool()
+        .ok_or(RpcErr::WrongParam("Expected boolean".to_string()))?;
+    dev.automine.store(automine, Ordering::Relaxed);
+    Ok(Value::Bool(true))
+}
+
+/// Handling of rpc endpoint `evm_setIntervalMining`, where an interval of 0 disables it
+pub fn set_interval_mining(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let interval_ms = parse_quantity(get_param(req, 0, "interval")?)?;
+    let interval = (interval_ms != 0).then(|| Duration::from_millis(interval_ms));
+    dev.mining_interval.send_replace(interval);
+    Ok(Value::Bool(true))
+}
+
+/// Handling of rpc endpoint `evm_snapshot`
+pub async fn snapshot(dev: &DevState) -> Result<Value, RpcErr> {
+    let id = dev.snapshot().await?;
+    Ok(Value::String(format!("{id:#x}")))
+}
+
+/// Handling of rpc endpoint `evm_revert`
+pub async fn revert(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let id = parse_quantity(get_param(req, 0, "snapshot id")?)?;
+    Ok(Value::Bool(dev.revert(id).await?))
+}
+
+/// Handling of rpc endpoint `evm_increaseTime`
+pub async fn increase_time(req: &RpcRequest, dev: &DevState) -> Result<Value, RpcErr> {
+    let seconds = parse_quantity(get_param(req, 0, "seconds")?)?;
+    Ok(Value::from(dev.increase_time(seconds).await))
+}
+
+fn get_param<'a>(req: &'a RpcRequest, index: usize, name: &str) -> Result<&'a Value, RpcErr> {
+    req.params
+        .as_ref()
+        .and_then(|params| params.get(index))
+        .ok_or(RpcErr::MissingParam(name.to_string()))
+}
+
+/// Parses a quantity given either as a JSON number or as a hex string
+fn parse_quantity(value: &Value) -> Result<u64, RpcErr> {
+    match value {
+        Value::Number(number) => number.as_u64().ok_or(RpcErr::WrongParam(format!(
+            "Expected unsigned integer, got {number}"
+        ))),
+        _ => parse_json_hex(value).map_err(RpcErr::BadParams),
+    }
+}
+
+/// Timestamp for the next block, which always moves forward even if blocks are mined in the
+/// same second
+fn next_timestamp(chain: &DevChainState, head: &BlockHeader) -> Result<u64, RpcErr> {
+    let now = unix_time()?.saturating_add(chain.time_offset);
+    Ok(now.max(head.timestamp.saturating_add(1)))
+}
+
+fn unix_time() -> Result<u64, RpcErr> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|error| RpcErr::Internal(error.to_string()))?
+        .as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::map_http_requests,
+        test_utils::{default_context_with_storage, setup_store},
+    };
+    use ethrex_common::{U256, types::DEFAULT_BUILDER_GAS_CEIL};
+    use serde_json::json;
+
+    async fn dev_context() -> RpcApiContext {
+        let mut context = default_context_with_storage(setup_store().await).await;
+        context.dev = Some(Arc::new(DevState::new(
+            context.storage.clone(),
+            context.blockchain.clone(),
+            DEFAULT_BUILDER_GAS_CEIL,
+            Bytes::new(),
+        )));
+        context
+    }
+
+    async fn request(context: &RpcApiContext, method: &str, params: Value) -> Value {
+        let params = serde_json::from_value(params).unwrap();
+        map_http_requests(&RpcRequest::new(method, Some(params)), context.clone())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dev_methods_not_found_without_dev_mode() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let result = map_http_requests(&RpcRequest::new("evm_mine", None), context).await;
+        assert!(matches!(result, Err(RpcErr::MethodNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn mine_and_revert_to_snapshot() {
+        let context = dev_context().await;
+
+        let snapshot_id = request(&context, "evm_snapshot", json!([])).await;
+        assert_eq!(snapshot_id, json!("0x1"));
+
+        request(&context, "evm_mine", json!([])).await;
+        request(&context, "evm_mine", json!([])).await;
+        assert_eq!(context.storage.get_latest_block_number().await.unwrap(), 2);
+
+        assert_eq!(
+            request(&context, "evm_revert", json!(["0x1"])).await,
+            json!(true)
+        );
+        assert_eq!(context.storage.get_latest_block_number().await.unwrap(), 0);
+        // A snapshot can only be reverted to once
+        assert_eq!(
+            request(&context, "evm_revert", json!(["0x1"])).await,
+            json!(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn set_balance_and_storage() {
+        let context = dev_context().await;
+        let address = Address::from_low_u64_be(0xdead);
+
+        request(&context, "anvil_setBalance", json!([address, "0x2a"])).await;
+        request(
+            &context,
+            "anvil_setStorageAt",
+            json!([address, "0x1", format!("{:#066x}", 7)]),
+        )
+        .await;
+
+        let latest_block_number = context.storage.get_latest_block_number().await.unwrap();
+        let info = context
+            .storage
+            .get_account_info(latest_block_number, address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.balance, U256::from(0x2a));
+        let value = context
+            .storage
+            .get_storage_at(latest_block_number, address, H256::from_low_u64_be(1))
+            .unwrap();
+        assert_eq!(value, Some(U256::from(7)));
+    }
+
+    #[tokio::test]
+    async fn send_transaction_from_impersonated_account() {
+        let context = dev_context().await;
+        let sender = Address::from_low_u64_be(0xdead);
+        let recipient = Address::from_low_u64_be(0xbeef);
+        let transaction = json!([{
+            "from": sender,
+            "to": recipient,
+            "value": "0x1",
+            "gas": "0x5208",
+        }]);
+
+        request(
+            &context,
+            "anvil_setBalance",
+            json!([sender, "0xde0b6b3a7640000"]),
+        )
+        .await;
+        let result = map_http_requests(
+            &RpcRequest::new(
+                "eth_sendTransaction",
+                Some(serde_json::from_value(transaction.clone()).unwrap()),
+            ),
+            context.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(RpcErr::BadParams(_))));
+
+        request(&context, "anvil_impersonateAccount", json!([sender])).await;
+        request(&context, "evm_setAutomine", json!([true])).await;
+        let hash = request(&context, "eth_sendTransaction", transaction).await;
+
+        // Mined right away, and the sender is recovered when reading it back
+        let block_number = context.storage.get_latest_block_number().await.unwrap();
+        let block = request(
+            &context,
+            "eth_getBlockByNumber",
+            json!([format!("{block_number:#x}"), true]),
+        )
+        .await;
+        assert_eq!(block["transactions"][0]["hash"], hash);
+        assert_eq!(block["transactions"][0]["from"], json!(sender));
+        let tx = request(&context, "eth_getTransactionByHash", json!([hash])).await;
+        assert_eq!(tx["from"], json!(sender));
+        let receipt = request(&context, "eth_getTransactionReceipt", json!([hash])).await;
+        assert_eq!(receipt["from"], json!(sender));
+        assert_eq!(receipt["status"], json!("0x1"));
+
+        // The placeholder signature is never accepted outside of the dev layer
+        let stored = context
+            .storage
+            .get_block_body(block_number)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.transactions[0].sender().is_err());
+        assert!(
+            context
+                .blockchain
+                .add_transaction_to_pool(stored.transactions[0].clone())
+                .await
+                .is_err()
+        );
+
+        let info = context
+            .storage
+            .get_account_info(block_number, recipient)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.balance, U256::one());
+
+        // Stopping the impersonation rejects new transactions
+        request(&context, "anvil_stopImpersonatingAccount", json!([sender])).await;
+        let result = map_http_requests(
+            &RpcRequest::new(
+                "eth_sendTransaction",
+                Some(
+                    serde_json::from_value(
+                        json!([{ "from": sender, "to": recipient, "gas": "0x5208" }]),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            context.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(RpcErr::BadParams(_))));
+    }
+}
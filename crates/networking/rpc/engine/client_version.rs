@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+
+/// Two-letter client code used in `engine_getClientVersionV1`
+pub const CLIENT_CODE: &str = "EX";
+
+/// Commit reported when the client version carries no git info
+const UNKNOWN_COMMIT: &str = "0x00000000";
+
+/// More info: https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#clientversionv1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientVersionV1 {
+    pub code: String,
+    pub name: String,
+    pub version: String,
+    /// First 4 bytes of the commit hash, hex encoded
+    pub commit: String,
+}
+
+impl ClientVersionV1 {
+    /// Builds the version from the client version string of the node, with the format
+    /// `{name}/v{version}-{branch}-{commit}/{os}/rustc-v{rustc_version}`
+    pub fn from_client_version(client_version: &str) -> Self {
+        let mut parts = client_version.split('/');
+        let name = parts.next().unwrap_or_default().to_owned();
+        let build = parts.next().unwrap_or_default();
+        let build = build.strip_prefix('v').unwrap_or(build);
+        let version = build.split('-').next().unwrap_or_default().to_owned();
+        let commit = build
+            .rsplit_once('-')
+            .map(|(_, sha)| sha)
+            .filter(|sha| sha.len() >= 8 && sha.chars().all(|c| c.is_ascii_hexdigit()))
+            .map(|sha| format!("0x{}", &sha[..8]))
+            .unwrap_or_else(|| UNKNOWN_COMMIT.to_owned());
+        Self {
+            code: CLIENT_CODE.to_owned(),
+            name,
+            version,
+            commit,
+        }
+    }
+}
+
+pub struct GetClientVersionV1Request {
+    /// Version of the consensus client calling us, only used for logging
+    pub client_version: ClientVersionV1,
+}
+
+impl RpcHandler for GetClientVersionV1Request {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        }
+        Ok(GetClientVersionV1Request {
+            client_version: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        debug!(
+            "Consensus client version: {} {} ({})",
+            self.client_version.name, self.client_version.version, self.client_version.commit
+        );
+        Ok(json!([ClientVersionV1::from_client_version(
+            &context.node_data.client_version
+        )]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_version_from_full_version_string() {
+        let version = ClientVersionV1::from_client_version(
+            "ethrex/v7.0.0-main-fa4ff922b1c3d8e0/x86_64-unknown-linux-gnu/rustc-v1.90.0",
+        );
+        assert_eq!(
+            version,
+            ClientVersionV1 {
+                code: "EX".to_owned(),
+                name: "ethrex".to_owned(),
+                version: "7.0.0".to_owned(),
+                commit: "0xfa4ff922".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn client_version_with_dashed_branch() {
+        let version = ClientVersionV1::from_client_version(
+            "ethrex/v7.0.0-feat-engine-api-0123456789abcdef/aarch64-apple-darwin/rustc-v1.90.0",
+        );
+        assert_eq!(version.version, "7.0.0");
+        assert_eq!(version.commit, "0x01234567");
+    }
+
+    #[test]
+    fn client_version_without_git_info() {
+        let version = ClientVersionV1::from_client_version("ethrex/test");
+        assert_eq!(version.name, "ethrex");
+        assert_eq!(version.version, "test");
+        assert_eq!(version.commit, UNKNOWN_COMMIT);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod blobs;
+pub mod client_version;
 pub mod exchange_transition_config;
 pub mod fork_choice;
 pub mod payload;
@@ -12,27 +13,95 @@ use serde_json::{Value, json};
 
 pub type ExchangeCapabilitiesRequest = Vec<String>;
 
-/// List of capabilities that the execution layer client supports. Add new capabilities here.
+/// Engine API methods routed by `map_engine_requests`. Add new methods here when routing them,
+/// the capabilities exchanged with the consensus client are derived from `EngineMethod::ALL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineMethod {
+    ExchangeCapabilities,
+    GetClientVersionV1,
+    ForkChoiceUpdatedV1,
+    ForkChoiceUpdatedV2,
+    ForkChoiceUpdatedV3,
+    NewPayloadV1,
+    NewPayloadV2,
+    NewPayloadV3,
+    NewPayloadV4,
+    GetPayloadV1,
+    GetPayloadV2,
+    GetPayloadV3,
+    GetPayloadV4,
+    GetPayloadV5,
+    ExchangeTransitionConfigurationV1,
+    GetPayloadBodiesByHashV1,
+    GetPayloadBodiesByRangeV1,
+    GetBlobsV1,
+    GetBlobsV2,
+}
+
+impl EngineMethod {
+    pub const ALL: [EngineMethod; 19] = [
+        EngineMethod::ExchangeCapabilities,
+        EngineMethod::GetClientVersionV1,
+        EngineMethod::ForkChoiceUpdatedV1,
+        EngineMethod::ForkChoiceUpdatedV2,
+        EngineMethod::ForkChoiceUpdatedV3,
+        EngineMethod::NewPayloadV1,
+        EngineMethod::NewPayloadV2,
+        EngineMethod::NewPayloadV3,
+        EngineMethod::NewPayloadV4,
+        EngineMethod::GetPayloadV1,
+        EngineMethod::GetPayloadV2,
+        EngineMethod::GetPayloadV3,
+        EngineMethod::GetPayloadV4,
+        EngineMethod::GetPayloadV5,
+        EngineMethod::ExchangeTransitionConfigurationV1,
+        EngineMethod::GetPayloadBodiesByHashV1,
+        EngineMethod::GetPayloadBodiesByRangeV1,
+        EngineMethod::GetBlobsV1,
+        EngineMethod::GetBlobsV2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EngineMethod::ExchangeCapabilities => "engine_exchangeCapabilities",
+            EngineMethod::GetClientVersionV1 => "engine_getClientVersionV1",
+            EngineMethod::ForkChoiceUpdatedV1 => "engine_forkchoiceUpdatedV1",
+            EngineMethod::ForkChoiceUpdatedV2 => "engine_forkchoiceUpdatedV2",
+            EngineMethod::ForkChoiceUpdatedV3 => "engine_forkchoiceUpdatedV3",
+            EngineMethod::NewPayloadV1 => "engine_newPayloadV1",
+            EngineMethod::NewPayloadV2 => "engine_newPayloadV2",
+            EngineMethod::NewPayloadV3 => "engine_newPayloadV3",
+            EngineMethod::NewPayloadV4 => "engine_newPayloadV4",
+            EngineMethod::GetPayloadV1 => "engine_getPayloadV1",
+            EngineMethod::GetPayloadV2 => "engine_getPayloadV2",
+            EngineMethod::GetPayloadV3 => "engine_getPayloadV3",
+            EngineMethod::GetPayloadV4 => "engine_getPayloadV4",
+            EngineMethod::GetPayloadV5 => "engine_getPayloadV5",
+            EngineMethod::ExchangeTransitionConfigurationV1 => {
+                "engine_exchangeTransitionConfigurationV1"
+            }
+            EngineMethod::GetPayloadBodiesByHashV1 => "engine_getPayloadBodiesByHashV1",
+            EngineMethod::GetPayloadBodiesByRangeV1 => "engine_getPayloadBodiesByRangeV1",
+            EngineMethod::GetBlobsV1 => "engine_getBlobsV1",
+            EngineMethod::GetBlobsV2 => "engine_getBlobsV2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.name() == name)
+    }
+}
+
+/// List of capabilities that the execution layer client supports: every routed engine method
+/// but `engine_exchangeCapabilities` itself, which the spec excludes.
 /// More info: https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#engine_exchangecapabilities
-pub const CAPABILITIES: [&str; 17] = [
-    "engine_forkchoiceUpdatedV1",
-    "engine_forkchoiceUpdatedV2",
-    "engine_forkchoiceUpdatedV3",
-    "engine_newPayloadV1",
-    "engine_newPayloadV2",
-    "engine_newPayloadV3",
-    "engine_newPayloadV4",
-    "engine_getPayloadV1",
-    "engine_getPayloadV2",
-    "engine_getPayloadV3",
-    "engine_getPayloadV4",
-    "engine_getPayloadV5",
-    "engine_exchangeTransitionConfigurationV1",
-    "engine_getPayloadBodiesByHashV1",
-    "engine_getPayloadBodiesByRangeV1",
-    "engine_getBlobsV1",
-    "engine_getBlobsV2",
-];
+pub fn capabilities() -> Vec<&'static str> {
+    EngineMethod::ALL
+        .into_iter()
+        .filter(|method| *method != EngineMethod::ExchangeCapabilities)
+        .map(EngineMethod::name)
+        .collect()
+}
 
 impl From<ExchangeCapabilitiesRequest> for RpcRequest {
     fn from(val: ExchangeCapabilitiesRequest) -> Self {
@@ -58,6 +127,6 @@ impl RpcHandler for ExchangeCapabilitiesRequest {
     }
 
     async fn handle(&self, _context: RpcApiContext) -> Result<Value, RpcErr> {
-        Ok(json!(CAPABILITIES))
+        Ok(json!(capabilities()))
     }
 }
@@ -71,6 +71,7 @@ impl RpcHandler for GetBlockByNumberRequest {
             Some(block_number) => block_number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(storage, block_number).await?;
         let header = storage.get_block_header(block_number)?;
         let body = storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
@@ -105,6 +106,7 @@ impl RpcHandler for GetBlockByHashRequest {
             Some(number) => number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(storage, block_number).await?;
         let header = storage.get_block_header(block_number)?;
         let body = storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
@@ -140,6 +142,7 @@ impl RpcHandler for GetBlockTransactionCountRequest {
             Some(block_number) => block_number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(&context.storage, block_number).await?;
         let block_body = match context.storage.get_block_body(block_number).await? {
             Some(block_body) => block_body,
             _ => return Ok(Value::Null),
@@ -171,6 +174,7 @@ impl RpcHandler for GetBlockReceiptsRequest {
             Some(block_number) => block_number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(storage, block_number).await?;
         let header = storage.get_block_header(block_number)?;
         let body = storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
@@ -236,6 +240,7 @@ impl RpcHandler for GetRawBlockRequest {
             Some(block_number) => block_number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(&context.storage, block_number).await?;
         let header = context.storage.get_block_header(block_number)?;
         let body = context.storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
@@ -269,6 +274,7 @@ impl RpcHandler for GetRawReceipts {
             Some(block_number) => block_number,
             _ => return Ok(Value::Null),
         };
+        check_history_available(storage, block_number).await?;
         let header = storage.get_block_header(block_number)?;
         let body = storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
@@ -330,6 +336,17 @@ impl RpcHandler for GetBlobBaseFee {
     }
 }
 
+/// Returns a `PrunedHistory` error if the body and receipts of the block expired (EIP-4444)
+pub async fn check_history_available(
+    storage: &Store,
+    block_number: BlockNumber,
+) -> Result<(), RpcErr> {
+    if storage.is_history_expired(block_number).await? {
+        return Err(RpcErr::PrunedHistory(block_number));
+    }
+    Ok(())
+}
+
 pub async fn get_all_block_rpc_receipts(
     block_number: BlockNumber,
     header: BlockHeader,
@@ -0,0 +1,392 @@
+use ethrex_blockchain::{
+    bundle::{Bundle, BundleTxResult},
+    error::ChainError,
+};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockNumber, MempoolTransaction, Transaction, TxKind},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    types::{block_identifier::BlockIdentifier, transaction::SendRawTransactionRequest},
+    utils::RpcErr,
+};
+
+/// Slot time used to compute the default timestamp of simulated blocks
+const SIMULATED_BLOCK_TIME: u64 = 12;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendBundleParams {
+    txs: Vec<String>,
+    #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+    block_number: BlockNumber,
+    #[serde(default)]
+    min_timestamp: Option<u64>,
+    #[serde(default)]
+    max_timestamp: Option<u64>,
+    #[serde(default)]
+    reverting_tx_hashes: Vec<H256>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleParams {
+    txs: Vec<String>,
+    #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+    block_number: BlockNumber,
+    state_block_number: Value,
+    #[serde(default)]
+    timestamp: Option<u64>,
+    #[serde(default)]
+    coinbase: Option<Address>,
+}
+
+pub struct SendBundleRequest {
+    transactions: Vec<Transaction>,
+    block_number: BlockNumber,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+    reverting_tx_hashes: Vec<H256>,
+}
+
+pub struct CallBundleRequest {
+    transactions: Vec<Transaction>,
+    block_number: BlockNumber,
+    state_block: BlockIdentifier,
+    timestamp: Option<u64>,
+    coinbase: Option<Address>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleResult {
+    bundle_gas_price: U256,
+    bundle_hash: H256,
+    coinbase_diff: U256,
+    eth_sent_to_coinbase: U256,
+    gas_fees: U256,
+    results: Vec<CallBundleTxResult>,
+    state_block_number: BlockNumber,
+    total_gas_used: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleTxResult {
+    tx_hash: H256,
+    from_address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_address: Option<Address>,
+    gas_used: u64,
+    gas_price: U256,
+    gas_fees: U256,
+    coinbase_diff: U256,
+    eth_sent_to_coinbase: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl CallBundleTxResult {
+    fn new(result: &BundleTxResult, tx: &Transaction) -> Self {
+        Self {
+            tx_hash: result.tx_hash,
+            from_address: result.sender,
+            to_address: match tx.to() {
+                TxKind::Call(address) => Some(address),
+                TxKind::Create => None,
+            },
+            gas_used: result.gas_used,
+            gas_price: result
+                .coinbase_diff
+                .checked_div(U256::from(result.gas_used))
+                .unwrap_or_default(),
+            gas_fees: result.gas_fees,
+            coinbase_diff: result.coinbase_diff,
+            eth_sent_to_coinbase: result.coinbase_diff.saturating_sub(result.gas_fees),
+            error: (!result.succeeded).then(|| "execution reverted".to_string()),
+        }
+    }
+}
+
+/// Parses the single object param of the bundle methods
+fn get_bundle_params<T: serde::de::DeserializeOwned>(
+    params: &Option<Vec<Value>>,
+) -> Result<T, RpcErr> {
+    let params = params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != 1 {
+        return Err(RpcErr::BadParams(format!(
+            "Expected one param and {} were provided",
+            params.len()
+        )));
+    };
+    Ok(serde_json::from_value(params[0].clone())?)
+}
+
+/// Decodes the raw signed transactions of a bundle
+fn decode_transactions(txs: &[String]) -> Result<Vec<Transaction>, RpcErr> {
+    txs.iter()
+        .map(|raw_tx| {
+            let raw_tx = raw_tx
+                .strip_prefix("0x")
+                .ok_or(RpcErr::BadParams("Params are not 0x prefixed".to_owned()))?;
+            let data = hex::decode(raw_tx).map_err(|error| RpcErr::BadParams(error.to_string()))?;
+            match SendRawTransactionRequest::decode_canonical(&data)
+                .map_err(|error| RpcErr::BadParams(error.to_string()))?
+            {
+                // Blobs can't be sent through bundles
+                SendRawTransactionRequest::EIP4844(_)
+                | SendRawTransactionRequest::PrivilegedL2(_) => {
+                    Err(RpcErr::BadParams("Invalid transaction type".to_string()))
+                }
+                transaction => Ok(transaction.to_transaction()),
+            }
+        })
+        .collect()
+}
+
+impl RpcHandler for SendBundleRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params: SendBundleParams = get_bundle_params(params)?;
+        Ok(SendBundleRequest {
+            transactions: decode_transactions(&params.txs)?,
+            block_number: params.block_number,
+            min_timestamp: params.min_timestamp,
+            max_timestamp: params.max_timestamp,
+            reverting_tx_hashes: params.reverting_tx_hashes,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let bundle_hash = context
+            .blockchain
+            .add_bundle_to_pool(
+                self.transactions.clone(),
+                self.block_number,
+                self.min_timestamp,
+                self.max_timestamp,
+                self.reverting_tx_hashes.clone(),
+            )
+            .await?;
+        Ok(serde_json::json!({ "bundleHash": format!("{bundle_hash:#x}") }))
+    }
+}
+
+impl RpcHandler for CallBundleRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params: CallBundleParams = get_bundle_params(params)?;
+        Ok(CallBundleRequest {
+            transactions: decode_transactions(&params.txs)?,
+            block_number: params.block_number,
+            state_block: BlockIdentifier::parse(params.state_block_number, 0)?,
+            timestamp: params.timestamp,
+            coinbase: params.coinbase,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        if self.transactions.is_empty() {
+            return Err(RpcErr::BadParams("Bundle has no transactions".to_owned()));
+        }
+        let parent = self
+            .state_block
+            .resolve_block_header(&context.storage)
+            .await?
+            .ok_or(RpcErr::BadParams("State block not found".to_owned()))?;
+
+        let mut transactions = Vec::with_capacity(self.transactions.len());
+        for tx in &self.transactions {
+            transactions.push(MempoolTransaction::new(tx.clone(), tx.sender()?));
+        }
+        let bundle = Bundle {
+            transactions,
+            block_number: self.block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        };
+
+        let execution = context
+            .blockchain
+            .simulate_bundle(
+                &bundle,
+                &parent,
+                self.timestamp
+                    .unwrap_or(parent.timestamp + SIMULATED_BLOCK_TIME),
+                self.coinbase.unwrap_or(parent.coinbase),
+            )
+            .map_err(|error| match error {
+                ChainError::EvmError(error) => RpcErr::from(error),
+                other => RpcErr::Internal(other.to_string()),
+            })?;
+
+        let result = CallBundleResult {
+            bundle_gas_price: execution.effective_gas_price(),
+            bundle_hash: bundle.hash(),
+            coinbase_diff: execution.coinbase_diff,
+            eth_sent_to_coinbase: execution.coinbase_diff.saturating_sub(execution.gas_fees),
+            gas_fees: execution.gas_fees,
+            results: execution
+                .results
+                .iter()
+                .zip(&self.transactions)
+                .map(|(result, tx)| CallBundleTxResult::new(result, tx))
+                .collect(),
+            state_block_number: parent.number,
+            total_gas_used: execution.gas_used,
+        };
+        serde_json::to_value(result).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_blockchain::bundle::bundle_hash;
+    use ethrex_common::types::{EIP1559Transaction, TxType};
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, SECP256K1, SecretKey};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        rpc::map_http_requests,
+        test_utils::{default_context_with_storage, setup_store},
+        utils::RpcRequest,
+    };
+
+    const GWEI: u64 = 1_000_000_000;
+
+    /// Rich account funded in the test genesis
+    fn rich_account() -> (SecretKey, Address) {
+        let key = SecretKey::from_slice(
+            &hex::decode("bcdf20249abf0ed6d944c0288fad489e33f66b3960d9e6229c1cd214ed3bbe31")
+                .unwrap(),
+        )
+        .unwrap();
+        let address = "0x8943545177806ED17B9F23F0a21ee5948eCaa776"
+            .parse()
+            .unwrap();
+        (key, address)
+    }
+
+    fn signed_transfer(key: &SecretKey, nonce: u64, to: Address) -> Transaction {
+        let mut tx = EIP1559Transaction {
+            chain_id: 9,
+            nonce,
+            max_priority_fee_per_gas: GWEI,
+            max_fee_per_gas: 100 * GWEI,
+            gas_limit: 21_000,
+            to: TxKind::Call(to),
+            value: U256::one(),
+            ..Default::default()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn raw(tx: &Transaction) -> String {
+        format!("0x{}", hex::encode(tx.encode_canonical_to_vec()))
+    }
+
+    #[tokio::test]
+    async fn send_bundle_adds_it_to_the_pool() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let (key, _) = rich_account();
+        let txs = [
+            signed_transfer(&key, 0, Address::from_low_u64_be(1)),
+            signed_transfer(&key, 1, Address::from_low_u64_be(2)),
+        ];
+        let request = RpcRequest::new(
+            "eth_sendBundle",
+            Some(vec![json!({
+                "txs": txs.iter().map(raw).collect::<Vec<_>>(),
+                "blockNumber": "0x1",
+                "revertingTxHashes": [txs[1].hash()],
+            })]),
+        );
+
+        let response = map_http_requests(&request, context.clone()).await.unwrap();
+
+        let expected_hash = bundle_hash(txs.iter().map(Transaction::hash));
+        assert_eq!(
+            response,
+            json!({ "bundleHash": format!("{expected_hash:#x}") })
+        );
+        assert_eq!(context.blockchain.bundle_pool.len().unwrap(), 1);
+        let bundles = context.blockchain.bundle_pool.get_bundles(1, 0).unwrap();
+        assert_eq!(bundles[0].reverting_tx_hashes, vec![txs[1].hash()]);
+    }
+
+    #[tokio::test]
+    async fn send_bundle_rejects_invalid_target_blocks() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let (key, _) = rich_account();
+        let tx = signed_transfer(&key, 0, Address::from_low_u64_be(1));
+
+        for block_number in ["0x0", "0x1a"] {
+            let request = RpcRequest::new(
+                "eth_sendBundle",
+                Some(vec![
+                    json!({ "txs": [raw(&tx)], "blockNumber": block_number }),
+                ]),
+            );
+            assert!(matches!(
+                map_http_requests(&request, context.clone()).await,
+                Err(RpcErr::BadParams(_))
+            ));
+        }
+        assert!(context.blockchain.bundle_pool.is_empty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn call_bundle_simulates_it_on_top_of_the_state_block() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let (key, sender) = rich_account();
+        let recipient = Address::from_low_u64_be(1);
+        let txs = [
+            signed_transfer(&key, 0, recipient),
+            signed_transfer(&key, 1, recipient),
+        ];
+        let request = RpcRequest::new(
+            "eth_callBundle",
+            Some(vec![json!({
+                "txs": txs.iter().map(raw).collect::<Vec<_>>(),
+                "blockNumber": "0x1",
+                "stateBlockNumber": "latest",
+            })]),
+        );
+
+        let response = map_http_requests(&request, context.clone()).await.unwrap();
+
+        assert_eq!(
+            response["bundleHash"],
+            json!(bundle_hash(txs.iter().map(Transaction::hash)))
+        );
+        assert_eq!(response["stateBlockNumber"], json!(0));
+        assert_eq!(response["totalGasUsed"], json!(42_000));
+        let results = response["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        for (result, tx) in results.iter().zip(&txs) {
+            assert_eq!(result["txHash"], json!(tx.hash()));
+            assert_eq!(result["fromAddress"], json!(sender));
+            assert_eq!(result["toAddress"], json!(recipient));
+            assert_eq!(result["gasUsed"], json!(21_000));
+            assert!(result.get("error").is_none());
+        }
+        // Simulating a bundle doesn't add it to the pool
+        assert!(context.blockchain.bundle_pool.is_empty().unwrap());
+    }
+}
@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::{
+    dev,
     eth::block,
     rpc::{RpcApiContext, RpcHandler},
     types::{
@@ -9,7 +10,7 @@ use crate::{
     },
     utils::RpcErr,
 };
-use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
+use ethrex_blockchain::{Blockchain, mempool::TxLifecycleEvent, vm::StoreVmDatabase};
 use ethrex_common::{
     H256, U256,
     types::{AccessListEntry, BlockHash, BlockHeader, BlockNumber, GenericTransaction, TxKind},
@@ -625,6 +626,82 @@ impl RpcHandler for SendRawTransactionRequest {
     }
 }
 
+/// Timeout of `eth_sendRawTransactionSync` when the request doesn't set one
+pub const DEFAULT_SEND_SYNC_TIMEOUT: Duration = Duration::from_secs(2);
+/// Maximum timeout a `eth_sendRawTransactionSync` request can set
+pub const MAX_SEND_SYNC_TIMEOUT: Duration = Duration::from_secs(60);
+/// Interval between checks for the receipt of a transaction sent through `eth_sendRawTransactionSync`
+const SEND_SYNC_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `eth_sendRawTransactionSync` (EIP-7966): sends a transaction and waits for its receipt
+pub struct SendRawTransactionSyncRequest {
+    pub transaction: SendRawTransactionRequest,
+    pub timeout: Duration,
+}
+
+impl RpcHandler for SendRawTransactionSyncRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<SendRawTransactionSyncRequest, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.is_empty() || params.len() > 2 {
+            return Err(RpcErr::BadParams(format!(
+                "Expected one or two params and {} were provided",
+                params.len()
+            )));
+        }
+        let transaction = SendRawTransactionRequest::parse(&Some(vec![params[0].clone()]))?;
+        let timeout = match params.get(1) {
+            Some(Value::Null) | None => DEFAULT_SEND_SYNC_TIMEOUT,
+            Some(timeout) => Duration::from_millis(serde_json::from_value(timeout.clone())?)
+                .min(MAX_SEND_SYNC_TIMEOUT),
+        };
+        Ok(SendRawTransactionSyncRequest {
+            transaction,
+            timeout,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let hash = serde_json::from_value(self.transaction.handle(context.clone()).await?)?;
+        dev::mine_if_automine(&context).await?;
+
+        let receipt_request = GetTransactionReceiptRequest {
+            transaction_hash: hash,
+        };
+        let wait_for_receipt = async {
+            loop {
+                let receipt = receipt_request.handle(context.clone()).await?;
+                if !receipt.is_null() {
+                    return Ok(receipt);
+                }
+                // Stop waiting if the transaction left the mempool without being included
+                if let Some(
+                    TxLifecycleEvent::ReplacedBy(_)
+                    | TxLifecycleEvent::Evicted
+                    | TxLifecycleEvent::DroppedInvalid(_),
+                ) = context
+                    .blockchain
+                    .mempool
+                    .get_transaction_lifecycle(&hash)?
+                    .and_then(|records| records.last().map(|record| record.event.clone()))
+                {
+                    return Err(RpcErr::Internal(format!(
+                        "Transaction {hash:#x} was removed from the mempool, see ethrex_getTransactionStatus"
+                    )));
+                }
+                tokio::time::sleep(SEND_SYNC_POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(self.timeout, wait_for_receipt)
+            .await
+            .map_err(|_| RpcErr::TransactionTimeout {
+                hash,
+                timeout_ms: self.timeout.as_millis() as u64,
+            })?
+    }
+}
+
 fn get_transaction_data(rpc_req_params: &Option<Vec<Value>>) -> Result<Vec<u8>, RpcErr> {
     let params = rpc_req_params
         .as_ref()
@@ -642,3 +719,112 @@ fn get_transaction_data(rpc_req_params: &Option<Vec<Value>>) -> Result<Vec<u8>,
         .ok_or(RpcErr::BadParams("Params are note 0x prefixed".to_owned()))?;
     hex::decode(str_data).map_err(|error| RpcErr::BadParams(error.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use ethrex_common::{
+        Address, Bytes,
+        types::{DEFAULT_BUILDER_GAS_CEIL, EIP1559Transaction, Transaction, TxType},
+    };
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, SECP256K1, SecretKey};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        dev::DevState,
+        rpc::map_http_requests,
+        test_utils::{default_context_with_storage, setup_store},
+        utils::RpcRequest,
+    };
+
+    const GWEI: u64 = 1_000_000_000;
+
+    /// Transfer signed by a rich account funded in the test genesis
+    fn signed_transfer(nonce: u64) -> Transaction {
+        let key = SecretKey::from_slice(
+            &hex::decode("bcdf20249abf0ed6d944c0288fad489e33f66b3960d9e6229c1cd214ed3bbe31")
+                .unwrap(),
+        )
+        .unwrap();
+        let mut tx = EIP1559Transaction {
+            chain_id: 9,
+            nonce,
+            max_priority_fee_per_gas: GWEI,
+            max_fee_per_gas: 100 * GWEI,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::from_low_u64_be(1)),
+            value: U256::one(),
+            ..Default::default()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), &key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    async fn send_sync(context: &RpcApiContext, params: Value) -> Result<Value, RpcErr> {
+        let request = RpcRequest::new(
+            "eth_sendRawTransactionSync",
+            Some(serde_json::from_value(params).unwrap()),
+        );
+        map_http_requests(&request, context.clone()).await
+    }
+
+    fn raw(tx: &Transaction) -> String {
+        format!("0x{}", hex::encode(tx.encode_canonical_to_vec()))
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_sync_returns_the_receipt() {
+        let mut context = default_context_with_storage(setup_store().await).await;
+        context.dev = Some(Arc::new(DevState::new(
+            context.storage.clone(),
+            context.blockchain.clone(),
+            DEFAULT_BUILDER_GAS_CEIL,
+            Bytes::new(),
+        )));
+        map_http_requests(
+            &RpcRequest::new("evm_setAutomine", Some(vec![json!(true)])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+        let tx = signed_transfer(0);
+
+        let receipt = send_sync(&context, json!([raw(&tx)])).await.unwrap();
+
+        assert_eq!(receipt["transactionHash"], json!(tx.hash()));
+        assert_eq!(receipt["blockNumber"], json!("0x1"));
+        assert_eq!(receipt["status"], json!("0x1"));
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_sync_times_out() {
+        // Nothing mines the transaction
+        let context = default_context_with_storage(setup_store().await).await;
+        let tx = signed_transfer(0);
+
+        let result = send_sync(&context, json!([raw(&tx), 200])).await;
+
+        assert!(matches!(
+            result,
+            Err(RpcErr::TransactionTimeout { hash, timeout_ms: 200 }) if hash == tx.hash()
+        ));
+        // The transaction stays in the mempool
+        assert!(
+            context
+                .blockchain
+                .mempool
+                .get_transaction_by_hash(tx.hash())
+                .unwrap()
+                .is_some()
+        );
+    }
+}
@@ -4,11 +4,13 @@
 mod admin;
 mod authentication;
 pub mod debug;
+pub mod dev;
 mod engine;
 mod eth;
 mod mempool;
 mod net;
 mod rpc;
+mod trace;
 mod tracing;
 
 pub mod clients;
@@ -16,7 +18,7 @@ pub mod types;
 pub mod utils;
 pub use clients::{EngineClient, EthClient};
 
-pub use rpc::{start_api, start_block_executor};
+pub use rpc::{start_api, start_block_executor, start_readonly_api};
 
 #[cfg(test)]
 mod test_utils;
@@ -27,10 +29,11 @@ pub use eth::{
     filter::{ActiveFilters, clean_outdated_filters},
     gas_price::GasPrice,
     gas_tip_estimator::GasTipEstimator,
-    transaction::EstimateGasRequest,
+    transaction::{EstimateGasRequest, SendRawTransactionSyncRequest},
 };
+pub use ethrex_metrics::rpc::propagate_trace_context;
 pub use rpc::{
     NodeData, RpcApiContext, RpcHandler, RpcRequestWrapper, map_debug_requests, map_eth_requests,
-    map_http_requests, rpc_response, shutdown_signal,
+    map_ethrex_requests, map_http_requests, rpc_response, shutdown_signal,
 };
 pub use utils::{RpcErr, RpcErrorMetadata, RpcNamespace};
@@ -1,14 +1,25 @@
 use std::collections::HashMap;
 
-use ethrex_common::Address;
+use ethrex_blockchain::mempool::{TxLifecycleEvent, TxLifecycleRecord};
+use ethrex_common::{
+    Address, H256,
+    types::{BlockHash, BlockNumber, MempoolTransaction, TxKind, TxType},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{rpc::RpcApiContext, types::transaction::RpcTransaction, utils::RpcErr};
+use crate::{
+    rpc::RpcApiContext,
+    types::transaction::RpcTransaction,
+    utils::{RpcErr, RpcRequest},
+};
 
 /// Maps account sender to its transactions indexed by nonce
 type MempoolContentEntry = HashMap<Address, HashMap<u64, RpcTransaction>>;
 
+/// Maps account sender to a textual summary of its transactions indexed by nonce
+type MempoolInspectEntry = HashMap<Address, HashMap<u64, String>>;
+
 /// Full content of the mempool
 /// Transactions are grouped by sender and indexed by nonce
 #[derive(Serialize, Deserialize)]
@@ -17,38 +28,408 @@ pub struct MempoolContent {
     pub queued: MempoolContentEntry,
 }
 
+/// Content of the mempool for a single sender
+/// Transactions are indexed by nonce
+#[derive(Serialize, Deserialize)]
+pub struct MempoolContentFrom {
+    pub pending: HashMap<u64, RpcTransaction>,
+    pub queued: HashMap<u64, RpcTransaction>,
+}
+
+/// Summary of the mempool content, matching geth's `txpool_inspect`
+#[derive(Serialize, Deserialize)]
+pub struct MempoolInspect {
+    pub pending: MempoolInspectEntry,
+    pub queued: MempoolInspectEntry,
+}
+
 #[derive(Serialize, Deserialize)]
 struct MempoolStatus {
     pending: String,
     queued: String,
+    /// Blob transactions in the pool, also accounted for in `pending` and `queued`
+    blobs: String,
 }
 
-/// Handling of rpc endpoint `mempool_content`
-pub async fn content(context: RpcApiContext) -> Result<Value, RpcErr> {
-    let transactions = context.blockchain.mempool.content()?;
-    // Group transactions by sender and nonce and map them to rpc transactions
-    let mut mempool_content = MempoolContentEntry::new();
+/// Lifecycle of a transaction as returned by `ethrex_getTransactionStatus`
+#[derive(Serialize)]
+struct TransactionStatus {
+    hash: H256,
+    /// Outcome of the latest event: pending, included, replaced, evicted or dropped
+    status: &'static str,
+    events: Vec<TransactionStatusEvent>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+enum TransactionStatusEvent {
+    Received {
+        #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+        timestamp: u64,
+    },
+    Replaced {
+        #[serde(rename = "replacedBy")]
+        replaced_by: H256,
+        #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+        timestamp: u64,
+    },
+    Evicted {
+        #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+        timestamp: u64,
+    },
+    Included {
+        #[serde(
+            rename = "blockNumber",
+            with = "ethrex_common::serde_utils::u64::hex_str"
+        )]
+        block_number: BlockNumber,
+        #[serde(rename = "blockHash")]
+        block_hash: BlockHash,
+        #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+        timestamp: u64,
+    },
+    Dropped {
+        reason: String,
+        #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+        timestamp: u64,
+    },
+}
+
+impl From<TxLifecycleRecord> for TransactionStatusEvent {
+    fn from(record: TxLifecycleRecord) -> Self {
+        let timestamp = record.timestamp;
+        match record.event {
+            TxLifecycleEvent::Received => Self::Received { timestamp },
+            TxLifecycleEvent::ReplacedBy(replaced_by) => Self::Replaced {
+                replaced_by,
+                timestamp,
+            },
+            TxLifecycleEvent::Evicted => Self::Evicted { timestamp },
+            TxLifecycleEvent::IncludedIn {
+                block_number,
+                block_hash,
+            } => Self::Included {
+                block_number,
+                block_hash,
+                timestamp,
+            },
+            TxLifecycleEvent::DroppedInvalid(reason) => Self::Dropped { reason, timestamp },
+        }
+    }
+}
+
+fn status_name(event: &TxLifecycleEvent) -> &'static str {
+    match event {
+        TxLifecycleEvent::Received => "pending",
+        TxLifecycleEvent::ReplacedBy(_) => "replaced",
+        TxLifecycleEvent::Evicted => "evicted",
+        TxLifecycleEvent::IncludedIn { .. } => "included",
+        TxLifecycleEvent::DroppedInvalid(_) => "dropped",
+    }
+}
+
+/// Transactions of a single sender split by whether they can be executed right away
+#[derive(Debug, Default)]
+struct SenderTransactions {
+    /// Transactions forming a gapless nonce sequence starting at the account nonce
+    pending: Vec<MempoolTransaction>,
+    /// Transactions that are waiting for a nonce gap to be filled
+    queued: Vec<MempoolTransaction>,
+}
+
+/// Splits the transactions of a sender, sorted by nonce, into pending and queued ones.
+/// Transactions with a nonce lower than the account nonce are already stale and are skipped.
+fn classify_sender_transactions(
+    account_nonce: u64,
+    transactions: Vec<MempoolTransaction>,
+) -> SenderTransactions {
+    let mut classified = SenderTransactions::default();
+    let mut next_nonce = account_nonce;
     for tx in transactions {
-        let sender_entry = mempool_content.entry(tx.sender()?).or_default();
-        sender_entry.insert(tx.nonce(), RpcTransaction::build(tx, None, None, None)?);
+        let nonce = tx.nonce();
+        if nonce < account_nonce {
+            continue;
+        }
+        if nonce == next_nonce && classified.queued.is_empty() {
+            next_nonce += 1;
+            classified.pending.push(tx);
+        } else {
+            classified.queued.push(tx);
+        }
+    }
+    classified
+}
+
+/// Fetches the nonce of the account at the latest block
+async fn account_nonce(context: &RpcApiContext, address: Address) -> Result<u64, RpcErr> {
+    let latest_block_number = context.storage.get_latest_block_number().await?;
+    Ok(context
+        .storage
+        .get_nonce_by_account_address(latest_block_number, address)
+        .await?
+        .unwrap_or_default())
+}
+
+/// Returns every transaction in the mempool grouped by sender and split into pending and queued
+async fn classified_content(
+    context: &RpcApiContext,
+) -> Result<HashMap<Address, SenderTransactions>, RpcErr> {
+    let transactions_by_sender = context.blockchain.mempool.get_all_txs_by_sender()?;
+    let mut classified = HashMap::with_capacity(transactions_by_sender.len());
+    for (sender, transactions) in transactions_by_sender {
+        let nonce = account_nonce(context, sender).await?;
+        classified.insert(sender, classify_sender_transactions(nonce, transactions));
+    }
+    Ok(classified)
+}
+
+fn index_by_nonce(
+    transactions: Vec<MempoolTransaction>,
+) -> Result<HashMap<u64, RpcTransaction>, RpcErr> {
+    transactions
+        .into_iter()
+        .map(|tx| {
+            let nonce = tx.nonce();
+            let tx = RpcTransaction::build(tx.transaction().clone(), None, None, None)?;
+            Ok((nonce, tx))
+        })
+        .collect()
+}
+
+/// Summarizes a transaction the same way geth does:
+/// `<to>: <value> wei + <gas limit> gas × <gas price> wei`
+fn summarize(tx: &MempoolTransaction) -> String {
+    let to = match tx.to() {
+        TxKind::Call(address) => format!("{address:#x}"),
+        TxKind::Create => "contract creation".to_string(),
+    };
+    format!(
+        "{to}: {} wei + {} gas × {} wei",
+        tx.value(),
+        tx.gas_limit(),
+        tx.gas_price()
+    )
+}
+
+fn summarize_by_nonce(transactions: &[MempoolTransaction]) -> HashMap<u64, String> {
+    transactions
+        .iter()
+        .map(|tx| (tx.nonce(), summarize(tx)))
+        .collect()
+}
+
+/// Handling of rpc endpoint `txpool_content`
+pub async fn content(context: RpcApiContext) -> Result<Value, RpcErr> {
+    let mut pending = MempoolContentEntry::new();
+    let mut queued = MempoolContentEntry::new();
+    for (sender, transactions) in classified_content(&context).await? {
+        if !transactions.pending.is_empty() {
+            pending.insert(sender, index_by_nonce(transactions.pending)?);
+        }
+        if !transactions.queued.is_empty() {
+            queued.insert(sender, index_by_nonce(transactions.queued)?);
+        }
     }
-    let response = MempoolContent {
-        pending: mempool_content,
-        // We have no concept of "queued" transactions yet so we will leave this empty
-        queued: MempoolContentEntry::new(),
+    let response = MempoolContent { pending, queued };
+    Ok(serde_json::to_value(response)?)
+}
+
+/// Handling of rpc endpoint `txpool_contentFrom`
+pub async fn content_from(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    let params = req
+        .params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != 1 {
+        return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
     };
+    let address: Address = serde_json::from_value(params[0].clone())?;
+
+    let transactions = context.blockchain.mempool.get_txs_by_sender(address)?;
+    let nonce = account_nonce(&context, address).await?;
+    let transactions = classify_sender_transactions(nonce, transactions);
+    let response = MempoolContentFrom {
+        pending: index_by_nonce(transactions.pending)?,
+        queued: index_by_nonce(transactions.queued)?,
+    };
+    Ok(serde_json::to_value(response)?)
+}
+
+/// Handling of rpc endpoint `txpool_inspect`
+pub async fn inspect(context: RpcApiContext) -> Result<Value, RpcErr> {
+    let mut pending = MempoolInspectEntry::new();
+    let mut queued = MempoolInspectEntry::new();
+    for (sender, transactions) in classified_content(&context).await? {
+        if !transactions.pending.is_empty() {
+            pending.insert(sender, summarize_by_nonce(&transactions.pending));
+        }
+        if !transactions.queued.is_empty() {
+            queued.insert(sender, summarize_by_nonce(&transactions.queued));
+        }
+    }
+    let response = MempoolInspect { pending, queued };
     Ok(serde_json::to_value(response)?)
 }
 
+/// Handling of rpc endpoint `txpool_status`
 pub async fn status(context: RpcApiContext) -> Result<Value, RpcErr> {
-    let pending = context.blockchain.mempool.status()?;
-    // We have no concept of "queued" transactions yet so we will leave this as 0
-    let queued = 0;
+    let (mut pending, mut queued, mut blobs) = (0, 0, 0);
+    for transactions in classified_content(&context).await?.values() {
+        pending += transactions.pending.len();
+        queued += transactions.queued.len();
+        blobs += transactions
+            .pending
+            .iter()
+            .chain(transactions.queued.iter())
+            .filter(|tx| tx.tx_type() == TxType::EIP4844)
+            .count();
+    }
 
     let response = MempoolStatus {
         pending: format!("{pending:#x}"),
         queued: format!("{queued:#x}"),
+        blobs: format!("{blobs:#x}"),
     };
 
     Ok(serde_json::to_value(response)?)
 }
+
+/// Handling of rpc endpoint `ethrex_getTransactionStatus`.
+/// Returns the events recorded by the mempool for the transaction, or null if it's unknown.
+/// Transactions included in a block the mempool doesn't remember anymore are reported as
+/// included without events.
+pub async fn transaction_status(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    let params = req
+        .params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != 1 {
+        return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+    };
+    let hash: H256 = serde_json::from_value(params[0].clone())?;
+
+    let records = context
+        .blockchain
+        .mempool
+        .get_transaction_lifecycle(&hash)?
+        .unwrap_or_default();
+    let status = match records.last() {
+        Some(record) => status_name(&record.event),
+        None if context
+            .storage
+            .get_transaction_location(hash)
+            .await?
+            .is_some() =>
+        {
+            "included"
+        }
+        None => return Ok(Value::Null),
+    };
+    let response = TransactionStatus {
+        hash,
+        status,
+        events: records.into_iter().map(Into::into).collect(),
+    };
+    Ok(serde_json::to_value(response)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::map_http_requests,
+        test_utils::{default_context_with_storage, setup_store},
+    };
+    use ethrex_common::types::{Block, BlockBody, BlockHeader, EIP1559Transaction, Transaction};
+    use serde_json::json;
+
+    fn mempool_tx(nonce: u64) -> MempoolTransaction {
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            nonce,
+            ..Default::default()
+        });
+        MempoolTransaction::new(tx, Address::zero())
+    }
+
+    fn nonces(transactions: &[MempoolTransaction]) -> Vec<u64> {
+        transactions.iter().map(|tx| tx.nonce()).collect()
+    }
+
+    #[test]
+    fn gapless_transactions_are_pending() {
+        let txs = vec![mempool_tx(3), mempool_tx(4), mempool_tx(5)];
+        let classified = classify_sender_transactions(3, txs);
+        assert_eq!(nonces(&classified.pending), vec![3, 4, 5]);
+        assert!(classified.queued.is_empty());
+    }
+
+    #[test]
+    fn transactions_after_a_nonce_gap_are_queued() {
+        let txs = vec![mempool_tx(3), mempool_tx(5), mempool_tx(6)];
+        let classified = classify_sender_transactions(3, txs);
+        assert_eq!(nonces(&classified.pending), vec![3]);
+        assert_eq!(nonces(&classified.queued), vec![5, 6]);
+    }
+
+    #[test]
+    fn transactions_ahead_of_account_nonce_are_queued() {
+        let txs = vec![mempool_tx(4), mempool_tx(5)];
+        let classified = classify_sender_transactions(3, txs);
+        assert!(classified.pending.is_empty());
+        assert_eq!(nonces(&classified.queued), vec![4, 5]);
+    }
+
+    #[test]
+    fn stale_transactions_are_skipped() {
+        let txs = vec![mempool_tx(1), mempool_tx(2), mempool_tx(3)];
+        let classified = classify_sender_transactions(2, txs);
+        assert_eq!(nonces(&classified.pending), vec![2, 3]);
+        assert!(classified.queued.is_empty());
+    }
+
+    async fn get_status(context: &RpcApiContext, hash: H256) -> Value {
+        let request = RpcRequest::new("ethrex_getTransactionStatus", Some(vec![json!(hash)]));
+        map_http_requests(&request, context.clone()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn transaction_status_follows_the_lifecycle() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let tx = mempool_tx(3);
+        let hash = tx.hash();
+        assert_eq!(get_status(&context, hash).await, Value::Null);
+
+        context
+            .blockchain
+            .mempool
+            .add_transaction(hash, tx.clone())
+            .unwrap();
+        let status = get_status(&context, hash).await;
+        assert_eq!(status["hash"], json!(hash));
+        assert_eq!(status["status"], json!("pending"));
+        assert_eq!(status["events"][0]["event"], json!("received"));
+
+        let block = Block::new(
+            BlockHeader {
+                number: 1,
+                ..Default::default()
+            },
+            BlockBody {
+                transactions: vec![tx.transaction().clone()],
+                ..Default::default()
+            },
+        );
+        context
+            .blockchain
+            .mempool
+            .remove_block_transactions(&block)
+            .unwrap();
+        let status = get_status(&context, hash).await;
+        assert_eq!(status["status"], json!("included"));
+        let events = status["events"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["event"], json!("included"));
+        assert_eq!(events[1]["blockNumber"], json!("0x1"));
+        assert_eq!(events[1]["blockHash"], json!(block.hash()));
+    }
+}
@@ -1,10 +1,12 @@
 use crate::authentication::authenticate;
 use crate::debug::execution_witness::ExecutionWitnessRequest;
+use crate::dev::{self, DevState};
 use crate::engine::blobs::BlobsV2Request;
 use crate::engine::payload::GetPayloadV5Request;
 use crate::engine::{
-    ExchangeCapabilitiesRequest,
+    EngineMethod, ExchangeCapabilitiesRequest,
     blobs::BlobsV1Request,
+    client_version::GetClientVersionV1Request,
     exchange_transition_config::ExchangeTransitionConfigV1Req,
     fork_choice::{ForkChoiceUpdatedV1, ForkChoiceUpdatedV2, ForkChoiceUpdatedV3},
     payload::{
@@ -24,6 +26,7 @@ use crate::eth::{
         GetBlockReceiptsRequest, GetBlockTransactionCountRequest, GetRawBlockRequest,
         GetRawHeaderRequest, GetRawReceipts,
     },
+    bundle::{CallBundleRequest, SendBundleRequest},
     client::{ChainId, Syncing},
     fee_market::FeeHistoryRequest,
     filter::{self, ActiveFilters, DeleteFilterRequest, FilterChangesRequest, NewFilterRequest},
@@ -33,9 +36,15 @@ use crate::eth::{
     transaction::{
         CallRequest, CreateAccessListRequest, EstimateGasRequest, GetRawTransaction,
         GetTransactionByBlockHashAndIndexRequest, GetTransactionByBlockNumberAndIndexRequest,
-        GetTransactionByHashRequest, GetTransactionReceiptRequest,
+        GetTransactionByHashRequest, GetTransactionReceiptRequest, SendRawTransactionSyncRequest,
     },
 };
+use crate::trace::{
+    self,
+    filter::TraceFilterRequest,
+    flat::{TraceBlockRequest, TraceGetRequest},
+    state_diff::{GetStateDiffRequest, ReplayBlockTransactionsRequest, ReplayTransactionRequest},
+};
 use crate::tracing::{TraceBlockByNumberRequest, TraceTransactionRequest};
 use crate::types::transaction::SendRawTransactionRequest;
 use crate::utils::{
@@ -55,7 +64,9 @@ use bytes::Bytes;
 use ethrex_blockchain::Blockchain;
 use ethrex_blockchain::error::ChainError;
 use ethrex_common::types::Block;
-use ethrex_metrics::rpc::{RpcOutcome, record_async_duration, record_rpc_outcome};
+use ethrex_metrics::rpc::{
+    RpcOutcome, propagate_trace_context, record_async_duration, record_rpc_outcome,
+};
 use ethrex_p2p::peer_handler::PeerHandler;
 use ethrex_p2p::sync_manager::SyncManager;
 use ethrex_p2p::types::Node;
@@ -78,7 +89,7 @@ use tokio::sync::{
 };
 use tokio::time::timeout;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info, warn};
+use tracing::{Instrument, error, info, info_span, warn};
 use tracing_subscriber::{EnvFilter, Registry, reload};
 
 #[cfg(all(feature = "jemalloc_profiling", target_os = "linux"))]
@@ -176,6 +187,8 @@ pub struct RpcApiContext {
     pub log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     pub gas_ceil: u64,
     pub block_worker_channel: UnboundedSender<(oneshot::Sender<Result<(), ChainError>>, Block)>,
+    // Only set when running with `--dev`
+    pub dev: Option<Arc<DevState>>,
 }
 
 #[derive(Debug, Clone)]
@@ -199,13 +212,23 @@ pub trait RpcHandler: Sized {
         };
         let method = req.method.as_str();
 
-        let result =
-            record_async_duration(
-                namespace,
-                method,
-                async move { request.handle(context).await },
-            )
-            .await;
+        let span = info_span!(
+            "RPC call",
+            otel.name = method,
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            rpc.system = "jsonrpc",
+            rpc.method = method,
+        );
+        let result = record_async_duration(
+            namespace,
+            method,
+            async move { request.handle(context).await }.instrument(span.clone()),
+        )
+        .await;
+        if result.is_err() {
+            span.record("otel.status_code", "error");
+        }
 
         let outcome = match &result {
             Ok(_) => RpcOutcome::Success,
@@ -236,6 +259,8 @@ fn get_error_kind(err: &RpcErr) -> &'static str {
         RpcErr::InvalidForkChoiceState(_) => "InvalidForkChoiceState",
         RpcErr::InvalidPayloadAttributes(_) => "InvalidPayloadAttributes",
         RpcErr::UnknownPayload(_) => "UnknownPayload",
+        RpcErr::PrunedHistory(_) => "PrunedHistory",
+        RpcErr::TransactionTimeout { .. } => "TransactionTimeout",
     }
 }
 
@@ -281,11 +306,25 @@ pub async fn start_api(
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     gas_ceil: u64,
     extra_data: String,
+    dev_mode: bool,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
     let active_filters = Arc::new(Mutex::new(HashMap::new()));
     let block_worker_channel = start_block_executor(blockchain.clone());
+    let extra_data: Bytes = extra_data.into();
+    let dev = dev_mode.then(|| {
+        Arc::new(DevState::new(
+            storage.clone(),
+            blockchain.clone(),
+            gas_ceil,
+            extra_data.clone(),
+        ))
+    });
+    if let Some(dev) = &dev {
+        // In dev mode there's no consensus client, so blocks are mined by the node itself.
+        tokio::task::spawn(dev.clone().interval_mining());
+    }
     let service_context = RpcApiContext {
         storage,
         blockchain,
@@ -297,12 +336,13 @@ pub async fn start_api(
             local_p2p_node,
             local_node_record,
             client_version,
-            extra_data: extra_data.into(),
+            extra_data,
         },
         gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
         log_filter_handler,
         gas_ceil,
         block_worker_channel,
+        dev,
     };
 
     // Periodically clean up the active filters for the filters endpoints.
@@ -330,6 +370,7 @@ pub async fn start_api(
             axum::routing::get(handle_get_heap_flamegraph),
         )
         .route("/", post(handle_http_request))
+        .layer(axum::middleware::from_fn(propagate_trace_context))
         .layer(cors.clone())
         .with_state(service_context.clone());
     let http_listener = TcpListener::bind(http_addr)
@@ -358,6 +399,7 @@ pub async fn start_api(
 
     let authrpc_router = Router::new()
         .route("/", post(authrpc_handler))
+        .layer(axum::middleware::from_fn(propagate_trace_context))
         .with_state(service_context.clone())
         // Bump the body limit for the engine API to 256MB
         // This is needed to receive payloads bigger than the default limit of 2MB
@@ -397,6 +439,94 @@ pub async fn start_api(
     Ok(())
 }
 
+/// Starts the HTTP and WS servers of a read-only node, which serves the data of a database
+/// owned by another node process through a store opened with `Store::new_secondary`.
+/// There's no Engine API, peer table or syncer, and transactions are rejected.
+#[allow(clippy::too_many_arguments)]
+pub async fn start_readonly_api(
+    http_addr: SocketAddr,
+    ws_addr: Option<SocketAddr>,
+    storage: Store,
+    blockchain: Arc<Blockchain>,
+    local_p2p_node: Node,
+    local_node_record: NodeRecord,
+    client_version: String,
+    log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
+    gas_ceil: u64,
+) -> Result<(), RpcErr> {
+    let active_filters = Arc::new(Mutex::new(HashMap::new()));
+    let block_worker_channel = start_block_executor(blockchain.clone());
+    let service_context = RpcApiContext {
+        storage,
+        blockchain,
+        active_filters: active_filters.clone(),
+        syncer: None,
+        peer_handler: None,
+        node_data: NodeData {
+            jwt_secret: Default::default(),
+            local_p2p_node,
+            local_node_record,
+            client_version,
+            extra_data: Default::default(),
+        },
+        gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
+        log_filter_handler,
+        gas_ceil,
+        block_worker_channel,
+        dev: None,
+    };
+
+    // Periodically clean up the active filters for the filters endpoints.
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(FILTER_DURATION);
+        loop {
+            interval.tick().await;
+            filter::clean_outdated_filters(active_filters.clone(), FILTER_DURATION);
+        }
+    });
+
+    let cors = CorsLayer::permissive();
+
+    let http_router = Router::new()
+        .route("/", post(handle_http_request))
+        .layer(axum::middleware::from_fn(propagate_trace_context))
+        .layer(cors.clone())
+        .with_state(service_context.clone());
+    let http_listener = TcpListener::bind(http_addr)
+        .await
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    let http_server = axum::serve(http_listener, http_router)
+        .with_graceful_shutdown(shutdown_signal())
+        .into_future();
+    info!("Starting read-only HTTP server at {http_addr}");
+
+    if let Some(address) = ws_addr {
+        let ws_handler = |ws: WebSocketUpgrade, ctx| async {
+            ws.on_upgrade(|socket| handle_websocket(socket, ctx))
+        };
+        let ws_router = Router::new()
+            .route("/", axum::routing::any(ws_handler))
+            .layer(cors)
+            .with_state(service_context);
+        let ws_listener = TcpListener::bind(address)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        let ws_server = axum::serve(ws_listener, ws_router)
+            .with_graceful_shutdown(shutdown_signal())
+            .into_future();
+        info!("Starting read-only WS server at {address}");
+
+        let _ = tokio::try_join!(http_server, ws_server)
+            .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
+    } else {
+        let _ = http_server
+            .await
+            .inspect_err(|e| error!("Error shutting down server: {e:?}"));
+    }
+
+    Ok(())
+}
+
 pub async fn shutdown_signal() {
     tokio::signal::ctrl_c()
         .await
@@ -492,6 +622,9 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
         Ok(RpcNamespace::Web3) => map_web3_requests(req, context),
         Ok(RpcNamespace::Net) => map_net_requests(req, context).await,
         Ok(RpcNamespace::Mempool) => map_mempool_requests(req, context).await,
+        Ok(RpcNamespace::Dev) => map_dev_requests(req, context).await,
+        Ok(RpcNamespace::Ethrex) => map_ethrex_requests(req, context).await,
+        Ok(RpcNamespace::Trace) => map_trace_requests(req, context).await,
         Ok(RpcNamespace::Engine) => Err(RpcErr::Internal(
             "Engine namespace not allowed in map_http_requests".to_owned(),
         )),
@@ -552,7 +685,24 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
         "eth_getFilterChanges" => {
             FilterChangesRequest::stateful_call(req, context.storage, context.active_filters).await
         }
-        "eth_sendRawTransaction" => SendRawTransactionRequest::call(req, context).await,
+        "eth_sendRawTransaction" if context.storage.is_read_only() => Err(RpcErr::Internal(
+            "Transactions can't be sent to a read-only node".to_string(),
+        )),
+        "eth_sendRawTransaction" => {
+            let tx_hash = SendRawTransactionRequest::call(req, context.clone()).await?;
+            dev::mine_if_automine(&context).await?;
+            Ok(tx_hash)
+        }
+        "eth_sendRawTransactionSync" if context.storage.is_read_only() => Err(RpcErr::Internal(
+            "Transactions can't be sent to a read-only node".to_string(),
+        )),
+        "eth_sendRawTransactionSync" => SendRawTransactionSyncRequest::call(req, context).await,
+        "eth_sendTransaction" => dev::send_transaction(req, context).await,
+        "eth_sendBundle" if context.storage.is_read_only() => Err(RpcErr::Internal(
+            "Bundles can't be sent to a read-only node".to_string(),
+        )),
+        "eth_sendBundle" => SendBundleRequest::call(req, context).await,
+        "eth_callBundle" => CallBundleRequest::call(req, context).await,
         "eth_getProof" => GetProofRequest::call(req, context).await,
         "eth_gasPrice" => GasPrice::call(req, context).await,
         "eth_maxPriorityFeePerGas" => {
@@ -572,6 +722,7 @@ pub async fn map_debug_requests(req: &RpcRequest, context: RpcApiContext) -> Res
         "debug_executionWitness" => ExecutionWitnessRequest::call(req, context).await,
         "debug_traceTransaction" => TraceTransactionRequest::call(req, context).await,
         "debug_traceBlockByNumber" => TraceBlockByNumberRequest::call(req, context).await,
+        "debug_getStateDiff" => GetStateDiffRequest::call(req, context).await,
         unknown_debug_method => Err(RpcErr::MethodNotFound(unknown_debug_method.to_owned())),
     }
 }
@@ -580,32 +731,34 @@ pub async fn map_engine_requests(
     req: &RpcRequest,
     context: RpcApiContext,
 ) -> Result<Value, RpcErr> {
-    match req.method.as_str() {
-        "engine_exchangeCapabilities" => ExchangeCapabilitiesRequest::call(req, context).await,
-        "engine_forkchoiceUpdatedV1" => ForkChoiceUpdatedV1::call(req, context).await,
-        "engine_forkchoiceUpdatedV2" => ForkChoiceUpdatedV2::call(req, context).await,
-        "engine_forkchoiceUpdatedV3" => ForkChoiceUpdatedV3::call(req, context).await,
-        "engine_newPayloadV4" => NewPayloadV4Request::call(req, context).await,
-        "engine_newPayloadV3" => NewPayloadV3Request::call(req, context).await,
-        "engine_newPayloadV2" => NewPayloadV2Request::call(req, context).await,
-        "engine_newPayloadV1" => NewPayloadV1Request::call(req, context).await,
-        "engine_exchangeTransitionConfigurationV1" => {
+    let method = EngineMethod::from_name(&req.method)
+        .ok_or_else(|| RpcErr::MethodNotFound(req.method.clone()))?;
+    match method {
+        EngineMethod::ExchangeCapabilities => ExchangeCapabilitiesRequest::call(req, context).await,
+        EngineMethod::GetClientVersionV1 => GetClientVersionV1Request::call(req, context).await,
+        EngineMethod::ForkChoiceUpdatedV1 => ForkChoiceUpdatedV1::call(req, context).await,
+        EngineMethod::ForkChoiceUpdatedV2 => ForkChoiceUpdatedV2::call(req, context).await,
+        EngineMethod::ForkChoiceUpdatedV3 => ForkChoiceUpdatedV3::call(req, context).await,
+        EngineMethod::NewPayloadV4 => NewPayloadV4Request::call(req, context).await,
+        EngineMethod::NewPayloadV3 => NewPayloadV3Request::call(req, context).await,
+        EngineMethod::NewPayloadV2 => NewPayloadV2Request::call(req, context).await,
+        EngineMethod::NewPayloadV1 => NewPayloadV1Request::call(req, context).await,
+        EngineMethod::ExchangeTransitionConfigurationV1 => {
             ExchangeTransitionConfigV1Req::call(req, context).await
         }
-        "engine_getPayloadV5" => GetPayloadV5Request::call(req, context).await,
-        "engine_getPayloadV4" => GetPayloadV4Request::call(req, context).await,
-        "engine_getPayloadV3" => GetPayloadV3Request::call(req, context).await,
-        "engine_getPayloadV2" => GetPayloadV2Request::call(req, context).await,
-        "engine_getPayloadV1" => GetPayloadV1Request::call(req, context).await,
-        "engine_getPayloadBodiesByHashV1" => {
+        EngineMethod::GetPayloadV5 => GetPayloadV5Request::call(req, context).await,
+        EngineMethod::GetPayloadV4 => GetPayloadV4Request::call(req, context).await,
+        EngineMethod::GetPayloadV3 => GetPayloadV3Request::call(req, context).await,
+        EngineMethod::GetPayloadV2 => GetPayloadV2Request::call(req, context).await,
+        EngineMethod::GetPayloadV1 => GetPayloadV1Request::call(req, context).await,
+        EngineMethod::GetPayloadBodiesByHashV1 => {
             GetPayloadBodiesByHashV1Request::call(req, context).await
         }
-        "engine_getPayloadBodiesByRangeV1" => {
+        EngineMethod::GetPayloadBodiesByRangeV1 => {
             GetPayloadBodiesByRangeV1Request::call(req, context).await
         }
-        "engine_getBlobsV1" => BlobsV1Request::call(req, context).await,
-        "engine_getBlobsV2" => BlobsV2Request::call(req, context).await,
-        unknown_engine_method => Err(RpcErr::MethodNotFound(unknown_engine_method.to_owned())),
+        EngineMethod::GetBlobsV1 => BlobsV1Request::call(req, context).await,
+        EngineMethod::GetBlobsV2 => BlobsV2Request::call(req, context).await,
     }
 }
 
@@ -644,11 +797,58 @@ pub async fn map_mempool_requests(
     match req.method.as_str() {
         // TODO: The endpoint name matches geth's endpoint for compatibility, consider changing it in the future
         "txpool_content" => mempool::content(contex).await,
+        "txpool_contentFrom" => mempool::content_from(req, contex).await,
+        "txpool_inspect" => mempool::inspect(contex).await,
         "txpool_status" => mempool::status(contex).await,
         unknown_mempool_method => Err(RpcErr::MethodNotFound(unknown_mempool_method.to_owned())),
     }
 }
 
+pub async fn map_trace_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    match req.method.as_str() {
+        "trace_block" => TraceBlockRequest::call(req, context).await,
+        "trace_transaction" => trace::flat::TraceTransactionRequest::call(req, context).await,
+        "trace_get" => TraceGetRequest::call(req, context).await,
+        "trace_filter" => TraceFilterRequest::call(req, context).await,
+        "trace_replayTransaction" => ReplayTransactionRequest::call(req, context).await,
+        "trace_replayBlockTransactions" => ReplayBlockTransactionsRequest::call(req, context).await,
+        unknown_trace_method => Err(RpcErr::MethodNotFound(unknown_trace_method.to_owned())),
+    }
+}
+
+/// Handle the `ethrex_*` methods available in both L1 and L2 nodes
+pub async fn map_ethrex_requests(
+    req: &RpcRequest,
+    context: RpcApiContext,
+) -> Result<Value, RpcErr> {
+    match req.method.as_str() {
+        "ethrex_getTransactionStatus" => mempool::transaction_status(req, context).await,
+        unknown_ethrex_method => Err(RpcErr::MethodNotFound(unknown_ethrex_method.to_owned())),
+    }
+}
+
+/// Handle the `evm_*` and `anvil_*` methods, which are only available when running with `--dev`
+pub async fn map_dev_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    let Some(dev) = context.dev else {
+        return Err(RpcErr::MethodNotFound(req.method.clone()));
+    };
+    match req.method.as_str() {
+        "evm_mine" => dev::mine(req, &dev).await,
+        "evm_setAutomine" => dev::set_automine(req, &dev),
+        "evm_setIntervalMining" => dev::set_interval_mining(req, &dev),
+        "evm_snapshot" => dev::snapshot(&dev).await,
+        "evm_revert" => dev::revert(req, &dev).await,
+        "evm_increaseTime" => dev::increase_time(req, &dev).await,
+        "anvil_setBalance" => dev::set_balance(req, &dev).await,
+        "anvil_setCode" => dev::set_code(req, &dev).await,
+        "anvil_setStorageAt" => dev::set_storage_at(req, &dev).await,
+        "anvil_setNonce" => dev::set_nonce(req, &dev).await,
+        "anvil_impersonateAccount" => dev::impersonate_account(req, &dev),
+        "anvil_stopImpersonatingAccount" => dev::stop_impersonating_account(req, &dev),
+        unknown_dev_method => Err(RpcErr::MethodNotFound(unknown_dev_method.to_owned())),
+    }
+}
+
 pub fn rpc_response<E>(id: RpcRequestId, res: Result<Value, E>) -> Result<Value, RpcErr>
 where
     E: Into<RpcErrorMetadata>,
@@ -965,4 +1165,65 @@ mod tests {
         let expected_response = to_rpc_response_success_value(&json.to_string());
         assert_eq!(rpc_response.to_string(), expected_response.to_string())
     }
+
+    #[tokio::test]
+    async fn engine_methods_are_routed_by_authrpc() {
+        let storage =
+            Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+        let context = default_context_with_storage(storage).await;
+        for method in EngineMethod::ALL {
+            assert_eq!(EngineMethod::from_name(method.name()), Some(method));
+            // Without params the handlers fail to parse the request, unless they aren't routed
+            let request = RpcRequest {
+                method: method.name().to_owned(),
+                params: None,
+                ..Default::default()
+            };
+            let result = map_authrpc_requests(&request, context.clone()).await;
+            assert!(
+                !matches!(result, Err(RpcErr::MethodNotFound(_))),
+                "{} is listed but not routed",
+                method.name()
+            );
+        }
+        let request = RpcRequest {
+            method: "engine_unknownMethodV1".to_owned(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            map_authrpc_requests(&request, context).await,
+            Err(RpcErr::MethodNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn exchange_capabilities_request() {
+        let body = r#"{"jsonrpc":"2.0", "method":"engine_exchangeCapabilities", "params":[["engine_newPayloadV4"]], "id":1}"#;
+        let request: RpcRequest = serde_json::from_str(body).unwrap();
+        let storage =
+            Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+        let context = default_context_with_storage(storage).await;
+        let result: Vec<String> =
+            serde_json::from_value(map_authrpc_requests(&request, context).await.unwrap()).unwrap();
+        assert!(result.contains(&"engine_getClientVersionV1".to_string()));
+        assert!(!result.contains(&"engine_exchangeCapabilities".to_string()));
+        assert_eq!(result.len(), EngineMethod::ALL.len() - 1);
+    }
+
+    #[tokio::test]
+    async fn get_client_version_request() {
+        let body = r#"{"jsonrpc":"2.0", "method":"engine_getClientVersionV1", "params":[{"code":"LH","name":"Lighthouse","version":"v8.0.0","commit":"0x12345678"}], "id":1}"#;
+        let request: RpcRequest = serde_json::from_str(body).unwrap();
+        let storage =
+            Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+        let context = default_context_with_storage(storage).await;
+        let result = map_authrpc_requests(&request, context).await.unwrap();
+        let expected = serde_json::json!([{
+            "code": "EX",
+            "name": "ethrex",
+            "version": "test",
+            "commit": "0x00000000",
+        }]);
+        assert_eq!(result, expected);
+    }
 }
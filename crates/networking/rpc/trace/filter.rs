@@ -0,0 +1,176 @@
+use std::collections::BTreeSet;
+
+use ethrex_common::{Address, types::BlockNumber};
+use ethrex_storage::Store;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    trace::flat::{FlatTrace, trace_block},
+    types::block_identifier::{BlockIdentifier, BlockTag},
+    utils::RpcErr,
+};
+
+/// Max amount of blocks re-executed to serve a single `trace_filter` request
+const MAX_TRACED_BLOCKS: u64 = 100;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFilterParams {
+    #[serde(default)]
+    from_block: Option<Value>,
+    #[serde(default)]
+    to_block: Option<Value>,
+    #[serde(default)]
+    from_address: Vec<Address>,
+    #[serde(default)]
+    to_address: Vec<Address>,
+    #[serde(default)]
+    after: usize,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+pub struct TraceFilterRequest {
+    from_block: BlockIdentifier,
+    to_block: BlockIdentifier,
+    from_addresses: Vec<Address>,
+    to_addresses: Vec<Address>,
+    after: usize,
+    count: Option<usize>,
+}
+
+impl TraceFilterRequest {
+    /// An empty address list matches any address
+    fn matches(&self, flat_trace: &FlatTrace) -> bool {
+        (self.from_addresses.is_empty() || self.from_addresses.contains(&flat_trace.from_address()))
+            && (self.to_addresses.is_empty()
+                || flat_trace
+                    .to_address()
+                    .is_some_and(|to| self.to_addresses.contains(&to)))
+    }
+
+    /// Returns the blocks in from..=to that may contain matching traces.
+    /// When filtering by address, the part of the range covered by the trace index is narrowed
+    /// down to the blocks where the addresses appear; the rest of the range is traced entirely.
+    async fn blocks_to_trace(
+        &self,
+        storage: &Store,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, RpcErr> {
+        let filters_by_address = !self.from_addresses.is_empty() || !self.to_addresses.is_empty();
+        let indexed_range = storage
+            .get_trace_indexed_range()
+            .await?
+            .filter(|_| filters_by_address)
+            .map(|(first, last)| (first.max(from), last.min(to)))
+            .filter(|(first, last)| first <= last);
+        let Some((first, last)) = indexed_range else {
+            check_traced_blocks(to - from + 1)?;
+            return Ok((from..=to).collect());
+        };
+        check_traced_blocks((first - from) + (to - last))?;
+
+        let mut indexed_blocks: Option<BTreeSet<BlockNumber>> = None;
+        for addresses in [&self.from_addresses, &self.to_addresses] {
+            if addresses.is_empty() {
+                continue;
+            }
+            let mut blocks = BTreeSet::new();
+            for address in addresses {
+                blocks.extend(
+                    storage
+                        .get_trace_index_blocks(*address, first, last)
+                        .await?,
+                );
+            }
+            // Traces must match both the from and to addresses
+            indexed_blocks = Some(match indexed_blocks {
+                Some(indexed_blocks) => indexed_blocks.intersection(&blocks).copied().collect(),
+                None => blocks,
+            });
+        }
+
+        let blocks: Vec<BlockNumber> = (from..first)
+            .chain(indexed_blocks.unwrap_or_default())
+            .chain(last + 1..=to)
+            .collect();
+        check_traced_blocks(blocks.len() as u64)?;
+        Ok(blocks)
+    }
+}
+
+fn check_traced_blocks(amount: u64) -> Result<(), RpcErr> {
+    if amount > MAX_TRACED_BLOCKS {
+        return Err(RpcErr::BadParams(format!(
+            "Too many blocks to trace, at most {MAX_TRACED_BLOCKS} blocks can be traced per request. Narrow down the block range or addresses, or enable the trace index"
+        )));
+    }
+    Ok(())
+}
+
+fn parse_block(value: Option<Value>, arg_index: u64) -> Result<BlockIdentifier, RpcErr> {
+    match value {
+        Some(value) => BlockIdentifier::parse(value, arg_index),
+        None => Ok(BlockIdentifier::Tag(BlockTag::Latest)),
+    }
+}
+
+impl RpcHandler for TraceFilterRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        let filter: TraceFilterParams = serde_json::from_value(params[0].clone())?;
+        Ok(TraceFilterRequest {
+            from_block: parse_block(filter.from_block, 0)?,
+            to_block: parse_block(filter.to_block, 0)?,
+            from_addresses: filter.from_address,
+            to_addresses: filter.to_address,
+            after: filter.after,
+            count: filter.count,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let (Some(from), Some(to)) = (
+            self.from_block
+                .resolve_block_number(&context.storage)
+                .await?,
+            self.to_block.resolve_block_number(&context.storage).await?,
+        ) else {
+            return Err(RpcErr::BadParams("Block not found".to_owned()));
+        };
+        if from > to {
+            return Err(RpcErr::BadParams(
+                "fromBlock can't be greater than toBlock".to_owned(),
+            ));
+        }
+
+        let mut flat_traces = Vec::new();
+        for block_number in self.blocks_to_trace(&context.storage, from, to).await? {
+            let block = context
+                .storage
+                .get_block_by_number(block_number)
+                .await?
+                .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+            flat_traces.extend(
+                trace_block(&context, block)
+                    .await?
+                    .into_iter()
+                    .filter(|flat_trace| self.matches(flat_trace)),
+            );
+        }
+        let flat_traces: Vec<FlatTrace> = flat_traces
+            .into_iter()
+            .skip(self.after)
+            .take(self.count.unwrap_or(usize::MAX))
+            .collect();
+        Ok(serde_json::to_value(flat_traces)?)
+    }
+}
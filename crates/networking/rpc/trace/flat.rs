@@ -0,0 +1,428 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    tracing::{CallTrace, CallTraceFrame, CallType},
+    types::{Block, BlockHash, BlockNumber},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    tracing::{DEFAULT_REEXEC, DEFAULT_TIMEOUT},
+    types::block_identifier::BlockIdentifier,
+    utils::RpcErr,
+};
+
+pub struct TraceTransactionRequest {
+    tx_hash: H256,
+}
+
+pub struct TraceGetRequest {
+    tx_hash: H256,
+    trace_address: Vec<usize>,
+}
+
+pub struct TraceBlockRequest {
+    block: BlockIdentifier,
+}
+
+/// Call frame in the Parity/OpenEthereum flat trace format
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FlatTrace {
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    result: Option<TraceResult>,
+    subtraces: usize,
+    /// Indices of the sub-calls leading to this call frame from the top one
+    trace_address: Vec<usize>,
+    #[serde(rename = "type")]
+    trace_type: &'static str,
+    block_hash: BlockHash,
+    block_number: BlockNumber,
+    transaction_hash: H256,
+    transaction_position: usize,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Action {
+    Call(CallAction),
+    Create(CreateAction),
+    Suicide(SuicideAction),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallAction {
+    from: Address,
+    call_type: &'static str,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    input: Bytes,
+    to: Address,
+    value: U256,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateAction {
+    from: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    init: Bytes,
+    value: U256,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SuicideAction {
+    address: Address,
+    refund_address: Address,
+    balance: U256,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum TraceResult {
+    Call {
+        #[serde(rename = "gasUsed", with = "serde_utils::u64::hex_str")]
+        gas_used: u64,
+        #[serde(with = "serde_utils::bytes")]
+        output: Bytes,
+    },
+    Create {
+        address: Address,
+        #[serde(with = "serde_utils::bytes")]
+        code: Bytes,
+        #[serde(rename = "gasUsed", with = "serde_utils::u64::hex_str")]
+        gas_used: u64,
+    },
+}
+
+impl FlatTrace {
+    /// Address that initiated the call frame
+    pub(crate) fn from_address(&self) -> Address {
+        match &self.action {
+            Action::Call(action) => action.from,
+            Action::Create(action) => action.from,
+            Action::Suicide(action) => action.address,
+        }
+    }
+
+    /// Address that received the call frame, the created contract for creations
+    pub(crate) fn to_address(&self) -> Option<Address> {
+        match (&self.action, &self.result) {
+            (Action::Call(action), _) => Some(action.to),
+            (Action::Create(_), Some(TraceResult::Create { address, .. })) => Some(*address),
+            (Action::Create(_), _) => None,
+            (Action::Suicide(action), _) => Some(action.refund_address),
+        }
+    }
+}
+
+/// Position of a transaction's traces in the chain
+#[derive(Clone, Copy)]
+struct TraceLocation {
+    block_hash: BlockHash,
+    block_number: BlockNumber,
+    transaction_hash: H256,
+    transaction_position: usize,
+}
+
+/// Flattens the call trace of a transaction, listing each call frame before its sub-calls
+fn flatten_call_trace(call_trace: CallTrace, location: TraceLocation) -> Vec<FlatTrace> {
+    let mut flat_traces = Vec::new();
+    for frame in call_trace {
+        flatten_frame(frame, Vec::new(), location, &mut flat_traces);
+    }
+    flat_traces
+}
+
+fn flatten_frame(
+    frame: CallTraceFrame,
+    trace_address: Vec<usize>,
+    location: TraceLocation,
+    flat_traces: &mut Vec<FlatTrace>,
+) {
+    let CallTraceFrame {
+        call_type,
+        from,
+        to,
+        value,
+        gas,
+        gas_used,
+        input,
+        output,
+        error,
+        calls,
+        ..
+    } = frame;
+    let succeeded = error.is_none();
+    let (trace_type, action, result) = match call_type {
+        CallType::CREATE | CallType::CREATE2 => (
+            "create",
+            Action::Create(CreateAction {
+                from,
+                gas,
+                init: input,
+                value,
+            }),
+            succeeded.then_some(TraceResult::Create {
+                address: to,
+                code: output,
+                gas_used,
+            }),
+        ),
+        CallType::SELFDESTRUCT => (
+            "suicide",
+            Action::Suicide(SuicideAction {
+                address: from,
+                refund_address: to,
+                balance: value,
+            }),
+            None,
+        ),
+        CallType::CALL | CallType::CALLCODE | CallType::STATICCALL | CallType::DELEGATECALL => (
+            "call",
+            Action::Call(CallAction {
+                from,
+                call_type: match call_type {
+                    CallType::CALLCODE => "callcode",
+                    CallType::STATICCALL => "staticcall",
+                    CallType::DELEGATECALL => "delegatecall",
+                    _ => "call",
+                },
+                gas,
+                input,
+                to,
+                value,
+            }),
+            succeeded.then_some(TraceResult::Call { gas_used, output }),
+        ),
+    };
+    flat_traces.push(FlatTrace {
+        action,
+        error,
+        result,
+        subtraces: calls.len(),
+        trace_address: trace_address.clone(),
+        trace_type,
+        block_hash: location.block_hash,
+        block_number: location.block_number,
+        transaction_hash: location.transaction_hash,
+        transaction_position: location.transaction_position,
+    });
+    for (index, call) in calls.into_iter().enumerate() {
+        let mut sub_trace_address = trace_address.clone();
+        sub_trace_address.push(index);
+        flatten_frame(call, sub_trace_address, location, flat_traces);
+    }
+}
+
+/// Traces every transaction of the block, returning their flat traces in order
+pub(crate) async fn trace_block(
+    context: &RpcApiContext,
+    block: Block,
+) -> Result<Vec<FlatTrace>, RpcErr> {
+    let block_hash = block.hash();
+    let block_number = block.header.number;
+    let call_traces = context
+        .blockchain
+        .trace_block_calls(block, DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+        .await
+        .map_err(|err| RpcErr::Internal(err.to_string()))?;
+    Ok(call_traces
+        .into_iter()
+        .enumerate()
+        .flat_map(|(transaction_position, (transaction_hash, call_trace))| {
+            flatten_call_trace(
+                call_trace,
+                TraceLocation {
+                    block_hash,
+                    block_number,
+                    transaction_hash,
+                    transaction_position,
+                },
+            )
+        })
+        .collect())
+}
+
+async fn trace_transaction(
+    context: &RpcApiContext,
+    tx_hash: H256,
+) -> Result<Vec<FlatTrace>, RpcErr> {
+    let Some((block_number, block_hash, index)) =
+        context.storage.get_transaction_location(tx_hash).await?
+    else {
+        return Err(RpcErr::Internal("Transaction not Found".to_string()));
+    };
+    let call_trace = context
+        .blockchain
+        .trace_transaction_calls(tx_hash, DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+        .await
+        .map_err(|err| RpcErr::Internal(err.to_string()))?;
+    Ok(flatten_call_trace(
+        call_trace,
+        TraceLocation {
+            block_hash,
+            block_number,
+            transaction_hash: tx_hash,
+            transaction_position: index as usize,
+        },
+    ))
+}
+
+impl RpcHandler for TraceTransactionRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        Ok(TraceTransactionRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let flat_traces = trace_transaction(&context, self.tx_hash).await?;
+        Ok(serde_json::to_value(flat_traces)?)
+    }
+}
+
+impl RpcHandler for TraceGetRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 2 params".to_owned()));
+        };
+        let trace_address = serde_json::from_value::<Vec<Value>>(params[1].clone())?
+            .into_iter()
+            .map(|index| Ok(serde_utils::u64::hex_str::deserialize(index)? as usize))
+            .collect::<Result<_, RpcErr>>()?;
+        Ok(TraceGetRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+            trace_address,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let flat_trace = trace_transaction(&context, self.tx_hash)
+            .await?
+            .into_iter()
+            .find(|flat_trace| flat_trace.trace_address == self.trace_address);
+        Ok(serde_json::to_value(flat_trace)?)
+    }
+}
+
+impl RpcHandler for TraceBlockRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        Ok(TraceBlockRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let Some(block_number) = self.block.resolve_block_number(&context.storage).await? else {
+            return Ok(Value::Null);
+        };
+        let Some(block) = context.storage.get_block_by_number(block_number).await? else {
+            return Ok(Value::Null);
+        };
+        let flat_traces = trace_block(&context, block).await?;
+        Ok(serde_json::to_value(flat_traces)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn call_trace_is_flattened_depth_first() {
+        let from = Address::repeat_byte(1);
+        let contract = Address::repeat_byte(2);
+        let created = Address::repeat_byte(3);
+        let call_trace = vec![CallTraceFrame {
+            from,
+            to: contract,
+            gas: 100_000,
+            gas_used: 50_000,
+            calls: vec![
+                CallTraceFrame {
+                    call_type: CallType::STATICCALL,
+                    from: contract,
+                    to: from,
+                    ..Default::default()
+                },
+                CallTraceFrame {
+                    call_type: CallType::CREATE,
+                    from: contract,
+                    to: created,
+                    calls: vec![CallTraceFrame {
+                        call_type: CallType::SELFDESTRUCT,
+                        from: created,
+                        to: from,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }];
+        let location = TraceLocation {
+            block_hash: H256::repeat_byte(4),
+            block_number: 5,
+            transaction_hash: H256::repeat_byte(6),
+            transaction_position: 1,
+        };
+
+        let flat_traces = flatten_call_trace(call_trace, location);
+
+        let trace_addresses: Vec<_> = flat_traces
+            .iter()
+            .map(|flat_trace| flat_trace.trace_address.clone())
+            .collect();
+        assert_eq!(trace_addresses, vec![vec![], vec![0], vec![1], vec![1, 0]]);
+        assert_eq!(flat_traces[2].to_address(), Some(created));
+        assert_eq!(flat_traces[3].from_address(), created);
+        assert_eq!(
+            serde_json::to_value(&flat_traces[0]).unwrap(),
+            json!({
+                "action": {
+                    "from": format!("{from:#x}"),
+                    "callType": "call",
+                    "gas": "0x186a0",
+                    "input": "0x",
+                    "to": format!("{contract:#x}"),
+                    "value": "0x0",
+                },
+                "result": { "gasUsed": "0xc350", "output": "0x" },
+                "subtraces": 2,
+                "traceAddress": [],
+                "type": "call",
+                "blockHash": format!("{:#x}", H256::repeat_byte(4)),
+                "blockNumber": 5,
+                "transactionHash": format!("{:#x}", H256::repeat_byte(6)),
+                "transactionPosition": 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod filter;
+pub mod flat;
+pub mod state_diff;
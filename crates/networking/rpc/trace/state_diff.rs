@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    tracing::{AccountStateDiff, StateDiff},
+    utils::u256_to_h256,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    tracing::{DEFAULT_REEXEC, DEFAULT_TIMEOUT},
+    types::block_identifier::{BlockIdentifier, BlockIdentifierOrHash},
+    utils::RpcErr,
+};
+
+pub struct ReplayTransactionRequest {
+    tx_hash: H256,
+    trace_types: TraceTypes,
+}
+
+pub struct ReplayBlockTransactionsRequest {
+    block: BlockIdentifier,
+    trace_types: TraceTypes,
+}
+
+pub struct GetStateDiffRequest {
+    block: BlockIdentifierOrHash,
+}
+
+/// Outputs requested from the `trace_replay*` methods, only `stateDiff` is supported for now
+struct TraceTypes {
+    state_diff: bool,
+}
+
+impl TraceTypes {
+    fn parse(value: &Value) -> Result<Self, RpcErr> {
+        let mut trace_types = TraceTypes { state_diff: false };
+        for trace_type in serde_json::from_value::<Vec<String>>(value.clone())? {
+            match trace_type.as_str() {
+                "stateDiff" => trace_types.state_diff = true,
+                other => {
+                    return Err(RpcErr::BadParams(format!(
+                        "Unsupported trace type: {other}"
+                    )));
+                }
+            }
+        }
+        Ok(trace_types)
+    }
+}
+
+/// Output of the `trace_replay*` methods for a single transaction
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceResults {
+    #[serde(with = "serde_utils::bytes")]
+    output: Bytes,
+    state_diff: Option<ParityStateDiff>,
+    trace: Vec<Value>,
+    vm_trace: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_hash: Option<H256>,
+}
+
+impl TraceResults {
+    fn new(
+        output: Bytes,
+        state_diff: StateDiff,
+        trace_types: &TraceTypes,
+        transaction_hash: Option<H256>,
+    ) -> Self {
+        TraceResults {
+            output,
+            state_diff: trace_types
+                .state_diff
+                .then(|| to_parity_state_diff(state_diff)),
+            trace: Vec::new(),
+            vm_trace: None,
+            transaction_hash,
+        }
+    }
+}
+
+/// State diff in the format used by Parity/OpenEthereum, indexed by account address
+type ParityStateDiff = BTreeMap<Address, ParityAccountDiff>;
+
+#[derive(Serialize)]
+struct ParityAccountDiff {
+    balance: Delta<U256>,
+    code: Delta<String>,
+    nonce: Delta<U256>,
+    storage: BTreeMap<H256, Delta<H256>>,
+}
+
+/// Change of a single value: `=` if it didn't change, `+` if the account was created, `-` if
+/// it was removed and `*` if it changed
+#[derive(Serialize)]
+enum Delta<T> {
+    #[serde(rename = "=")]
+    Unchanged,
+    #[serde(rename = "+")]
+    Added(T),
+    #[serde(rename = "-")]
+    Removed(T),
+    #[serde(rename = "*")]
+    Changed { from: T, to: T },
+}
+
+impl<T: PartialEq> Delta<T> {
+    fn new(pre: Option<T>, post: Option<T>) -> Self {
+        match (pre, post) {
+            (None, Some(post)) => Delta::Added(post),
+            (Some(pre), None) => Delta::Removed(pre),
+            (Some(pre), Some(post)) if pre != post => Delta::Changed {
+                from: pre,
+                to: post,
+            },
+            _ => Delta::Unchanged,
+        }
+    }
+}
+
+fn to_parity_state_diff(state_diff: StateDiff) -> ParityStateDiff {
+    state_diff
+        .into_iter()
+        .map(|(address, account_diff)| (address, to_parity_account_diff(account_diff)))
+        .collect()
+}
+
+fn to_parity_account_diff(account_diff: AccountStateDiff) -> ParityAccountDiff {
+    let AccountStateDiff { pre, post, storage } = account_diff;
+    let storage = storage
+        .into_iter()
+        .map(|(key, (pre_value, post_value))| {
+            let (pre_value, post_value) = (u256_to_h256(pre_value), u256_to_h256(post_value));
+            let delta = match (&pre, &post) {
+                (None, Some(_)) => Delta::Added(post_value),
+                (Some(_), None) => Delta::Removed(pre_value),
+                _ => Delta::Changed {
+                    from: pre_value,
+                    to: post_value,
+                },
+            };
+            (key, delta)
+        })
+        .collect();
+    ParityAccountDiff {
+        balance: Delta::new(
+            pre.as_ref().map(|account| account.balance),
+            post.as_ref().map(|account| account.balance),
+        ),
+        code: Delta::new(
+            pre.as_ref()
+                .map(|account| format!("0x{}", hex::encode(&account.code))),
+            post.as_ref()
+                .map(|account| format!("0x{}", hex::encode(&account.code))),
+        ),
+        nonce: Delta::new(
+            pre.as_ref().map(|account| U256::from(account.nonce)),
+            post.as_ref().map(|account| U256::from(account.nonce)),
+        ),
+        storage,
+    }
+}
+
+fn get_params(params: &Option<Vec<Value>>, expected: usize) -> Result<&Vec<Value>, RpcErr> {
+    let params = params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != expected {
+        return Err(RpcErr::BadParams(format!("Expected {expected} params")));
+    };
+    Ok(params)
+}
+
+impl RpcHandler for ReplayTransactionRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = get_params(params, 2)?;
+        Ok(ReplayTransactionRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+            trace_types: TraceTypes::parse(&params[1])?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let (output, state_diff) = context
+            .blockchain
+            .trace_transaction_state_diff(self.tx_hash, DEFAULT_REEXEC, DEFAULT_TIMEOUT)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        Ok(serde_json::to_value(TraceResults::new(
+            output,
+            state_diff,
+            &self.trace_types,
+            None,
+        ))?)
+    }
+}
+
+impl RpcHandler for ReplayBlockTransactionsRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = get_params(params, 2)?;
+        Ok(ReplayBlockTransactionsRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+            trace_types: TraceTypes::parse(&params[1])?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block_number = self
+            .block
+            .resolve_block_number(&context.storage)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let block = context
+            .storage
+            .get_block_by_number(block_number)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let state_diffs = context
+            .blockchain
+            .trace_block_transactions_state_diff(block, DEFAULT_REEXEC, DEFAULT_TIMEOUT)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        let results: Vec<TraceResults> = state_diffs
+            .into_iter()
+            .map(|(tx_hash, output, state_diff)| {
+                TraceResults::new(output, state_diff, &self.trace_types, Some(tx_hash))
+            })
+            .collect();
+        Ok(serde_json::to_value(results)?)
+    }
+}
+
+impl RpcHandler for GetStateDiffRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = get_params(params, 1)?;
+        Ok(GetStateDiffRequest {
+            block: BlockIdentifierOrHash::parse(params[0].clone(), 0)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block_number = self
+            .block
+            .resolve_block_number(&context.storage)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let block = context
+            .storage
+            .get_block_by_number(block_number)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let state_diff = context
+            .blockchain
+            .trace_block_state_diff(block, DEFAULT_REEXEC, DEFAULT_TIMEOUT)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        Ok(serde_json::to_value(to_parity_state_diff(state_diff))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_common::tracing::AccountStateSnapshot;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parity_state_diff_format() {
+        let snapshot = |balance: u64, nonce| AccountStateSnapshot {
+            balance: U256::from(balance),
+            nonce,
+            code: Bytes::new(),
+        };
+        let changed = Address::repeat_byte(1);
+        let created = Address::repeat_byte(2);
+        let state_diff = StateDiff::from([
+            (
+                changed,
+                AccountStateDiff {
+                    pre: Some(snapshot(10, 1)),
+                    post: Some(snapshot(4, 2)),
+                    storage: BTreeMap::from([(H256::zero(), (U256::from(1), U256::zero()))]),
+                },
+            ),
+            (
+                created,
+                AccountStateDiff {
+                    pre: None,
+                    post: Some(snapshot(6, 0)),
+                    storage: BTreeMap::new(),
+                },
+            ),
+        ]);
+
+        let zero = format!("{:#x}", H256::zero());
+        let one = format!("{:#x}", u256_to_h256(U256::from(1)));
+        assert_eq!(
+            serde_json::to_value(to_parity_state_diff(state_diff)).unwrap(),
+            json!({
+                format!("{changed:#x}"): {
+                    "balance": { "*": { "from": "0xa", "to": "0x4" } },
+                    "code": "=",
+                    "nonce": { "*": { "from": "0x1", "to": "0x2" } },
+                    "storage": { zero.clone(): { "*": { "from": one, "to": zero } } },
+                },
+                format!("{created:#x}"): {
+                    "balance": { "+": "0x6" },
+                    "code": { "+": "0x" },
+                    "nonce": { "+": "0x0" },
+                    "storage": {},
+                },
+            })
+        );
+    }
+}
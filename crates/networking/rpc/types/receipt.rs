@@ -11,7 +11,7 @@ use ethrex_common::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::utils::RpcErr;
+use crate::{dev::impersonated_sender, utils::RpcErr};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RpcReceipt {
@@ -175,7 +175,10 @@ impl RpcReceiptTxInfo {
         base_fee_per_gas: Option<u64>,
     ) -> Result<Self, RpcErr> {
         let nonce = transaction.nonce();
-        let from = transaction.sender()?;
+        let from = match impersonated_sender(&transaction) {
+            Some(sender) => sender,
+            None => transaction.sender()?,
+        };
         let transaction_hash = transaction.hash();
         let effective_gas_price = transaction
             .effective_gas_price(base_fee_per_gas)
@@ -1,4 +1,4 @@
-use crate::utils::RpcErr;
+use crate::{dev::impersonated_sender, utils::RpcErr};
 use ethrex_common::{
     Address, H256, serde_utils,
     types::{
@@ -32,7 +32,10 @@ impl RpcTransaction {
         block_hash: Option<BlockHash>,
         transaction_index: Option<usize>,
     ) -> Result<Self, RpcErr> {
-        let from = tx.sender()?;
+        let from = match impersonated_sender(&tx) {
+            Some(sender) => sender,
+            None => tx.sender()?,
+        };
         let hash = tx.hash();
         let transaction_index = transaction_index.map(|n| n as u64);
         Ok(RpcTransaction {
@@ -1,4 +1,4 @@
-use ethrex_common::U256;
+use ethrex_common::{H256, U256};
 use ethrex_storage::error::StoreError;
 use ethrex_vm::EvmError;
 use serde::{Deserialize, Serialize};
@@ -39,6 +39,10 @@ pub enum RpcErr {
     InvalidPayloadAttributes(String),
     #[error("Unknown payload: {0}")]
     UnknownPayload(String),
+    #[error("Pruned history unavailable for block {0}")]
+    PrunedHistory(u64),
+    #[error("Transaction {hash:#x} wasn't included within {timeout_ms}ms")]
+    TransactionTimeout { hash: H256, timeout_ms: u64 },
 }
 
 impl From<RpcErr> for RpcErrorMetadata {
@@ -140,6 +144,20 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Unknown payload: {context}"),
             },
+            // Same code as geth, taken from EIP-4444
+            RpcErr::PrunedHistory(block_number) => RpcErrorMetadata {
+                code: 4444,
+                data: None,
+                message: format!("pruned history unavailable for block {block_number}"),
+            },
+            // Code and data defined by EIP-7966
+            RpcErr::TransactionTimeout { hash, timeout_ms } => RpcErrorMetadata {
+                code: 4,
+                data: Some(format!("{hash:#x}")),
+                message: format!(
+                    "The transaction was added to the transaction pool but wasn't processed in {timeout_ms}ms"
+                ),
+            },
         }
     }
 }
@@ -175,6 +193,9 @@ pub enum RpcNamespace {
     Web3,
     Net,
     Mempool,
+    Dev,
+    Ethrex,
+    Trace,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -221,6 +242,11 @@ pub fn resolve_namespace(maybe_namespace: &str, method: String) -> Result<RpcNam
         "net" => Ok(RpcNamespace::Net),
         // TODO: The namespace is set to match geth's namespace for compatibility, consider changing it in the future
         "txpool" => Ok(RpcNamespace::Mempool),
+        // Anvil/Hardhat compatible methods, only served in dev mode
+        "evm" | "anvil" => Ok(RpcNamespace::Dev),
+        "ethrex" => Ok(RpcNamespace::Ethrex),
+        // Parity/OpenEthereum compatible tracing methods
+        "trace" => Ok(RpcNamespace::Trace),
         _ => Err(RpcErr::MethodNotFound(method)),
     }
 }
@@ -1,4 +1,4 @@
-use ethereum_types::H256;
+use ethereum_types::{Address, H256};
 use ethrex_common::types::{
     Block, BlockBody, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code, Index, Receipt,
     Transaction,
@@ -53,6 +53,33 @@ pub trait StoreEngine: Debug + Send + Sync + RefUnwindSafe {
     /// Remove canonical block
     async fn remove_block(&self, block_number: BlockNumber) -> Result<(), StoreError>;
 
+    /// Remove the bodies, receipts and transaction locations of the given blocks,
+    /// keeping their headers and canonical hashes (EIP-4444 history expiry)
+    async fn prune_block_history(&self, block_hashes: Vec<BlockHash>) -> Result<(), StoreError>;
+
+    /// Adds the addresses appearing in the call traces of the block to the trace index and sets
+    /// the range of blocks covered by the index
+    async fn add_trace_index_entries(
+        &self,
+        block_number: BlockNumber,
+        addresses: Vec<Address>,
+        indexed_range: (BlockNumber, BlockNumber),
+    ) -> Result<(), StoreError>;
+
+    /// Returns the blocks in from..=to whose call traces involve the address, according to the
+    /// trace index
+    async fn get_trace_index_blocks(
+        &self,
+        address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, StoreError>;
+
+    /// Returns the first and last block covered by the trace index, if any
+    async fn get_trace_indexed_range(
+        &self,
+    ) -> Result<Option<(BlockNumber, BlockNumber)>, StoreError>;
+
     /// Obtain canonical block bodies in from..=to
     async fn get_block_bodies(
         &self,
@@ -187,6 +214,9 @@ pub trait StoreEngine: Debug + Send + Sync + RefUnwindSafe {
     /// Ignores previously stored values if present
     async fn set_chain_config(&self, chain_config: &ChainConfig) -> Result<(), StoreError>;
 
+    /// Obtain the chain configuration values stored by `set_chain_config`
+    async fn get_chain_config(&self) -> Result<Option<ChainConfig>, StoreError>;
+
     /// Update earliest block number
     async fn update_earliest_block_number(
         &self,
@@ -386,4 +416,16 @@ pub trait StoreEngine: Debug + Send + Sync + RefUnwindSafe {
     fn flatkeyvalue_computed(&self, _account: H256) -> Result<bool, StoreError> {
         Ok(false)
     }
+
+    /// Returns true if the engine was opened as a read-only view of a database owned by
+    /// another process, in which case every write fails
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// Refreshes a read-only engine with the writes made by the primary since it was opened
+    /// or last refreshed. Does nothing for engines that own their database.
+    async fn try_catch_up_with_primary(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
 }
@@ -0,0 +1,266 @@
+use std::fmt::Display;
+
+use ethereum_types::H256;
+use ethrex_common::types::{BlockHash, BlockNumber};
+use tracing::info;
+
+use crate::{Store, error::StoreError};
+
+/// Default amount of blocks below the head checked for consistency.
+/// With RocksDB the state persisted to disk lags the head by up to 128 blocks, kept in
+/// in-memory trie layers that are lost if the node stops, so the checked range covers them.
+pub const CONSISTENCY_CHECK_DEPTH: u64 = 256;
+
+/// An inconsistency found in the database, usually left by a node killed mid-import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    MissingCanonicalHash(BlockNumber),
+    MissingHeader(BlockNumber),
+    /// The block's parent hash doesn't match the canonical block below it
+    UnlinkedHeader(BlockNumber),
+    MissingBody(BlockNumber),
+    MissingReceipts(BlockNumber),
+    /// The forkchoice pointers aren't ordered as finalized <= safe <= head
+    UnorderedForkchoice {
+        head: BlockNumber,
+        safe: Option<BlockNumber>,
+        finalized: Option<BlockNumber>,
+    },
+    /// None of the checked blocks has its state available, so it can't be regenerated
+    MissingState {
+        from: BlockNumber,
+        to: BlockNumber,
+    },
+    /// The state of the newest consistent block is missing, usually because the in-memory trie
+    /// layers were lost. It is regenerated by re-executing the blocks above
+    /// `newest_state_block` on startup
+    MissingHeadState {
+        head: BlockNumber,
+        newest_state_block: BlockNumber,
+    },
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Inconsistency::MissingCanonicalHash(number) => {
+                write!(f, "Canonical hash of block {number} is missing")
+            }
+            Inconsistency::MissingHeader(number) => {
+                write!(f, "Header of block {number} is missing")
+            }
+            Inconsistency::UnlinkedHeader(number) => write!(
+                f,
+                "Parent hash of block {number} doesn't match the canonical chain"
+            ),
+            Inconsistency::MissingBody(number) => write!(f, "Body of block {number} is missing"),
+            Inconsistency::MissingReceipts(number) => {
+                write!(f, "Receipts of block {number} are missing")
+            }
+            Inconsistency::UnorderedForkchoice {
+                head,
+                safe,
+                finalized,
+            } => write!(
+                f,
+                "Forkchoice pointers are unordered: head {head}, safe {safe:?}, finalized {finalized:?}"
+            ),
+            Inconsistency::MissingState { from, to } => write!(
+                f,
+                "State is missing for every block between {from} and {to}"
+            ),
+            Inconsistency::MissingHeadState {
+                head,
+                newest_state_block,
+            } => write!(
+                f,
+                "State of head block {head} is missing, it will be regenerated from block {newest_state_block}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Head pointed to by the latest block number
+    pub head: BlockNumber,
+    /// Newest block that is complete along with every checked block below it, which the head
+    /// is rolled back to on repair. None if the oldest checked block is incomplete
+    pub newest_consistent_block: Option<BlockNumber>,
+    /// Newest consistent block whose state is available. The state of the blocks above it is
+    /// regenerated by re-executing them on startup
+    pub newest_state_block: Option<BlockNumber>,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Returns true if every inconsistency found can be fixed, by [`Store::repair_consistency`]
+    /// or, for a missing head state, by re-executing the blocks when the node starts
+    pub fn is_repairable(&self) -> bool {
+        self.newest_consistent_block.is_some()
+            && !self
+                .inconsistencies
+                .iter()
+                .any(|inconsistency| matches!(inconsistency, Inconsistency::MissingState { .. }))
+    }
+}
+
+impl Store {
+    /// Checks that the `depth` canonical blocks below the head (or down to the earliest block
+    /// kept by history expiry) have their headers, bodies and receipts, that the head has its
+    /// state available, or at least one of them to regenerate it from, and that the forkchoice
+    /// pointers are ordered.
+    /// The earliest block isn't required to have receipts, as it is the pivot after a snap
+    /// sync, which is never executed.
+    /// Reads the database directly, so it can run before the initial state is loaded.
+    /// Returns None if the database is empty.
+    pub async fn check_consistency(
+        &self,
+        depth: u64,
+    ) -> Result<Option<ConsistencyReport>, StoreError> {
+        let Some(head) = self.engine.get_latest_block_number().await? else {
+            return Ok(None);
+        };
+        let earliest = self.engine.get_earliest_block_number().await?.unwrap_or(0);
+        let oldest = head.saturating_sub(depth).max(earliest).min(head);
+        let pivot = (earliest > 0).then_some(earliest);
+
+        let mut inconsistencies = Vec::new();
+        // The chain is checked from the oldest block up, stopping at the first incomplete one
+        let mut consistent_blocks = Vec::new();
+        let mut parent_hash = None;
+        for number in oldest..=head {
+            let check_receipts = pivot != Some(number);
+            match self
+                .check_block(number, parent_hash, check_receipts)
+                .await?
+            {
+                Ok((hash, state_root)) => {
+                    consistent_blocks.push((number, state_root));
+                    parent_hash = Some(hash);
+                }
+                Err(inconsistency) => {
+                    inconsistencies.push(inconsistency);
+                    break;
+                }
+            }
+        }
+        let newest_consistent_block = consistent_blocks.last().map(|(number, _)| *number);
+
+        let mut newest_state_block = None;
+        for (number, state_root) in consistent_blocks.iter().rev() {
+            if self.has_state_root(*state_root)? {
+                newest_state_block = Some(*number);
+                break;
+            }
+        }
+        match (newest_consistent_block, newest_state_block) {
+            (Some(newest_consistent_block), None) => {
+                inconsistencies.push(Inconsistency::MissingState {
+                    from: oldest,
+                    to: newest_consistent_block,
+                });
+            }
+            (Some(head), Some(newest_state_block)) if newest_state_block != head => {
+                inconsistencies.push(Inconsistency::MissingHeadState {
+                    head,
+                    newest_state_block,
+                });
+            }
+            _ => {}
+        }
+
+        let safe = self.engine.get_safe_block_number().await?;
+        let finalized = self.engine.get_finalized_block_number().await?;
+        if safe.is_some_and(|safe| safe > head)
+            || finalized.is_some_and(|finalized| finalized > safe.unwrap_or(head))
+        {
+            inconsistencies.push(Inconsistency::UnorderedForkchoice {
+                head,
+                safe,
+                finalized,
+            });
+        }
+
+        Ok(Some(ConsistencyReport {
+            head,
+            newest_consistent_block,
+            newest_state_block,
+            inconsistencies,
+        }))
+    }
+
+    /// Checks the canonical block is complete and follows `parent_hash`, returning its hash and
+    /// state root
+    async fn check_block(
+        &self,
+        number: BlockNumber,
+        parent_hash: Option<BlockHash>,
+        check_receipts: bool,
+    ) -> Result<Result<(BlockHash, H256), Inconsistency>, StoreError> {
+        let Some(hash) = self.engine.get_canonical_block_hash(number).await? else {
+            return Ok(Err(Inconsistency::MissingCanonicalHash(number)));
+        };
+        let Some(header) = self.engine.get_block_header_by_hash(hash)? else {
+            return Ok(Err(Inconsistency::MissingHeader(number)));
+        };
+        if parent_hash.is_some_and(|parent_hash| parent_hash != header.parent_hash) {
+            return Ok(Err(Inconsistency::UnlinkedHeader(number)));
+        }
+        let Some(body) = self.engine.get_block_body_by_hash(hash).await? else {
+            return Ok(Err(Inconsistency::MissingBody(number)));
+        };
+        if check_receipts {
+            let receipts = self.engine.get_receipts_for_block(&hash).await?;
+            if receipts.len() != body.transactions.len() {
+                return Ok(Err(Inconsistency::MissingReceipts(number)));
+            }
+        }
+        Ok(Ok((hash, header.state_root)))
+    }
+
+    /// Rolls the head back to the newest consistent block of the report and moves the safe and
+    /// finalized pointers down to it if they're ahead.
+    /// A missing head state is left to be regenerated when the node starts, and inconsistencies
+    /// that can't be repaired are left as they are, see [`ConsistencyReport::is_repairable`].
+    pub async fn repair_consistency(&self, report: &ConsistencyReport) -> Result<(), StoreError> {
+        let Some(head) = report.newest_consistent_block else {
+            return Ok(());
+        };
+        let Some(head_hash) = self.engine.get_canonical_block_hash(head).await? else {
+            return Err(StoreError::Custom(format!(
+                "Canonical hash of block {head} is missing"
+            )));
+        };
+        let safe = self
+            .engine
+            .get_safe_block_number()
+            .await?
+            .map(|safe| safe.min(head));
+        let finalized = self
+            .engine
+            .get_finalized_block_number()
+            .await?
+            .map(|finalized| finalized.min(safe.unwrap_or(head)));
+        if head == report.head
+            && !report.inconsistencies.iter().any(|inconsistency| {
+                matches!(inconsistency, Inconsistency::UnorderedForkchoice { .. })
+            })
+        {
+            return Ok(());
+        }
+        if head < report.head {
+            info!(
+                from = report.head,
+                to = head,
+                "Rolling back head to the newest consistent block"
+            );
+        }
+        self.forkchoice_update(None, head, head_hash, safe, finalized)
+            .await
+    }
+}
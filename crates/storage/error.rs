@@ -1,3 +1,4 @@
+use ethereum_types::H256;
 use ethrex_rlp::error::RLPDecodeError;
 use ethrex_trie::TrieError;
 use thiserror::Error;
@@ -40,4 +41,6 @@ pub enum StoreError {
     UpdateBatchNoBlocks,
     #[error("Pivot changed")]
     PivotChanged,
+    #[error("State {0:#x} is not persisted to disk yet, so read-only stores can't access it")]
+    StateNotPersisted(H256),
 }
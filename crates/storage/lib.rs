@@ -1,4 +1,5 @@
 mod api;
+mod consistency;
 
 #[cfg(feature = "rocksdb")]
 mod rlp;
@@ -9,6 +10,7 @@ mod trie_db;
 mod utils;
 
 pub mod error;
+pub use consistency::{CONSISTENCY_CHECK_DEPTH, ConsistencyReport, Inconsistency};
 pub use store::{
     AccountUpdatesList, EngineType, MAX_SNAPSHOT_READS, STATE_TRIE_SEGMENTS, Store, UpdateBatch,
     hash_address, hash_key,
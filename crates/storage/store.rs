@@ -30,6 +30,11 @@ pub const STATE_TRIE_SEGMENTS: usize = 2;
 /// Maximum amount of reads from the snapshot in a single transaction to avoid performance hits due to long-living reads
 /// This will always be the amount yielded by snapshot reads unless there are less elements left
 pub const MAX_SNAPSHOT_READS: usize = 100;
+/// Maximum amount of blocks whose history is removed in a single write when pruning expired history
+pub const HISTORY_PRUNING_BATCH_SIZE: u64 = 1024;
+/// Maximum amount of blocks a read-only store walks back from the head looking for the one whose state is persisted
+/// The node keeps around 128 blocks in memory, this leaves room for the ones it's still persisting
+const MAX_UNPERSISTED_BLOCKS: u64 = 1024;
 
 #[derive(Debug, Clone)]
 pub struct Store {
@@ -100,6 +105,68 @@ impl Store {
         Ok(store)
     }
 
+    /// Opens the RocksDB database at `path` read-only, while the node that owns it keeps
+    /// running, as a RocksDB secondary instance keeping its own files at `secondary_path`.
+    ///
+    /// The store sees the database as it was when opened; call [`Store::catch_up_with_primary`]
+    /// to follow the node's writes. Only the state of the block the node last persisted to disk
+    /// can be read, so that block is reported as the latest one. It lags the node's head by the
+    /// blocks it keeps in in-memory diff layers, which can still be read by number or hash.
+    #[cfg(feature = "rocksdb")]
+    pub async fn new_secondary(
+        path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+    ) -> Result<Self, StoreError> {
+        let path = path.as_ref();
+        info!(?path, "Opening storage engine as read-only secondary");
+        let engine = RocksDBStore::new_secondary(path, secondary_path.as_ref())?;
+        let chain_config = engine.get_chain_config().await?.ok_or_else(|| {
+            StoreError::Custom("Chain config not found in the database".to_string())
+        })?;
+        let store = Self {
+            engine: Arc::new(engine),
+            chain_config,
+            latest_block_header: Default::default(),
+        };
+        store.update_latest_to_persisted_state().await?;
+        Ok(store)
+    }
+
+    /// Returns true if the store was opened with [`Store::new_secondary`]
+    pub fn is_read_only(&self) -> bool {
+        self.engine.is_read_only()
+    }
+
+    /// Makes a read-only store see the writes made to the database since it was opened or
+    /// last caught up, moving the latest block to the one whose state the node last persisted
+    pub async fn catch_up_with_primary(&self) -> Result<(), StoreError> {
+        if !self.is_read_only() {
+            return Ok(());
+        }
+        self.engine.try_catch_up_with_primary().await?;
+        self.update_latest_to_persisted_state().await
+    }
+
+    /// Sets the latest block of a read-only store to the newest canonical block whose state
+    /// is persisted, walking back from the node's head
+    async fn update_latest_to_persisted_state(&self) -> Result<(), StoreError> {
+        let Some(head) = self.engine.get_latest_block_number().await? else {
+            return Err(StoreError::MissingLatestBlockNumber);
+        };
+        for number in (head.saturating_sub(MAX_UNPERSISTED_BLOCKS)..=head).rev() {
+            let Some(header) = self.engine.get_block_header(number)? else {
+                continue;
+            };
+            if self.has_state_root(header.state_root)? {
+                self.latest_block_header.update(header);
+                return Ok(());
+            }
+        }
+        Err(StoreError::Custom(format!(
+            "no persisted state found in the {MAX_UNPERSISTED_BLOCKS} blocks before block {head}"
+        )))
+    }
+
     pub async fn new_from_genesis(
         store_path: &Path,
         engine_type: EngineType,
@@ -235,6 +302,78 @@ impl Store {
         self.engine.remove_block(block_number).await
     }
 
+    /// Removes the bodies and receipts of every canonical block below `boundary`, keeping
+    /// their headers, and moves the earliest block number up to it (EIP-4444 history expiry).
+    /// Returns the amount of blocks pruned.
+    pub async fn prune_history(&self, boundary: BlockNumber) -> Result<u64, StoreError> {
+        let earliest_block_number = self.get_earliest_block_number().await?;
+        if boundary <= earliest_block_number {
+            return Ok(0);
+        }
+
+        let mut from = earliest_block_number;
+        while from < boundary {
+            let to = boundary.min(from.saturating_add(HISTORY_PRUNING_BATCH_SIZE));
+            let mut block_hashes = Vec::with_capacity((to - from) as usize);
+            for block_number in from..to {
+                if let Some(block_hash) = self.engine.get_canonical_block_hash(block_number).await?
+                {
+                    block_hashes.push(block_hash);
+                }
+            }
+            self.engine.prune_block_history(block_hashes).await?;
+            // Advance the earliest block after each batch so an interrupted run leaves the
+            // store consistent and is resumed from where it stopped
+            self.update_earliest_block_number(to).await?;
+            from = to;
+        }
+
+        Ok(boundary - earliest_block_number)
+    }
+
+    /// Returns true if the bodies and receipts of the block were removed by history expiry
+    pub async fn is_history_expired(&self, block_number: BlockNumber) -> Result<bool, StoreError> {
+        Ok(block_number < self.get_earliest_block_number().await?)
+    }
+
+    /// Adds the addresses appearing in the call traces of the block to the trace index.
+    /// The indexed range is extended if the block follows it; otherwise the index restarts at
+    /// the block, as the ones in between can't be served from it.
+    pub async fn add_trace_index_entries(
+        &self,
+        block_number: BlockNumber,
+        addresses: Vec<Address>,
+    ) -> Result<(), StoreError> {
+        let indexed_range = match self.engine.get_trace_indexed_range().await? {
+            Some((first, last)) if last + 1 == block_number => (first, block_number),
+            _ => (block_number, block_number),
+        };
+        self.engine
+            .add_trace_index_entries(block_number, addresses, indexed_range)
+            .await
+    }
+
+    /// Returns the blocks in from..=to whose call traces involve the address.
+    /// Only blocks within the range returned by `get_trace_indexed_range` are covered.
+    pub async fn get_trace_index_blocks(
+        &self,
+        address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, StoreError> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+        self.engine.get_trace_index_blocks(address, from, to).await
+    }
+
+    /// Returns the first and last block covered by the trace index, if any
+    pub async fn get_trace_indexed_range(
+        &self,
+    ) -> Result<Option<(BlockNumber, BlockNumber)>, StoreError> {
+        self.engine.get_trace_indexed_range().await
+    }
+
     pub async fn get_block_bodies(
         &self,
         from: BlockNumber,
@@ -1181,7 +1320,11 @@ impl Store {
         if state_root == *EMPTY_TRIE_HASH {
             return Ok(true);
         }
-        let trie = self.engine.open_state_trie(state_root)?;
+        let trie = match self.engine.open_state_trie(state_root) {
+            Ok(trie) => trie,
+            Err(StoreError::StateNotPersisted(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        };
         // NOTE: here we hash the root because the trie doesn't check the state root is correct
         let Some(root) = trie.db().get(Nibbles::default())? else {
             return Ok(false);
@@ -1458,6 +1601,7 @@ mod tests {
     use std::{fs, str::FromStr};
 
     use super::*;
+    use crate::Inconsistency;
 
     #[tokio::test]
     async fn test_in_memory_store() {
@@ -1470,6 +1614,129 @@ mod tests {
         test_store_suite(EngineType::RocksDB).await;
     }
 
+    #[cfg(feature = "rocksdb")]
+    #[tokio::test]
+    async fn test_rocksdb_secondary_store() {
+        const GENESIS_KURTOSIS: &str = include_str!("../../fixtures/genesis/kurtosis.json");
+        let genesis: Genesis =
+            serde_json::from_str(GENESIS_KURTOSIS).expect("deserialize kurtosis.json");
+        let nonce: u64 = H256::random().to_low_u64_be();
+        let path = format!("store-test-db-{nonce}");
+        let secondary_path = format!("store-test-db-{nonce}-secondary");
+        remove_test_dbs(&path);
+        remove_test_dbs(&secondary_path);
+
+        let mut primary = Store::new(&path, EngineType::RocksDB).expect("open primary");
+        primary.add_initial_state(genesis).await.unwrap();
+        let secondary = Store::new_secondary(&path, &secondary_path)
+            .await
+            .expect("open secondary");
+        assert!(secondary.is_read_only());
+        assert!(!primary.is_read_only());
+        assert_eq!(secondary.get_chain_config(), primary.get_chain_config());
+        assert_eq!(secondary.get_latest_block_number().await.unwrap(), 0);
+        let genesis_state_root = secondary.get_block_header(0).unwrap().unwrap().state_root;
+        assert!(secondary.has_state_root(genesis_state_root).unwrap());
+
+        // The persisted state can be read
+        let account = secondary
+            .get_account_info(0, Address::zero())
+            .await
+            .unwrap()
+            .expect("genesis account");
+        assert_eq!(account.balance, U256::one());
+        let deposit_contract =
+            Address::from_str("0x4242424242424242424242424242424242424242").unwrap();
+        let storage_key = H256::from_low_u64_be(0x39);
+        assert_eq!(
+            secondary
+                .get_storage_at(0, deposit_contract, storage_key)
+                .unwrap(),
+            primary
+                .get_storage_at(0, deposit_contract, storage_key)
+                .unwrap()
+        );
+        assert!(
+            secondary
+                .get_code_by_account_address(0, deposit_contract)
+                .await
+                .unwrap()
+                .is_some_and(|code| !code.bytecode.is_empty())
+        );
+
+        // Writes from the primary are only seen after catching up. A block whose state is
+        // still in the primary's memory is readable, but isn't reported as the latest one.
+        let (block_header, block_body) = create_block_for_testing();
+        let hash = block_header.hash();
+        primary
+            .add_block_header(hash, block_header.clone())
+            .await
+            .unwrap();
+        primary
+            .add_block_body(hash, block_body.clone())
+            .await
+            .unwrap();
+        primary
+            .forkchoice_update(None, 6, hash, None, None)
+            .await
+            .unwrap();
+        assert!(secondary.get_block_header(6).unwrap().is_none());
+        secondary.catch_up_with_primary().await.unwrap();
+        assert_eq!(secondary.get_latest_block_number().await.unwrap(), 0);
+        assert_eq!(
+            secondary.get_block_header(6).unwrap(),
+            Some(block_header.clone())
+        );
+        assert!(matches!(
+            secondary.get_account_info(6, Address::zero()).await,
+            Err(StoreError::StateNotPersisted(_))
+        ));
+
+        // Once a block on top has persisted state, it becomes the latest one
+        let persisted_header = BlockHeader {
+            number: 7,
+            parent_hash: hash,
+            state_root: genesis_state_root,
+            hash: Default::default(),
+            ..block_header
+        };
+        let persisted_hash = persisted_header.hash();
+        primary
+            .add_block_header(persisted_hash, persisted_header)
+            .await
+            .unwrap();
+        primary
+            .add_block_body(persisted_hash, block_body)
+            .await
+            .unwrap();
+        primary
+            .forkchoice_update(None, 7, persisted_hash, None, None)
+            .await
+            .unwrap();
+        secondary.catch_up_with_primary().await.unwrap();
+        assert_eq!(secondary.get_latest_block_number().await.unwrap(), 7);
+        let account = secondary
+            .get_account_info(7, Address::zero())
+            .await
+            .unwrap()
+            .expect("genesis account");
+        assert_eq!(account.balance, U256::one());
+
+        // The secondary can't write, nor read state that isn't persisted
+        assert!(
+            secondary
+                .add_block_header(H256::random(), create_block_for_testing().0)
+                .await
+                .is_err()
+        );
+        assert!(!secondary.has_state_root(H256::random()).unwrap());
+
+        drop(secondary);
+        drop(primary);
+        remove_test_dbs(&path);
+        remove_test_dbs(&secondary_path);
+    }
+
     // Creates an empty store, runs the test and then removes the store (if needed)
     async fn run_test<F, Fut>(test_func: F, engine_type: EngineType)
     where
@@ -1502,6 +1769,11 @@ mod tests {
         run_test(test_genesis_block, engine_type).await;
         run_test(test_iter_accounts, engine_type).await;
         run_test(test_iter_storage, engine_type).await;
+        run_test(test_prune_history, engine_type).await;
+        run_test(test_trace_index, engine_type).await;
+        run_test(test_consistency_check, engine_type).await;
+        run_test(test_consistency_check_after_snap_sync, engine_type).await;
+        run_test(test_consistency_check_missing_head_state, engine_type).await;
     }
 
     async fn test_iter_accounts(store: Store) {
@@ -1680,6 +1952,271 @@ mod tests {
         (block_header, block_body)
     }
 
+    async fn test_prune_history(store: Store) {
+        let receipt = Receipt {
+            tx_type: TxType::EIP1559,
+            succeeded: true,
+            cumulative_gas_used: 1747,
+            logs: vec![],
+        };
+        let (block_header, block_body) = create_block_for_testing();
+        let mut canonical_blocks = Vec::new();
+        for block_number in 1..=3 {
+            let mut header = block_header.clone();
+            header.number = block_number;
+            let hash = header.hash();
+            store.add_block_header(hash, header).await.unwrap();
+            store
+                .add_block_body(hash, block_body.clone())
+                .await
+                .unwrap();
+            store
+                .add_receipts(hash, vec![receipt.clone(), receipt.clone()])
+                .await
+                .unwrap();
+            canonical_blocks.push((block_number, hash));
+        }
+        let (head_number, head_hash) = *canonical_blocks.last().unwrap();
+        store
+            .forkchoice_update(Some(canonical_blocks), head_number, head_hash, None, None)
+            .await
+            .unwrap();
+        store.update_earliest_block_number(1).await.unwrap();
+
+        let pruned = store.prune_history(3).await.unwrap();
+
+        assert_eq!(pruned, 2);
+        assert_eq!(store.get_earliest_block_number().await.unwrap(), 3);
+        for block_number in 1..=2 {
+            assert!(store.is_history_expired(block_number).await.unwrap());
+            assert!(store.get_block_header(block_number).unwrap().is_some());
+            assert!(store.get_block_body(block_number).await.unwrap().is_none());
+            assert!(store.get_receipt(block_number, 0).await.unwrap().is_none());
+        }
+        assert!(!store.is_history_expired(3).await.unwrap());
+        assert!(store.get_block_body(3).await.unwrap().is_some());
+        assert_eq!(store.get_receipt(3, 1).await.unwrap(), Some(receipt));
+        // Pruning up to an already expired boundary is a no-op
+        assert_eq!(store.prune_history(2).await.unwrap(), 0);
+    }
+
+    async fn test_consistency_check(store: Store) {
+        let receipt = Receipt {
+            tx_type: TxType::EIP1559,
+            succeeded: true,
+            cumulative_gas_used: 1747,
+            logs: vec![],
+        };
+        let (block_header, block_body) = create_block_for_testing();
+        let mut canonical_blocks = Vec::new();
+        let mut parent_hash = H256::zero();
+        for block_number in 1..=3 {
+            let mut header = block_header.clone();
+            header.number = block_number;
+            header.parent_hash = parent_hash;
+            header.state_root = *EMPTY_TRIE_HASH;
+            let hash = header.hash();
+            store.add_block_header(hash, header).await.unwrap();
+            store
+                .add_block_body(hash, block_body.clone())
+                .await
+                .unwrap();
+            // The last block is left without receipts, as if the import was interrupted
+            if block_number < 3 {
+                store
+                    .add_receipts(hash, vec![receipt.clone(), receipt.clone()])
+                    .await
+                    .unwrap();
+            }
+            canonical_blocks.push((block_number, hash));
+            parent_hash = hash;
+        }
+        let (head_number, head_hash) = *canonical_blocks.last().unwrap();
+        store
+            .forkchoice_update(
+                Some(canonical_blocks),
+                head_number,
+                head_hash,
+                Some(3),
+                Some(3),
+            )
+            .await
+            .unwrap();
+        store.update_earliest_block_number(1).await.unwrap();
+
+        let report = store.check_consistency(10).await.unwrap().unwrap();
+        assert_eq!(report.head, 3);
+        assert_eq!(report.newest_consistent_block, Some(2));
+        assert_eq!(report.newest_state_block, Some(2));
+        assert_eq!(
+            report.inconsistencies,
+            vec![Inconsistency::MissingReceipts(3)]
+        );
+        assert!(report.is_repairable());
+
+        store.repair_consistency(&report).await.unwrap();
+
+        assert_eq!(store.get_latest_block_number().await.unwrap(), 2);
+        assert_eq!(store.get_safe_block_number().await.unwrap(), Some(2));
+        assert_eq!(store.get_finalized_block_number().await.unwrap(), Some(2));
+        assert_eq!(store.get_canonical_block_hash(3).await.unwrap(), None);
+        let report = store.check_consistency(10).await.unwrap().unwrap();
+        assert!(report.is_consistent());
+    }
+
+    /// Stores the canonical chain `numbers` with two transactions per block, with receipts for
+    /// the blocks `with_receipts` accepts and the state root `state_root` returns
+    async fn store_chain_for_consistency_check(
+        store: &Store,
+        numbers: std::ops::RangeInclusive<BlockNumber>,
+        with_receipts: impl Fn(BlockNumber) -> bool,
+        state_root: impl Fn(BlockNumber) -> H256,
+    ) {
+        let receipt = Receipt {
+            tx_type: TxType::EIP1559,
+            succeeded: true,
+            cumulative_gas_used: 1747,
+            logs: vec![],
+        };
+        let (block_header, block_body) = create_block_for_testing();
+        let mut canonical_blocks = Vec::new();
+        let mut parent_hash = H256::zero();
+        for block_number in numbers {
+            let mut header = block_header.clone();
+            header.number = block_number;
+            header.parent_hash = parent_hash;
+            header.state_root = state_root(block_number);
+            let hash = header.hash();
+            store.add_block_header(hash, header).await.unwrap();
+            store
+                .add_block_body(hash, block_body.clone())
+                .await
+                .unwrap();
+            if with_receipts(block_number) {
+                store
+                    .add_receipts(hash, vec![receipt.clone(), receipt.clone()])
+                    .await
+                    .unwrap();
+            }
+            canonical_blocks.push((block_number, hash));
+            parent_hash = hash;
+        }
+        let (head_number, head_hash) = *canonical_blocks.last().unwrap();
+        store
+            .forkchoice_update(Some(canonical_blocks), head_number, head_hash, None, None)
+            .await
+            .unwrap();
+    }
+
+    async fn test_consistency_check_after_snap_sync(store: Store) {
+        // The pivot is stored without receipts and history is expired up to it, the blocks
+        // after it are executed
+        store_chain_for_consistency_check(
+            &store,
+            5..=7,
+            |block_number| block_number > 5,
+            |_| *EMPTY_TRIE_HASH,
+        )
+        .await;
+        store.update_earliest_block_number(5).await.unwrap();
+
+        let report = store.check_consistency(10).await.unwrap().unwrap();
+        assert_eq!(report.newest_consistent_block, Some(7));
+        assert!(report.is_consistent());
+
+        // Receipts are still required for the executed blocks
+        store_chain_for_consistency_check(
+            &store,
+            5..=8,
+            |block_number| block_number != 8,
+            |_| *EMPTY_TRIE_HASH,
+        )
+        .await;
+        let report = store.check_consistency(10).await.unwrap().unwrap();
+        assert_eq!(report.newest_consistent_block, Some(7));
+        assert_eq!(
+            report.inconsistencies,
+            vec![Inconsistency::MissingReceipts(8)]
+        );
+    }
+
+    async fn test_consistency_check_missing_head_state(store: Store) {
+        // The state of the last blocks was kept in memory and lost
+        store_chain_for_consistency_check(
+            &store,
+            1..=3,
+            |_| true,
+            |block_number| {
+                if block_number == 1 {
+                    *EMPTY_TRIE_HASH
+                } else {
+                    H256::from_low_u64_be(block_number)
+                }
+            },
+        )
+        .await;
+        store.update_earliest_block_number(1).await.unwrap();
+
+        let report = store.check_consistency(10).await.unwrap().unwrap();
+        assert_eq!(report.newest_consistent_block, Some(3));
+        assert_eq!(report.newest_state_block, Some(1));
+        assert_eq!(
+            report.inconsistencies,
+            vec![Inconsistency::MissingHeadState {
+                head: 3,
+                newest_state_block: 1,
+            }]
+        );
+        assert!(report.is_repairable());
+
+        // The head is kept, its state is regenerated on startup
+        store.repair_consistency(&report).await.unwrap();
+        assert_eq!(store.get_latest_block_number().await.unwrap(), 3);
+    }
+
+    async fn test_trace_index(store: Store) {
+        let address = Address::repeat_byte(1);
+        let other_address = Address::repeat_byte(2);
+        assert_eq!(store.get_trace_indexed_range().await.unwrap(), None);
+
+        store
+            .add_trace_index_entries(10, vec![address, other_address])
+            .await
+            .unwrap();
+        store.add_trace_index_entries(11, vec![]).await.unwrap();
+        store
+            .add_trace_index_entries(12, vec![address])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_trace_indexed_range().await.unwrap(),
+            Some((10, 12))
+        );
+        assert_eq!(
+            store.get_trace_index_blocks(address, 0, 100).await.unwrap(),
+            vec![10, 12]
+        );
+        assert_eq!(
+            store.get_trace_index_blocks(address, 11, 12).await.unwrap(),
+            vec![12]
+        );
+        assert_eq!(
+            store
+                .get_trace_index_blocks(other_address, 11, 100)
+                .await
+                .unwrap(),
+            Vec::<BlockNumber>::new()
+        );
+
+        // A gap restarts the indexed range
+        store.add_trace_index_entries(20, vec![]).await.unwrap();
+        assert_eq!(
+            store.get_trace_indexed_range().await.unwrap(),
+            Some((20, 20))
+        );
+    }
+
     async fn test_store_block_number(store: Store) {
         let block_hash = H256::random();
         let block_number = 6;
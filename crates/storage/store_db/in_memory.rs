@@ -6,13 +6,13 @@ use crate::{
     store::STATE_TRIE_SEGMENTS,
     trie_db::layering::{TrieLayerCache, TrieWrapper},
 };
-use ethereum_types::H256;
+use ethereum_types::{Address, H256};
 use ethrex_common::types::{
     Block, BlockBody, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code, Index, Receipt,
 };
 use ethrex_trie::{InMemoryTrieDB, Nibbles, Trie, db::NodeMap};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     fmt::Debug,
     path::{Path, PathBuf},
     sync::{Arc, Mutex, MutexGuard},
@@ -49,6 +49,9 @@ pub struct StoreInner {
     snap_state: SnapState,
     // Stores fetched headers during a fullsync
     fullsync_headers: HashMap<BlockNumber, BlockHeader>,
+    // Addresses appearing in the call traces of each block
+    trace_index: BTreeSet<(Address, BlockNumber)>,
+    trace_indexed_range: Option<(BlockNumber, BlockNumber)>,
 }
 
 #[derive(Default, Debug)]
@@ -208,6 +211,56 @@ impl StoreEngine for Store {
         Ok(())
     }
 
+    async fn prune_block_history(&self, block_hashes: Vec<BlockHash>) -> Result<(), StoreError> {
+        let mut store = self.inner()?;
+        for block_hash in block_hashes {
+            let Some(body) = store.bodies.remove(&block_hash) else {
+                continue;
+            };
+            for transaction in body.transactions {
+                if let Some(locations) = store.transaction_locations.get_mut(&transaction.hash()) {
+                    locations.retain(|(_, hash, _)| *hash != block_hash);
+                }
+            }
+            store.receipts.remove(&block_hash);
+        }
+        Ok(())
+    }
+
+    async fn add_trace_index_entries(
+        &self,
+        block_number: BlockNumber,
+        addresses: Vec<Address>,
+        indexed_range: (BlockNumber, BlockNumber),
+    ) -> Result<(), StoreError> {
+        let mut store = self.inner()?;
+        store
+            .trace_index
+            .extend(addresses.into_iter().map(|address| (address, block_number)));
+        store.trace_indexed_range = Some(indexed_range);
+        Ok(())
+    }
+
+    async fn get_trace_index_blocks(
+        &self,
+        address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, StoreError> {
+        Ok(self
+            .inner()?
+            .trace_index
+            .range((address, from)..=(address, to))
+            .map(|(_, block_number)| *block_number)
+            .collect())
+    }
+
+    async fn get_trace_indexed_range(
+        &self,
+    ) -> Result<Option<(BlockNumber, BlockNumber)>, StoreError> {
+        Ok(self.inner()?.trace_indexed_range)
+    }
+
     async fn get_block_bodies(
         &self,
         from: BlockNumber,
@@ -383,6 +436,10 @@ impl StoreEngine for Store {
         Ok(())
     }
 
+    async fn get_chain_config(&self) -> Result<Option<ChainConfig>, StoreError> {
+        Ok(self.inner()?.chain_data.chain_config)
+    }
+
     async fn update_earliest_block_number(
         &self,
         block_number: BlockNumber,
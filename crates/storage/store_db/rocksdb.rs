@@ -7,13 +7,14 @@ use crate::{
 };
 use bytes::Bytes;
 use ethrex_common::{
-    H256,
+    Address, H256,
+    constants::EMPTY_TRIE_HASH,
     types::{
         AccountState, Block, BlockBody, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code,
         Index, Receipt, Transaction,
     },
 };
-use ethrex_trie::{Nibbles, Node, Trie};
+use ethrex_trie::{Nibbles, Node, Trie, TrieDB};
 use lru::LruCache;
 use rocksdb::{
     BlockBasedOptions, BoundColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, MultiThreaded,
@@ -135,6 +136,33 @@ pub const CF_STORAGE_FLATKEYVALUE: &str = "storage_flatkeyvalue";
 
 pub const CF_MISC_VALUES: &str = "misc_values";
 
+/// Addresses appearing in the call traces of each block, used to serve `trace_filter`
+/// - [`Vec<u8>`] = `address + block_number.to_be_bytes()`
+/// - [`Vec<u8>`] = empty
+const CF_TRACE_INDEX: &str = "trace_index";
+
+/// Current column families that the code expects
+const EXPECTED_COLUMN_FAMILIES: [&str; 18] = [
+    CF_CANONICAL_BLOCK_HASHES,
+    CF_BLOCK_NUMBERS,
+    CF_HEADERS,
+    CF_BODIES,
+    CF_ACCOUNT_CODES,
+    CF_RECEIPTS,
+    CF_TRANSACTION_LOCATIONS,
+    CF_CHAIN_DATA,
+    CF_SNAP_STATE,
+    CF_ACCOUNT_TRIE_NODES,
+    CF_STORAGE_TRIE_NODES,
+    CF_PENDING_BLOCKS,
+    CF_INVALID_ANCESTORS,
+    CF_FULLSYNC_HEADERS,
+    CF_ACCOUNT_FLATKEYVALUE,
+    CF_STORAGE_FLATKEYVALUE,
+    CF_MISC_VALUES,
+    CF_TRACE_INDEX,
+];
+
 pub type StorageUpdates = Vec<(H256, Vec<(Nibbles, Vec<u8>)>)>;
 
 pub type TriedUpdateWorkerTx = std::sync::mpsc::SyncSender<(
@@ -169,6 +197,10 @@ pub struct Store {
     /// may result in this cache having useless data.
     account_code_cache: Arc<Mutex<CodeCache>>,
     account_code_cache_size: AtomicU64,
+    /// Root of the state trie persisted by the primary, only set for secondary instances.
+    /// Those don't have the in-memory diff layers of the primary, so this is the only state
+    /// they can read.
+    persisted_state_root: Option<Arc<Mutex<H256>>>,
 }
 
 impl Clone for Store {
@@ -184,6 +216,7 @@ impl Clone for Store {
                 self.account_code_cache_size
                     .load(std::sync::atomic::Ordering::Relaxed),
             ),
+            persisted_state_root: self.persisted_state_root.clone(),
         }
     }
 }
@@ -229,27 +262,6 @@ impl Store {
         // db_options.enable_statistics();
         // db_options.set_stats_dump_period_sec(600);
 
-        // Current column families that the code expects
-        let expected_column_families = vec![
-            CF_CANONICAL_BLOCK_HASHES,
-            CF_BLOCK_NUMBERS,
-            CF_HEADERS,
-            CF_BODIES,
-            CF_ACCOUNT_CODES,
-            CF_RECEIPTS,
-            CF_TRANSACTION_LOCATIONS,
-            CF_CHAIN_DATA,
-            CF_SNAP_STATE,
-            CF_ACCOUNT_TRIE_NODES,
-            CF_STORAGE_TRIE_NODES,
-            CF_PENDING_BLOCKS,
-            CF_INVALID_ANCESTORS,
-            CF_FULLSYNC_HEADERS,
-            CF_ACCOUNT_FLATKEYVALUE,
-            CF_STORAGE_FLATKEYVALUE,
-            CF_MISC_VALUES,
-        ];
-
         // Get existing column families to know which ones to drop later
         let existing_cfs = match DBWithThreadMode::<MultiThreaded>::list_cf(&db_options, path) {
             Ok(cfs) => {
@@ -267,7 +279,7 @@ impl Store {
         let mut all_cfs_to_open = HashSet::new();
 
         // Add all expected CFs
-        for cf in &expected_column_families {
+        for cf in EXPECTED_COLUMN_FAMILIES {
             all_cfs_to_open.insert(cf.to_string());
         }
 
@@ -394,7 +406,7 @@ impl Store {
 
         // Clean up obsolete column families
         for cf_name in &existing_cfs {
-            if cf_name != "default" && !expected_column_families.contains(&cf_name.as_str()) {
+            if cf_name != "default" && !EXPECTED_COLUMN_FAMILIES.contains(&cf_name.as_str()) {
                 info!("Dropping obsolete column family: {}", cf_name);
                 match db.drop_cf(cf_name) {
                     Ok(_) => info!("Successfully dropped column family: {}", cf_name),
@@ -412,16 +424,7 @@ impl Store {
         let (fkv_tx, fkv_rx) = std::sync::mpsc::sync_channel(0);
         let (trie_upd_tx, trie_upd_rx) = std::sync::mpsc::sync_channel(0);
 
-        let cf_misc = db
-            .cf_handle(CF_MISC_VALUES)
-            .ok_or_else(|| StoreError::Custom("column not found".to_string()))?;
-        let mut last_written = db
-            .get_cf(&cf_misc, "last_written")?
-            .unwrap_or_else(|| vec![0u8; 64]);
-        if last_written == vec![0xff] {
-            last_written = vec![0xff; 64];
-        }
-        drop(cf_misc); // dropped to remove borrow on db
+        let last_written = read_last_written(&db)?;
 
         let store = Self {
             db: Arc::new(db),
@@ -433,6 +436,7 @@ impl Store {
                 FxBuildHasher,
             ))),
             account_code_cache_size: AtomicU64::new(0),
+            persisted_state_root: None,
         };
         let store_clone = store.clone();
         std::thread::spawn(move || {
@@ -504,6 +508,102 @@ impl Store {
         Ok(store)
     }
 
+    /// Opens the database at `path` as a RocksDB secondary instance, which can be used
+    /// while a primary instance (i.e. a running node) owns the database.
+    ///
+    /// The secondary keeps its own info logs and metadata at `secondary_path`, sees the
+    /// database as it was when opened and only follows the primary's writes when
+    /// [`StoreEngine::try_catch_up_with_primary`] is called. Every write fails, and no
+    /// background workers are started.
+    pub fn new_secondary(path: &Path, secondary_path: &Path) -> Result<Self, StoreError> {
+        let mut db_options = Options::default();
+        // Secondary instances need to keep all files open to follow the primary
+        db_options.set_max_open_files(-1);
+
+        let existing_cfs = DBWithThreadMode::<MultiThreaded>::list_cf(&db_options, path)
+            .map_err(|e| StoreError::Custom(format!("Failed to read database at {path:?}: {e}")))?;
+        // Column families not yet created by the primary can't be opened, and obsolete
+        // ones are the primary's to drop
+        let cf_names = EXPECTED_COLUMN_FAMILIES
+            .into_iter()
+            .filter(|cf_name| existing_cfs.iter().any(|existing| existing == cf_name));
+
+        let db = DBWithThreadMode::<MultiThreaded>::open_cf_as_secondary(
+            &db_options,
+            path,
+            secondary_path,
+            cf_names,
+        )
+        .map_err(|e| StoreError::Custom(format!("Failed to open RocksDB as secondary: {}", e)))?;
+
+        let last_written = read_last_written(&db)?;
+        // The receivers are dropped right away: there are no workers to talk to, so trying
+        // to apply updates or to generate the FlatKeyValue fails
+        let (fkv_tx, _) = std::sync::mpsc::sync_channel(0);
+        let (trie_upd_tx, _) = std::sync::mpsc::sync_channel(0);
+
+        let store = Self {
+            db: Arc::new(db),
+            trie_cache: Default::default(),
+            flatkeyvalue_control_tx: fkv_tx,
+            trie_update_worker_tx: trie_upd_tx,
+            last_computed_flatkeyvalue: Arc::new(Mutex::new(last_written)),
+            account_code_cache: Arc::new(Mutex::new(LruCache::unbounded_with_hasher(
+                FxBuildHasher,
+            ))),
+            account_code_cache_size: AtomicU64::new(0),
+            persisted_state_root: Some(Arc::new(Mutex::new(*EMPTY_TRIE_HASH))),
+        };
+        store.refresh_persisted_state()?;
+        Ok(store)
+    }
+
+    /// Reloads the FlatKeyValue progress and the persisted state root of a secondary
+    /// instance, to be called after catching up with the primary
+    fn refresh_persisted_state(&self) -> Result<(), StoreError> {
+        let Some(persisted_state_root) = &self.persisted_state_root else {
+            return Ok(());
+        };
+        let last_written = read_last_written(&self.db)?;
+        let trie_db = RocksDBTrieDB::new(
+            self.db.clone(),
+            CF_ACCOUNT_TRIE_NODES,
+            CF_ACCOUNT_FLATKEYVALUE,
+            None,
+            last_written.clone(),
+        )?;
+        // NOTE: here we hash the root because the trie doesn't check the state root is correct
+        let state_root = match trie_db.get(Nibbles::default())? {
+            Some(root) => Node::decode(&root)?.compute_hash().finalize(),
+            None => *EMPTY_TRIE_HASH,
+        };
+
+        *self
+            .last_computed_flatkeyvalue
+            .lock()
+            .map_err(|_| StoreError::LockError)? = last_written;
+        *persisted_state_root
+            .lock()
+            .map_err(|_| StoreError::LockError)? = state_root;
+        Ok(())
+    }
+
+    /// Fails if this is a secondary instance and `state_root` isn't the state persisted by
+    /// the primary: the diff layers on top of it only live in the primary's memory, and
+    /// reading the persisted nodes as if they were that state would silently give wrong results
+    fn ensure_state_available(&self, state_root: H256) -> Result<(), StoreError> {
+        let Some(persisted_state_root) = &self.persisted_state_root else {
+            return Ok(());
+        };
+        let persisted_state_root = *persisted_state_root
+            .lock()
+            .map_err(|_| StoreError::LockError)?;
+        if state_root != persisted_state_root && state_root != *EMPTY_TRIE_HASH {
+            return Err(StoreError::StateNotPersisted(state_root));
+        }
+        Ok(())
+    }
+
     // Helper method to get column family handle
     fn cf_handle(
         &self,
@@ -1150,6 +1250,117 @@ impl StoreEngine for Store {
             .map_err(|e| StoreError::Custom(format!("RocksDB batch write error: {}", e)))
     }
 
+    async fn prune_block_history(&self, block_hashes: Vec<BlockHash>) -> Result<(), StoreError> {
+        let db = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut batch = WriteBatch::default();
+
+            let [cf_bodies, cf_receipts, cf_tx_locations] =
+                open_cfs(&db, [CF_BODIES, CF_RECEIPTS, CF_TRANSACTION_LOCATIONS])?;
+
+            for block_hash in block_hashes {
+                let hash_key = BlockHashRLP::from(block_hash).bytes().clone();
+                let Some(body_bytes) = db
+                    .get_cf(&cf_bodies, &hash_key)
+                    .map_err(|e| StoreError::Custom(format!("RocksDB read error: {}", e)))?
+                else {
+                    // Already pruned
+                    continue;
+                };
+                let body: BlockBody = BlockBodyRLP::from_bytes(body_bytes).to()?;
+
+                for (index, transaction) in body.transactions.iter().enumerate() {
+                    // Key: tx_hash + block_hash
+                    let mut composite_key = Vec::with_capacity(64);
+                    composite_key.extend_from_slice(transaction.hash().as_bytes());
+                    composite_key.extend_from_slice(block_hash.as_bytes());
+                    batch.delete_cf(&cf_tx_locations, composite_key);
+                    batch.delete_cf(&cf_receipts, (block_hash, index as u64).encode_to_vec());
+                }
+                batch.delete_cf(&cf_bodies, hash_key);
+            }
+
+            db.write(batch)
+                .map_err(|e| StoreError::Custom(format!("RocksDB batch write error: {}", e)))
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    async fn add_trace_index_entries(
+        &self,
+        block_number: BlockNumber,
+        addresses: Vec<Address>,
+        indexed_range: (BlockNumber, BlockNumber),
+    ) -> Result<(), StoreError> {
+        let mut batch_ops: Vec<(String, Vec<u8>, Vec<u8>)> = addresses
+            .into_iter()
+            .map(|address| {
+                (
+                    CF_TRACE_INDEX.to_string(),
+                    trace_index_key(address, block_number),
+                    Vec::new(),
+                )
+            })
+            .collect();
+        batch_ops.push((
+            CF_CHAIN_DATA.to_string(),
+            Self::chain_data_key(ChainDataIndex::TraceIndexedRange),
+            indexed_range.encode_to_vec(),
+        ));
+        self.write_batch_async(batch_ops).await
+    }
+
+    async fn get_trace_index_blocks(
+        &self,
+        address: Address,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, StoreError> {
+        let db = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let [cf] = open_cfs(&db, [CF_TRACE_INDEX])?;
+            let start_key = trace_index_key(address, from);
+            let iter = db.iterator_cf(
+                &cf,
+                rocksdb::IteratorMode::From(&start_key, rocksdb::Direction::Forward),
+            );
+
+            let mut block_numbers = Vec::new();
+            for entry in iter {
+                let (key, _) =
+                    entry.map_err(|e| StoreError::Custom(format!("RocksDB read error: {}", e)))?;
+                let Some(number_bytes) = key.strip_prefix(address.as_bytes()) else {
+                    break;
+                };
+                let block_number =
+                    BlockNumber::from_be_bytes(number_bytes.try_into().map_err(|_| {
+                        StoreError::Custom("Invalid BlockNumber bytes".to_string())
+                    })?);
+                if block_number > to {
+                    break;
+                }
+                block_numbers.push(block_number);
+            }
+            Ok(block_numbers)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    async fn get_trace_indexed_range(
+        &self,
+    ) -> Result<Option<(BlockNumber, BlockNumber)>, StoreError> {
+        let key = Self::chain_data_key(ChainDataIndex::TraceIndexedRange);
+        self.read_async(CF_CHAIN_DATA, key)
+            .await?
+            .map(|bytes| <(BlockNumber, BlockNumber)>::decode(&bytes))
+            .transpose()
+            .map_err(StoreError::from)
+    }
+
     async fn get_block_bodies(
         &self,
         from: BlockNumber,
@@ -1537,6 +1748,18 @@ impl StoreEngine for Store {
         self.write_async(CF_CHAIN_DATA, key, value).await
     }
 
+    async fn get_chain_config(&self) -> Result<Option<ChainConfig>, StoreError> {
+        let key = Self::chain_data_key(ChainDataIndex::ChainConfig);
+        self.read_async(CF_CHAIN_DATA, key)
+            .await?
+            .map(|bytes| {
+                serde_json::from_slice(&bytes).map_err(|_| {
+                    StoreError::Custom("Failed to deserialize chain config".to_string())
+                })
+            })
+            .transpose()
+    }
+
     async fn update_earliest_block_number(
         &self,
         block_number: BlockNumber,
@@ -1631,6 +1854,7 @@ impl StoreEngine for Store {
         storage_root: H256,
         state_root: H256,
     ) -> Result<Trie, StoreError> {
+        self.ensure_state_available(state_root)?;
         // FIXME: use a DB snapshot here
         let db = Box::new(RocksDBTrieDB::new(
             self.db.clone(),
@@ -1653,6 +1877,7 @@ impl StoreEngine for Store {
     }
 
     fn open_state_trie(&self, state_root: H256) -> Result<Trie, StoreError> {
+        self.ensure_state_available(state_root)?;
         // FIXME: use a DB snapshot here
         let db = Box::new(RocksDBTrieDB::new(
             self.db.clone(),
@@ -1701,6 +1926,7 @@ impl StoreEngine for Store {
     }
 
     fn open_locked_state_trie(&self, state_root: H256) -> Result<Trie, StoreError> {
+        self.ensure_state_available(state_root)?;
         let db = Box::new(RocksDBLockedTrieDB::new(
             self.db.clone(),
             CF_ACCOUNT_TRIE_NODES,
@@ -1727,6 +1953,7 @@ impl StoreEngine for Store {
         storage_root: H256,
         state_root: H256,
     ) -> Result<Trie, StoreError> {
+        self.ensure_state_available(state_root)?;
         let db = Box::new(RocksDBLockedTrieDB::new(
             self.db.clone(),
             CF_STORAGE_TRIE_NODES,
@@ -2133,6 +2360,37 @@ impl StoreEngine for Store {
         let last_computed_flatkeyvalue = self.last_written()?;
         Ok(&last_computed_flatkeyvalue[0..64] > account_nibbles.as_ref())
     }
+
+    fn is_read_only(&self) -> bool {
+        self.persisted_state_root.is_some()
+    }
+
+    async fn try_catch_up_with_primary(&self) -> Result<(), StoreError> {
+        if self.persisted_state_root.is_none() {
+            return Ok(());
+        }
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            store.db.try_catch_up_with_primary()?;
+            store.refresh_persisted_state()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+}
+
+/// Reads how far the FlatKeyValue generation got, all of it being generated once it's done
+fn read_last_written(db: &DBWithThreadMode<MultiThreaded>) -> Result<Vec<u8>, StoreError> {
+    let cf_misc = db
+        .cf_handle(CF_MISC_VALUES)
+        .ok_or_else(|| StoreError::Custom("column not found".to_string()))?;
+    let last_written = db
+        .get_cf(&cf_misc, "last_written")?
+        .unwrap_or_else(|| vec![0u8; 64]);
+    if last_written == vec![0xff] {
+        return Ok(vec![0xff; 64]);
+    }
+    Ok(last_written)
 }
 
 /// Open column families
@@ -2153,3 +2411,11 @@ fn open_cfs<'a, const N: usize>(
         .try_into()
         .map_err(|_| StoreError::Custom("Unexpected number of column families".to_string()))
 }
+
+/// Key of the trace index, big endian so the blocks of an address are iterated in order
+fn trace_index_key(address: Address, block_number: BlockNumber) -> Vec<u8> {
+    let mut key = Vec::with_capacity(28);
+    key.extend_from_slice(address.as_bytes());
+    key.extend_from_slice(&block_number.to_be_bytes());
+    key
+}
@@ -8,6 +8,7 @@ pub enum ChainDataIndex {
     SafeBlockNumber = 3,
     LatestBlockNumber = 4,
     PendingBlockNumber = 5,
+    TraceIndexedRange = 6,
 }
 
 impl From<u8> for ChainDataIndex {
@@ -25,6 +26,7 @@ impl From<u8> for ChainDataIndex {
             x if x == ChainDataIndex::PendingBlockNumber as u8 => {
                 ChainDataIndex::PendingBlockNumber
             }
+            x if x == ChainDataIndex::TraceIndexedRange as u8 => ChainDataIndex::TraceIndexedRange,
             _ => panic!("Invalid value when casting to ChainDataIndex: {value}"),
         }
     }
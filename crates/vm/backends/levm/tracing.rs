@@ -1,5 +1,12 @@
-use ethrex_common::types::{Block, Transaction};
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use ethrex_common::tracing::{AccountStateDiff, AccountStateSnapshot, StateDiff};
+use ethrex_common::types::{AccountInfo, Block, Transaction};
+use ethrex_common::{Address, H256, U256};
 use ethrex_common::{tracing::CallTrace, types::BlockHeader};
+use ethrex_levm::account::{AccountStatus, LevmAccount};
+use ethrex_levm::db::gen_db::CacheDB;
 use ethrex_levm::vm::VMType;
 use ethrex_levm::{db::gen_db::GeneralizedDatabase, tracing::LevmCallTracer, vm::VM};
 
@@ -74,4 +81,393 @@ impl LEVM {
         // We only return the top call because a transaction only has one call with subcalls
         Ok(vec![callframe])
     }
+
+    /// Run transaction and output its return data along with the changes it made to the state.
+    pub fn trace_tx_state_diff(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        vm_type: VMType,
+    ) -> Result<(Bytes, StateDiff), EvmError> {
+        let sender = tx.sender().map_err(|error| {
+            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+        })?;
+        // Accounts changed by the previous transactions of the block are compared against their
+        // value before this one, so they're left out unless it changes them too
+        let pre_state = db.current_accounts_state.clone();
+        let env = Self::setup_env(tx, sender, block_header, db, vm_type)?;
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+        let report = vm.execute()?;
+        let state_diff = Self::state_diff(db, &pre_state)?;
+        Ok((report.output, state_diff))
+    }
+
+    /// Rerun the whole block and output the changes it made to the state, including the ones
+    /// made by system calls and withdrawals.
+    pub fn trace_block_state_diff(
+        db: &mut GeneralizedDatabase,
+        block: &Block,
+        vm_type: VMType,
+    ) -> Result<StateDiff, EvmError> {
+        let pre_state = db.current_accounts_state.clone();
+        Self::rerun_block(db, block, None, vm_type)?;
+        Self::state_diff(db, &pre_state)
+    }
+
+    /// Compares the cached state against `pre_state`, the cached state before the execution.
+    /// Accounts and storage slots missing from `pre_state` were loaded during the execution, so
+    /// their previous value is the one in the database.
+    fn state_diff(
+        db: &mut GeneralizedDatabase,
+        pre_state: &CacheDB,
+    ) -> Result<StateDiff, EvmError> {
+        let mut state_diff = StateDiff::new();
+        let modified_accounts: Vec<(Address, LevmAccount)> = db
+            .current_accounts_state
+            .iter()
+            .filter(|(_, account)| !account.is_unmodified())
+            .map(|(address, account)| (*address, account.clone()))
+            .collect();
+        for (address, post_account) in modified_accounts {
+            let db_account = db.initial_accounts_state.get(&address).cloned();
+            let Some(pre_account) = pre_state.get(&address).or(db_account.as_ref()) else {
+                continue;
+            };
+
+            let mut storage: BTreeMap<H256, (U256, U256)> = post_account
+                .storage
+                .iter()
+                .filter_map(|(key, post_value)| {
+                    let pre_value = pre_storage_value(pre_account, db_account.as_ref(), key);
+                    (pre_value != *post_value).then_some((*key, (pre_value, *post_value)))
+                })
+                .collect();
+            // Slots cleared by a self-destruct aren't in the cache anymore
+            if matches!(
+                post_account.status,
+                AccountStatus::Destroyed | AccountStatus::DestroyedModified
+            ) && !matches!(
+                pre_account.status,
+                AccountStatus::Destroyed | AccountStatus::DestroyedModified
+            ) {
+                for (key, pre_value) in &pre_account.storage {
+                    if !pre_value.is_zero() && !post_account.storage.contains_key(key) {
+                        storage.insert(*key, (*pre_value, U256::zero()));
+                    }
+                }
+            }
+
+            let pre = Self::account_snapshot(db, &pre_account.info)?;
+            let post = Self::account_snapshot(db, &post_account.info)?;
+            if pre == post && storage.is_empty() {
+                continue;
+            }
+            state_diff.insert(address, AccountStateDiff { pre, post, storage });
+        }
+        Ok(state_diff)
+    }
+
+    /// Returns `None` for empty accounts, as they don't exist in the state (EIP-161)
+    fn account_snapshot(
+        db: &mut GeneralizedDatabase,
+        info: &AccountInfo,
+    ) -> Result<Option<AccountStateSnapshot>, EvmError> {
+        if info.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(AccountStateSnapshot {
+            balance: info.balance,
+            nonce: info.nonce,
+            code: db.get_code(info.code_hash)?.bytecode.clone(),
+        }))
+    }
+}
+
+/// Value of a storage slot before the execution, `db_account` being the account as loaded from
+/// the database
+fn pre_storage_value(
+    pre_account: &LevmAccount,
+    db_account: Option<&LevmAccount>,
+    key: &H256,
+) -> U256 {
+    if let Some(value) = pre_account.storage.get(key) {
+        return *value;
+    }
+    // The database values of destroyed accounts aren't valid anymore
+    if pre_account.status == AccountStatus::DestroyedModified {
+        return U256::zero();
+    }
+    db_account
+        .and_then(|account| account.storage.get(key))
+        .copied()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use ethrex_common::evm::calculate_create_address;
+    use ethrex_common::types::{
+        AccountState, ChainConfig, Code, EIP1559Transaction, TxKind, TxType,
+    };
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_levm::db::Database;
+    use ethrex_levm::errors::DatabaseError;
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, PublicKey, SECP256K1, SecretKey};
+
+    use super::*;
+
+    const BALANCE: u64 = 1_000_000_000_000;
+    /// Reads slot 1, sets slot 0 to 7 and reads the balance of `untouched()`
+    const STORING_CODE: [u8; 31] = [
+        0x60, 0x01, 0x54, 0x50, 0x60, 0x07, 0x60, 0x00, 0x55, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd0,
+        0x31,
+    ];
+    /// Reads slot 0 and self-destructs in favour of `beneficiary()`
+    const SELF_DESTRUCTING_CODE: [u8; 26] = [
+        0x60, 0x00, 0x54, 0x50, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xbe, 0xff,
+    ];
+    /// Sets slot 0 to 1 and deploys a contract whose code is a single `STOP`
+    const INIT_CODE: [u8; 15] = [
+        0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3,
+    ];
+
+    #[derive(Default)]
+    struct TestDatabase {
+        accounts: HashMap<Address, (AccountState, HashMap<H256, U256>)>,
+        codes: HashMap<H256, Code>,
+    }
+
+    impl TestDatabase {
+        fn insert(&mut self, address: Address, balance: u64, code: &[u8], storage: &[(u64, u64)]) {
+            let code = Code::from_bytecode(Bytes::copy_from_slice(code));
+            let state = AccountState {
+                nonce: u64::from(!code.bytecode.is_empty()),
+                balance: U256::from(balance),
+                // Anything but the empty trie root, the storage itself is kept aside
+                storage_root: H256::repeat_byte(1),
+                code_hash: code.hash,
+            };
+            let storage = storage
+                .iter()
+                .map(|(key, value)| (H256::from_low_u64_be(*key), U256::from(*value)))
+                .collect();
+            self.codes.insert(code.hash, code);
+            self.accounts.insert(address, (state, storage));
+        }
+    }
+
+    impl Database for TestDatabase {
+        fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+            Ok(self
+                .accounts
+                .get(&address)
+                .map(|(state, _)| state.clone())
+                .unwrap_or_default())
+        }
+
+        fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+            Ok(self
+                .accounts
+                .get(&address)
+                .and_then(|(_, storage)| storage.get(&key).copied())
+                .unwrap_or_default())
+        }
+
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            // Shanghai, so that self-destructs remove existing contracts
+            Ok(ChainConfig {
+                chain_id: 1,
+                shanghai_time: Some(0),
+                ..Default::default()
+            })
+        }
+
+        fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+            self.codes
+                .get(&code_hash)
+                .cloned()
+                .ok_or(DatabaseError::Custom("Code not found".to_string()))
+        }
+    }
+
+    fn key() -> SecretKey {
+        SecretKey::from_slice(&[1; 32]).unwrap()
+    }
+
+    fn sender() -> Address {
+        let public_key = PublicKey::from_secret_key(SECP256K1, &key()).serialize_uncompressed();
+        Address::from_slice(&keccak_hash(&public_key[1..])[12..])
+    }
+
+    fn storing_contract() -> Address {
+        Address::from_low_u64_be(0xc0)
+    }
+
+    fn untouched() -> Address {
+        Address::from_low_u64_be(0xd0)
+    }
+
+    fn self_destructing_contract() -> Address {
+        Address::from_low_u64_be(0xdd)
+    }
+
+    fn beneficiary() -> Address {
+        Address::from_low_u64_be(0xbe)
+    }
+
+    fn test_db() -> GeneralizedDatabase {
+        let mut store = TestDatabase::default();
+        store.insert(sender(), BALANCE, &[], &[]);
+        store.insert(untouched(), 1, &[], &[]);
+        store.insert(storing_contract(), 0, &STORING_CODE, &[(0, 5), (1, 3)]);
+        store.insert(
+            self_destructing_contract(),
+            5,
+            &SELF_DESTRUCTING_CODE,
+            &[(0, 9)],
+        );
+        GeneralizedDatabase::new(Arc::new(store))
+    }
+
+    fn header() -> BlockHeader {
+        BlockHeader {
+            coinbase: Address::from_low_u64_be(0xcb),
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn signed_tx(nonce: u64, to: TxKind, data: &[u8]) -> Transaction {
+        let mut tx = EIP1559Transaction {
+            chain_id: 1,
+            nonce,
+            max_priority_fee_per_gas: 0,
+            max_fee_per_gas: 1,
+            gas_limit: 100_000,
+            to,
+            data: Bytes::copy_from_slice(data),
+            ..Default::default()
+        };
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak_hash(&payload)), &key())
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = Into::<i32>::into(recovery_id) != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn trace(db: &mut GeneralizedDatabase, tx: &Transaction) -> StateDiff {
+        LEVM::trace_tx_state_diff(db, &header(), tx, VMType::L1)
+            .unwrap()
+            .1
+    }
+
+    fn sorted(mut addresses: Vec<Address>) -> Vec<Address> {
+        addresses.sort();
+        addresses
+    }
+
+    fn slot(key: u64, pre: u64, post: u64) -> (H256, (U256, U256)) {
+        (
+            H256::from_low_u64_be(key),
+            (U256::from(pre), U256::from(post)),
+        )
+    }
+
+    #[test]
+    fn state_diff_of_modified_and_created_accounts() {
+        let mut db = test_db();
+        // Accounts already cached, even as modified, are left out unless the transaction changes them
+        db.get_account_mut(untouched()).unwrap();
+
+        let state_diff = trace(
+            &mut db,
+            &signed_tx(0, TxKind::Call(storing_contract()), &[]),
+        );
+        assert_eq!(
+            state_diff.keys().copied().collect::<Vec<_>>(),
+            sorted(vec![storing_contract(), sender()])
+        );
+        let contract_diff = &state_diff[&storing_contract()];
+        assert_eq!(contract_diff.pre, contract_diff.post);
+        // Slot 1 was only read
+        assert_eq!(contract_diff.storage, BTreeMap::from([slot(0, 5, 7)]));
+        let sender_diff = &state_diff[&sender()];
+        let (pre, post) = (
+            sender_diff.pre.clone().unwrap(),
+            sender_diff.post.clone().unwrap(),
+        );
+        assert_eq!((pre.nonce, post.nonce), (0, 1));
+        assert_eq!(pre.balance, U256::from(BALANCE));
+        assert!(post.balance < pre.balance);
+
+        // Each diff only holds the changes of its own transaction
+        let state_diff = trace(&mut db, &signed_tx(1, TxKind::Create, &INIT_CODE));
+        let created = calculate_create_address(sender(), 1);
+        assert_eq!(
+            state_diff.keys().copied().collect::<Vec<_>>(),
+            sorted(vec![created, sender()])
+        );
+        assert_eq!(
+            state_diff[&created],
+            AccountStateDiff {
+                pre: None,
+                post: Some(AccountStateSnapshot {
+                    balance: U256::zero(),
+                    nonce: 1,
+                    code: Bytes::from_static(&[0x00]),
+                }),
+                storage: BTreeMap::from([slot(0, 0, 1)]),
+            }
+        );
+    }
+
+    #[test]
+    fn state_diff_of_self_destructed_account() {
+        let mut db = test_db();
+        let state_diff = trace(
+            &mut db,
+            &signed_tx(0, TxKind::Call(self_destructing_contract()), &[]),
+        );
+
+        assert_eq!(
+            state_diff[&self_destructing_contract()],
+            AccountStateDiff {
+                pre: Some(AccountStateSnapshot {
+                    balance: U256::from(5),
+                    nonce: 1,
+                    code: Bytes::from_static(&SELF_DESTRUCTING_CODE),
+                }),
+                post: None,
+                storage: BTreeMap::from([slot(0, 9, 0)]),
+            }
+        );
+        assert_eq!(
+            state_diff[&beneficiary()],
+            AccountStateDiff {
+                pre: None,
+                post: Some(AccountStateSnapshot {
+                    balance: U256::from(5),
+                    nonce: 0,
+                    code: Bytes::new(),
+                }),
+                storage: BTreeMap::new(),
+            }
+        );
+        assert!(!state_diff.contains_key(&header().coinbase));
+    }
 }
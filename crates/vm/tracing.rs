@@ -1,5 +1,6 @@
 use crate::backends::levm::LEVM;
-use ethrex_common::tracing::CallTrace;
+use bytes::Bytes;
+use ethrex_common::tracing::{CallTrace, StateDiff};
 use ethrex_common::types::Block;
 
 use crate::{Evm, EvmError};
@@ -34,6 +35,33 @@ impl Evm {
         )
     }
 
+    /// Runs a single tx and outputs its return data along with the changes it made to the state.
+    /// Assumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block.
+    /// Wraps LEVM::trace_tx_state_diff depending on the feature.
+    pub fn trace_tx_state_diff(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+    ) -> Result<(Bytes, StateDiff), EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_state_diff(&mut self.db, &block.header, tx, self.vm_type)
+    }
+
+    /// Reruns the given block and outputs the changes it made to the state.
+    /// Assumes that the received state is the block's parent state.
+    /// Wraps LEVM::trace_block_state_diff depending on the feature.
+    pub fn trace_block_state_diff(&mut self, block: &Block) -> Result<StateDiff, EvmError> {
+        LEVM::trace_block_state_diff(&mut self.db, block, self.vm_type)
+    }
+
     /// Reruns the given block, saving the changes on the state, doesn't output any results or receipts.
     /// If the optional argument `stop_index` is set, the run will stop just before executing the transaction at that index
     /// and won't process the withdrawals afterwards.
@@ -50,6 +50,13 @@ impl GuestProgramStateWrapper {
             .cloned()
     }
 
+    pub fn get_account_state_checked(
+        &self,
+        address: Address,
+    ) -> Result<Option<AccountState>, GuestProgramStateError> {
+        self.lock_mutex()?.get_account_state_checked(address)
+    }
+
     pub fn initialize_block_header_hashes(
         &self,
         blocks: &[Block],